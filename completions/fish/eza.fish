@@ -1,14 +1,25 @@
 # Meta-stuff
 complete -c eza -s v -l version -d "Show version of eza"
 complete -c eza -l help -d "Show list of command-line options"
+complete -c eza -s q -l quiet -d "Don't print warnings"
+complete -c eza -l strict-warnings -d "Exit with an error if any warnings were printed"
+complete -c eza -l notify -d "Ring the bell when a long run finishes"
+complete -c eza -l debug-layout -d "Explain how the listing was laid out"
+complete -c eza -l dump-scale -d "Print the colour scales worked out for the listing"
 
 # Display options
 complete -c eza -s 1 -l oneline -d "Display one entry per line"
 complete -c eza -s l -l long -d "Display extended file metadata as a table"
 complete -c eza -s G -l grid -d "Display entries in a grid"
 complete -c eza -s x -l across -d "Sort the grid across, rather than downwards"
+complete -c eza -l grid-layout -d "How to size the grid's columns" -x -a "
+    auto\t'Exact for up to 10,000 files, fast for more'
+    exact\t'Fit as many columns as possible'
+    fast\t'Make every column as wide as the widest name'
+"
 complete -c eza -s R -l recurse -d "Recurse into directories"
 complete -c eza -s T -l tree -d "Recurse into directories as a tree"
+complete -c eza -l tree-summary -d "Show the number and size of files under each directory in the tree"
 complete -c eza -s X -l dereference -d "Dereference symbolic links when displaying information"
 complete -c eza -s F -l classify -d "Display type indicator by file names"
 complete -c eza -l color \
@@ -30,6 +41,43 @@ complete -c eza -l color-scale-mode \
     fixed\t'Highlight based on fixed colors'
     gradient\t'Highlight based \'field\' in relation to other files'
 "
+complete -c eza -l color-by \
+    -l colour-by -d "Colour by each file's owner or last commit" -x -a "
+    owner\t'Colour the user column by owner'
+    owner-names\t'Colour the user column and file names by owner'
+    git-age\t'Brighten the names of recently committed files'
+"
+complete -c eza -l color-glob \
+    -l colour-glob -d "Paint files matching a glob, over EZA_COLORS" -x
+complete -c eza -l highlight-recent -d "Highlight files modified within a length of time" -x
+complete -c eza -l highlight -d "Highlight the parts of names that match a regular expression" -x
+complete -c eza -l name-clashes -d "Flag names that differ only by case or normalization"
+complete -c eza -l show-trash -d "Mark files that something was trashed from the same path as"
+complete -c eza -l badges -d "Show a badge from an extended attribute after names"
+complete -c eza -l relative-root -d "Show paths inside a directory as if it were the root" -x -a "(__fish_complete_directories)"
+complete -c eza -l trash -d "List what's in the trash"
+complete -c eza -l resolve-shortcuts -d "Show the names of .desktop files and targets of .lnk files"
+complete -c eza -l finder-tags -d "Show Finder tags as coloured dots after file names"
+complete -c eza -l mount-usage -d "Draw how full the filesystem on each mount point is"
+complete -c eza -l special-dirs -d "Decorate XDG user dirs and dirs like node_modules"
+complete -c eza -l blocks-map -d "Draw where a single sparse file's data and holes are"
+complete -c eza -l index -d "Start with a numbered list of the directories given"
+complete -c eza -l merge -d "List the contents of all the directories given as one"
+complete -c eza -l stats -d "Finish with statistics about the listed files" -x -a "
+    ext\t'Sizes by extension'
+"
+complete -c eza -l depth-colors \
+    -l depth-colours -d "Colour the tree by depth" -x -a "
+    lines\t'Colour the lines of the tree'
+    names\t'Tint file names'
+    all\t'Colour lines and tint names'
+"
+complete -c eza -l truncate -d "How to shorten long names" -x -a "
+    end\t'Cut the end off'
+    middle\t'Cut the middle out, keeping the extension'
+    off\t'Show names in full'
+"
+complete -c eza -l max-name-width -d "Shorten names wider than this many columns" -x
 complete -c eza -l icons -d "When to display icons" -x -a "
   always\t'Always display icons'
   auto\t'Display icons if standard output is a terminal'
@@ -37,27 +85,56 @@ complete -c eza -l icons -d "When to display icons" -x -a "
   never\t'Never display icons'
 "
 complete -c eza -l no-quotes -d "Don't quote file names with spaces"
-complete -c eza -l hyperlink -d "Display entries as hyperlinks"
+complete -c eza -l hyperlink -d "Display entries as hyperlinks" -x -a "
+    always\t'Always make file names hyperlinks'
+    auto\t'Make file names hyperlinks only when output is to a terminal'
+    never\t'Never make hyperlinks'
+    all-cells\t'Make file names, link targets, mount sources and repository branches hyperlinks'
+"
+complete -c eza -l json -d "Print the listing as JSON"
+complete -c eza -l format -d "Print one row per file as CSV or TSV" -x -a "
+    csv\t'Comma-separated values'
+    tsv\t'Tab-separated values'
+"
+complete -c eza -l no-metadata -d "Leave out how the listing was made from JSON, CSV and TSV output"
+complete -c eza -l output -d "Write the listing for another program" -x -a "
+    fzf\t'Displayed names and raw paths, null-separated'
+"
 complete -c eza -l smart-group -d "Only show group if it has a different name from owner"
 
 # Filtering and sorting options
 complete -c eza -l group-directories-first -d "Sort directories before other files"
 complete -c eza -l git-ignore -d "Ignore files mentioned in '.gitignore'"
+complete -c eza -l git-tracked -d "List only files tracked by Git"
+complete -c eza -l git-untracked -d "List only files not tracked by Git"
 complete -c eza -s a -l all -d "Show hidden and 'dot' files. Use this twice to also show the '.' and '..' directories"
 complete -c eza -s A -l almost-all -d "Equivalent to --all; included for compatibility with `ls -A`"
 complete -c eza -s d -l list-dirs -d "List directories like regular files"
 complete -c eza -s L -l level -d "Limit the depth of recursion" -x -a "1 2 3 4 5 6 7 8 9"
+complete -c eza -l follow-symlinks -d "Recurse into symlinks to directories"
+complete -c eza -l resume -d "Save where a recursive listing is up to, and carry on from it" -r
 complete -c eza -s w -l width -d "Limits column output of grid, 0 implies auto-width"
+complete -c eza -l height -d "Screen height in rows for --paginate, 0 implies auto-height" -x
+complete -c eza -l paginate -d "Page listings taller than the screen"
+complete -c eza -l flush -d "When to write output" -x -a "
+    line\t'After every line'
+    block\t'In large blocks'
+    end\t'All at once at the end'
+"
 complete -c eza -s r -l reverse -d "Reverse the sort order"
+complete -c eza -l allow-sort-command -d "Allow sorting by keys from a command"
 complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
     accessed\t'Sort by file accessed time'
     age\t'Sort by file modified time (newest first)'
+    bytes\t'Sort by file name, byte by byte'
     changed\t'Sort by changed time'
     created\t'Sort by file modified time'
     date\t'Sort by file modified time'
+    device\t'Sort by device major and minor numbers'
     ext\t'Sort by file extension'
     Ext\t'Sort by file extension (uppercase first)'
     extension\t'Sort by file extension'
+    git\t'Sort by Git status'
     Extension\t'Sort by file extension (uppercase first)'
     filename\t'Sort by filename'
     Filename\t'Sort by filename (uppercase first)'
@@ -74,15 +151,28 @@ complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
 "
 
 complete -c eza -s I -l ignore-glob -d "Ignore files that match these glob patterns" -r
+complete -c eza -l only-glob -d "List only files that match these glob patterns" -r
 complete -c eza -s D -l only-dirs -d "List only directories"
 complete -c eza -s f -l only-files -d "List only files"
+complete -c eza -l empty -d "List only empty files and directories"
+complete -c eza -l non-empty -d "List only files and directories that aren't empty"
+complete -c eza -l perm -d "List only files with these permission bits" -x
+complete -c eza -l ext -d "List only files with these extensions" -x
+complete -c eza -l size -d "List only files with sizes in this range" -x
+complete -c eza -l newer-than -d "List only files modified after this time" -x
+complete -c eza -l older-than -d "List only files modified before this time" -x
+complete -c eza -l owner -d "List only files owned by this user" -x -a "(__fish_complete_users) me"
+complete -c eza -l in-group -d "List only files in this group" -x -a "(__fish_complete_groups) me"
 
 # Long view options
 complete -c eza -s b -l binary -d "List file sizes with binary prefixes"
 complete -c eza -s B -l bytes -d "List file sizes in bytes, without any prefixes"
 complete -c eza -s g -l group -d "List each file's group"
 complete -c eza -s h -l header -d "Add a header row to each column"
-complete -c eza -s H -l links -d "List each file's number of hard links"
+complete -c eza -s H -l links -d "List each file's number of hard links" -x -a "
+    always\t'Always list the number of hard links'
+    auto\t'Only list files with more than one hard link'
+"
 complete -c eza -s i -l inode -d "List each file's inode number"
 complete -c eza -s S -l blocksize -d "List each file's size of allocated file system blocks"
 complete -c eza -s t -l time -d "Which timestamp field to list" -x -a "
@@ -90,9 +180,39 @@ complete -c eza -s t -l time -d "Which timestamp field to list" -x -a "
     changed\t'Display changed time'
     accessed\t'Display accessed time'
     created\t'Display created time'
+    all\t'Display all four times'
 "
 complete -c eza -s X -l dereference -d "dereference symlinks for file information"
 complete -c eza -s m -l modified -d "Use the modified timestamp field"
+complete -c eza -l virtual-files -d "How to size files in /proc and /sys" -x -a "
+    show\t'Show the size they claim'
+    mark\t'Mark them as virtual'
+    read\t'Read them to find their length'
+"
+complete -c eza -l socket-info -d "List whether sockets are listening or connected"
+complete -c eza -l overlay -d "List which overlayfs layer each file comes from"
+complete -c eza -l age-rank -d "List each file's rank by modified time"
+complete -c eza -l find-dupes -d "Number groups of duplicate files" -x -a "
+    size\t'Files of the same size'
+    content\t'Files with the same contents'
+"
+complete -c eza -l file-version -d "List the version of each executable and DLL" -x -a "
+    file\t'The version of the file'
+    product\t'The version of the product it comes with'
+"
+complete -c eza -l text-size -d "List each text file's size with LF line endings"
+complete -c eza -l encoding -d "List each text file's line endings and byte order mark"
+complete -c eza -l preview -d "List the first line of each text file"
+complete -c eza -l name-first -d "List file names before the other columns"
+complete -c eza -l diff-against -d "Compare each file with its counterpart in another directory" -x -a "(__fish_complete_directories)"
+complete -c eza -l manifest-write -d "Write a manifest of every file under the listed paths" -r
+complete -c eza -l manifest-check -d "Compare each file with a manifest" -r
+complete -c eza -l diff-by -d "How to compare files with the other directory or manifest" -x -a "
+    meta\t'Compare sizes and modification times'
+    content\t'Compare contents'
+"
+complete -c eza -l align -d "Align columns to the left or right" -x
+complete -c eza -l fields -d "List just these columns, in this order" -x
 complete -c eza -s n -l numeric -d "List numeric user and group IDs."
 complete -c eza -l changed -d "Use the changed timestamp field"
 complete -c eza -s u -l accessed -d "Use the accessed timestamp field"
@@ -105,14 +225,22 @@ complete -c eza -l time-style -d "How to format timestamps" -x -a "
     relative\t'Display relative timestamps'
     +FORMAT\t'Use custom time style'
 "
+complete -c eza -l relative-to -d "Measure relative times from a date or a file's mtime" -x
 complete -c eza -l total-size -d "Show recursive directory size (unix only)"
+complete -c eza -l cache -d "Cache xattrs and Git statuses between runs" -x -a "
+    on\t'Use and update the cache'
+    refresh\t'Ignore what is cached and read everything again'
+    off\t'Do not use the cache'
+"
 complete -c eza -l no-permissions -d "Suppress the permissions field"
 complete -c eza -s o -l octal-permissions -d "List each file's permission in octal format"
+complete -c eza -l simulate-umask -d "List the permissions new files in each directory would get" -x
 complete -c eza -l no-filesize -d "Suppress the filesize field"
 complete -c eza -l no-user -d "Suppress the user field"
 complete -c eza -l no-time -d "Suppress the time field"
 complete -c eza -s M -l mounts -d "Show mount details"
 complete -c eza -l stdin -d "When piping to eza. Read file names from stdin"
+complete -c eza -l skip-missing -d "Don't report file names from stdin that don't exist"
 
 # Optional extras
 complete -c eza -l git -d "List each file's Git status, if tracked"
@@ -120,4 +248,16 @@ complete -c eza -l no-git -d "Suppress Git status"
 complete -c eza -l git-repos -d "List each git-repos status and branch name"
 complete -c eza -l git-repos-no-status -d "List each git-repos branch name (much faster)"
 complete -c eza -s '@' -l extended -d "List each file's extended attributes and sizes"
-complete -c eza -s Z -l context -d "List each file's security context"
+complete -c eza -s Z -l context -d "List each file's security context" -x -a "
+    full\t'List the whole security context'
+    smart\t'Leave out the parts most files have in common'
+"
+
+# Views
+complete -c eza -n "__fish_is_first_arg" -a view -d "Use a named view"
+complete -c eza -n "__fish_seen_subcommand_from view" -x -a "
+    big\t'Largest files first'
+    code\t'Git status, ignoring ignored files'
+    media\t'Grid of files with icons, by extension'
+    recent\t'Most recently modified first'
+"
@@ -0,0 +1,67 @@
+//! Non-fatal warnings, such as unparseable colour globs, unreadable
+//! directories, or extended attributes that couldn’t be read.
+//!
+//! Rather than printing these wherever they happen, everything goes through
+//! this one sink, so the `--quiet` and `--strict-warnings` options can decide
+//! whether the user gets to see them and whether they should fail the run.
+//! Errors that stop eza, or stop it listing something, go through here too,
+//! and are always printed.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use log::debug;
+
+/// How much attention to pay to warnings.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum Verbosity {
+    /// Don’t print warnings at all, only log them.
+    Quiet,
+
+    /// Print warnings to standard error, then carry on.
+    #[default]
+    Normal,
+
+    /// Print warnings, and make eza exit with an error once it’s done.
+    Strict,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+static ESCALATED: AtomicBool = AtomicBool::new(false);
+
+/// Sets how warnings emitted from now on should be treated.
+pub fn configure(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        v if v == Verbosity::Quiet as u8 => Verbosity::Quiet,
+        v if v == Verbosity::Strict as u8 => Verbosity::Strict,
+        _ => Verbosity::Normal,
+    }
+}
+
+/// Reports a warning. This can be called from any thread.
+pub fn warning(message: impl fmt::Display) {
+    match verbosity() {
+        Verbosity::Quiet => debug!("Suppressed warning: {message}"),
+        Verbosity::Normal => eprintln!("{message}"),
+        Verbosity::Strict => {
+            ESCALATED.store(true, Ordering::Relaxed);
+            eprintln!("{message}");
+        }
+    }
+}
+
+/// Reports an error. Unlike warnings, these are printed whatever the
+/// verbosity, as they explain why eza is exiting or leaving something out.
+pub fn error(message: impl fmt::Display) {
+    eprintln!("{message}");
+}
+
+/// Whether a warning was reported while warnings were being escalated, in
+/// which case the program should exit with an error.
+pub fn escalated() -> bool {
+    ESCALATED.load(Ordering::Relaxed)
+}
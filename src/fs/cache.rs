@@ -0,0 +1,446 @@
+//! An on-disk cache of metadata that’s slow to gather, for `--cache`, so
+//! listing a huge directory on a network mount a second time doesn’t have
+//! to read everything over again.
+//!
+//! Extended attributes are cached here. Each entry is keyed by the file’s
+//! device and inode numbers, and is only used while the file’s modification
+//! and change times are the same as when it was cached, as setting an
+//! attribute changes the latter but not the former. Git statuses are cached
+//! separately, one file per repository, by `fs::feature::git`, as they
+//! change with the repository’s index rather than with the files themselves.
+//!
+//! The recursive sizes of directories aren’t cached: a directory’s times only
+//! change when files are added to or removed from it, not when a file further
+//! down grows, so there’d be no way to tell when a size had gone stale.
+//!
+//! The cache holds at most `MAX_ENTRIES` entries. When it grows past that,
+//! entries that weren’t used during the run are dropped first.
+//!
+//! The cache is a text file with a header, then one entry per line, each
+//! being fields separated by tabs (shown here as spaces):
+//!
+//! ```text
+//! # eza cache
+//! x  2049  1835043  1700000000  0  1700000000  5  user.origin  6c6f63616c  user.flag  -
+//! ```
+//!
+//! These are the kind of entry (`x`attrs), the device and inode numbers, the
+//! modification and change times in seconds and nanoseconds, then each
+//! attribute’s name and its value in hex (`-` for none). Names are escaped
+//! the same way as they are in manifests.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::{self, Metadata};
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use log::*;
+
+use crate::diagnostics;
+use crate::fs::feature::xattr::Attribute;
+use crate::fs::manifest::{escape, unescape};
+
+/// The first line of every cache file.
+static HEADER: &str = "# eza cache";
+
+/// The name of the file, in the cache directory, that the cache is kept in.
+pub static CACHE_FILE: &str = "metadata";
+
+/// The most entries the cache keeps, so it doesn’t grow forever as files
+/// come and go.
+const MAX_ENTRIES: usize = 100_000;
+
+/// Where the cache is, and whether to ignore what’s already in it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CacheOptions {
+    pub path: PathBuf,
+
+    /// The directory the Git statuses of each repository are cached in.
+    pub git_dir: PathBuf,
+
+    pub refresh: bool,
+}
+
+/// A file’s device and inode numbers.
+type Key = (u64, u64);
+
+/// A file’s modification and change times, in seconds and nanoseconds.
+type Stamp = (i64, i64, i64, i64);
+
+/// What’s cached about one file.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+struct Entry {
+    stamp: Stamp,
+    xattrs: Option<Vec<Attribute>>,
+
+    /// Whether the entry was looked up or stored during this run, which
+    /// isn’t saved.
+    used: bool,
+}
+
+/// The cached entries, and where to save them.
+#[derive(PartialEq, Eq, Debug)]
+struct MetadataCache {
+    path: PathBuf,
+    entries: HashMap<Key, Entry>,
+    changed: bool,
+}
+
+static CACHE: OnceLock<Mutex<MetadataCache>> = OnceLock::new();
+
+/// Loads the cache, if one is being used. A cache that can’t be read is
+/// warned about and started again from empty, as it only ever saves time.
+pub fn configure(options: Option<&CacheOptions>) {
+    let Some(options) = options else {
+        return;
+    };
+
+    let entries = if options.refresh {
+        HashMap::new()
+    } else {
+        match fs::read_to_string(&options.path) {
+            Ok(contents) => parse(&contents).unwrap_or_else(|line| {
+                diagnostics::warning(format_args!(
+                    "eza: {}:{line}: not an eza cache file, so starting it again",
+                    options.path.display()
+                ));
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                diagnostics::warning(format_args!(
+                    "eza: Couldn’t read the cache {}: {e}",
+                    options.path.display()
+                ));
+                HashMap::new()
+            }
+        }
+    };
+
+    debug!("Loaded {} cache entries", entries.len());
+    let _ = CACHE.set(Mutex::new(MetadataCache {
+        path: options.path.clone(),
+        entries,
+        changed: options.refresh,
+    }));
+}
+
+/// The cached extended attributes of a file.
+pub fn xattrs(metadata: &Metadata) -> Option<Vec<Attribute>> {
+    lookup(metadata, |entry| entry.xattrs.clone())
+}
+
+/// Caches the extended attributes of a file.
+pub fn store_xattrs(metadata: &Metadata, xattrs: &[Attribute]) {
+    store(metadata, |entry| entry.xattrs = Some(xattrs.to_vec()));
+}
+
+/// Saves the cache, if anything in it changed, writing it alongside first
+/// so that being cancelled part-way through can’t leave it half-written.
+pub fn save() -> io::Result<()> {
+    let Some(cache) = CACHE.get() else {
+        return Ok(());
+    };
+
+    let mut cache = cache.lock().unwrap();
+    if !cache.changed {
+        return Ok(());
+    }
+
+    evict(&mut cache.entries, MAX_ENTRIES);
+
+    if let Some(parent) = cache.path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temporary = cache.path.clone().into_os_string();
+    temporary.push(".tmp");
+    fs::write(&temporary, write(&cache.entries))?;
+    fs::rename(&temporary, &cache.path)
+}
+
+fn lookup<T>(metadata: &Metadata, get: impl FnOnce(&Entry) -> Option<T>) -> Option<T> {
+    let (key, stamp) = key(metadata)?;
+    let mut cache = CACHE.get()?.lock().unwrap();
+    let entry = cache
+        .entries
+        .get_mut(&key)
+        .filter(|entry| entry.stamp == stamp)?;
+    entry.used = true;
+    get(entry)
+}
+
+fn store(metadata: &Metadata, set: impl FnOnce(&mut Entry)) {
+    let (Some((key, stamp)), Some(cache)) = (key(metadata), CACHE.get()) else {
+        return;
+    };
+
+    let mut cache = cache.lock().unwrap();
+    let entry = cache.entries.entry(key).or_default();
+
+    // A file that’s changed since it was cached has nothing cached any more.
+    if entry.stamp != stamp {
+        *entry = Entry {
+            stamp,
+            ..Entry::default()
+        };
+    }
+
+    set(entry);
+    entry.used = true;
+    cache.changed = true;
+}
+
+/// Drops entries until there are at most `limit` of them, starting with the
+/// ones that weren’t used during this run.
+fn evict(entries: &mut HashMap<Key, Entry>, limit: usize) {
+    if entries.len() <= limit {
+        return;
+    }
+
+    let excess = entries.len() - limit;
+
+    let mut keys = entries
+        .iter()
+        .map(|(key, entry)| (entry.used, *key))
+        .collect::<Vec<_>>();
+    keys.sort_unstable();
+
+    for (_, key) in keys.into_iter().take(excess) {
+        entries.remove(&key);
+    }
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)] // Needs to match the Windows function
+fn key(metadata: &Metadata) -> Option<(Key, Stamp)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((
+        (metadata.dev(), metadata.ino()),
+        (
+            metadata.mtime(),
+            metadata.mtime_nsec(),
+            metadata.ctime(),
+            metadata.ctime_nsec(),
+        ),
+    ))
+}
+
+/// Windows has no stable way to get a file’s identity, so nothing is
+/// cached there.
+#[cfg(windows)]
+fn key(_metadata: &Metadata) -> Option<(Key, Stamp)> {
+    None
+}
+
+/// Parses the contents of a cache file into its entries, returning the
+/// number of the line that’s wrong if it isn’t one.
+fn parse(contents: &str) -> Result<HashMap<Key, Entry>, usize> {
+    let mut lines = contents.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(1);
+    }
+
+    let mut entries: HashMap<Key, Entry> = HashMap::new();
+
+    for (index, line) in lines.enumerate() {
+        let number = index + 2;
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields = line.split('\t').collect::<Vec<_>>();
+        if fields.len() < 7 {
+            return Err(number);
+        }
+
+        let int = |field: &str| field.parse().map_err(|_| number);
+        let time = |field: &str| field.parse().map_err(|_| number);
+        let key = (int(fields[1])?, int(fields[2])?);
+        let stamp = (
+            time(fields[3])?,
+            time(fields[4])?,
+            time(fields[5])?,
+            time(fields[6])?,
+        );
+
+        let entry = entries.entry(key).or_insert_with(|| Entry {
+            stamp,
+            ..Entry::default()
+        });
+        if entry.stamp != stamp {
+            return Err(number);
+        }
+
+        match (fields[0], &fields[7..]) {
+            ("x", attributes) if attributes.len() % 2 == 0 => {
+                let xattrs = attributes
+                    .chunks(2)
+                    .map(|pair| {
+                        let value = match pair[1] {
+                            "-" => None,
+                            hex => Some(from_hex(hex).ok_or(number)?),
+                        };
+                        Ok(Attribute {
+                            name: unescape(pair[0]),
+                            value,
+                        })
+                    })
+                    .collect::<Result<_, usize>>()?;
+                entry.xattrs = Some(xattrs);
+            }
+            _ => return Err(number),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Writes entries out in the format `parse` reads.
+fn write(entries: &HashMap<Key, Entry>) -> String {
+    let mut contents = format!("{HEADER}\n");
+
+    for ((dev, ino), entry) in entries {
+        let (m_seconds, m_nanos, c_seconds, c_nanos) = entry.stamp;
+
+        if let Some(xattrs) = &entry.xattrs {
+            let _ = write!(
+                contents,
+                "x\t{dev}\t{ino}\t{m_seconds}\t{m_nanos}\t{c_seconds}\t{c_nanos}"
+            );
+            for attribute in xattrs {
+                let value = attribute
+                    .value
+                    .as_deref()
+                    .map_or_else(|| String::from("-"), to_hex);
+                let _ = write!(contents, "\t{}\t{value}", escape(&attribute.name));
+            }
+            contents.push('\n');
+        }
+    }
+
+    contents
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attribute(name: &str, value: Option<&[u8]>) -> Attribute {
+        Attribute {
+            name: name.into(),
+            value: value.map(<[u8]>::to_vec),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut entries = HashMap::new();
+        entries.insert(
+            (1, 2),
+            Entry {
+                stamp: (1_700_000_000, 5, 1_700_000_001, 6),
+                xattrs: Some(vec![
+                    attribute("user.tab\there", Some(b"\0value")),
+                    attribute("user.none", None),
+                    attribute("user.empty", Some(b"")),
+                ]),
+                used: false,
+            },
+        );
+        entries.insert(
+            (1, 3),
+            Entry {
+                stamp: (-1, 0, -1, 0),
+                xattrs: Some(Vec::new()),
+                used: false,
+            },
+        );
+
+        assert_eq!(parse(&write(&entries)), Ok(entries));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(parse("# eza cache\n"), Ok(HashMap::new()));
+    }
+
+    #[test]
+    fn no_header() {
+        assert_eq!(parse("x\t1\t2\t3\t4\t5\t6\n"), Err(1));
+    }
+
+    #[test]
+    fn bad_kind() {
+        assert_eq!(parse("# eza cache\nq\t1\t2\t3\t4\t5\t6\n"), Err(2));
+    }
+
+    #[test]
+    fn sizes_not_cached() {
+        assert_eq!(parse("# eza cache\ns\t1\t2\t3\t4\t5\t6\t7\t8\n"), Err(2));
+    }
+
+    #[test]
+    fn odd_attributes() {
+        assert_eq!(parse("# eza cache\nx\t1\t2\t3\t4\t5\t6\tuser.a\n"), Err(2));
+    }
+
+    #[test]
+    fn bad_hex() {
+        assert_eq!(
+            parse("# eza cache\nx\t1\t2\t3\t4\t5\t6\tuser.a\tzz\n"),
+            Err(2)
+        );
+    }
+
+    #[test]
+    fn evicts_unused_first() {
+        let entry = |used| Entry {
+            used,
+            ..Entry::default()
+        };
+        let mut entries = HashMap::new();
+        entries.insert((1, 1), entry(false));
+        entries.insert((1, 2), entry(true));
+        entries.insert((1, 3), entry(false));
+        entries.insert((1, 4), entry(true));
+
+        evict(&mut entries, 3);
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains_key(&(1, 2)) && entries.contains_key(&(1, 4)));
+
+        evict(&mut entries, 1);
+        assert_eq!(entries.len(), 1);
+        assert!(entries.values().all(|entry| entry.used));
+    }
+
+    #[test]
+    fn evicts_nothing_under_limit() {
+        let mut entries = HashMap::new();
+        entries.insert((1, 1), Entry::default());
+
+        evict(&mut entries, 1);
+        assert_eq!(entries.len(), 1);
+    }
+}
@@ -0,0 +1,261 @@
+//! Comparing the files being listed against their counterparts in another
+//! directory, for `--diff-against`, or in a manifest, for `--manifest-check`.
+//!
+//! Each directory argument stands in for the other directory itself, so its
+//! contents (and, in a tree, their contents) are compared with the entries
+//! at the same relative paths over there. A file argument is compared with
+//! the file of the same name in the other directory. Manifests work the
+//! same way, with their paths being relative to the directory they were
+//! written for.
+
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::fs::fields::DiffStatus;
+use crate::fs::manifest::Manifest;
+
+/// How to decide whether two files are the same.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum CompareMethod {
+    /// Files with the same size and modification time, to the second, are
+    /// assumed to be the same, which is what rsync does by default.
+    #[default]
+    Metadata,
+
+    /// Files are only the same if their contents are.
+    Contents,
+}
+
+/// What to compare the listed files with.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Baseline {
+    /// The files in another directory.
+    Directory(PathBuf),
+
+    /// The entries in a manifest file.
+    Manifest(PathBuf),
+}
+
+/// What to compare against, and how to compare with it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DiffAgainst {
+    pub baseline: Baseline,
+    pub method: CompareMethod,
+}
+
+/// The comparison of every listed file with its counterpart.
+#[derive(Debug)]
+pub struct Comparison {
+    method: CompareMethod,
+
+    /// Each argument path, paired with the path it corresponds to in the
+    /// other directory, or the key it has in the manifest.
+    roots: Vec<(PathBuf, PathBuf)>,
+
+    /// The manifest being checked against, if it’s not a directory.
+    manifest: Option<Manifest>,
+}
+
+impl Comparison {
+    /// Pairs up each of the paths being listed with where they are in the
+    /// other directory or manifest, reading the manifest if there is one.
+    pub fn new(diff: &DiffAgainst, args: &[PathBuf]) -> io::Result<Self> {
+        let (other, manifest) = match &diff.baseline {
+            Baseline::Directory(other) => (other.clone(), None),
+            Baseline::Manifest(path) => (PathBuf::new(), Some(Manifest::read(path)?)),
+        };
+
+        let roots = args
+            .iter()
+            .map(|arg| {
+                let counterpart = match arg.file_name() {
+                    Some(name) if !arg.is_dir() => other.join(name),
+                    _ => other.clone(),
+                };
+                (arg.clone(), counterpart)
+            })
+            .collect();
+
+        Ok(Self {
+            method: diff.method,
+            roots,
+            manifest,
+        })
+    }
+
+    /// The path in the other directory that corresponds to the given one,
+    /// found through the deepest argument it’s under.
+    fn counterpart(&self, path: &Path) -> Option<PathBuf> {
+        self.roots
+            .iter()
+            .filter_map(|(root, counterpart)| {
+                // Joining an empty path would add a trailing slash, which
+                // only directories can have.
+                let rest = path.strip_prefix(root).ok()?;
+                let counterpart = if rest.as_os_str().is_empty() {
+                    counterpart.clone()
+                } else {
+                    counterpart.join(rest)
+                };
+                Some((root.components().count(), counterpart))
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, counterpart)| counterpart)
+    }
+
+    /// How the file at the given path compares with its counterpart.
+    pub fn status(&self, path: &Path) -> DiffStatus {
+        let Some(other) = self.counterpart(path) else {
+            return DiffStatus::Unknown;
+        };
+
+        if let Some(manifest) = &self.manifest {
+            return manifest.status(&other, path, self.method);
+        }
+
+        let other_metadata = match fs::symlink_metadata(&other) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return DiffStatus::OnlyHere,
+            Err(_) => return DiffStatus::Unknown,
+        };
+
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            return DiffStatus::Unknown;
+        };
+
+        let (this_type, other_type) = (metadata.file_type(), other_metadata.file_type());
+        let same = if this_type.is_dir() && other_type.is_dir() {
+            true
+        } else if this_type.is_symlink() && other_type.is_symlink() {
+            match (fs::read_link(path), fs::read_link(&other)) {
+                (Ok(this_target), Ok(other_target)) => this_target == other_target,
+                _ => return DiffStatus::Unknown,
+            }
+        } else if this_type.is_file() && other_type.is_file() {
+            if metadata.len() != other_metadata.len() {
+                false
+            } else if self.method == CompareMethod::Contents {
+                match same_contents(path, &other) {
+                    Ok(same) => same,
+                    Err(_) => return DiffStatus::Unknown,
+                }
+            } else {
+                modified_secs(&metadata) == modified_secs(&other_metadata)
+            }
+        } else {
+            false
+        };
+
+        if same {
+            DiffStatus::Same
+        } else {
+            DiffStatus::Different
+        }
+    }
+
+    /// The paths in the manifest that aren’t under any of the listed paths
+    /// any more, which don’t have a file to be shown next to.
+    pub fn removed(&self) -> Vec<&Path> {
+        let Some(manifest) = &self.manifest else {
+            return Vec::new();
+        };
+
+        manifest
+            .keys()
+            .filter(|key| {
+                !self.roots.iter().any(|(root, counterpart)| {
+                    key.strip_prefix(counterpart)
+                        .is_ok_and(|rest| fs::symlink_metadata(root.join(rest)).is_ok())
+                })
+            })
+            .collect()
+    }
+}
+
+/// A file’s modification time in whole seconds, as copies don’t always
+/// keep anything finer.
+fn modified_secs(metadata: &fs::Metadata) -> Option<u64> {
+    let modified = metadata.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Whether two files, already known to be the same length, have the same
+/// contents.
+fn same_contents(this: &Path, other: &Path) -> io::Result<bool> {
+    let mut this = BufReader::new(fs::File::open(this)?);
+    let mut other = BufReader::new(fs::File::open(other)?);
+    let mut this_buf = [0; 8192];
+    let mut other_buf = [0; 8192];
+
+    loop {
+        let read = this.read(&mut this_buf)?;
+        if read == 0 {
+            return Ok(true);
+        }
+
+        other.read_exact(&mut other_buf[..read])?;
+        if this_buf[..read] != other_buf[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn comparison(roots: &[(&str, &str)]) -> Comparison {
+        Comparison {
+            method: CompareMethod::Metadata,
+            roots: roots
+                .iter()
+                .map(|(root, counterpart)| (root.into(), counterpart.into()))
+                .collect(),
+            manifest: None,
+        }
+    }
+
+    #[test]
+    fn directory_argument() {
+        let comparison = comparison(&[("src", "../backup")]);
+        assert_eq!(
+            comparison.counterpart(Path::new("src/fs/dir.rs")),
+            Some(PathBuf::from("../backup/fs/dir.rs"))
+        );
+    }
+
+    #[test]
+    fn current_directory() {
+        let comparison = comparison(&[(".", "/backup")]);
+        assert_eq!(
+            comparison.counterpart(Path::new("./README.md")),
+            Some(PathBuf::from("/backup/README.md"))
+        );
+    }
+
+    #[test]
+    fn deepest_argument_wins() {
+        let comparison = comparison(&[("src", "/backup"), ("src/fs", "/backup")]);
+        assert_eq!(
+            comparison.counterpart(Path::new("src/fs/dir.rs")),
+            Some(PathBuf::from("/backup/dir.rs"))
+        );
+    }
+
+    #[test]
+    fn file_argument() {
+        let comparison = comparison(&[("src/main.rs", "/backup/main.rs")]);
+        assert_eq!(
+            comparison.counterpart(Path::new("src/main.rs")),
+            Some(PathBuf::from("/backup/main.rs"))
+        );
+    }
+
+    #[test]
+    fn outside_every_argument() {
+        let comparison = comparison(&[("src", "/backup")]);
+        assert_eq!(comparison.counterpart(Path::new("tests/cli.rs")), None);
+    }
+}
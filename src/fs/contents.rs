@@ -0,0 +1,215 @@
+//! Reading what’s inside files, for the columns that need to know more
+//! than their metadata can say. Files are only read up to a limit, so
+//! listing a directory with a few huge files in it doesn’t mean reading
+//! every byte of them.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::fs::fields as f;
+
+/// The biggest file that gets read, in bytes.
+pub const READ_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// How much of the start of a file gets read to sniff out what’s in it.
+pub const SNIFF_LIMIT: u64 = 64 * 1024;
+
+/// Reads up to `limit` bytes from the start of a file.
+pub fn read_head(path: &Path, limit: u64) -> io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    fs::File::open(path)?
+        .take(limit)
+        .read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+/// Reads the whole of a file, as long as it’s no bigger than `limit`,
+/// returning `None` if it is.
+pub fn read_bounded(path: &Path, limit: u64) -> io::Result<Option<Vec<u8>>> {
+    let contents = read_head(path, limit + 1)?;
+
+    if contents.len() as u64 > limit {
+        Ok(None)
+    } else {
+        Ok(Some(contents))
+    }
+}
+
+/// The size that text would be if its line endings were all LF, which is
+/// one byte smaller for each CRLF. Anything with a null byte in it is taken
+/// to be binary, and gets `None`.
+pub fn normalized_text_size(contents: &[u8]) -> Option<u64> {
+    if contents.contains(&0) {
+        return None;
+    }
+
+    let crlfs = contents.windows(2).filter(|pair| pair == b"\r\n").count();
+    Some((contents.len() - crlfs) as u64)
+}
+
+/// Works out how text is encoded from the start of it: whether it begins
+/// with a byte order mark, and which line ending it uses most. Anything
+/// else with a null byte in it is taken to be binary.
+pub fn sniff_encoding(contents: &[u8]) -> f::TextEncoding {
+    if contents.starts_with(b"\xFF\xFE") || contents.starts_with(b"\xFE\xFF") {
+        return f::TextEncoding::Utf16;
+    }
+
+    let (bom, text) = match contents.strip_prefix(b"\xEF\xBB\xBF") {
+        Some(text) => (true, text),
+        None => (false, contents),
+    };
+    if text.contains(&0) {
+        return f::TextEncoding::Binary;
+    }
+
+    let mut lfs = 0;
+    let mut crlfs = 0;
+    let mut crs = 0;
+    let mut bytes = text.iter().peekable();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'\n' => lfs += 1,
+            b'\r' if bytes.peek() == Some(&&b'\n') => {
+                bytes.next();
+                crlfs += 1;
+            }
+            b'\r' => crs += 1,
+            _ => {}
+        }
+    }
+
+    // Ties go to LF, as it’s what most tools expect.
+    let line_endings = if lfs + crlfs + crs == 0 {
+        f::LineEndings::None
+    } else if crlfs > lfs && crlfs >= crs {
+        f::LineEndings::Crlf
+    } else if crs > lfs && crs > crlfs {
+        f::LineEndings::Cr
+    } else {
+        f::LineEndings::Lf
+    };
+
+    f::TextEncoding::Text { bom, line_endings }
+}
+
+/// The first line of some text, cut down to `length` characters with an
+/// ellipsis if it’s any longer, and with tabs and other control characters
+/// turned into spaces so they can’t upset the table. Anything that isn’t
+/// text, going by `sniff_encoding`, gets `None`.
+pub fn preview(contents: &[u8], length: usize) -> Option<String> {
+    if !matches!(sniff_encoding(contents), f::TextEncoding::Text { .. }) {
+        return None;
+    }
+
+    let text = contents.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(contents);
+    let line = text
+        .split(|byte| *byte == b'\n' || *byte == b'\r')
+        .next()
+        .unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end();
+
+    let mut preview = line
+        .chars()
+        .take(length)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>();
+    if line.chars().count() > length {
+        preview.push('…');
+    }
+
+    Some(preview)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(bom: bool, line_endings: f::LineEndings) -> f::TextEncoding {
+        f::TextEncoding::Text { bom, line_endings }
+    }
+
+    #[test]
+    fn sniff_lf() {
+        assert_eq!(sniff_encoding(b"a\nb\n"), text(false, f::LineEndings::Lf));
+    }
+
+    #[test]
+    fn sniff_mostly_crlf() {
+        assert_eq!(
+            sniff_encoding(b"a\r\nb\r\nc\n"),
+            text(false, f::LineEndings::Crlf)
+        );
+    }
+
+    #[test]
+    fn sniff_utf8_bom() {
+        assert_eq!(
+            sniff_encoding(b"\xEF\xBB\xBFa"),
+            text(true, f::LineEndings::None)
+        );
+    }
+
+    #[test]
+    fn sniff_utf16() {
+        assert_eq!(sniff_encoding(b"\xFF\xFEa\0"), f::TextEncoding::Utf16);
+    }
+
+    #[test]
+    fn sniff_binary() {
+        assert_eq!(sniff_encoding(b"\x7fELF\0"), f::TextEncoding::Binary);
+    }
+
+    #[test]
+    fn lf_only() {
+        assert_eq!(normalized_text_size(b"a\nb\n"), Some(4));
+    }
+
+    #[test]
+    fn crlf() {
+        assert_eq!(normalized_text_size(b"a\r\nb\r\n"), Some(4));
+    }
+
+    #[test]
+    fn lone_cr() {
+        assert_eq!(normalized_text_size(b"a\rb\r"), Some(4));
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(normalized_text_size(b"\x7fELF\0\r\n"), None);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(normalized_text_size(b""), Some(0));
+    }
+
+    #[test]
+    fn preview_first_line() {
+        assert_eq!(
+            preview(b"[core]\n\tbare = false\n", 40),
+            Some("[core]".into())
+        );
+    }
+
+    #[test]
+    fn preview_cut_short() {
+        assert_eq!(
+            preview(b"\xEF\xBB\xBFhello world", 5),
+            Some("hello…".into())
+        );
+    }
+
+    #[test]
+    fn preview_tabs() {
+        assert_eq!(preview(b"a\tb\r\n", 40), Some("a b".into()));
+    }
+
+    #[test]
+    fn preview_binary() {
+        assert_eq!(preview(b"\x7fELF\0", 40), None);
+    }
+}
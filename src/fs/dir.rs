@@ -1,12 +1,18 @@
-use crate::fs::feature::git::GitCache;
+use crate::fs::feature::git::{self, GitCache};
 use crate::fs::fields::GitStatus;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::slice::Iter as SliceIter;
+use std::sync::OnceLock;
 
 use log::*;
+use unicode_normalization::UnicodeNormalization;
 
+use crate::fs::dir_action::Ancestors;
 use crate::fs::File;
 
 /// A **Dir** provides a cached list of the file paths in a directory that’s
@@ -21,6 +27,20 @@ pub struct Dir {
 
     /// The path that was read.
     pub path: PathBuf,
+
+    /// Whether the directory is on a virtual filesystem such as procfs or
+    /// sysfs, where files report a size of zero whatever their contents.
+    /// This is only found out the first time it’s needed.
+    is_virtual: OnceLock<bool>,
+
+    /// The paths of files whose names are the same as another file’s in
+    /// this directory, once differences in Unicode normalisation and case
+    /// are ignored.
+    name_clashes: HashSet<PathBuf>,
+
+    /// The directories this one is inside in a recursive listing, and this
+    /// one itself, when it was read as part of one.
+    pub ancestors: Ancestors,
 }
 
 impl Dir {
@@ -35,12 +55,27 @@ impl Dir {
     pub fn read_dir(path: PathBuf) -> io::Result<Self> {
         info!("Reading directory {:?}", &path);
 
-        let contents = fs::read_dir(&path)?
+        let contents: Vec<PathBuf> = fs::read_dir(&path)?
             .map(|result| result.map(|entry| entry.path()))
             .collect::<Result<_, _>>()?;
 
         info!("Read directory success {:?}", &path);
-        Ok(Self { contents, path })
+        let name_clashes = find_name_clashes(&contents);
+        Ok(Self {
+            contents,
+            path,
+            is_virtual: OnceLock::new(),
+            name_clashes,
+            ancestors: Ancestors::default(),
+        })
+    }
+
+    /// Whether the directory is on a virtual filesystem such as procfs or
+    /// sysfs, where files report a size of zero whatever their contents.
+    pub fn is_virtual(&self) -> bool {
+        *self
+            .is_virtual
+            .get_or_init(|| is_virtual_filesystem(&self.path))
     }
 
     /// Produce an iterator of IO results of trying to read all the files in
@@ -65,17 +100,97 @@ impl Dir {
         }
     }
 
+    /// Whether this directory has no entries at all, dotfiles included.
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+
     /// Whether this directory contains a file with the given path.
     pub fn contains(&self, path: &Path) -> bool {
         self.contents.iter().any(|p| p.as_path() == path)
     }
 
+    /// Whether the file with the given path has a name that clashes with
+    /// another file’s in this directory. Such files can’t both survive being
+    /// copied to a filesystem that normalises or ignores the case of names,
+    /// such as the default ones on macOS and Windows.
+    pub fn has_name_clash(&self, path: &Path) -> bool {
+        self.name_clashes.contains(path)
+    }
+
     /// Append a path onto the path specified by this directory.
     pub fn join(&self, child: &Path) -> PathBuf {
         self.path.join(child)
     }
 }
 
+/// Finds the paths whose file names are equal to another’s once they’ve
+/// both been put into the same Unicode normal form and case. Names that
+/// aren’t valid UTF-8 are left out, as they can’t be normalised.
+fn find_name_clashes(contents: &[PathBuf]) -> HashSet<PathBuf> {
+    let mut seen: HashMap<String, &PathBuf> = HashMap::new();
+    let mut clashes = HashSet::new();
+
+    for path in contents {
+        let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        match seen.entry(clash_key(name)) {
+            Entry::Occupied(first) => {
+                clashes.insert((*first.get()).clone());
+                clashes.insert(path.clone());
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(path);
+            }
+        }
+    }
+
+    clashes
+}
+
+/// The form of a file name used to compare it with others for clashes:
+/// decomposed, so that precomposed characters match their combining
+/// equivalents, and lowercased.
+fn clash_key(name: &str) -> String {
+    name.nfd().flat_map(char::to_lowercase).collect()
+}
+
+/// Whether the given path is on a filesystem whose files are generated by
+/// the kernel on demand, found from the filesystem’s magic number.
+#[cfg(target_os = "linux")]
+pub fn is_virtual_filesystem(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+
+    // SAFETY: `statfs` is plain old data, and the call only writes to it.
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return false;
+    }
+
+    [
+        libc::PROC_SUPER_MAGIC,
+        libc::SYSFS_MAGIC,
+        libc::DEBUGFS_MAGIC,
+        libc::TRACEFS_MAGIC,
+        libc::SECURITYFS_MAGIC,
+        libc::CGROUP_SUPER_MAGIC,
+        libc::CGROUP2_SUPER_MAGIC,
+    ]
+    .contains(&stats.f_type)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_virtual_filesystem(_path: &Path) -> bool {
+    false
+}
+
 /// Iterator over reading the contents of a directory as `File` objects.
 #[allow(clippy::struct_excessive_bools)]
 pub struct Files<'dir, 'ig> {
@@ -132,7 +247,7 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
 
                 if self.git_ignoring {
                     let git_status = self.git.map(|g| g.get(path, false)).unwrap_or_default();
-                    if git_status.unstaged == GitStatus::Ignored {
+                    if git_status.unstaged == GitStatus::Ignored || git::is_ignored(path) {
                         continue;
                     }
                 }
@@ -218,7 +333,7 @@ pub enum DotFilter {
 
 impl DotFilter {
     /// Whether this filter should show dotfiles in a listing.
-    fn shows_dotfiles(self) -> bool {
+    pub fn shows_dotfiles(self) -> bool {
         match self {
             Self::JustFiles => false,
             Self::Dotfiles => true,
@@ -235,3 +350,44 @@ impl DotFilter {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn clashes(names: &[&str]) -> Vec<String> {
+        let contents: Vec<PathBuf> = names.iter().map(|n| Path::new("dir").join(n)).collect();
+        let mut found: Vec<String> = find_name_clashes(&contents)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn no_clashes() {
+        assert!(clashes(&["a", "b", "c"]).is_empty());
+    }
+
+    #[test]
+    fn case() {
+        assert_eq!(
+            clashes(&["README", "readme", "other"]),
+            vec!["README", "readme"]
+        );
+    }
+
+    #[test]
+    fn normalisation() {
+        assert_eq!(
+            clashes(&["caf\u{e9}", "cafe\u{301}"]),
+            vec!["cafe\u{301}", "caf\u{e9}"]
+        );
+    }
+
+    #[test]
+    fn normalisation_and_case() {
+        assert_eq!(clashes(&["CAF\u{c9}", "cafe\u{301}", "cafe"]).len(), 2);
+    }
+}
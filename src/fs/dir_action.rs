@@ -1,5 +1,10 @@
 //! What to do when encountering a directory?
 
+use std::fs::Metadata;
+use std::io;
+
+use crate::fs::File;
+
 /// The action to take when trying to list a file that turns out to be a
 /// directory.
 ///
@@ -64,6 +69,10 @@ pub struct RecurseOptions {
     /// The maximum number of times that recursion should descend to, if one
     /// is specified.
     pub max_depth: Option<usize>,
+
+    /// Whether to descend into symlinks that point to directories, as well
+    /// as into directories themselves.
+    pub follow_symlinks: bool,
 }
 
 impl RecurseOptions {
@@ -74,4 +83,55 @@ impl RecurseOptions {
             Some(d) => d <= depth,
         }
     }
+
+    /// Whether the given file is one to descend into: a directory, or a
+    /// symlink to one if symlinks are being followed.
+    pub fn descends_into(self, file: &File<'_>) -> bool {
+        file.is_directory()
+            || (self.follow_symlinks && file.is_link() && file.points_to_directory())
+    }
+}
+
+/// The device and inode numbers of the directories a recursive listing is
+/// inside, from the top down.
+///
+/// Descending into a directory only ever goes further down, unless a
+/// symlink gets followed back up to a directory that’s already being listed,
+/// which would go round in circles forever. Keeping track of where each
+/// directory is in the tree means that can be noticed and reported instead.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct Ancestors(Vec<(u64, u64)>);
+
+impl Ancestors {
+    /// The ancestors of the directory with the given metadata, which is
+    /// inside the ones these are the ancestors of, or an error if it’s one
+    /// of them already.
+    pub fn enter(&self, metadata: &Metadata) -> io::Result<Self> {
+        let Some(id) = id(metadata) else {
+            return Ok(self.clone());
+        };
+
+        if self.0.contains(&id) {
+            return Err(io::Error::new(io::ErrorKind::Other, "symlink loop"));
+        }
+
+        let mut ancestors = self.clone();
+        ancestors.0.push(id);
+        Ok(ancestors)
+    }
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)] // Needs to match the Windows function
+fn id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Windows has no stable way to get a directory’s identity, so loops can’t
+/// be noticed there.
+#[cfg(windows)]
+fn id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
 }
@@ -0,0 +1,132 @@
+//! Reading the default POSIX ACLs of directories, which Linux keeps in the
+//! `system.posix_acl_default` extended attribute, for working out what
+//! permissions new files get with `--simulate-umask`.
+//!
+//! The attribute is a little-endian version number, which is always 2,
+//! followed by eight bytes for each entry: a two-byte tag saying who the
+//! entry is for, two bytes of `rwx` permission bits, and a four-byte user or
+//! group ID, which is only used by the entries for named users and groups.
+//!
+//! A directory with a default ACL hands it down to everything created in it,
+//! and the umask isn’t used at all. Instead, the permissions that the
+//! program creating a file asks for are limited by the ACL’s entries for
+//! the owner, for everyone else, and for the group class, which is the mask
+//! entry if there is one, and the owning group’s entry if there isn’t.
+
+/// The name of the extended attribute that default ACLs are kept in.
+pub static DEFAULT_ACL_ATTRIBUTE: &str = "system.posix_acl_default";
+
+const VERSION: u32 = 2;
+
+const USER_OBJ: u16 = 0x01;
+const GROUP_OBJ: u16 = 0x04;
+const MASK: u16 = 0x10;
+const OTHER: u16 = 0x20;
+
+/// The entries of a default ACL that decide the permission bits of new
+/// files. Entries for named users and groups only affect those users and
+/// groups, so they’re skipped.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct DefaultAcl {
+    owner: u32,
+    group: u32,
+    mask: Option<u32>,
+    other: u32,
+}
+
+impl DefaultAcl {
+    /// Parses the value of a default ACL attribute, returning `None` if it
+    /// isn’t one, or if it’s missing any of the entries every ACL has.
+    pub fn parse(value: &[u8]) -> Option<Self> {
+        if value.len() < 4 {
+            return None;
+        }
+
+        let (version, entries) = value.split_at(4);
+        if u32::from_le_bytes(version.try_into().ok()?) != VERSION || entries.len() % 8 != 0 {
+            return None;
+        }
+
+        let (mut owner, mut group, mut mask, mut other) = (None, None, None, None);
+        for entry in entries.chunks(8) {
+            let tag = u16::from_le_bytes([entry[0], entry[1]]);
+            let perm = u32::from(u16::from_le_bytes([entry[2], entry[3]]) & 0o7);
+            match tag {
+                USER_OBJ => owner = Some(perm),
+                GROUP_OBJ => group = Some(perm),
+                MASK => mask = Some(perm),
+                OTHER => other = Some(perm),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            owner: owner?,
+            group: group?,
+            mask,
+            other: other?,
+        })
+    }
+
+    /// The permission bits that a new file gets when it’s created asking
+    /// for the given mode.
+    pub fn apply(self, requested: u32) -> u32 {
+        let group = self.mask.unwrap_or(self.group);
+        requested & ((self.owner << 6) | (group << 3) | self.other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn acl(entries: &[(u16, u16)]) -> Vec<u8> {
+        let mut value = VERSION.to_le_bytes().to_vec();
+        for (tag, perm) in entries {
+            value.extend(tag.to_le_bytes());
+            value.extend(perm.to_le_bytes());
+            value.extend(u32::MAX.to_le_bytes());
+        }
+        value
+    }
+
+    #[test]
+    fn owner_group_other() {
+        let value = acl(&[(USER_OBJ, 7), (GROUP_OBJ, 7), (OTHER, 5)]);
+        let acl = DefaultAcl::parse(&value).unwrap();
+        assert_eq!(acl.apply(0o666), 0o664);
+        assert_eq!(acl.apply(0o777), 0o775);
+    }
+
+    #[test]
+    fn mask_limits_group() {
+        let value = acl(&[
+            (USER_OBJ, 7),
+            (GROUP_OBJ, 7),
+            (0x08, 7),
+            (MASK, 5),
+            (OTHER, 0),
+        ]);
+        let acl = DefaultAcl::parse(&value).unwrap();
+        assert_eq!(acl.apply(0o777), 0o750);
+    }
+
+    #[test]
+    fn missing_entries() {
+        assert_eq!(DefaultAcl::parse(&acl(&[(USER_OBJ, 7), (OTHER, 5)])), None);
+    }
+
+    #[test]
+    fn wrong_version() {
+        let mut value = acl(&[(USER_OBJ, 7), (GROUP_OBJ, 7), (OTHER, 5)]);
+        value[0] = 1;
+        assert_eq!(DefaultAcl::parse(&value), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let value = acl(&[(USER_OBJ, 7), (GROUP_OBJ, 7), (OTHER, 5)]);
+        assert_eq!(DefaultAcl::parse(&value[..value.len() - 1]), None);
+        assert_eq!(DefaultAcl::parse(&[2, 0]), None);
+    }
+}
@@ -1,15 +1,364 @@
 //! Getting the Git status of files and directories.
+//!
+//! With `--cache`, each repository’s statuses are also kept on disk between
+//! runs, so listing a huge repository a second time doesn’t have to scan the
+//! whole of it again. They’re only used while the repository’s `HEAD` commit
+//! and the modification time of its index are the same as when they were
+//! cached, and while the working tree looks the same as well: like Git’s own
+//! index, the cache holds the size, modification time, and change time of
+//! every file that’s tracked or has a status, which are checked to spot
+//! files being edited, and the modification time of every directory above
+//! them, which changes when files are created or deleted in it. Statuses
+//! aren’t cached if anything changed while they were being worked out.
+//!
+//! Each repository’s cache is a text file with a header, the repository’s
+//! working directory, `HEAD` commit, and index modification time in seconds
+//! and nanoseconds, then one line per status, tracked path, or checked path,
+//! each being fields separated by tabs (shown here as spaces):
+//!
+//! ```text
+//! # eza git cache
+//! /src/eza  4b825dc642cb6eb9a060e54bf8d69288fbee4904  1700000000  123456789
+//! s  256  /src/eza/Cargo.toml
+//! t  /src/eza/Cargo.toml
+//! w  1024  1700000000  5  1700000000  5  /src/eza/Cargo.toml
+//! ```
+//!
+//! Status lines hold the `git2` status bits, checked lines the size, then
+//! the modification and change times in seconds and nanoseconds, and the
+//! paths are escaped the same way as they are in manifests.
+//!
+//! For `--color-by=git-age`, this also works out when each file in a
+//! repository was last committed, by walking back through its history from
+//! `HEAD` until every file in `HEAD` has turned up in a commit. Merges count
+//! as changing whatever they bring in from their other parents.
+//!
+//! For `--git-ignore`, files are also hidden by the `.gitignore` files
+//! around them, and by the global excludes file, `$XDG_CONFIG_HOME/git/ignore`
+//! (or `~/.config/git/ignore`), even when they’re not in a repository at all,
+//! so ignored files stay hidden while recursing through directories that
+//! aren’t checked out from one. Each directory’s rules are layered on top of
+//! the rules of the directories above it, up to the top of the repository,
+//! or of the filesystem outside of one, with the deepest matching rule
+//! winning, the same way as Git decides.
 
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
 #[cfg(target_family = "unix")]
 use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
 
 use log::*;
 
+use crate::diagnostics;
+use crate::fs::cache::CacheOptions;
 use crate::fs::fields as f;
+use crate::fs::manifest::{escape, unescape};
+
+/// The first line of every repository’s cache file.
+static CACHE_HEADER: &str = "# eza git cache";
+
+/// The directory each repository’s statuses are cached in, and whether to
+/// ignore what’s already there.
+static STATUS_CACHE: OnceLock<(PathBuf, bool)> = OnceLock::new();
+
+/// Starts caching Git statuses on disk, if a cache is being used.
+pub fn configure_cache(options: Option<&CacheOptions>) {
+    if let Some(options) = options {
+        let _ = STATUS_CACHE.set((options.git_dir.clone(), options.refresh));
+    }
+}
+
+/// When the files in each repository seen so far were last committed, along
+/// with the directories that turned out not to be in one.
+static COMMIT_TIMES: Mutex<Vec<CommitTimes>> = Mutex::new(Vec::new());
+static COMMITLESS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// How recently the given file was last committed, as a ratio from 0 for
+/// the least recently committed file in its repository to 1 for the most
+/// recently committed one. A directory counts as being committed whenever
+/// anything under it was. Files that aren’t in a repository, or that have
+/// never been committed, get `None`.
+pub fn commit_age(path: &Path) -> Option<f32> {
+    let path = reorient(path);
+    let mut repos = COMMIT_TIMES.lock().unwrap();
+
+    if !repos.iter().any(|r| path.starts_with(&r.workdir)) {
+        let dir = if path.is_dir() { &path } else { path.parent()? };
+        let mut commitless = COMMITLESS.lock().unwrap();
+        if commitless.iter().any(|d| d == dir) {
+            return None;
+        }
+
+        let Some(times) = CommitTimes::discover(dir) else {
+            commitless.push(dir.to_path_buf());
+            return None;
+        };
+        repos.push(times);
+    }
+
+    let times = repos.iter().find(|r| path.starts_with(&r.workdir))?;
+    let time = *times.times.get(path.strip_prefix(&times.workdir).ok()?)?;
+    Some(age_ratio(time, times.oldest, times.newest))
+}
+
+/// Where a time falls between the oldest and newest times, with 1 for the
+/// newest, and for everything when they’re all the same.
+fn age_ratio(time: i64, oldest: i64, newest: i64) -> f32 {
+    if newest <= oldest {
+        1.0
+    } else {
+        (time - oldest) as f32 / (newest - oldest) as f32
+    }
+}
+
+/// When each file and directory in one repository was last committed, in
+/// seconds, keyed by their paths relative to its working directory.
+struct CommitTimes {
+    workdir: PathBuf,
+    times: HashMap<PathBuf, i64>,
+    oldest: i64,
+    newest: i64,
+}
+
+impl CommitTimes {
+    /// Finds the repository the given directory is in, and walks through
+    /// its history. Directories outside of any repository get `None`.
+    fn discover(dir: &Path) -> Option<Self> {
+        let repo = git2::Repository::discover(dir).ok()?;
+        let workdir = repo.workdir()?.to_path_buf();
+
+        info!(
+            "Getting last commit times for repo with workdir {:?}",
+            workdir
+        );
+        let times = match last_commit_times(&repo) {
+            Ok(times) => times,
+            Err(e) => {
+                error!("Error walking Git history: {:?}", e);
+                HashMap::new()
+            }
+        };
+
+        Some(Self {
+            workdir,
+            oldest: times.values().copied().min().unwrap_or_default(),
+            newest: times.values().copied().max().unwrap_or_default(),
+            times,
+        })
+    }
+}
+
+/// Walks back through a repository’s history, newest first, noting the time
+/// of the first commit to touch each file that’s in `HEAD`, and the newest
+/// of those times for each directory above it.
+fn last_commit_times(repo: &git2::Repository) -> Result<HashMap<PathBuf, i64>, git2::Error> {
+    let head = repo.head()?.peel_to_tree()?;
+    let mut remaining = HashSet::new();
+    head.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                remaining.insert(Path::new(dir).join(name));
+            }
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+
+    let mut times = HashMap::new();
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(git2::Sort::TIME)?;
+
+    for oid in walk {
+        if remaining.is_empty() {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+        let parent = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent.as_ref(), Some(&tree), None)?;
+        let time = commit.time().seconds();
+
+        for delta in diff.deltas() {
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+            if !remaining.remove(path) {
+                continue;
+            }
+
+            for ancestor in path.ancestors() {
+                times.entry(ancestor.to_path_buf()).or_insert(time);
+            }
+        }
+    }
+
+    Ok(times)
+}
+
+/// The `.gitignore` rules of each directory seen so far, and whether it’s
+/// the top of a repository, past which no more rules apply.
+static IGNORE_DIRS: Mutex<Option<HashMap<PathBuf, Arc<IgnoreDir>>>> = Mutex::new(None);
+
+/// The rules in the global excludes file, which apply everywhere.
+static GLOBAL_IGNORES: OnceLock<Vec<IgnoreRule>> = OnceLock::new();
+
+/// Whether the given file is ignored by the `.gitignore` files in the
+/// directories above it, or by the global excludes file.
+pub fn is_ignored(path: &Path) -> bool {
+    let path = match env::current_dir() {
+        Ok(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_path_buf(),
+    };
+    let is_dir = || path.is_dir();
+
+    let mut top = path.as_path();
+    for dir in path.ancestors().skip(1) {
+        let ignores = ignore_dir(dir);
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        if let Some(ignored) = last_match(&ignores.rules, relative, &is_dir) {
+            return ignored;
+        }
+
+        top = dir;
+        if ignores.repo_root {
+            break;
+        }
+    }
+
+    let global = GLOBAL_IGNORES.get_or_init(global_ignores);
+    let relative = path.strip_prefix(top).unwrap_or(&path);
+    last_match(global, relative, &is_dir).unwrap_or(false)
+}
+
+/// The rules for one directory, reading its `.gitignore` the first time.
+fn ignore_dir(dir: &Path) -> Arc<IgnoreDir> {
+    let mut dirs = IGNORE_DIRS.lock().unwrap();
+    let dirs = dirs.get_or_insert_with(HashMap::new);
+    if let Some(ignores) = dirs.get(dir) {
+        return Arc::clone(ignores);
+    }
+
+    let rules = fs::read_to_string(dir.join(".gitignore"))
+        .map(|contents| parse_ignores(&contents))
+        .unwrap_or_default();
+    let ignores = Arc::new(IgnoreDir {
+        rules,
+        repo_root: dir.join(".git").exists(),
+    });
+    dirs.insert(dir.to_path_buf(), Arc::clone(&ignores));
+    ignores
+}
+
+/// Reads the global excludes file, if there is one.
+fn global_ignores() -> Vec<IgnoreRule> {
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".config"),
+            None => return Vec::new(),
+        },
+    };
+
+    fs::read_to_string(config.join("git").join("ignore"))
+        .map(|contents| parse_ignores(&contents))
+        .unwrap_or_default()
+}
+
+/// Whether the last of the given rules to match a path ignores it or
+/// un-ignores it, if any of them match it at all.
+fn last_match(rules: &[IgnoreRule], relative: &Path, is_dir: &dyn Fn() -> bool) -> Option<bool> {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(relative, is_dir))
+        .map(|rule| !rule.negated)
+}
+
+/// The `.gitignore` rules of one directory.
+struct IgnoreDir {
+    rules: Vec<IgnoreRule>,
+    repo_root: bool,
+}
+
+/// One line of a `.gitignore` file.
+#[derive(Debug)]
+struct IgnoreRule {
+    pattern: glob::Pattern,
+
+    /// Whether the line starts with `!`, un-ignoring what it matches.
+    negated: bool,
+
+    /// Whether the line ends with `/`, only matching directories.
+    dir_only: bool,
+
+    /// Whether the line has a `/` before its end, matching paths from the
+    /// directory it’s in rather than file names at any depth.
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn matches(&self, relative: &Path, is_dir: &dyn Fn() -> bool) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        let matched = if self.anchored {
+            self.pattern.matches_path_with(relative, options)
+        } else {
+            relative
+                .file_name()
+                .is_some_and(|name| self.pattern.matches_with(&name.to_string_lossy(), options))
+        };
+
+        matched && (!self.dir_only || is_dir())
+    }
+}
+
+/// Parses the contents of a `.gitignore` file, skipping blank lines,
+/// comments, and patterns that aren’t valid.
+fn parse_ignores(contents: &str) -> Vec<IgnoreRule> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let anchored = line.contains('/');
+            let line = line.strip_prefix('/').unwrap_or(line);
+
+            let pattern = glob::Pattern::new(line).ok()?;
+            Some(IgnoreRule {
+                pattern,
+                negated,
+                dir_only,
+                anchored,
+            })
+        })
+        .collect()
+}
 
 /// A **Git cache** is assembled based on the user’s input arguments.
 ///
@@ -35,6 +384,28 @@ impl GitCache {
             .map(|repo| repo.search(index, prefix_lookup))
             .unwrap_or_default()
     }
+
+    /// Whether the given file is in its repository’s index, or, for a
+    /// directory, whether anything under it is. Nothing outside of a
+    /// repository is tracked.
+    pub fn is_tracked(&self, index: &Path, prefix_lookup: bool) -> bool {
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(index))
+            .is_some_and(|repo| repo.with_statuses(|git| git.is_tracked(index, prefix_lookup)))
+    }
+
+    /// Whether the given file isn’t in its repository’s index, or, for a
+    /// directory, whether anything under it isn’t. Everything outside of a
+    /// repository is untracked.
+    pub fn has_untracked(&self, index: &Path, prefix_lookup: bool) -> bool {
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(index))
+            .map_or(true, |repo| {
+                repo.with_statuses(|git| git.has_untracked(index, prefix_lookup))
+            })
+    }
 }
 
 use std::iter::FromIterator;
@@ -141,18 +512,24 @@ impl GitRepo {
     /// repository is moved out, but before the results have been moved in!
     /// See <https://stackoverflow.com/q/45985827/3484614>
     fn search(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+        self.with_statuses(|statuses| statuses.status(index, prefix_lookup))
+    }
+
+    /// Runs the given function over this repository’s statuses, querying
+    /// the repository for them first if that hasn’t been done yet.
+    fn with_statuses<T>(&self, f: impl FnOnce(&Git) -> T) -> T {
         use std::mem::replace;
 
         let mut contents = self.contents.lock().unwrap();
         if let GitContents::After { ref statuses } = *contents {
             debug!("Git repo {:?} has been found in cache", &self.workdir);
-            return statuses.status(index, prefix_lookup);
+            return f(statuses);
         }
 
         debug!("Querying Git repo {:?} for the first time", &self.workdir);
         let repo = replace(&mut *contents, GitContents::Processing).inner_repo();
-        let statuses = repo_to_statuses(&repo, &self.workdir);
-        let result = statuses.status(index, prefix_lookup);
+        let statuses = cached_statuses(&repo, &self.workdir);
+        let result = f(&statuses);
         let _processing = replace(&mut *contents, GitContents::After { statuses });
         result
     }
@@ -240,7 +617,214 @@ fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
         }
     }
 
-    Git { statuses }
+    let mut tracked = Vec::new();
+    match repo.index() {
+        Ok(index) => {
+            for entry in index.iter() {
+                #[cfg(target_family = "unix")]
+                let path = workdir.join(Path::new(OsStr::from_bytes(&entry.path)));
+                #[cfg(not(target_family = "unix"))]
+                let path = workdir.join(String::from_utf8_lossy(&entry.path).as_ref());
+                tracked.push(path);
+            }
+            tracked.sort_unstable();
+        }
+        Err(e) => {
+            error!("Error reading Git index: {:?}", e);
+        }
+    }
+
+    Git {
+        statuses,
+        tracked,
+        stamps: Vec::new(),
+    }
+}
+
+/// Gets a repository’s statuses from the on-disk cache, if there is one and
+/// they’re still current, otherwise querying the repository and caching
+/// what it returns.
+fn cached_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
+    let Some((dir, refresh)) = STATUS_CACHE.get() else {
+        return repo_to_statuses(repo, workdir);
+    };
+    let Some(key) = CacheKey::of(repo, workdir) else {
+        return repo_to_statuses(repo, workdir);
+    };
+
+    let path = dir.join(key.file_name());
+    if !refresh {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match Git::parse(&contents, &key) {
+                Some(statuses) if statuses.is_current(Stamp::of) => {
+                    debug!("Loaded Git statuses for {:?} from {:?}", workdir, path);
+                    return statuses;
+                }
+                Some(_) => debug!("Git cache {:?} is out of date", path),
+                None => {}
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                diagnostics::warning(format_args!(
+                    "eza: Couldn’t read the Git cache {}: {e}",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    let started = Stamp::now();
+    let mut statuses = repo_to_statuses(repo, workdir);
+    statuses.stamp(workdir);
+    if statuses.changed_since(started) {
+        debug!(
+            "Not caching Git statuses for {:?}, as files changed",
+            workdir
+        );
+        return statuses;
+    }
+
+    if let Some(contents) = statuses.write(&key) {
+        if let Err(e) = save(&path, &contents) {
+            diagnostics::warning(format_args!(
+                "eza: Couldn’t save the Git cache {}: {e}",
+                path.display()
+            ));
+        }
+    }
+    statuses
+}
+
+/// Writes a cache file alongside first, so that being cancelled part-way
+/// through can’t leave it half-written.
+fn save(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temporary = path.to_path_buf().into_os_string();
+    temporary.push(".tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
+
+/// What a path in the working tree looked like when the statuses were
+/// worked out: its size, and its modification and change times, in seconds
+/// and nanoseconds. A path that can’t be read is all zeroes.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+struct Stamp {
+    size: u64,
+    modified: (i64, i64),
+    changed: (i64, i64),
+}
+
+impl Stamp {
+    /// Reads the stamp of the given path, without following symlinks.
+    #[cfg(target_family = "unix")]
+    fn of(path: &Path) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        fs::symlink_metadata(path).map_or_else(
+            |_| Self::default(),
+            |metadata| Self {
+                size: metadata.size(),
+                modified: (metadata.mtime(), metadata.mtime_nsec()),
+                changed: (metadata.ctime(), metadata.ctime_nsec()),
+            },
+        )
+    }
+
+    /// Reads the stamp of the given path, without following symlinks. There
+    /// are no change times here, so only the modification time is checked.
+    #[cfg(not(target_family = "unix"))]
+    fn of(path: &Path) -> Self {
+        fs::symlink_metadata(path).map_or_else(
+            |_| Self::default(),
+            |metadata| Self {
+                size: metadata.len(),
+                modified: metadata.modified().map_or((0, 0), time_pair),
+                changed: (0, 0),
+            },
+        )
+    }
+
+    /// A stamp with both times set to now, to compare others with.
+    fn now() -> Self {
+        let now = time_pair(std::time::SystemTime::now());
+        Self {
+            size: 0,
+            modified: now,
+            changed: now,
+        }
+    }
+}
+
+/// A time as seconds and nanoseconds since the epoch.
+fn time_pair(time: std::time::SystemTime) -> (i64, i64) {
+    time.duration_since(UNIX_EPOCH).map_or((0, 0), |since| {
+        (
+            i64::try_from(since.as_secs()).unwrap_or(i64::MAX),
+            i64::from(since.subsec_nanos()),
+        )
+    })
+}
+
+/// What a repository’s cached statuses are only valid for: its working
+/// directory, its `HEAD` commit (`-` if there isn’t one yet), and the
+/// modification time of its index, in seconds and nanoseconds.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct CacheKey {
+    workdir: String,
+    head: String,
+    index_modified: (u64, u32),
+}
+
+impl CacheKey {
+    /// The key for a repository as it is now, or `None` if its working
+    /// directory can’t be written down, in which case nothing is cached.
+    fn of(repo: &git2::Repository, workdir: &Path) -> Option<Self> {
+        let head = repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map_or_else(|| String::from("-"), |oid| oid.to_string());
+
+        // A repository without an index yet has nothing staged, so any
+        // time will do until one gets written.
+        let index_modified = fs::metadata(repo.path().join("index"))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or((0, 0), |since| (since.as_secs(), since.subsec_nanos()));
+
+        Some(Self {
+            workdir: workdir.to_str()?.to_owned(),
+            head,
+            index_modified,
+        })
+    }
+
+    /// The name of the repository’s cache file: a hash of its working
+    /// directory, which is checked again when the file is read.
+    fn file_name(&self) -> String {
+        // FNV-1a, as the hash has to be the same from one build to the next.
+        let hash = self
+            .workdir
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("{hash:016x}")
+    }
+
+    fn line(&self) -> String {
+        let (seconds, nanos) = self.index_modified;
+        format!(
+            "{}\t{}\t{seconds}\t{nanos}",
+            escape(&self.workdir),
+            self.head
+        )
+    }
 }
 
 // The `repo.statuses` call above takes a long time. exa debug output:
@@ -252,11 +836,132 @@ fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
 // look any faster.
 
 /// Container of Git statuses for all the files in this folder’s Git repository.
+#[derive(PartialEq, Eq, Debug)]
 struct Git {
     statuses: Vec<(PathBuf, git2::Status)>,
+
+    /// The paths of every file in the index, sorted so that everything
+    /// under a directory is next to each other.
+    tracked: Vec<PathBuf>,
+
+    /// What the files with statuses, the tracked files, and the directories
+    /// above them looked like, if these statuses are being cached.
+    stamps: Vec<(PathBuf, Stamp)>,
 }
 
 impl Git {
+    /// Parses the contents of a cache file, returning `None` if it isn’t one
+    /// or if it was written for a different key, so the repository has to be
+    /// queried again.
+    fn parse(contents: &str, key: &CacheKey) -> Option<Self> {
+        let mut lines = contents.lines();
+        if lines.next() != Some(CACHE_HEADER) || lines.next() != Some(&key.line()) {
+            return None;
+        }
+
+        let mut statuses = Vec::new();
+        let mut tracked = Vec::new();
+        let mut stamps = Vec::new();
+
+        for line in lines.filter(|line| !line.is_empty()) {
+            match line.split('\t').collect::<Vec<_>>()[..] {
+                ["s", bits, path] => {
+                    let status = git2::Status::from_bits_truncate(bits.parse().ok()?);
+                    statuses.push((PathBuf::from(unescape(path)), status));
+                }
+                ["t", path] => tracked.push(PathBuf::from(unescape(path))),
+                ["w", size, m_secs, m_nanos, c_secs, c_nanos, path] => {
+                    let stamp = Stamp {
+                        size: size.parse().ok()?,
+                        modified: (m_secs.parse().ok()?, m_nanos.parse().ok()?),
+                        changed: (c_secs.parse().ok()?, c_nanos.parse().ok()?),
+                    };
+                    stamps.push((PathBuf::from(unescape(path)), stamp));
+                }
+                _ => return None,
+            }
+        }
+
+        tracked.sort_unstable();
+        Some(Self {
+            statuses,
+            tracked,
+            stamps,
+        })
+    }
+
+    /// Records the stamps of every file that’s tracked or has a status, and
+    /// of every directory between them and the working directory.
+    fn stamp(&mut self, workdir: &Path) {
+        let files: HashSet<&Path> = self
+            .statuses
+            .iter()
+            .map(|(path, _)| path.as_path())
+            .chain(self.tracked.iter().map(PathBuf::as_path))
+            .collect();
+
+        let mut dirs = HashSet::new();
+        for file in &files {
+            for dir in file.ancestors().skip(1) {
+                if !dir.starts_with(workdir) || !dirs.insert(dir) {
+                    break;
+                }
+            }
+        }
+
+        let mut stamps: Vec<_> = files
+            .into_iter()
+            .chain(dirs)
+            .map(|path| (path.to_path_buf(), Stamp::of(path)))
+            .collect();
+        stamps.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        self.stamps = stamps;
+    }
+
+    /// Whether every recorded path still has the same stamp, reading them
+    /// with the given function.
+    fn is_current(&self, stamp: impl Fn(&Path) -> Stamp) -> bool {
+        self.stamps.iter().all(|(path, old)| stamp(path) == *old)
+    }
+
+    /// Whether any recorded path was modified or changed at or after the
+    /// given time, in which case its status might not match its stamp.
+    fn changed_since(&self, time: Stamp) -> bool {
+        self.stamps
+            .iter()
+            .any(|(_, stamp)| stamp.modified >= time.modified || stamp.changed >= time.changed)
+    }
+
+    /// Writes these statuses out in the format `parse` reads, or returns
+    /// `None` if any of their paths can’t be written down.
+    fn write(&self, key: &CacheKey) -> Option<String> {
+        let mut contents = format!("{CACHE_HEADER}\n{}\n", key.line());
+
+        for (path, status) in &self.statuses {
+            let path = escape(path.to_str()?);
+            let _ = writeln!(contents, "s\t{}\t{path}", status.bits());
+        }
+
+        for path in &self.tracked {
+            let _ = writeln!(contents, "t\t{}", escape(path.to_str()?));
+        }
+
+        for (path, stamp) in &self.stamps {
+            let _ = writeln!(
+                contents,
+                "w\t{}\t{}\t{}\t{}\t{}\t{}",
+                stamp.size,
+                stamp.modified.0,
+                stamp.modified.1,
+                stamp.changed.0,
+                stamp.changed.1,
+                escape(path.to_str()?)
+            );
+        }
+
+        Some(contents)
+    }
+
     /// Get either the file or directory status for the given path.
     /// “Prefix lookup” means that it should report an aggregate status of all
     /// paths starting with the given prefix (in other words, a directory).
@@ -268,6 +973,41 @@ impl Git {
         }
     }
 
+    /// Whether a file is in the index, or a directory has anything under it
+    /// that is.
+    fn is_tracked(&self, index: &Path, prefix_lookup: bool) -> bool {
+        let path = reorient(index);
+        let first = self.tracked.partition_point(|p| *p < path);
+
+        match self.tracked.get(first) {
+            Some(p) if prefix_lookup => p.starts_with(&path),
+            Some(p) => *p == path,
+            None => false,
+        }
+    }
+
+    /// Whether a file isn’t in the index, or a directory has anything under
+    /// it that isn’t, which is the case for any ignored path, any new file,
+    /// and any directory without tracked files in it.
+    fn has_untracked(&self, index: &Path, prefix_lookup: bool) -> bool {
+        if !self.is_tracked(index, prefix_lookup) {
+            return true;
+        }
+
+        if !prefix_lookup {
+            return false;
+        }
+
+        let path = reorient(index);
+        self.statuses.iter().any(|(p, s)| {
+            if *s == git2::Status::IGNORED {
+                p.starts_with(&path) || path.starts_with(p)
+            } else {
+                s.contains(git2::Status::WT_NEW) && p.starts_with(&path)
+            }
+        })
+    }
+
     /// Get the user-facing status of a file.
     /// We check the statuses directly applying to a file, and for the ignored
     /// status we check if any of its parents directories is ignored by git.
@@ -442,3 +1182,172 @@ impl f::SubdirGitRepo {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key() -> CacheKey {
+        CacheKey {
+            workdir: String::from("/src/eza/"),
+            head: String::from("4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+            index_modified: (1_700_000_000, 5),
+        }
+    }
+
+    fn statuses() -> Git {
+        Git {
+            statuses: vec![
+                (
+                    PathBuf::from("/src/eza/Cargo.toml"),
+                    git2::Status::WT_MODIFIED,
+                ),
+                (PathBuf::from("/src/eza/tab\there"), git2::Status::INDEX_NEW),
+                (PathBuf::from("/src/eza/.git"), git2::Status::IGNORED),
+            ],
+            tracked: vec![
+                PathBuf::from("/src/eza/Cargo.toml"),
+                PathBuf::from("/src/eza/tab\there"),
+            ],
+            stamps: vec![
+                (PathBuf::from("/src/eza"), stamp(4096, 10)),
+                (PathBuf::from("/src/eza/Cargo.toml"), stamp(1024, 20)),
+            ],
+        }
+    }
+
+    fn stamp(size: u64, time: i64) -> Stamp {
+        Stamp {
+            size,
+            modified: (time, 5),
+            changed: (time, 6),
+        }
+    }
+
+    fn ignored(contents: &str, path: &str, is_dir: bool) -> Option<bool> {
+        last_match(&parse_ignores(contents), Path::new(path), &|| is_dir)
+    }
+
+    #[test]
+    fn ignore_names_at_any_depth() {
+        assert_eq!(ignored("*.log\n", "build/debug.log", false), Some(true));
+        assert_eq!(ignored("*.log\n", "build/debug.txt", false), None);
+    }
+
+    #[test]
+    fn ignore_anchored() {
+        assert_eq!(ignored("/target\n", "target", true), Some(true));
+        assert_eq!(ignored("/target\n", "src/target", true), None);
+        assert_eq!(
+            ignored("docs/*.html\n", "docs/index.html", false),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn ignore_dirs_only() {
+        assert_eq!(ignored("cache/\n", "cache", true), Some(true));
+        assert_eq!(ignored("cache/\n", "cache", false), None);
+    }
+
+    #[test]
+    fn ignore_last_rule_wins() {
+        let contents = "*.log\n!keep.log\n";
+        assert_eq!(ignored(contents, "keep.log", false), Some(false));
+        assert_eq!(ignored(contents, "other.log", false), Some(true));
+    }
+
+    #[test]
+    fn ignore_comments_and_escapes() {
+        assert_eq!(ignored("# notes\n\n", "# notes", false), None);
+        assert_eq!(ignored("\\#notes\n", "#notes", false), Some(true));
+    }
+
+    #[test]
+    fn round_trip() {
+        let contents = statuses().write(&key()).unwrap();
+        assert_eq!(Git::parse(&contents, &key()), Some(statuses()));
+    }
+
+    #[test]
+    fn different_head() {
+        let contents = statuses().write(&key()).unwrap();
+        let moved = CacheKey {
+            head: String::from("-"),
+            ..key()
+        };
+        assert_eq!(Git::parse(&contents, &moved), None);
+    }
+
+    #[test]
+    fn index_touched() {
+        let contents = statuses().write(&key()).unwrap();
+        let touched = CacheKey {
+            index_modified: (1_700_000_001, 5),
+            ..key()
+        };
+        assert_eq!(Git::parse(&contents, &touched), None);
+    }
+
+    #[test]
+    fn working_tree_unchanged() {
+        let git = statuses();
+        assert!(git.is_current(|path| {
+            if path == Path::new("/src/eza") {
+                stamp(4096, 10)
+            } else {
+                stamp(1024, 20)
+            }
+        }));
+    }
+
+    #[test]
+    fn file_edited() {
+        let git = statuses();
+        assert!(!git.is_current(|path| {
+            if path == Path::new("/src/eza") {
+                stamp(4096, 10)
+            } else {
+                stamp(1030, 30)
+            }
+        }));
+    }
+
+    #[test]
+    fn file_created() {
+        let git = statuses();
+        assert!(!git.is_current(|path| {
+            if path == Path::new("/src/eza") {
+                stamp(4096, 30)
+            } else {
+                stamp(1024, 20)
+            }
+        }));
+    }
+
+    #[test]
+    fn changed_while_scanning() {
+        assert!(statuses().changed_since(stamp(0, 20)));
+        assert!(!statuses().changed_since(stamp(0, 21)));
+    }
+
+    #[test]
+    fn bad_line() {
+        let contents = format!("{CACHE_HEADER}\n{}\nq\tfoo\n", key().line());
+        assert_eq!(Git::parse(&contents, &key()), None);
+    }
+
+    #[test]
+    fn file_name_is_stable() {
+        assert_eq!(key().file_name(), key().file_name());
+        assert_eq!(key().file_name().len(), 16);
+    }
+
+    #[test]
+    fn age_ratios() {
+        assert!(age_ratio(100, 100, 200).abs() < f32::EPSILON);
+        assert!((age_ratio(150, 100, 200) - 0.5).abs() < f32::EPSILON);
+        assert!((age_ratio(200, 100, 200) - 1.0).abs() < f32::EPSILON);
+        assert!((age_ratio(100, 100, 100) - 1.0).abs() < f32::EPSILON);
+    }
+}
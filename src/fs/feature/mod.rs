@@ -1,15 +1,58 @@
+pub mod acl;
+pub mod overlay;
+pub mod sockets;
+pub mod sparse;
+pub mod special_dirs;
+pub mod trash;
+pub mod version_info;
 pub mod xattr;
 
 #[cfg(feature = "git")]
 pub mod git;
 
+#[cfg(feature = "sort-command")]
+pub mod sort_command;
+
+#[cfg(not(feature = "sort-command"))]
+pub mod sort_command {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    #[derive(PartialEq, Eq, Debug, Clone)]
+    pub struct SortCommand {
+        pub program: String,
+        pub args: Vec<String>,
+    }
+
+    impl SortCommand {
+        #[allow(clippy::needless_pass_by_value)]
+        pub fn keys<'p>(
+            &self,
+            _paths: impl IntoIterator<Item = &'p Path>,
+        ) -> HashMap<PathBuf, String> {
+            unreachable!();
+        }
+    }
+}
+
 #[cfg(not(feature = "git"))]
 pub mod git {
     use std::iter::FromIterator;
     use std::path::{Path, PathBuf};
 
+    use crate::fs::cache::CacheOptions;
     use crate::fs::fields as f;
 
+    pub fn configure_cache(_options: Option<&CacheOptions>) {}
+
+    pub fn commit_age(_path: &Path) -> Option<f32> {
+        None
+    }
+
+    pub fn is_ignored(_path: &Path) -> bool {
+        false
+    }
+
     pub struct GitCache;
 
     impl FromIterator<PathBuf> for GitCache {
@@ -29,6 +72,14 @@ pub mod git {
         pub fn get(&self, _index: &Path, _prefix_lookup: bool) -> f::Git {
             unreachable!();
         }
+
+        pub fn is_tracked(&self, _index: &Path, _prefix_lookup: bool) -> bool {
+            false
+        }
+
+        pub fn has_untracked(&self, _index: &Path, _prefix_lookup: bool) -> bool {
+            true
+        }
     }
 
     impl f::SubdirGitRepo {
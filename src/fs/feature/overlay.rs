@@ -0,0 +1,245 @@
+//! Working out which layer of an overlay filesystem a file comes from, for
+//! looking inside containers, whose root filesystems are overlays of a
+//! writable “upper” directory over the read-only “lower” layers of their
+//! image.
+//!
+//! A file that’s only in the lower layers is as it was in the image. One
+//! that’s in the upper directory has either been created in the container,
+//! or copied up from a lower layer when it was changed, which overlayfs
+//! records with an `overlay.origin` extended attribute when it can, and
+//! which can otherwise be seen from the file still being in a lower layer.
+//!
+//! The layer directories are taken from the overlay’s mount options, so
+//! this only works from somewhere they can be read, such as from the host
+//! rather than from inside the container.
+
+use std::path::{Path, PathBuf};
+
+use crate::fs::fields as f;
+
+/// The directories that make up an overlay filesystem.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Layers {
+    /// The writable directory, which read-only overlays don’t have.
+    pub upper: Option<PathBuf>,
+
+    /// The read-only directories, topmost first.
+    pub lowers: Vec<PathBuf>,
+
+    /// Whether overlayfs keeps its extended attributes in the `user`
+    /// namespace, rather than `trusted`, as it does for rootless containers.
+    pub user_xattrs: bool,
+}
+
+impl Layers {
+    /// Reads the layer directories from an overlay’s mount options.
+    pub fn from_options(options: &[String]) -> Self {
+        let mut layers = Self::default();
+
+        for option in options {
+            if let Some(upper) = option.strip_prefix("upperdir=") {
+                layers.upper = Some(PathBuf::from(upper));
+            } else if let Some(lowers) = option.strip_prefix("lowerdir=") {
+                layers
+                    .lowers
+                    .extend(split_lowers(lowers).map(PathBuf::from));
+            } else if let Some(lower) = option.strip_prefix("lowerdir+=") {
+                layers.lowers.push(PathBuf::from(lower));
+            } else if option == "userxattr" {
+                layers.user_xattrs = true;
+            }
+        }
+
+        layers
+    }
+
+    /// Which layer the file at the given path, relative to the top of the
+    /// overlay, comes from.
+    #[cfg(target_os = "linux")]
+    fn layer_of(&self, relative: &Path) -> f::OverlayLayer {
+        use std::io;
+
+        let Some(upper) = &self.upper else {
+            return f::OverlayLayer::Lower;
+        };
+
+        let upper_path = upper.join(relative);
+        match std::fs::symlink_metadata(&upper_path) {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return f::OverlayLayer::Lower,
+            Err(_) => return f::OverlayLayer::Unknown,
+        }
+
+        let prefix = if self.user_xattrs { "user" } else { "trusted" };
+        let has_xattr =
+            |name: &str| has_attribute(&upper_path, &format!("{prefix}.overlay.{name}"));
+
+        // An opaque directory hides whatever the lower layers have there,
+        // so it counts as new even if they do.
+        let in_lower = || {
+            !has_xattr("opaque")
+                && self
+                    .lowers
+                    .iter()
+                    .any(|lower| std::fs::symlink_metadata(lower.join(relative)).is_ok())
+        };
+
+        if has_xattr("origin") || in_lower() {
+            f::OverlayLayer::Modified
+        } else {
+            f::OverlayLayer::Upper
+        }
+    }
+}
+
+/// Splits the list of lower directories on the colons that aren’t escaped
+/// with backslashes, unescaping the ones that are.
+fn split_lowers(lowers: &str) -> impl Iterator<Item = String> + '_ {
+    let mut chars = lowers.chars();
+    std::iter::from_fn(move || {
+        let mut lower = String::new();
+        let mut ended = true;
+
+        while let Some(c) = chars.next() {
+            ended = false;
+            match c {
+                '\\' => lower.extend(chars.next()),
+                ':' => break,
+                c => lower.push(c),
+            }
+        }
+
+        // Data-only layers come after a double colon, which leaves an
+        // empty entry to skip over.
+        if ended {
+            None
+        } else {
+            Some(lower)
+        }
+    })
+    .filter(|lower| !lower.is_empty())
+}
+
+/// Whether the file at the given path has the extended attribute with the
+/// given name, without following symlinks.
+#[cfg(target_os = "linux")]
+fn has_attribute(path: &Path, name: &str) -> bool {
+    use crate::fs::feature::xattr::FileAttributes;
+
+    path.symlink_attributes()
+        .is_ok_and(|attrs| attrs.iter().any(|attr| attr.name == name))
+}
+
+/// Which layer of an overlay filesystem the file at the given path comes
+/// from, if it’s on one.
+#[cfg(target_os = "linux")]
+pub fn layer(path: &Path) -> f::OverlayLayer {
+    use std::collections::HashMap;
+    use std::sync::OnceLock;
+
+    use crate::fs::mounts::all_mounts;
+
+    static OVERLAYS: OnceLock<HashMap<PathBuf, Layers>> = OnceLock::new();
+    let overlays = OVERLAYS.get_or_init(|| {
+        all_mounts()
+            .values()
+            .filter(|mount| mount.fstype == "overlay")
+            .map(|mount| (mount.dest.clone(), Layers::from_options(&mount.options)))
+            .collect()
+    });
+
+    if overlays.is_empty() {
+        return f::OverlayLayer::NotOverlay;
+    }
+
+    // The symlink itself is what’s wanted, rather than its target, so only
+    // its parent gets resolved.
+    let absolute = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            std::fs::canonicalize(parent).map(|parent| parent.join(name))
+        }
+        _ => std::fs::canonicalize(path),
+    };
+    let Ok(absolute) = absolute else {
+        return f::OverlayLayer::Unknown;
+    };
+
+    // Whatever’s mounted deepest is what the file is on, which might be
+    // another filesystem mounted inside an overlay.
+    let Some(dest) = all_mounts()
+        .keys()
+        .filter(|dest| absolute.starts_with(dest))
+        .max_by_key(|dest| dest.components().count())
+    else {
+        return f::OverlayLayer::NotOverlay;
+    };
+
+    match overlays.get(dest) {
+        Some(layers) => match absolute.strip_prefix(dest) {
+            Ok(relative) => layers.layer_of(relative),
+            Err(_) => f::OverlayLayer::Unknown,
+        },
+        None => f::OverlayLayer::NotOverlay,
+    }
+}
+
+/// Overlay filesystems are only read on Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn layer(_path: &Path) -> f::OverlayLayer {
+    f::OverlayLayer::NotOverlay
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn options(options: &[&str]) -> Vec<String> {
+        options.iter().map(|o| (*o).to_string()).collect()
+    }
+
+    #[test]
+    fn container_mount() {
+        let layers = Layers::from_options(&options(&[
+            "rw",
+            "lowerdir=/var/lib/l/A:/var/lib/l/B",
+            "upperdir=/var/lib/c/diff",
+            "workdir=/var/lib/c/work",
+        ]));
+        assert_eq!(
+            layers,
+            Layers {
+                upper: Some(PathBuf::from("/var/lib/c/diff")),
+                lowers: vec![PathBuf::from("/var/lib/l/A"), PathBuf::from("/var/lib/l/B")],
+                user_xattrs: false,
+            }
+        );
+    }
+
+    #[test]
+    fn read_only_mount() {
+        let layers = Layers::from_options(&options(&["ro", "lowerdir=/a", "userxattr"]));
+        assert_eq!(layers.upper, None);
+        assert_eq!(layers.lowers, vec![PathBuf::from("/a")]);
+        assert!(layers.user_xattrs);
+    }
+
+    #[test]
+    fn appended_lowers() {
+        let layers = Layers::from_options(&options(&["lowerdir+=/a", "lowerdir+=/b"]));
+        assert_eq!(
+            layers.lowers,
+            vec![PathBuf::from("/a"), PathBuf::from("/b")]
+        );
+    }
+
+    #[test]
+    fn escaped_colons() {
+        let lowers: Vec<_> = split_lowers(r"/a\:b:/c::/data").collect();
+        assert_eq!(lowers, vec!["/a:b", "/c", "/data"]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Looking up the state of Unix domain sockets in the kernel’s socket table.
+//!
+//! On Linux, `/proc/net/unix` lists every Unix domain socket along with the
+//! path it was bound to, if any. The inode number in that table belongs to
+//! the socket itself, not to the file it was bound to, so sockets are
+//! matched up with files by their canonical path instead.
+//!
+//! The table is only read the first time a socket’s state is asked for.
+
+use std::path::Path;
+
+use crate::fs::fields as f;
+
+#[cfg(target_os = "linux")]
+mod table {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use log::*;
+    use once_cell::sync::Lazy;
+
+    use crate::fs::fields as f;
+
+    /// The `__SO_ACCEPTCON` flag, set on sockets that are listening.
+    const ACCEPT_CONNECTIONS: u32 = 0x0001_0000;
+
+    /// The `SS_CONNECTED` socket state.
+    const CONNECTED: &str = "03";
+
+    static SOCKETS: Lazy<HashMap<PathBuf, f::SocketState>> =
+        Lazy::new(|| match fs::read_to_string("/proc/net/unix") {
+            Ok(contents) => parse(&contents),
+            Err(e) => {
+                debug!("Failed to read /proc/net/unix: {e}");
+                HashMap::new()
+            }
+        });
+
+    /// Collects the state of each bound socket from the contents of
+    /// `/proc/net/unix`. The same path appears once for the listening socket
+    /// and once more for each connection it has accepted.
+    pub fn parse(contents: &str) -> HashMap<PathBuf, f::SocketState> {
+        let mut sockets = HashMap::new();
+
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (Some(flags), Some(state), Some(path)) = (fields.get(3), fields.get(5), fields.get(7))
+            else {
+                continue;
+            };
+
+            // Abstract sockets have no file on disk.
+            if path.starts_with('@') {
+                continue;
+            }
+
+            let path = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+            let listening =
+                u32::from_str_radix(flags, 16).is_ok_and(|f| f & ACCEPT_CONNECTIONS != 0);
+            let connected = *state == CONNECTED;
+
+            let entry = sockets.entry(path).or_insert(f::SocketState::Unconnected);
+            *entry = match (*entry, listening, connected) {
+                (f::SocketState::Listening(n), _, true) => f::SocketState::Listening(n + 1),
+                (f::SocketState::Listening(n), _, false) => f::SocketState::Listening(n),
+                (f::SocketState::Connected(n), true, _) => f::SocketState::Listening(n),
+                (f::SocketState::Connected(n), false, true) => f::SocketState::Connected(n + 1),
+                (_, true, _) => f::SocketState::Listening(0),
+                (_, false, true) => f::SocketState::Connected(1),
+                (state, false, false) => state,
+            };
+        }
+
+        sockets
+    }
+
+    pub fn state_of(path: &Path) -> f::SocketState {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        SOCKETS.get(&path).copied().unwrap_or(f::SocketState::Stale)
+    }
+}
+
+/// The state of the socket bound to the given path, or `None` if it can’t be
+/// determined on this platform.
+#[allow(clippy::unnecessary_wraps)] // Needs to match the function on other platforms
+#[cfg(target_os = "linux")]
+pub fn state_of(path: &Path) -> Option<f::SocketState> {
+    Some(table::state_of(path))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn state_of(_path: &Path) -> Option<f::SocketState> {
+    None
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use std::path::Path;
+
+    use super::table::parse;
+    use crate::fs::fields as f;
+
+    const TABLE: &str = "\
+Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 2001 /nonexistent/eza/listen.sock
+0000000000000000: 00000003 00000000 00000000 0001 03 2002 /nonexistent/eza/listen.sock
+0000000000000000: 00000003 00000000 00000000 0001 03 2003 /nonexistent/eza/listen.sock
+0000000000000000: 00000002 00000000 00000000 0002 01 2004 /nonexistent/eza/dgram.sock
+0000000000000000: 00000002 00000000 00010000 0001 01 2005 @abstract
+0000000000000000: 00000003 00000000 00000000 0001 03 2006
+";
+
+    #[test]
+    fn listening_with_connections() {
+        let sockets = parse(TABLE);
+        assert_eq!(
+            sockets.get(Path::new("/nonexistent/eza/listen.sock")),
+            Some(&f::SocketState::Listening(2))
+        );
+    }
+
+    #[test]
+    fn unconnected() {
+        let sockets = parse(TABLE);
+        assert_eq!(
+            sockets.get(Path::new("/nonexistent/eza/dgram.sock")),
+            Some(&f::SocketState::Unconnected)
+        );
+    }
+
+    #[test]
+    fn skips_unbound_and_abstract() {
+        assert_eq!(parse(TABLE).len(), 2);
+    }
+}
@@ -0,0 +1,196 @@
+//! Sorting by keys that an external command produces, for orderings eza
+//! can’t know about, such as by a ticket number that’s part of each name.
+//!
+//! The command is given the paths of the files being sorted on its standard
+//! input, one per line, and has to print one key for each of them, in the
+//! same order. Paths are sent in batches, so a large directory doesn’t need
+//! to fit in one pipe, and the command is run once per batch rather than
+//! once per file. The keys are compared naturally, like names are.
+//!
+//! Running programs while listing files is something that should never
+//! happen by surprise, so this is only built with the `sort-command`
+//! feature, and only used when `--allow-sort-command` is given.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+
+use log::*;
+use once_cell::sync::Lazy;
+
+use crate::diagnostics;
+
+/// The most paths to send to the command at once.
+const BATCH_SIZE: usize = 1024;
+
+/// The keys the command has already given, as the same files can get sorted
+/// more than once on their way to being listed.
+static KEY_CACHE: Lazy<Mutex<HashMap<PathBuf, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The command to run to get the keys to sort files by.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SortCommand {
+    /// The program to run, which is looked up in `PATH`.
+    pub program: String,
+
+    /// The arguments to give it.
+    pub args: Vec<String>,
+}
+
+impl SortCommand {
+    /// Runs the command over the given paths, returning the key it gave for
+    /// each one. If it fails, the paths in the batches it failed for get no
+    /// keys, and a warning is printed.
+    pub fn keys<'p>(&self, paths: impl IntoIterator<Item = &'p Path>) -> HashMap<PathBuf, String> {
+        let mut cache = KEY_CACHE.lock().unwrap();
+        let mut keys = HashMap::new();
+        let mut missing = Vec::new();
+
+        for path in paths {
+            match cache.get(path) {
+                Some(key) => {
+                    keys.insert(path.to_path_buf(), key.clone());
+                }
+                None => missing.push(path),
+            }
+        }
+
+        for batch in missing.chunks(BATCH_SIZE) {
+            match self.run(batch) {
+                Ok(batch_keys) => {
+                    for (path, key) in batch.iter().zip(batch_keys) {
+                        cache.insert(path.to_path_buf(), key.clone());
+                        keys.insert(path.to_path_buf(), key);
+                    }
+                }
+                Err(e) => {
+                    diagnostics::warning(format_args!("eza: Sort command {:?} {e}", self.program));
+                }
+            }
+        }
+
+        keys
+    }
+
+    /// Runs the command once, over one batch of paths.
+    fn run(&self, batch: &[&Path]) -> Result<Vec<String>, String> {
+        debug!(
+            "Running sort command {:?} over {} paths",
+            self.program,
+            batch.len()
+        );
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("couldn’t be run: {e}"))?;
+
+        let mut input = String::new();
+        for path in batch {
+            input.push_str(&path.to_string_lossy());
+            input.push('\n');
+        }
+
+        // Write on another thread, so a command that prints keys before it’s
+        // read everything can’t fill its output pipe and leave both sides
+        // waiting on the other.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = thread::spawn(move || {
+            block_sigpipe();
+            stdin.write_all(input.as_bytes())
+        });
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("couldn’t be run: {e}"))?;
+        let written = writer.join().unwrap_or(Ok(()));
+
+        if !output.status.success() {
+            return Err(format!("failed ({})", output.status));
+        }
+
+        written.map_err(|e| format!("didn’t read its input: {e}"))?;
+
+        let keys = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        if keys.len() == batch.len() {
+            Ok(keys)
+        } else {
+            Err(format!(
+                "printed {} keys for {} paths",
+                keys.len(),
+                batch.len()
+            ))
+        }
+    }
+}
+
+/// Stops a command that exits without reading all its input from killing
+/// eza, which otherwise dies on `SIGPIPE` like other command-line programs
+/// do. Blocking it on just the writing thread turns it into an error from
+/// the write instead.
+#[cfg(unix)]
+fn block_sigpipe() {
+    // SAFETY: this only changes the signal mask of the calling thread.
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::sigset_t>();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGPIPE);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &set, std::ptr::null_mut());
+    }
+}
+
+#[cfg(not(unix))]
+fn block_sigpipe() {}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::*;
+
+    fn command(program: &str, args: &[&str]) -> SortCommand {
+        SortCommand {
+            program: program.into(),
+            args: args.iter().map(|a| (*a).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn one_key_per_path() {
+        let paths = [Path::new("b-2"), Path::new("a-10")];
+        let keys = command("cut", &["-d-", "-f2"]).keys(paths);
+        assert_eq!(keys[Path::new("b-2")], "2");
+        assert_eq!(keys[Path::new("a-10")], "10");
+    }
+
+    #[test]
+    fn batches() {
+        let names = (0..BATCH_SIZE * 2 + 1)
+            .map(|i| PathBuf::from(format!("file{i}")))
+            .collect::<Vec<_>>();
+        let keys = command("cat", &[]).keys(names.iter().map(PathBuf::as_path));
+        assert_eq!(keys.len(), names.len());
+        assert_eq!(keys[Path::new("file2048")], "file2048");
+    }
+
+    #[test]
+    fn failure() {
+        let keys = command("false", &[]).keys([Path::new("a")]);
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn wrong_number_of_keys() {
+        let keys = command("head", &["-n1"]).keys([Path::new("a"), Path::new("b")]);
+        assert!(keys.is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+//! Finding which parts of a sparse file have data in them, and which are
+//! holes that take up no space on disk.
+//!
+//! This asks the filesystem with `SEEK_DATA` and `SEEK_HOLE`, which is what
+//! `cp --sparse` uses too. Filesystems that don’t track holes report the
+//! whole file as data, as do platforms that can’t be asked.
+
+use std::fs;
+use std::io;
+
+/// A run of bytes in a file that are either all data or all hole.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Extent {
+    /// The offset of the first byte in the run.
+    pub start: u64,
+
+    /// The offset just past the last byte in the run.
+    pub end: u64,
+
+    /// Whether the run has data in it, rather than being a hole.
+    pub data: bool,
+}
+
+/// Finds the data and hole extents of the given file, which is `len` bytes
+/// long, in order from the start.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+pub fn extents(file: &fs::File, len: u64) -> io::Result<Vec<Extent>> {
+    use std::os::unix::io::AsRawFd;
+
+    let fd = file.as_raw_fd();
+    let seek = |offset: u64, whence: libc::c_int| -> io::Result<Option<u64>> {
+        let offset = libc::off_t::try_from(offset)
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+
+        // SAFETY: the file descriptor stays open for as long as `file` is
+        // borrowed, and seeking doesn’t touch any memory.
+        let result = unsafe { libc::lseek(fd, offset, whence) };
+        if result >= 0 {
+            Ok(Some(result as u64))
+        } else {
+            match io::Error::last_os_error() {
+                // There’s no more data past the offset.
+                e if e.raw_os_error() == Some(libc::ENXIO) => Ok(None),
+                e => Err(e),
+            }
+        }
+    };
+
+    let mut extents = Vec::new();
+    let mut offset = 0;
+
+    while offset < len {
+        let data_start = seek(offset, libc::SEEK_DATA)?.unwrap_or(len).min(len);
+        if data_start > offset {
+            extents.push(Extent {
+                start: offset,
+                end: data_start,
+                data: false,
+            });
+        }
+        if data_start >= len {
+            break;
+        }
+
+        let data_end = seek(data_start, libc::SEEK_HOLE)?.unwrap_or(len).min(len);
+        extents.push(Extent {
+            start: data_start,
+            end: data_end,
+            data: true,
+        });
+        offset = data_end;
+    }
+
+    Ok(extents)
+}
+
+/// Without a way to find holes, the whole file counts as data.
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+pub fn extents(_file: &fs::File, len: u64) -> io::Result<Vec<Extent>> {
+    if len == 0 {
+        Ok(Vec::new())
+    } else {
+        Ok(vec![Extent {
+            start: 0,
+            end: len,
+            data: true,
+        }])
+    }
+}
@@ -0,0 +1,207 @@
+//! Recognising directories that have a special meaning, so they can be
+//! decorated differently from the rest: the user’s XDG user directories,
+//! such as Downloads or Documents, and directories that tools fill with
+//! things that aren’t the user’s own, such as `node_modules`.
+//!
+//! The user directories are read from `user-dirs.dirs` in the XDG
+//! configuration directory, which `xdg-user-dirs-update` writes and which
+//! looks like this:
+//!
+//! ```text
+//! XDG_DOWNLOAD_DIR="$HOME/Downloads"
+//! XDG_MUSIC_DIR="/media/music"
+//! ```
+//!
+//! Reading the file, rather than going by name, means the directories are
+//! still recognised when they’ve been translated or moved elsewhere.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::fs::File;
+
+static USER_DIRS: OnceLock<HashMap<PathBuf, UserDir>> = OnceLock::new();
+
+/// Directories that tools fill with caches and dependencies, and that get
+/// recognised by name alone.
+const WELL_KNOWN_DIRS: &[&str] = &[".cache", "node_modules", "__pycache__", ".venv"];
+
+/// Where the user directories file is, and the home directory that the
+/// paths in it can be relative to.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct UserDirsFile {
+    pub path: PathBuf,
+    pub home: PathBuf,
+}
+
+/// One of the user directories from the XDG user directories specification.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum UserDir {
+    Desktop,
+    Documents,
+    Download,
+    Music,
+    Pictures,
+    PublicShare,
+    Templates,
+    Videos,
+}
+
+/// Why a directory is special.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SpecialDir {
+    /// It’s one of the user’s XDG user directories.
+    User(UserDir),
+
+    /// It has the name of a directory that tools fill.
+    WellKnown,
+}
+
+impl UserDirsFile {
+    /// Reads the user directories from the file. A file that doesn’t exist
+    /// yet lists no directories.
+    pub fn entries(&self) -> io::Result<Vec<(UserDir, PathBuf)>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(parse_user_dirs(&contents, &self.home)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl UserDir {
+    /// The user directory that a variable in the file sets.
+    fn from_key(key: &str) -> Option<Self> {
+        #[rustfmt::skip]
+        return match key {
+            "XDG_DESKTOP_DIR"     => Some(Self::Desktop),
+            "XDG_DOCUMENTS_DIR"   => Some(Self::Documents),
+            "XDG_DOWNLOAD_DIR"    => Some(Self::Download),
+            "XDG_MUSIC_DIR"       => Some(Self::Music),
+            "XDG_PICTURES_DIR"    => Some(Self::Pictures),
+            "XDG_PUBLICSHARE_DIR" => Some(Self::PublicShare),
+            "XDG_TEMPLATES_DIR"   => Some(Self::Templates),
+            "XDG_VIDEOS_DIR"      => Some(Self::Videos),
+            _                     => None,
+        };
+    }
+}
+
+/// Parses the contents of a user directories file. Paths can be absolute
+/// or start with `$HOME`, and a directory set to the home directory itself
+/// is turned off, so it gets left out.
+fn parse_user_dirs(contents: &str, home: &Path) -> Vec<(UserDir, PathBuf)> {
+    let mut dirs = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some(dir) = UserDir::from_key(key.trim()) else {
+            continue;
+        };
+
+        let value = value.trim().trim_matches('"');
+        let path = if let Some(rest) = value.strip_prefix("$HOME") {
+            home.join(rest.trim_start_matches('/'))
+        } else if value.starts_with('/') {
+            PathBuf::from(value)
+        } else {
+            continue;
+        };
+
+        if path != home {
+            dirs.push((dir, path));
+        }
+    }
+
+    dirs
+}
+
+/// Remembers where each user directory is. This only has an effect the
+/// first time it’s called, before anything has been drawn.
+pub fn configure(entries: &[(UserDir, PathBuf)]) {
+    let dirs = entries
+        .iter()
+        .map(|(dir, path)| {
+            (
+                fs::canonicalize(path).unwrap_or_else(|_| path.clone()),
+                *dir,
+            )
+        })
+        .collect();
+    let _ = USER_DIRS.set(dirs);
+}
+
+impl File<'_> {
+    /// Why this directory is special, if it is, returning `None` for any
+    /// other file.
+    pub fn special_dir(&self) -> Option<SpecialDir> {
+        if !self.is_directory() {
+            return None;
+        }
+
+        if WELL_KNOWN_DIRS.contains(&self.name.as_str()) {
+            return Some(SpecialDir::WellKnown);
+        }
+
+        // Only directories with the same name as a user directory are worth
+        // resolving the path of.
+        let dirs = USER_DIRS.get().filter(|dirs| !dirs.is_empty())?;
+        if !dirs
+            .keys()
+            .any(|path| path.file_name() == Some(self.name.as_ref()))
+        {
+            return None;
+        }
+
+        let path = fs::canonicalize(&self.path).ok()?;
+        dirs.get(&path).copied().map(SpecialDir::User)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn home_relative() {
+        let dirs = parse_user_dirs(
+            "# written by xdg-user-dirs-update\nXDG_DOWNLOAD_DIR=\"$HOME/Téléchargements\"\n",
+            &PathBuf::from("/home/u"),
+        );
+        assert_eq!(
+            dirs,
+            vec![(UserDir::Download, PathBuf::from("/home/u/Téléchargements"))]
+        );
+    }
+
+    #[test]
+    fn absolute() {
+        let dirs = parse_user_dirs("XDG_MUSIC_DIR=\"/media/music\"", &PathBuf::from("/home/u"));
+        assert_eq!(dirs, vec![(UserDir::Music, PathBuf::from("/media/music"))]);
+    }
+
+    #[test]
+    fn turned_off() {
+        let dirs = parse_user_dirs("XDG_TEMPLATES_DIR=\"$HOME/\"", &PathBuf::from("/home/u"));
+        assert_eq!(dirs, vec![]);
+    }
+
+    #[test]
+    fn unknown_and_relative() {
+        let dirs = parse_user_dirs(
+            "XDG_OTHER_DIR=\"$HOME/x\"\nXDG_VIDEOS_DIR=\"videos\"",
+            &PathBuf::from("/home/u"),
+        );
+        assert_eq!(dirs, vec![]);
+    }
+}
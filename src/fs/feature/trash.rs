@@ -0,0 +1,196 @@
+//! Reading the user’s trash can, as laid out by the FreeDesktop.org Trash
+//! specification, so files that have been trashed from the same path can
+//! be marked, and so the trash itself can be listed.
+//!
+//! The trash directory holds the trashed files in `files`, and a
+//! `.trashinfo` file for each of them in `info`, which looks like this:
+//!
+//! ```text
+//! [Trash Info]
+//! Path=/home/user/notes%20old.txt
+//! DeletionDate=2024-01-31T12:00:00
+//! ```
+//!
+//! Only the trash in the user’s home directory is read; the ones at the
+//! top of other mounted filesystems aren’t.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+static TRASHED: OnceLock<HashSet<PathBuf>> = OnceLock::new();
+
+/// Where the trash directory is.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Trash {
+    pub dir: PathBuf,
+}
+
+/// One file in the trash.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TrashEntry {
+    /// The absolute path the file was trashed from.
+    pub original: PathBuf,
+
+    /// When the file was trashed, in the local time zone, as written in
+    /// its info file.
+    pub deleted: Option<String>,
+}
+
+impl Trash {
+    /// Reads the info file for everything in the trash, in the order they
+    /// were trashed. A trash that doesn’t exist yet is empty.
+    pub fn entries(&self) -> io::Result<Vec<TrashEntry>> {
+        let info_dir = self.dir.join("info");
+        let read_dir = match fs::read_dir(info_dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut entries = Vec::new();
+        for child in read_dir {
+            let path = child?.path();
+            if path.extension().map_or(true, |ext| ext != "trashinfo") {
+                continue;
+            }
+
+            // Info files can be removed by something emptying the trash
+            // while it’s being read, which is no reason to fail.
+            match fs::read_to_string(&path) {
+                Ok(contents) => entries.extend(parse_trash_info(&contents)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        entries.sort_by(|a, b| a.deleted.cmp(&b.deleted));
+        Ok(entries)
+    }
+}
+
+/// Parses the contents of an info file, returning `None` if it doesn’t say
+/// where the file was trashed from.
+fn parse_trash_info(contents: &str) -> Option<TrashEntry> {
+    let mut original = None;
+    let mut deleted = None;
+    let mut in_section = false;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line == "[Trash Info]";
+        } else if !in_section {
+            continue;
+        } else if let Some(path) = line.strip_prefix("Path=") {
+            original = Some(PathBuf::from(percent_decode(path)));
+        } else if let Some(date) = line.strip_prefix("DeletionDate=") {
+            deleted = Some(date.to_string());
+        }
+    }
+
+    Some(TrashEntry {
+        original: original?,
+        deleted,
+    })
+}
+
+/// Decodes the `%XX` escapes that paths in info files are written with,
+/// leaving any that aren’t valid as they are.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        if let Some(byte) = escape {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Makes a path absolute, without resolving symlinks, so it can be compared
+/// with the paths in info files.
+fn absolute(path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir().unwrap_or_default().join(path)
+    };
+
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .collect()
+}
+
+/// Remembers where each file in the trash was trashed from. This only has
+/// an effect the first time it’s called, before anything has been drawn.
+pub fn configure(entries: &[TrashEntry]) {
+    let _ = TRASHED.set(entries.iter().map(|e| e.original.clone()).collect());
+}
+
+/// Whether something was trashed from the given path.
+pub fn is_trashed(path: &Path) -> bool {
+    TRASHED
+        .get()
+        .is_some_and(|trashed| !trashed.is_empty() && trashed.contains(&absolute(path)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn info_file() {
+        let entry = parse_trash_info(
+            "[Trash Info]\nPath=/home/user/notes%20old.txt\nDeletionDate=2024-01-31T12:00:00\n",
+        );
+        assert_eq!(
+            entry,
+            Some(TrashEntry {
+                original: PathBuf::from("/home/user/notes old.txt"),
+                deleted: Some("2024-01-31T12:00:00".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn other_section() {
+        assert_eq!(parse_trash_info("[Other]\nPath=/tmp/a\n"), None);
+    }
+
+    #[test]
+    fn no_date() {
+        let entry = parse_trash_info("[Trash Info]\nPath=/tmp/a\n").unwrap();
+        assert_eq!(entry.deleted, None);
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(percent_decode("caf%C3%A9%2fx"), "café/x");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn absolute_paths() {
+        assert_eq!(absolute(Path::new("/tmp/./a")), PathBuf::from("/tmp/a"));
+        assert!(absolute(Path::new("./a")).is_absolute());
+    }
+}
@@ -0,0 +1,327 @@
+//! Reading the version resource of Windows executables and DLLs, for the
+//! `--file-version` column.
+//!
+//! These are Portable Executable files, and the version numbers are kept in
+//! a `VS_FIXEDFILEINFO` structure at the start of the `RT_VERSION` resource.
+//! Getting there means following the headers to the resource section, then
+//! walking its three-level directory of resource types, names, and
+//! languages. Each step only reads the few bytes it needs, and the number of
+//! sections and directory entries looked at is capped, so a large or
+//! corrupted file can’t make this read much more than a page or two of it.
+//!
+//! This doesn’t use the Windows API, so the versions of DLLs can be seen
+//! from other platforms too, such as on a mounted Windows drive.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::fs::fields as f;
+
+/// Which of the two version numbers in a version resource to show.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum VersionKind {
+    /// The version of the file itself.
+    File,
+
+    /// The version of the product the file is distributed with.
+    Product,
+}
+
+/// Both of the version numbers in a file’s version resource.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct VersionInfo {
+    pub file: f::FileVersion,
+    pub product: f::FileVersion,
+}
+
+impl VersionInfo {
+    /// The version number of the given kind.
+    pub fn get(self, kind: VersionKind) -> f::FileVersion {
+        match kind {
+            VersionKind::File => self.file,
+            VersionKind::Product => self.product,
+        }
+    }
+}
+
+/// The extensions of the files that are looked inside for a version
+/// resource, so that other files don’t even have to be opened.
+pub static EXTENSIONS: &[&str] = &["exe", "dll", "sys", "ocx", "cpl", "scr", "mui"];
+
+const RT_VERSION: u32 = 16;
+const FIXED_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+const MAX_SECTIONS: u16 = 96;
+const MAX_ENTRIES: u16 = 256;
+
+/// Reads the version resource of the file at the given path, returning
+/// `None` if it isn’t a Portable Executable file or doesn’t have one.
+pub fn read(path: &Path) -> io::Result<Option<VersionInfo>> {
+    find(&mut std::fs::File::open(path)?)
+}
+
+/// Follows the headers of a Portable Executable file to its version
+/// resource, and reads the version numbers out of it.
+fn find<R: Read + Seek>(r: &mut R) -> io::Result<Option<VersionInfo>> {
+    let dos_header = read_at(r, 0, 64)?;
+    if dos_header.len() < 64 || &dos_header[..2] != b"MZ" {
+        return Ok(None);
+    }
+
+    let pe_offset = u64::from(u32_at(&dos_header, 0x3C));
+    let pe_header = read_at(r, pe_offset, 24)?;
+    if pe_header.len() < 24 || &pe_header[..4] != b"PE\0\0" {
+        return Ok(None);
+    }
+
+    let section_count = u16_at(&pe_header, 6).min(MAX_SECTIONS);
+    let optional_size = u16_at(&pe_header, 20);
+    let optional_header = read_at(r, pe_offset + 24, usize::from(optional_size))?;
+
+    // The data directories come after the rest of the optional header,
+    // which is 16 bytes longer for 64-bit files. The resource table is the
+    // third of them.
+    let directories_at = match optional_header.get(..2) {
+        Some([0x0B, 0x01]) => 96,
+        Some([0x0B, 0x02]) => 112,
+        _ => return Ok(None),
+    };
+    if optional_header.len() < directories_at + 24
+        || u32_at(&optional_header, directories_at - 4) < 3
+    {
+        return Ok(None);
+    }
+    let resource_rva = u32_at(&optional_header, directories_at + 16);
+    if resource_rva == 0 {
+        return Ok(None);
+    }
+
+    let sections_at = pe_offset + 24 + u64::from(optional_size);
+    let sections = read_at(r, sections_at, usize::from(section_count) * 40)?;
+    let sections: Vec<Section> = sections.chunks_exact(40).map(Section::parse).collect();
+
+    let Some(resources_at) = file_offset(&sections, resource_rva) else {
+        return Ok(None);
+    };
+
+    // Resource type, then name, then language. Only the type has to match;
+    // the first name and language of a version resource are as good as any.
+    let mut offset = 0;
+    for id in [Some(RT_VERSION), None, None] {
+        match entry(r, resources_at, offset, id)? {
+            Some(next) => offset = next,
+            None => return Ok(None),
+        }
+    }
+
+    let data_entry = read_at(r, resources_at + u64::from(offset), 8)?;
+    if data_entry.len() < 8 {
+        return Ok(None);
+    }
+    let Some(data_at) = file_offset(&sections, u32_at(&data_entry, 0)) else {
+        return Ok(None);
+    };
+
+    Ok(parse_version_info(&read_at(r, data_at, 92)?))
+}
+
+/// Looks through the resource directory at the given offset for the entry
+/// with the given ID, or the first entry if there isn’t one to look for,
+/// returning the offset it points to.
+fn entry<R: Read + Seek>(
+    r: &mut R,
+    resources_at: u64,
+    offset: u32,
+    id: Option<u32>,
+) -> io::Result<Option<u32>> {
+    let directory_at = resources_at + u64::from(offset & 0x7FFF_FFFF);
+    let header = read_at(r, directory_at, 16)?;
+    if header.len() < 16 {
+        return Ok(None);
+    }
+
+    let count = u16_at(&header, 12)
+        .saturating_add(u16_at(&header, 14))
+        .min(MAX_ENTRIES);
+    let entries = read_at(r, directory_at + 16, usize::from(count) * 8)?;
+    let found = entries
+        .chunks_exact(8)
+        .find(|entry| id.map_or(true, |id| u32_at(entry, 0) == id))
+        .map(|entry| u32_at(entry, 4) & 0x7FFF_FFFF);
+
+    Ok(found)
+}
+
+/// Reads the version numbers from the start of a `VS_VERSIONINFO`
+/// structure, where the `VS_FIXEDFILEINFO` one goes after a length, a
+/// type, and a null-terminated UTF-16 key, padded to four bytes.
+fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    if data.len() < 92 || u16_at(data, 2) < 52 {
+        return None;
+    }
+
+    let key: Vec<u8> = "VS_VERSION_INFO"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    if data[6..6 + key.len()] != key[..] || u32_at(data, 40) != FIXED_INFO_SIGNATURE {
+        return None;
+    }
+
+    let version = |at| {
+        let (high, low) = (u32_at(data, at), u32_at(data, at + 4));
+        #[allow(clippy::cast_possible_truncation)]
+        f::FileVersion([
+            (high >> 16) as u16,
+            high as u16,
+            (low >> 16) as u16,
+            low as u16,
+        ])
+    };
+
+    Some(VersionInfo {
+        file: version(48),
+        product: version(56),
+    })
+}
+
+/// Where one section of the file gets loaded, and where it is in the file.
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_size: u32,
+    raw_offset: u32,
+}
+
+impl Section {
+    fn parse(header: &[u8]) -> Self {
+        Self {
+            virtual_size: u32_at(header, 8),
+            virtual_address: u32_at(header, 12),
+            raw_size: u32_at(header, 16),
+            raw_offset: u32_at(header, 20),
+        }
+    }
+}
+
+/// Turns an address that the file would be loaded at into an offset into
+/// the file, going by the section it’s in.
+fn file_offset(sections: &[Section], rva: u32) -> Option<u64> {
+    sections
+        .iter()
+        .find(|s| {
+            let size = s.virtual_size.max(s.raw_size);
+            rva >= s.virtual_address && rva - s.virtual_address < size
+        })
+        .map(|s| u64::from(rva - s.virtual_address) + u64::from(s.raw_offset))
+}
+
+/// Reads up to `len` bytes from the given offset, stopping early at the end
+/// of the file.
+fn read_at<R: Read + Seek>(r: &mut R, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::with_capacity(len);
+    r.seek(SeekFrom::Start(offset))?;
+    r.take(len as u64).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Builds a 32-bit PE file with one section, loaded at 0x1000 and kept
+    /// at 0x200 in the file, that holds a version resource.
+    fn executable(file: [u16; 4], product: [u16; 4]) -> Vec<u8> {
+        let mut image = vec![0; 0x200];
+        image[..2].copy_from_slice(b"MZ");
+        image[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+
+        let pe = 0x40;
+        image[pe..pe + 4].copy_from_slice(b"PE\0\0");
+        image[pe + 6..pe + 8].copy_from_slice(&1u16.to_le_bytes());
+        image[pe + 20..pe + 22].copy_from_slice(&224u16.to_le_bytes());
+
+        let optional = pe + 24;
+        image[optional..optional + 2].copy_from_slice(&0x10Bu16.to_le_bytes());
+        image[optional + 92..optional + 96].copy_from_slice(&16u32.to_le_bytes());
+        image[optional + 112..optional + 116].copy_from_slice(&0x1000u32.to_le_bytes());
+
+        let section = optional + 224;
+        image[section + 8..section + 12].copy_from_slice(&0x200u32.to_le_bytes());
+        image[section + 12..section + 16].copy_from_slice(&0x1000u32.to_le_bytes());
+        image[section + 16..section + 20].copy_from_slice(&0x200u32.to_le_bytes());
+        image[section + 20..section + 24].copy_from_slice(&0x200u32.to_le_bytes());
+
+        let mut resources = vec![0; 0x200];
+        let mut directory = |at: usize, id: u32, next: u32| {
+            resources[at + 14..at + 16].copy_from_slice(&1u16.to_le_bytes());
+            resources[at + 16..at + 20].copy_from_slice(&id.to_le_bytes());
+            resources[at + 20..at + 24].copy_from_slice(&next.to_le_bytes());
+        };
+        directory(0x00, RT_VERSION, 0x8000_0018);
+        directory(0x18, 1, 0x8000_0030);
+        directory(0x30, 0x409, 0x48);
+        resources[0x48..0x4C].copy_from_slice(&0x1060u32.to_le_bytes());
+        resources[0x4C..0x50].copy_from_slice(&92u32.to_le_bytes());
+
+        let info = 0x60;
+        resources[info..info + 2].copy_from_slice(&92u16.to_le_bytes());
+        resources[info + 2..info + 4].copy_from_slice(&52u16.to_le_bytes());
+        for (i, unit) in "VS_VERSION_INFO".encode_utf16().enumerate() {
+            resources[info + 6 + i * 2..info + 8 + i * 2].copy_from_slice(&unit.to_le_bytes());
+        }
+        let fixed = info + 40;
+        resources[fixed..fixed + 4].copy_from_slice(&FIXED_INFO_SIGNATURE.to_le_bytes());
+        for (at, [a, b, c, d]) in [(8, file), (16, product)] {
+            let high = u32::from(a) << 16 | u32::from(b);
+            let low = u32::from(c) << 16 | u32::from(d);
+            resources[fixed + at..fixed + at + 4].copy_from_slice(&high.to_le_bytes());
+            resources[fixed + at + 4..fixed + at + 8].copy_from_slice(&low.to_le_bytes());
+        }
+
+        image.extend(resources);
+        image
+    }
+
+    #[test]
+    fn file_and_product() {
+        let image = executable([10, 0, 19041, 1], [10, 2, 0, 7]);
+        let info = find(&mut Cursor::new(image)).unwrap().unwrap();
+        assert_eq!(
+            info.get(VersionKind::File),
+            f::FileVersion([10, 0, 19041, 1])
+        );
+        assert_eq!(
+            info.get(VersionKind::Product),
+            f::FileVersion([10, 2, 0, 7])
+        );
+    }
+
+    #[test]
+    fn not_an_executable() {
+        let text = b"MZ is how this line of text starts".to_vec();
+        assert_eq!(find(&mut Cursor::new(text)).unwrap(), None);
+    }
+
+    #[test]
+    fn no_version_resource() {
+        let mut image = executable([1, 0, 0, 0], [1, 0, 0, 0]);
+        image[0x200 + 16..0x200 + 20].copy_from_slice(&3u32.to_le_bytes());
+        assert_eq!(find(&mut Cursor::new(image)).unwrap(), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let image = executable([1, 0, 0, 0], [1, 0, 0, 0]);
+        assert_eq!(find(&mut Cursor::new(&image[..0x280])).unwrap(), None);
+    }
+}
@@ -14,7 +14,7 @@ pub const ENABLED: bool = cfg!(any(
     target_os = "freebsd"
 ));
 
-#[derive(Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Attribute {
     pub name: String,
     pub value: Option<Vec<u8>>,
@@ -659,6 +659,52 @@ fn display_macl(attribute: &Attribute) -> Option<String> {
         })
 }
 
+/// The name of the extended attribute that Finder keeps a file’s tags in.
+#[cfg(target_os = "macos")]
+pub const FINDER_TAGS_ATTRIBUTE: &str = "com.apple.metadata:_kMDItemUserTags";
+
+/// The colour of a tag that Finder has put on a file, in the order Finder
+/// numbers them.
+#[cfg(target_os = "macos")]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum FinderColour {
+    None,
+    Gray,
+    Green,
+    Purple,
+    Blue,
+    Yellow,
+    Red,
+    Orange,
+}
+
+/// Decodes the colours of a file’s Finder tags from the value of its
+/// `_kMDItemUserTags` attribute. This is a binary plist holding an array
+/// of strings, each of which is a tag’s name, followed by a newline and
+/// the number of its colour if it has one.
+#[cfg(target_os = "macos")]
+pub fn finder_tags(value: &[u8]) -> Vec<FinderColour> {
+    let Ok(plist::Value::Array(tags)) = plist::Value::from_reader(io::Cursor::new(value)) else {
+        return Vec::new();
+    };
+
+    tags.iter()
+        .filter_map(plist::Value::as_string)
+        .map(
+            |tag| match tag.rsplit_once('\n').map(|(_, colour)| colour) {
+                Some("1") => FinderColour::Gray,
+                Some("2") => FinderColour::Green,
+                Some("3") => FinderColour::Purple,
+                Some("4") => FinderColour::Blue,
+                Some("5") => FinderColour::Yellow,
+                Some("6") => FinderColour::Red,
+                Some("7") => FinderColour::Orange,
+                _ => FinderColour::None,
+            },
+        )
+        .collect()
+}
+
 // plist::XmlWriter takes the writer instead of borrowing it.  This is a
 // wrapper around a borrowed vector that just forwards the Write trait
 // calls to the borrowed vector.
@@ -706,3 +752,30 @@ fn plist_value_display(value: &[u8]) -> Option<String> {
         .map(|s| format!("<plist version=\"1.0\">{}</plist>", s.replace('\n', "")))
     })
 }
+
+#[cfg(all(test, target_os = "macos"))]
+mod test {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<u8> {
+        let mut value = Vec::new();
+        plist::Value::Array(tags.iter().map(|&tag| tag.into()).collect())
+            .to_writer_binary(&mut value)
+            .unwrap();
+        value
+    }
+
+    #[test]
+    fn coloured_and_plain() {
+        let value = tags(&["Red\n6", "Work", "Orange\n7"]);
+        assert_eq!(
+            finder_tags(&value),
+            [FinderColour::Red, FinderColour::None, FinderColour::Orange]
+        );
+    }
+
+    #[test]
+    fn not_a_plist() {
+        assert_eq!(finder_tags(b"Red\n6"), []);
+    }
+}
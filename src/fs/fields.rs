@@ -79,6 +79,29 @@ pub struct Permissions {
     pub setuid:         bool,
 }
 
+impl Permissions {
+    /// The permissions as a Unix mode, such as `0o4755`, without the bits
+    /// that encode the file’s type.
+    pub fn bits(self) -> u32 {
+        [
+            self.setuid,
+            self.setgid,
+            self.sticky,
+            self.user_read,
+            self.user_write,
+            self.user_execute,
+            self.group_read,
+            self.group_write,
+            self.group_execute,
+            self.other_read,
+            self.other_write,
+            self.other_execute,
+        ]
+        .iter()
+        .fold(0, |bits, &bit| bits << 1 | u32::from(bit))
+    }
+}
+
 /// The file's `FileAttributes` field, available only on Windows.
 #[derive(Copy, Clone)]
 #[rustfmt::skip]
@@ -110,6 +133,18 @@ pub struct OctalPermissions {
     pub permissions: Permissions,
 }
 
+/// The permission bits that new files and directories would get if they
+/// were created in a directory, for `--simulate-umask`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct NewPermissions {
+    pub file: u32,
+    pub directory: u32,
+
+    /// Whether the directory’s default ACL decided them, rather than the
+    /// umask.
+    pub from_acl: bool,
+}
+
 /// A file’s number of hard links on the filesystem.
 ///
 /// Under Unix, a file can exist on the filesystem only once but appear in
@@ -170,6 +205,11 @@ pub enum Size {
     /// See this answer for more: <https://unix.stackexchange.com/a/68266>
     None,
 
+    /// This file is on a virtual filesystem such as procfs or sysfs, where
+    /// files claim to be empty and only produce their contents when read,
+    /// so the size it reports is meaningless.
+    Virtual,
+
     /// This file is a block or character device, so instead of a size, print
     /// out the file’s major and minor device IDs.
     ///
@@ -183,12 +223,30 @@ pub enum Size {
 /// You can see what these device numbers mean:
 /// - <http://www.lanana.org/docs/device-list/>
 /// - <http://www.lanana.org/docs/device-list/devices-2.6+.txt>
-#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
 pub struct DeviceIDs {
     pub major: u32,
     pub minor: u32,
 }
 
+/// What the kernel knows about the Unix domain socket bound to a socket
+/// file, for the sockets that can be found in its socket table.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SocketState {
+    /// The socket is accepting connections, and has this many open.
+    Listening(usize),
+
+    /// The socket is connected to this many peers without listening.
+    Connected(usize),
+
+    /// The socket is bound, but neither listening nor connected.
+    Unconnected,
+
+    /// No socket is bound to the file, usually because whatever created it
+    /// has since exited without cleaning up.
+    Stale,
+}
+
 /// One of a file’s timestamps (created, accessed, or modified).
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Time {
@@ -196,6 +254,90 @@ pub struct Time {
     pub nanoseconds: time_t,
 }
 
+/// How a file compares with its counterpart in the directory given to
+/// `--diff-against`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum DiffStatus {
+    /// The counterpart looks the same.
+    Same,
+
+    /// The counterpart differs, or is a different type of file.
+    Different,
+
+    /// There’s no counterpart.
+    OnlyHere,
+
+    /// The counterpart couldn’t be compared with.
+    Unknown,
+}
+
+/// A file’s place in its listing when ordered by modification time, with
+/// the newest file being 1. Files modified at the same time share a rank.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct AgeRank(pub usize);
+
+/// Which group of files with the same contents a file is in, numbered from
+/// 1, for files that have at least one duplicate.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct DupeGroup(pub usize);
+
+/// A version number from the version resource of a Windows executable or
+/// DLL, in its four parts: major, minor, build, and revision.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct FileVersion(pub [u16; 4]);
+
+/// How a text file is encoded, as far as it matters when sharing it between
+/// platforms.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TextEncoding {
+    /// The file isn’t a regular file, or couldn’t be read.
+    Unknown,
+
+    /// The file has null bytes in it, so it isn’t text.
+    Binary,
+
+    /// The file starts with a UTF-16 byte order mark.
+    Utf16,
+
+    /// The file is text in an ASCII-compatible encoding.
+    Text {
+        /// Whether it starts with a UTF-8 byte order mark.
+        bom: bool,
+
+        /// Which line ending it uses the most.
+        line_endings: LineEndings,
+    },
+}
+
+/// The line ending that a text file uses the most.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum LineEndings {
+    /// There aren’t any line endings.
+    None,
+    Lf,
+    Crlf,
+    Cr,
+}
+
+/// Which layer of an overlay filesystem a file comes from.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum OverlayLayer {
+    /// The file isn’t on an overlay filesystem.
+    NotOverlay,
+
+    /// The file is only in the lower layers, as it was in the image.
+    Lower,
+
+    /// The file was copied up from the lower layers when it was changed.
+    Modified,
+
+    /// The file was created in the upper layer.
+    Upper,
+
+    /// The file is on an overlay, but its layers couldn’t be read.
+    Unknown,
+}
+
 /// A file’s status in a Git repository. Whether a file is in a repository or
 /// not is handled by the Git module, rather than having a “null” variant in
 /// this enum.
@@ -281,3 +423,101 @@ impl Default for SubdirGitRepo {
 /// The user file flags on the file. This will only ever be a number;
 /// looking up the flags is done in the `display` module.
 pub struct Flags(pub flag_t);
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+impl Flags {
+    /// The flags that are set, from the lowest bit to the highest.
+    pub fn iter(self) -> impl Iterator<Item = FileFlag> {
+        (0..flag_t::BITS)
+            .map(|bit| 1 << bit)
+            .filter(move |bit| self.0 & bit != 0)
+            .map(FileFlag::from_bit)
+    }
+}
+
+/// One of the BSD file flags that `chflags` sets. The bits for these are the
+/// same on every BSD, though not every BSD has all of them. The `u` flags
+/// can be set by a file’s owner, and the `s` flags only by root.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum FileFlag {
+    /// `nodump`: the file is skipped by `dump`.
+    NoDump,
+
+    /// `uchg`: the file can’t be changed, renamed, or deleted.
+    UserImmutable,
+
+    /// `uappnd`: the file can only be appended to.
+    UserAppend,
+
+    /// `opaque`: the directory hides what’s beneath it in a union mount.
+    Opaque,
+
+    /// `hidden`: the file is hidden from Finder and other file browsers.
+    Hidden,
+
+    /// `arch`: the file has been archived.
+    Archived,
+
+    /// `schg`: the file can’t be changed, renamed, or deleted, even by root.
+    SystemImmutable,
+
+    /// `sappnd`: the file can only be appended to, even by root.
+    SystemAppend,
+
+    /// Any other flag, such as `uunlnk` on FreeBSD or `restricted` on macOS,
+    /// given by its bit.
+    Other(flag_t),
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+impl FileFlag {
+    /// The flags with names of their own, along with the bits they’re kept in.
+    #[rustfmt::skip]
+    const NAMED: [(flag_t, Self); 8] = [
+        (0x0000_0001, Self::NoDump),           // UF_NODUMP
+        (0x0000_0002, Self::UserImmutable),    // UF_IMMUTABLE
+        (0x0000_0004, Self::UserAppend),       // UF_APPEND
+        (0x0000_0008, Self::Opaque),           // UF_OPAQUE
+        (0x0000_8000, Self::Hidden),           // UF_HIDDEN
+        (0x0001_0000, Self::Archived),         // SF_ARCHIVED
+        (0x0002_0000, Self::SystemImmutable),  // SF_IMMUTABLE
+        (0x0004_0000, Self::SystemAppend),     // SF_APPEND
+    ];
+
+    fn from_bit(bit: flag_t) -> Self {
+        Self::NAMED
+            .iter()
+            .find(|(named, _)| *named == bit)
+            .map_or(Self::Other(bit), |(_, flag)| *flag)
+    }
+
+    /// The bit this flag is kept in.
+    pub fn bit(self) -> flag_t {
+        match self {
+            Self::Other(bit) => bit,
+            _ => Self::NAMED
+                .iter()
+                .find(|(_, flag)| *flag == self)
+                .map_or(0, |(bit, _)| *bit),
+        }
+    }
+}
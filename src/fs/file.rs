@@ -13,6 +13,7 @@ use std::str;
 #[cfg(unix)]
 use std::sync::Mutex;
 use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 
 use chrono::prelude::*;
 
@@ -20,7 +21,16 @@ use log::*;
 #[cfg(unix)]
 use once_cell::sync::Lazy;
 
+use crate::diagnostics;
+use crate::fs::cache;
+use crate::fs::contents;
 use crate::fs::dir::Dir;
+use crate::fs::dir_action::Ancestors;
+#[cfg(unix)]
+use crate::fs::feature::acl::{self, DefaultAcl};
+use crate::fs::feature::overlay;
+use crate::fs::feature::sockets::state_of as socket_state_of;
+use crate::fs::feature::version_info::{self, VersionInfo};
 use crate::fs::feature::xattr;
 use crate::fs::feature::xattr::{Attribute, FileAttributes};
 use crate::fs::fields as f;
@@ -29,6 +39,7 @@ use crate::fs::recursive_size::RecursiveSize;
 
 use super::mounts::all_mounts;
 use super::mounts::MountedFs;
+use super::mounts::{self, Usage};
 
 // Maps (device_id, inode) => (size_in_bytes, size_in_blocks)
 // Mutex::new is const but HashMap::new is not const requiring us to use lazy
@@ -237,19 +248,32 @@ impl<'dir> File<'dir> {
     /// Read the extended attributes of a file path.
     fn gather_extended_attributes(&self) -> Vec<Attribute> {
         if xattr::ENABLED {
+            // The metadata is the link’s own, so it can only key the cache
+            // for attributes read from the link too.
+            if !self.deref_links {
+                if let Some(xattrs) = cache::xattrs(&self.metadata) {
+                    return xattrs;
+                }
+            }
+
             let attributes = if self.deref_links {
                 self.path.attributes()
             } else {
                 self.path.symlink_attributes()
             };
             match attributes {
-                Ok(xattrs) => xattrs,
+                Ok(xattrs) => {
+                    if !self.deref_links {
+                        cache::store_xattrs(&self.metadata, &xattrs);
+                    }
+                    xattrs
+                }
                 Err(e) => {
-                    error!(
-                        "Error looking up extended attributes for {}: {}",
+                    diagnostics::warning(format_args!(
+                        "eza: Error looking up extended attributes for {}: {}",
                         self.path.display(),
                         e
-                    );
+                    ));
                     Vec::new()
                 }
             }
@@ -264,6 +288,31 @@ impl<'dir> File<'dir> {
             .get_or_init(|| self.gather_extended_attributes())
     }
 
+    /// The value of the extended attribute with the given name, if this
+    /// file has it and it has a value.
+    pub fn extended_attribute(&self, name: &str) -> Option<&[u8]> {
+        self.extended_attributes()
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .and_then(|attribute| attribute.value.as_deref())
+    }
+
+    /// The colours of the tags that Finder has put on this file.
+    #[cfg(target_os = "macos")]
+    pub fn finder_tags(&self) -> Vec<xattr::FinderColour> {
+        self.extended_attribute(xattr::FINDER_TAGS_ATTRIBUTE)
+            .map(xattr::finder_tags)
+            .unwrap_or_default()
+    }
+
+    /// Whether this file has any capabilities set, which are kept in an
+    /// extended attribute.
+    pub fn has_capabilities(&self) -> bool {
+        self.extended_attributes()
+            .iter()
+            .any(|attribute| attribute.name == "security.capability")
+    }
+
     /// Whether this file is a directory on the filesystem.
     pub fn is_directory(&self) -> bool {
         self.metadata.is_dir()
@@ -291,9 +340,28 @@ impl<'dir> File<'dir> {
     ///
     /// Returns an IO error upon failure, but this shouldn’t be used to check
     /// if a `File` is a directory or not! For that, just use `is_directory()`.
+    ///
+    /// A directory read from inside another one knows the directories it’s
+    /// inside, and fails if it turns out to be one of them, which can happen
+    /// when this file is a symlink back up the tree.
     pub fn to_dir(&self) -> io::Result<Dir> {
         trace!("to_dir: reading dir");
-        Dir::read_dir(self.path.clone())
+        let target_metadata;
+        let metadata = if self.is_link() {
+            target_metadata = std::fs::metadata(&self.path)?;
+            &target_metadata
+        } else {
+            &self.metadata
+        };
+
+        let ancestors = match self.parent_dir {
+            Some(parent) => parent.ancestors.enter(metadata)?,
+            None => Ancestors::default().enter(metadata)?,
+        };
+
+        let mut dir = Dir::read_dir(self.path.clone())?;
+        dir.ancestors = ancestors;
+        Ok(dir)
     }
 
     /// Whether this file is a regular file on the filesystem — that is, not a
@@ -364,6 +432,17 @@ impl<'dir> File<'dir> {
         None
     }
 
+    /// How much space the filesystem mounted on this directory has, if
+    /// it’s a mount point. Virtual filesystems such as `/proc` don’t have
+    /// any space, so there’s nothing to say about them.
+    pub fn mount_usage(&self) -> Option<Usage> {
+        if self.is_mount_point() {
+            mounts::usage(&self.path).filter(|usage| usage.total > 0)
+        } else {
+            None
+        }
+    }
+
     /// Re-prefixes the path pointed to by this file, if it’s a symlink, to
     /// make it an absolute path that can be accessed from whichever
     /// directory exa is being run from.
@@ -409,7 +488,11 @@ impl<'dir> File<'dir> {
                 let ext = File::ext(&path);
                 let name = File::filename(&path);
                 let extended_attributes = OnceLock::new();
-                let absolute_path_cell = OnceLock::from(Some(absolute_path));
+                // The reoriented path is only relative to where eza is run
+                // from, so it gets made absolute like any other file’s.
+                let absolute_path_cell = OnceLock::from(Some(
+                    std::fs::canonicalize(&absolute_path).unwrap_or(absolute_path),
+                ));
                 let file = File {
                     parent_dir: None,
                     path,
@@ -566,6 +649,132 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// Whether this file is on a virtual filesystem, going by the directory
+    /// it was listed in, or by asking about the file itself otherwise.
+    pub fn is_on_virtual_filesystem(&self) -> bool {
+        self.parent_dir.map_or_else(
+            || crate::fs::dir::is_virtual_filesystem(&self.path),
+            Dir::is_virtual,
+        )
+    }
+
+    /// The actual length of a file on a virtual filesystem, found by reading
+    /// up to the first mebibyte of it. Reads that would block, such as on
+    /// `/proc/kmsg`, or that fail outright give `None`.
+    #[cfg(unix)]
+    pub fn virtual_length(&self) -> Option<u64> {
+        use std::io::Read;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        const LIMIT: u64 = 1024 * 1024;
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&self.path)
+            .ok()?;
+
+        io::copy(&mut file.take(LIMIT), &mut io::sink()).ok()
+    }
+
+    /// The state of the socket bound to this file, if it’s a socket and its
+    /// state can be found on this platform.
+    #[cfg(unix)]
+    pub fn socket_state(&self) -> Option<f::SocketState> {
+        if self.is_socket() {
+            socket_state_of(&self.path)
+        } else {
+            None
+        }
+    }
+
+    /// The size this file would be with its line endings normalised to LF,
+    /// for text files no bigger than the read limit. Anything else, such as
+    /// a directory, a binary file, or one that can’t be read, has no size.
+    pub fn text_size(&self) -> f::Size {
+        if !self.is_file() {
+            return f::Size::None;
+        }
+
+        match contents::read_bounded(&self.path, contents::READ_LIMIT) {
+            Ok(Some(bytes)) => {
+                contents::normalized_text_size(&bytes).map_or(f::Size::None, f::Size::Some)
+            }
+            Ok(None) => f::Size::None,
+            Err(e) => {
+                trace!("Error reading {:?} for its text size: {e}", self.path);
+                f::Size::None
+            }
+        }
+    }
+
+    /// How this file is encoded, going by the start of it, for regular
+    /// files that can be read.
+    pub fn text_encoding(&self) -> f::TextEncoding {
+        if !self.is_file() {
+            return f::TextEncoding::Unknown;
+        }
+
+        match contents::read_head(&self.path, contents::SNIFF_LIMIT) {
+            Ok(bytes) => contents::sniff_encoding(&bytes),
+            Err(e) => {
+                trace!("Error reading {:?} for its encoding: {e}", self.path);
+                f::TextEncoding::Unknown
+            }
+        }
+    }
+
+    /// The first line of this file, cut down to `length` characters, for
+    /// regular files that can be read and turn out to be text.
+    pub fn preview(&self, length: usize) -> Option<String> {
+        if !self.is_file() {
+            return None;
+        }
+
+        match contents::read_head(&self.path, contents::SNIFF_LIMIT) {
+            Ok(bytes) => contents::preview(&bytes, length),
+            Err(e) => {
+                trace!("Error reading {:?} for its preview: {e}", self.path);
+                None
+            }
+        }
+    }
+
+    /// The version numbers in this file’s version resource, for Windows
+    /// executables and DLLs that have one.
+    pub fn version_info(&self) -> Option<VersionInfo> {
+        let is_executable = self
+            .ext
+            .as_deref()
+            .is_some_and(|ext| version_info::EXTENSIONS.contains(&ext));
+        if !self.is_file() || !is_executable {
+            return None;
+        }
+
+        match version_info::read(&self.path) {
+            Ok(info) => info,
+            Err(e) => {
+                trace!("Error reading {:?} for its version: {e}", self.path);
+                None
+            }
+        }
+    }
+
+    /// Which layer of an overlay filesystem this file comes from.
+    pub fn overlay_layer(&self) -> f::OverlayLayer {
+        overlay::layer(&self.path)
+    }
+
+    /// This file’s major and minor device numbers, if it’s a block or
+    /// character device.
+    #[cfg(unix)]
+    pub fn device_ids(&self) -> Option<f::DeviceIDs> {
+        match self.size() {
+            f::Size::DeviceIDs(ids) => Some(ids),
+            _ => None,
+        }
+    }
+
     /// Returns the size of the file or indicates no size if it's a directory.
     ///
     /// For Windows platforms, the size of directories is not computed and will
@@ -690,18 +899,21 @@ impl<'dir> File<'dir> {
     /// The naive approach, as one would think that this info may have been cached.
     /// but as mentioned in the size function comment above, different filesystems
     /// make it difficult to get any info about a dir by it's size, so this may be it.
+    /// Only the first entry is ever read, so this stays cheap for huge directories.
     fn is_empty_directory(&self) -> bool {
         trace!("is_empty_directory: reading dir");
-        match Dir::read_dir(self.path.clone()) {
+        match std::fs::read_dir(&self.path) {
             // . & .. are skipped, if the returned iterator has .next(), it's not empty
-            Ok(has_files) => has_files
-                .files(super::DotFilter::Dotfiles, None, false, false, false)
-                .next()
-                .is_none(),
+            Ok(mut entries) => entries.next().is_none(),
             Err(_) => false,
         }
     }
 
+    /// Whether this file is a regular file with nothing in it.
+    pub fn is_empty_file(&self) -> bool {
+        self.is_file() && self.metadata.len() == 0
+    }
+
     /// This file’s last modified timestamp, if available on this platform.
     pub fn modified_time(&self) -> Option<NaiveDateTime> {
         if self.is_link() && self.deref_links {
@@ -716,6 +928,31 @@ impl<'dir> File<'dir> {
             .ok()
     }
 
+    /// Whether this file was modified no longer ago than the given length of
+    /// time. Files with modification times in the future don’t count, as
+    /// their clocks are probably wrong rather than them being recent.
+    pub fn modified_within(&self, window: Duration) -> bool {
+        if self.is_link() && self.deref_links {
+            return match self.link_target_recurse() {
+                FileTarget::Ok(f) => f.modified_within(window),
+                _ => false,
+            };
+        }
+        self.metadata
+            .modified()
+            .ok()
+            .and_then(|mtime| SystemTime::now().duration_since(mtime).ok())
+            .is_some_and(|age| age <= window)
+    }
+
+    /// Whether this file’s name is the same as another’s in its directory
+    /// once Unicode normalisation and case are ignored. Files that weren’t
+    /// read from a directory have nothing to clash with.
+    pub fn has_name_clash(&self) -> bool {
+        self.parent_dir
+            .is_some_and(|dir| dir.has_name_clash(&self.path))
+    }
+
     /// This file’s last changed timestamp, if available on this platform.
     #[cfg(unix)]
     pub fn changed_time(&self) -> Option<NaiveDateTime> {
@@ -832,6 +1069,31 @@ impl<'dir> File<'dir> {
         })
     }
 
+    /// The permissions that new files and directories created in this
+    /// directory would get with the given umask, going by its default ACL
+    /// instead if it has one, and passing its setgid bit on to new
+    /// directories. Anything that isn’t a directory gets `None`.
+    #[cfg(unix)]
+    pub fn new_permissions(&self, umask: u32) -> Option<f::NewPermissions> {
+        if !self.is_directory() {
+            return None;
+        }
+
+        let acl = self
+            .extended_attribute(acl::DEFAULT_ACL_ATTRIBUTE)
+            .and_then(DefaultAcl::parse);
+        let create = |requested: u32| match acl {
+            Some(acl) => acl.apply(requested),
+            None => requested & !umask,
+        };
+
+        Some(f::NewPermissions {
+            file: create(0o666),
+            directory: create(0o777) | (self.metadata.mode() & modes::SETGID),
+            from_acl: acl.is_some(),
+        })
+    }
+
     #[cfg(windows)]
     pub fn attributes(&self) -> f::Attributes {
         let bits = self.metadata.file_attributes();
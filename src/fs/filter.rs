@@ -3,10 +3,18 @@
 use std::cmp::Ordering;
 use std::iter::FromIterator;
 #[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 
+use chrono::NaiveDateTime;
+
+use crate::fs::feature::git::GitCache;
+use crate::fs::feature::sort_command::SortCommand;
+use crate::fs::fields as f;
 use crate::fs::DotFilter;
 use crate::fs::File;
+use crate::output::table::TimeType;
 
 /// Flags used to manage the **file filter** process
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -43,8 +51,13 @@ pub struct FileFilter {
     /// second. Some users prefer it like this.
     pub list_dirs_first: bool,
 
-    /// The metadata field to sort by.
-    pub sort_field: SortField,
+    /// The metadata fields to sort by, with files that sort the same by
+    /// one field being sorted by the next.
+    pub sort_fields: Vec<SortKey>,
+
+    /// The command that produces the keys to sort by, when sorting by an
+    /// external command.
+    pub sort_command: Option<SortCommand>,
 
     // Flags that the file filtering process follow
     pub flags: Vec<FileFilterFlags>,
@@ -66,17 +79,80 @@ pub struct FileFilter {
     /// patterns won’t be displayed in the list.
     pub ignore_patterns: IgnorePatterns,
 
+    /// Glob patterns that files have to match to be listed, if any. Any
+    /// file name that matches *none* of these patterns won’t be displayed,
+    /// though directories always are, so that recursing can still find
+    /// the matching files inside them.
+    pub only_patterns: Option<IgnorePatterns>,
+
+    /// Glob patterns for files that are hidden in the same way as dotfiles,
+    /// read from the `hidden` configuration file. Any file name that matches
+    /// these patterns is only displayed when dotfiles are.
+    pub hidden_patterns: IgnorePatterns,
+
     /// Whether to ignore Git-ignored patterns.
     pub git_ignore: GitIgnore,
+
+    /// Whether to only list files that are, or aren’t, tracked by Git.
+    pub git_tracking: GitTracking,
+
+    /// Which user or group files have to be owned by to be listed.
+    pub owner: OwnerFilter,
+
+    /// Which permission bits files have to have to be listed, if any.
+    pub perm: Option<PermFilter>,
+
+    /// Whether to only list empty files and directories, or to hide them.
+    pub emptiness: EmptyFilter,
+
+    /// Whether directories are being recursed into, either with `--recurse`
+    /// or `--tree`, in which case the filters that would otherwise hide a
+    /// directory have to keep it so the files inside it can be found.
+    pub recursing: bool,
+
+    /// Which extensions files have to have to be listed, if any.
+    pub extensions: Option<ExtFilter>,
+
+    /// Which sizes files have to be between to be listed, if any.
+    pub size: Option<SizeFilter>,
+
+    /// Which times files have to have been modified (or changed, accessed,
+    /// or created) between to be listed, if any.
+    pub time: Option<TimeFilter>,
 }
 
 impl FileFilter {
     /// Remove every file in the given vector that does *not* pass the
     /// filter predicate for files found inside a directory.
-    pub fn filter_child_files(&self, files: &mut Vec<File<'_>>) {
+    pub fn filter_child_files(&self, files: &mut Vec<File<'_>>, git: Option<&GitCache>) {
         use FileFilterFlags::{OnlyDirs, OnlyFiles};
 
         files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        if let Some(only) = &self.only_patterns {
+            files.retain(|f| only.is_shown(f));
+        }
+        if !self.dot_filter.shows_dotfiles() {
+            files.retain(|f| !self.hidden_patterns.is_ignored(&f.name));
+        }
+        files.retain(|f| (self.recursing && f.is_directory()) || self.owner.is_owned(f));
+        if let Some(perm) = self.perm {
+            files.retain(|f| (self.recursing && f.is_directory()) || perm.matches(f));
+        }
+        if self.emptiness != EmptyFilter::Any {
+            files.retain(|f| self.emptiness.is_shown(f, self.recursing));
+        }
+        if let Some(extensions) = &self.extensions {
+            files.retain(|f| extensions.is_shown(f));
+        }
+        if let Some(size) = self.size {
+            files.retain(|f| size.is_shown(f));
+        }
+        if let Some(time) = self.time {
+            files.retain(|f| time.is_shown(f));
+        }
+        if self.git_tracking != GitTracking::Any {
+            files.retain(|f| self.git_tracking.is_shown(f, git));
+        }
 
         match (
             self.flags.contains(&OnlyDirs),
@@ -103,16 +179,59 @@ impl FileFilter {
     /// dotfile, because it’s been directly specified. But running
     /// `exa -I='*.ogg' music/*` should filter out the ogg files obtained
     /// from the glob, even though the globbing is done by the shell!
-    pub fn filter_argument_files(&self, files: &mut Vec<File<'_>>) {
+    ///
+    /// Directories aren’t checked for emptiness or Git tracking here, as the
+    /// directory at the root of a tree is listed as an argument file, and
+    /// hiding it would hide the whole tree.
+    pub fn filter_argument_files(&self, files: &mut Vec<File<'_>>, git: Option<&GitCache>) {
         files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        if let Some(only) = &self.only_patterns {
+            files.retain(|f| only.is_shown(f));
+        }
+        files.retain(|f| (self.recursing && f.is_directory()) || self.owner.is_owned(f));
+        if let Some(perm) = self.perm {
+            files.retain(|f| (self.recursing && f.is_directory()) || perm.matches(f));
+        }
+        if self.emptiness != EmptyFilter::Any {
+            files.retain(|f| f.is_directory() || self.emptiness.is_shown(f, self.recursing));
+        }
+        if let Some(extensions) = &self.extensions {
+            files.retain(|f| extensions.is_shown(f));
+        }
+        if let Some(size) = self.size {
+            files.retain(|f| size.is_shown(f));
+        }
+        if let Some(time) = self.time {
+            files.retain(|f| time.is_shown(f));
+        }
+        if self.git_tracking != GitTracking::Any {
+            files.retain(|f| f.is_directory() || self.git_tracking.is_shown(f, git));
+        }
     }
 
-    /// Sort the files in the given vector based on the sort field option.
-    pub fn sort_files<'a, F>(&self, files: &mut [F])
+    /// Sort the files in the given vector based on the sort field option,
+    /// using the Git cache to sort by each file’s status.
+    pub fn sort_files<'a, F>(&self, files: &mut [F], git: Option<&GitCache>)
     where
         F: AsRef<File<'a>>,
     {
-        files.sort_by(|a, b| self.sort_field.compare_files(a.as_ref(), b.as_ref()));
+        let by_command = self.sort_fields.first().map(|key| key.field) == Some(SortField::Command);
+        if let (true, Some(command)) = (by_command, &self.sort_command) {
+            // Files the command gave no key for go last.
+            let keys = command.keys(files.iter().map(|f| f.as_ref().path.as_path()));
+            files.sort_by(|a, b| {
+                let (a, b) = (a.as_ref(), b.as_ref());
+                match (keys.get(&a.path), keys.get(&b.path)) {
+                    (Some(ka), Some(kb)) => natord::compare(ka, kb),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+                .then_with(|| self.compare_files(a, b, git))
+            });
+        } else {
+            files.sort_by(|a, b| self.compare_files(a.as_ref(), b.as_ref(), git));
+        }
 
         if self.flags.contains(&FileFilterFlags::Reverse) {
             files.reverse();
@@ -128,6 +247,224 @@ impl FileFilter {
             });
         }
     }
+
+    /// Compares two files by each sort field in turn, with only the last
+    /// one falling back to names and breaking any tie that’s left, the same
+    /// way as when there’s only one.
+    fn compare_files(&self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+        let Some((last, rest)) = self.sort_fields.split_last() else {
+            return Ordering::Equal;
+        };
+
+        rest.iter()
+            .fold(Ordering::Equal, |order, key| {
+                order.then_with(|| key.compare_field(a, b, git))
+            })
+            .then_with(|| last.compare_files_deterministically(a, b, git))
+    }
+}
+
+/// Restricts the listing to files owned by a particular user, group, or
+/// both, such as only listing your own files in `/tmp`.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct OwnerFilter {
+    /// The ID of the user that has to own each file.
+    pub user: Option<u32>,
+
+    /// The ID of the group that has to own each file.
+    pub group: Option<u32>,
+}
+
+impl OwnerFilter {
+    /// Whether the given file is owned by the right user and group. Files
+    /// whose owner can’t be determined, such as broken symlinks being
+    /// dereferenced, don’t count as being owned by anyone.
+    #[cfg(unix)]
+    pub fn is_owned(&self, file: &File<'_>) -> bool {
+        self.user
+            .map_or(true, |uid| file.user().is_some_and(|u| u.0 == uid))
+            && self
+                .group
+                .map_or(true, |gid| file.group().is_some_and(|g| g.0 == gid))
+    }
+
+    #[cfg(windows)]
+    pub fn is_owned(&self, _file: &File<'_>) -> bool {
+        true
+    }
+}
+
+/// Restricts the listing to files with certain permission bits, in the same
+/// way as `find -perm`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct PermFilter {
+    /// The permission bits to compare against, such as `0o644`.
+    pub mode: u32,
+
+    /// How the file’s bits are compared to the mode.
+    pub matching: PermMatch,
+}
+
+/// The ways a file’s permission bits can be compared with a mode, picked
+/// with the prefix of the value given to `--perm`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum PermMatch {
+    /// The bits have to be exactly the mode, with no prefix.
+    Exact,
+
+    /// Every bit in the mode has to be set, with a `-` prefix.
+    All,
+
+    /// At least one bit in the mode has to be set, with a `/` prefix. A mode
+    /// of zero matches every file.
+    Any,
+}
+
+impl PermFilter {
+    /// Whether the given file’s permissions match. Files whose permissions
+    /// can’t be determined never match.
+    #[cfg(unix)]
+    pub fn matches(self, file: &File<'_>) -> bool {
+        let Some(bits) = file.permissions().map(f::Permissions::bits) else {
+            return false;
+        };
+
+        match self.matching {
+            PermMatch::Exact => bits == self.mode,
+            PermMatch::All => bits & self.mode == self.mode,
+            PermMatch::Any => self.mode == 0 || bits & self.mode != 0,
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn matches(self, _file: &File<'_>) -> bool {
+        true
+    }
+}
+
+/// Restricts the listing to files with certain extensions. Directories are
+/// always listed, so the filter can be used while recursing.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ExtFilter {
+    /// The extensions to match, without their leading dots. These can have
+    /// dots of their own, such as `tar.gz`.
+    pub extensions: Vec<String>,
+}
+
+impl ExtFilter {
+    /// Whether the given file should be listed.
+    pub fn is_shown(&self, file: &File<'_>) -> bool {
+        file.is_directory() || self.matches(&file.name)
+    }
+
+    /// Whether the given file name ends with any of the extensions. This is
+    /// case-insensitive, the same as sorting by extension, unless the
+    /// extension has any capital letters in it.
+    fn matches(&self, name: &str) -> bool {
+        let lowercase = name.to_ascii_lowercase();
+
+        self.extensions.iter().any(|ext| {
+            let name = if ext.chars().any(|c| c.is_ascii_uppercase()) {
+                name
+            } else {
+                &lowercase
+            };
+
+            name.len() > ext.len() + 1
+                && name.ends_with(ext.as_str())
+                && name[..name.len() - ext.len()].ends_with('.')
+        })
+    }
+}
+
+/// Restricts the listing to files with sizes in a range, such as only
+/// listing files of a megabyte or more. Directories are always listed, so
+/// the filter can be used while recursing.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct SizeFilter {
+    /// The smallest size in bytes that files can be, if there is one.
+    pub min: Option<u64>,
+
+    /// The largest size in bytes that files can be, if there is one.
+    pub max: Option<u64>,
+}
+
+impl SizeFilter {
+    /// Whether the given file should be listed. Both ends of the range are
+    /// included in it.
+    pub fn is_shown(self, file: &File<'_>) -> bool {
+        if file.is_directory() {
+            return true;
+        }
+
+        let size = file.length();
+        self.min.map_or(true, |min| size >= min) && self.max.map_or(true, |max| size <= max)
+    }
+}
+
+/// Restricts the listing to files with one of their timestamps in a range,
+/// such as only listing files modified in the last day. Directories are
+/// always listed, as with sizes, so the filter can be used while recursing.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct TimeFilter {
+    /// Which of the file’s timestamps to check.
+    pub time_type: TimeType,
+
+    /// The time, in UTC, that files have to be newer than, if there is one.
+    pub newer_than: Option<NaiveDateTime>,
+
+    /// The time, in UTC, that files have to be older than, if there is one.
+    pub older_than: Option<NaiveDateTime>,
+}
+
+impl TimeFilter {
+    /// Whether the given file should be listed. Files without the timestamp
+    /// being checked, such as creation times on filesystems that don’t keep
+    /// them, are never listed.
+    pub fn is_shown(self, file: &File<'_>) -> bool {
+        if file.is_directory() {
+            return true;
+        }
+
+        let Some(time) = self.time_type.get_corresponding_time(file) else {
+            return false;
+        };
+        self.newer_than.map_or(true, |newer| time > newer)
+            && self.older_than.map_or(true, |older| time < older)
+    }
+}
+
+/// Which files to list based on whether there’s anything in them.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum EmptyFilter {
+    /// List files regardless of their contents.
+    #[default]
+    Any,
+
+    /// Only list zero-byte regular files and directories without entries.
+    Empty,
+
+    /// Hide zero-byte regular files and directories without entries.
+    NonEmpty,
+}
+
+impl EmptyFilter {
+    /// Whether the given file should be listed. Other kinds of file, such as
+    /// devices and symlinks, are never considered empty. While recursing,
+    /// directories with entries are always listed, so only the leaves of the
+    /// tree are tested.
+    pub fn is_shown(self, file: &File<'_>, recursing: bool) -> bool {
+        let is_empty = file.is_empty_file() || file.is_empty_dir();
+        if recursing && file.is_directory() && !is_empty {
+            return true;
+        }
+
+        match self {
+            Self::Any => true,
+            Self::Empty => is_empty,
+            Self::NonEmpty => !is_empty,
+        }
+    }
 }
 
 /// User-supplied field to sort by.
@@ -143,6 +480,12 @@ pub enum SortField {
     /// The file’s extension, with extensionless files being listed first.
     Extension(SortCase),
 
+    /// The file name, compared byte by byte, with no natural sorting, case
+    /// folding, or anything else that could depend on the locale or on how
+    /// the name happens to be normalised. This order is guaranteed not to
+    /// change between versions, so it’s the one to use in scripts.
+    Bytes,
+
     /// The file’s size, in bytes.
     Size,
 
@@ -200,6 +543,73 @@ pub enum SortField {
     /// The file's name, however if the name of the file begins with `.`
     /// ignore the leading `.` and then sort as Name
     NameMixHidden(SortCase),
+
+    /// The major and then minor device numbers of block and character
+    /// devices, which come after every other kind of file.
+    #[cfg(unix)]
+    DeviceID,
+
+    /// Keys produced by an external command, compared naturally. The keys
+    /// have to be worked out for a whole directory at once, so they’re
+    /// compared while sorting files rather than here.
+    Command,
+
+    /// The file’s Git status, with files that have changes first, then new
+    /// files, then ignored files, and then files that haven’t changed or
+    /// aren’t in a repository at all.
+    GitStatus,
+}
+
+/// One of the fields to sort by, and whether to sort by it backwards.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct SortKey {
+    pub field: SortField,
+    pub reverse: bool,
+}
+
+impl From<SortField> for SortKey {
+    fn from(field: SortField) -> Self {
+        Self {
+            field,
+            reverse: false,
+        }
+    }
+}
+
+impl SortKey {
+    /// Compares two files by this key’s field, backwards if need be.
+    pub fn compare_files(self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+        self.directed(self.field.compare_files(a, b, git))
+    }
+
+    /// Compares two files by this key’s field alone, backwards if need be.
+    pub fn compare_field(self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+        self.directed(self.field.compare_field(a, b, git))
+    }
+
+    fn directed(self, order: Ordering) -> Ordering {
+        if self.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+
+    /// Compares two files by this key’s field, backwards if need be, then
+    /// breaks any tie the same way `compare_files_deterministically` does.
+    pub fn compare_files_deterministically(
+        self,
+        a: &File<'_>,
+        b: &File<'_>,
+        git: Option<&GitCache>,
+    ) -> Ordering {
+        if self.field == SortField::Unsorted {
+            return Ordering::Equal;
+        }
+
+        self.compare_files(a, b, git)
+            .then_with(|| SortField::break_tie(a, b))
+    }
 }
 
 /// Whether a field should be sorted case-sensitively or case-insensitively.
@@ -223,47 +633,60 @@ pub enum SortCase {
 
 impl SortField {
     /// Compares two files to determine the order they should be listed in,
-    /// depending on the search field.
+    /// depending on the search field. Files of the same type, extension, or
+    /// device are then listed by name.
+    pub fn compare_files(self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+        use self::SortCase::{ABCabc, AaBbCc};
+
+        let order = self.compare_field(a, b, git);
+
+        #[rustfmt::skip]
+        return match self {
+            #[cfg(unix)]
+            Self::DeviceID                               => order.then_with(|| natord::compare(&a.name, &b.name)),
+            Self::FileType | Self::Extension(ABCabc)     => order.then_with(|| natord::compare(&a.name, &b.name)),
+            Self::Extension(AaBbCc)                      => order.then_with(|| natord::compare_ignore_case(&a.name, &b.name)),
+            _                                            => order,
+        };
+    }
+
+    /// Compares two files by this field alone, without falling back to
+    /// their names, so that the next of several sort fields can be used.
     ///
     /// The `natord` crate is used here to provide a more *natural* sorting
     /// order than just sorting character-by-character. This splits filenames
     /// into groups between letters and numbers, and then sorts those blocks
     /// together, so `file10` will sort after `file9`, instead of before it
     /// because of the `1`.
-    pub fn compare_files(self, a: &File<'_>, b: &File<'_>) -> Ordering {
+    pub fn compare_field(self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
         use self::SortCase::{ABCabc, AaBbCc};
 
         #[rustfmt::skip]
         return match self {
             Self::Unsorted  => Ordering::Equal,
+            Self::Command   => Ordering::Equal,
 
             Self::Name(ABCabc)  => natord::compare(&a.name, &b.name),
             Self::Name(AaBbCc)  => natord::compare_ignore_case(&a.name, &b.name),
 
+            Self::Bytes         => Self::name_bytes(a).cmp(Self::name_bytes(b))
+                                        .then_with(|| a.path.as_os_str().cmp(b.path.as_os_str())),
+
             Self::Size          => a.length().cmp(&b.length()),
 
             #[cfg(unix)]
             Self::FileInode     => a.metadata.ino().cmp(&b.metadata.ino()),
+            #[cfg(unix)]
+            Self::DeviceID      => a.device_ids().cmp(&b.device_ids()),
             Self::ModifiedDate  => a.modified_time().cmp(&b.modified_time()),
             Self::AccessedDate  => a.accessed_time().cmp(&b.accessed_time()),
             Self::ChangedDate   => a.changed_time().cmp(&b.changed_time()),
             Self::CreatedDate   => a.created_time().cmp(&b.created_time()),
             Self::ModifiedAge   => b.modified_time().cmp(&a.modified_time()),  // flip b and a
 
-            Self::FileType => match a.type_char().cmp(&b.type_char()) { // todo: this recomputes
-                Ordering::Equal  => natord::compare(&a.name, &b.name),
-                order            => order,
-            },
-
-            Self::Extension(ABCabc) => match a.ext.cmp(&b.ext) {
-                Ordering::Equal  => natord::compare(&a.name, &b.name),
-                order            => order,
-            },
-
-            Self::Extension(AaBbCc) => match a.ext.cmp(&b.ext) {
-                Ordering::Equal  => natord::compare_ignore_case(&a.name, &b.name),
-                order            => order,
-            },
+            Self::FileType      => a.type_char().cmp(&b.type_char()), // todo: this recomputes
+            Self::Extension(_)  => a.ext.cmp(&b.ext),
+            Self::GitStatus     => Self::git_rank(a, git).cmp(&Self::git_rank(b, git)),
 
             Self::NameMixHidden(ABCabc) => natord::compare(
                 Self::strip_dot(&a.name),
@@ -276,6 +699,73 @@ impl SortField {
         };
     }
 
+    /// Compares two files the same way as `compare_files`, but breaks ties
+    /// between files that sort the same, such as two empty files when
+    /// sorting by size, by the bytes of their names and then their paths.
+    /// This way, files come out in the same order on every run, whatever
+    /// order the directory happened to list them in, which keeps the output
+    /// of `--recurse` and `--tree` stable enough to diff. Unsorted files are
+    /// left in the directory’s order, as that’s what was asked for.
+    pub fn compare_files_deterministically(
+        self,
+        a: &File<'_>,
+        b: &File<'_>,
+        git: Option<&GitCache>,
+    ) -> Ordering {
+        if self == Self::Unsorted {
+            return Ordering::Equal;
+        }
+
+        self.compare_files(a, b, git)
+            .then_with(|| Self::break_tie(a, b))
+    }
+
+    /// Where a file goes when sorting by Git status, going by whichever of
+    /// its staged and unstaged statuses comes first. Directories take the
+    /// status of the files inside them, like they do in the Git column.
+    fn git_rank(file: &File<'_>, git: Option<&GitCache>) -> u8 {
+        fn rank(status: f::GitStatus) -> u8 {
+            match status {
+                f::GitStatus::Conflicted => 0,
+                f::GitStatus::Modified
+                | f::GitStatus::Deleted
+                | f::GitStatus::Renamed
+                | f::GitStatus::TypeChange => 1,
+                f::GitStatus::New => 2,
+                f::GitStatus::Ignored => 3,
+                f::GitStatus::NotModified => 4,
+            }
+        }
+
+        let status = git
+            .map(|git| git.get(&file.path, file.is_directory()))
+            .unwrap_or_default();
+        rank(status.staged).min(rank(status.unstaged))
+    }
+
+    /// Orders two files that sort the same by the bytes of their names, and
+    /// then their paths.
+    fn break_tie(a: &File<'_>, b: &File<'_>) -> Ordering {
+        Self::name_bytes(a)
+            .cmp(Self::name_bytes(b))
+            .then_with(|| a.path.as_os_str().cmp(b.path.as_os_str()))
+    }
+
+    /// The bytes of a file’s name as they are on disk, so names that aren’t
+    /// valid UTF-8 don’t sort the same once they’ve been decoded. Files
+    /// whose names don’t come from their paths, such as `.` and `..`, and
+    /// files on other platforms, use the decoded name.
+    fn name_bytes<'a>(file: &'a File<'_>) -> &'a [u8] {
+        #[cfg(unix)]
+        if let Some(name) = file.path.file_name() {
+            if name.to_string_lossy() == file.name {
+                return name.as_bytes();
+            }
+        }
+
+        file.name.as_bytes()
+    }
+
     fn strip_dot(n: &str) -> &str {
         match n.strip_prefix('.') {
             Some(s) => s,
@@ -342,6 +832,12 @@ impl IgnorePatterns {
     fn is_ignored(&self, file: &str) -> bool {
         self.patterns.iter().any(|p| p.matches(file))
     }
+
+    /// Test whether the given file should be listed, when these are the
+    /// patterns files have to match. Directories are always listed.
+    fn is_shown(&self, file: &File<'_>) -> bool {
+        file.is_directory() || self.is_ignored(&file.name)
+    }
 }
 
 /// Whether to ignore or display files that Git would ignore.
@@ -354,6 +850,37 @@ pub enum GitIgnore {
     Off,
 }
 
+/// Which files to list based on whether Git is tracking them.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum GitTracking {
+    /// List files whether they’re tracked or not.
+    #[default]
+    Any,
+
+    /// Only list files in the index, and directories with any under them.
+    Tracked,
+
+    /// Only list files that aren’t in the index, including ignored ones,
+    /// and directories with any under them.
+    Untracked,
+}
+
+impl GitTracking {
+    /// Whether the given file should be listed. Files outside of a Git
+    /// repository count as untracked.
+    pub fn is_shown(self, file: &File<'_>, git: Option<&GitCache>) -> bool {
+        let dir = file.is_directory();
+
+        match (self, git) {
+            (Self::Any, _) => true,
+            (Self::Tracked, Some(git)) => git.is_tracked(&file.path, dir),
+            (Self::Tracked, None) => false,
+            (Self::Untracked, Some(git)) => git.has_untracked(&file.path, dir),
+            (Self::Untracked, None) => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_ignores {
     use super::*;
@@ -389,3 +916,138 @@ mod test_ignores {
         assert!(pats.is_ignored("test.mp3"));
     }
 }
+
+#[cfg(test)]
+mod test_extensions {
+    use super::*;
+
+    fn filter(extensions: &[&str]) -> ExtFilter {
+        ExtFilter {
+            extensions: extensions.iter().map(|e| (*e).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn matches_any() {
+        let exts = filter(&["rs", "toml"]);
+        assert!(exts.matches("main.rs"));
+        assert!(exts.matches("Cargo.toml"));
+        assert!(!exts.matches("README.md"));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        assert!(filter(&["jpg"]).matches("HOLIDAY.JPG"));
+    }
+
+    #[test]
+    fn case_sensitive_with_capitals() {
+        assert!(filter(&["R"]).matches("script.R"));
+        assert!(!filter(&["R"]).matches("script.r"));
+    }
+
+    #[test]
+    fn whole_extension_only() {
+        assert!(!filter(&["rs"]).matches("stars"));
+        assert!(!filter(&["rs"]).matches(".rs"));
+        assert!(!filter(&["gz"]).matches("file.tgz"));
+    }
+
+    #[test]
+    fn dotted_extension() {
+        assert!(filter(&["tar.gz"]).matches("eza.tar.gz"));
+        assert!(!filter(&["tar.gz"]).matches("eza.gz"));
+    }
+}
+
+#[cfg(test)]
+mod test_sorting {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn ties_broken_by_name() {
+        let dir = std::env::temp_dir().join(format!("eza-sort-ties-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["b", "c", "a"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let mut files: Vec<File<'_>> = ["b", "c", "a"]
+            .iter()
+            .map(|name| File::from_args(dir.join(name), None, None, false, false).unwrap())
+            .collect();
+        files.sort_by(|a, b| SortField::Size.compare_files_deterministically(a, b, None));
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, ["a", "b", "c"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn bytes_of_undecodable_names() {
+        use std::ffi::OsStr;
+
+        // Both names decode to the same replacement character, and are in
+        // directories that sort the other way round from them.
+        let dir = std::env::temp_dir().join(format!("eza-sort-bytes-{}", std::process::id()));
+        let paths = [
+            dir.join("a").join(OsStr::from_bytes(b"\xff")),
+            dir.join("b").join(OsStr::from_bytes(b"\xfe")),
+        ];
+        for path in &paths {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let mut files: Vec<File<'_>> = paths
+            .iter()
+            .map(|path| File::from_args(path.clone(), None, None, false, false).unwrap())
+            .collect();
+        files.sort_by(|a, b| SortField::Bytes.compare_files_deterministically(a, b, None));
+        let names: Vec<&[u8]> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().as_bytes())
+            .collect();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, [b"\xfe", b"\xff"]);
+    }
+}
+
+#[cfg(test)]
+mod test_emptiness {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn recursing_tests_only_leaves() {
+        let dir = std::env::temp_dir().join(format!("eza-emptiness-{}", std::process::id()));
+        fs::create_dir_all(dir.join("full")).unwrap();
+        fs::create_dir_all(dir.join("hollow")).unwrap();
+        fs::write(dir.join("full").join("empty.txt"), "").unwrap();
+
+        let file = |name: &str| File::from_args(dir.join(name), None, None, false, false).unwrap();
+        let full = file("full");
+        let hollow = file("hollow");
+        let empty = file("full/empty.txt");
+
+        let flat = [
+            EmptyFilter::Empty.is_shown(&full, false),
+            EmptyFilter::Empty.is_shown(&hollow, false),
+            EmptyFilter::NonEmpty.is_shown(&full, false),
+        ];
+        let recursing = [
+            EmptyFilter::Empty.is_shown(&full, true),
+            EmptyFilter::Empty.is_shown(&hollow, true),
+            EmptyFilter::Empty.is_shown(&empty, true),
+            EmptyFilter::NonEmpty.is_shown(&full, true),
+            EmptyFilter::NonEmpty.is_shown(&hollow, true),
+        ];
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(flat, [false, true, true]);
+        assert_eq!(recursing, [true, true, true, true, false]);
+    }
+}
@@ -0,0 +1,273 @@
+//! The frontier of a recursive listing: the directories it still has to
+//! get to. With `--resume`, this gets saved to a state file after each
+//! directory is listed, so a run that gets cancelled part-way through a
+//! large tree can carry on from where it stopped instead of starting over.
+//!
+//! A state file is a text file with a header, then the path of each
+//! directory still to be listed on its own line, in the order they’ll be
+//! listed in. Paths are written as their bytes, so that names that aren’t
+//! valid UTF-8 survive being saved and loaded again, with backslashes, tabs,
+//! and newlines escaped the same way as they are in manifests.
+//!
+//! ```text
+//! # eza resume
+//! /srv/archive/2019/04
+//! /srv/archive/2019/05
+//! /srv/archive/2020
+//! ```
+
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The first line of every state file.
+static HEADER: &str = "# eza resume";
+
+/// The directories still to be listed, and where to save them.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Frontier {
+    /// The state file this gets saved to.
+    path: PathBuf,
+
+    /// The directories still to be listed, with the next one last, so
+    /// that listing one can replace it with its subdirectories.
+    pending: Vec<PathBuf>,
+}
+
+impl Frontier {
+    /// Reads the frontier saved in the given state file, which is empty if
+    /// there’s no state file yet.
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        let pending = match fs::read(&path) {
+            Ok(contents) => parse(&contents).map_err(|line| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{line}: not an eza state file", path.display()),
+                )
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self { path, pending })
+    }
+
+    /// Takes the directories a previous run still had to list, in the
+    /// order it would have listed them, leaving the frontier empty.
+    pub fn take_pending(&mut self) -> Vec<PathBuf> {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.reverse();
+        pending
+    }
+
+    /// Adds directories that are about to be listed, in the order they’ll
+    /// be listed in, ahead of any that were already pending.
+    pub fn push<'a>(&mut self, dirs: impl DoubleEndedIterator<Item = &'a Path>) {
+        self.pending.extend(dirs.rev().map(Path::to_path_buf));
+    }
+
+    /// Marks the next directory as listed, replacing it with the given
+    /// subdirectories of it, and saves the frontier.
+    pub fn advance<'a>(
+        &mut self,
+        children: impl DoubleEndedIterator<Item = &'a Path>,
+    ) -> io::Result<()> {
+        let _ = self.pending.pop();
+        self.push(children);
+        self.save()
+    }
+
+    /// Saves the frontier to its state file, writing it alongside first so
+    /// that being cancelled part-way through can’t leave it half-written.
+    pub fn save(&self) -> io::Result<()> {
+        let mut contents = format!("{HEADER}\n").into_bytes();
+        for dir in self.pending.iter().rev() {
+            contents.extend(escape(&path_bytes(dir)));
+            contents.push(b'\n');
+        }
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, contents)
+            .and_then(|()| fs::rename(&temporary, &self.path))
+            .map_err(|e| self.annotate(&e))
+    }
+
+    /// Removes the state file, once there’s nothing left to list.
+    pub fn finish(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(self.annotate(&e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds the state file’s path to an error’s message.
+    fn annotate(&self, e: &io::Error) -> io::Error {
+        io::Error::new(e.kind(), format!("{}: {e}", self.path.display()))
+    }
+}
+
+/// The bytes a path is saved as, which are the path’s own on Unix.
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+/// Windows paths aren’t bytes, but are nearly always valid Unicode, so
+/// they’re saved as UTF-8.
+#[cfg(windows)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    match path.to_string_lossy() {
+        Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+        Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+    }
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(windows)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Escapes backslashes, tabs, and newlines, as `manifest::escape` does, but
+/// on bytes rather than a string.
+fn escape(field: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(field.len());
+    for &byte in field {
+        match byte {
+            b'\\' => escaped.extend(b"\\\\"),
+            b'\t' => escaped.extend(b"\\t"),
+            b'\n' => escaped.extend(b"\\n"),
+            _ => escaped.push(byte),
+        }
+    }
+    escaped
+}
+
+/// Undoes `escape`.
+fn unescape(field: &[u8]) -> Vec<u8> {
+    let mut unescaped = Vec::with_capacity(field.len());
+    let mut bytes = field.iter();
+
+    while let Some(&byte) = bytes.next() {
+        if byte != b'\\' {
+            unescaped.push(byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some(b't') => unescaped.push(b'\t'),
+            Some(b'n') => unescaped.push(b'\n'),
+            Some(&other) => unescaped.push(other),
+            None => unescaped.push(b'\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Parses the contents of a state file into the pending directories, with
+/// the next one last, returning the number of the line that’s wrong if
+/// it isn’t one.
+fn parse(contents: &[u8]) -> Result<Vec<PathBuf>, usize> {
+    let mut lines = contents.split(|&byte| byte == b'\n');
+    if lines.next() != Some(HEADER.as_bytes()) {
+        return Err(1);
+    }
+
+    let mut pending: Vec<_> = lines
+        .filter(|line| !line.is_empty())
+        .map(|line| path_from_bytes(unescape(line)))
+        .collect();
+    pending.reverse();
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn frontier(pending: &[&str]) -> Frontier {
+        Frontier {
+            path: PathBuf::from("state"),
+            pending: pending.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            parse(b"# eza resume\n/a\n/b\\tc\n"),
+            Ok(vec![PathBuf::from("/b\tc"), PathBuf::from("/a")])
+        );
+    }
+
+    #[test]
+    fn not_a_state_file() {
+        assert_eq!(parse(b"/a\n/b\n"), Err(1));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn non_utf8_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = std::ffi::OsStr::from_bytes(b"/a\xff\\b\tc");
+        let path = std::env::temp_dir().join(format!("eza-frontier-{}", std::process::id()));
+        let mut f = Frontier {
+            path: path.clone(),
+            pending: vec![PathBuf::from(name)],
+        };
+        f.save().unwrap();
+        let loaded = Frontier::load(path.clone()).map(|mut l| l.take_pending());
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), f.take_pending());
+    }
+
+    #[test]
+    fn save_errors_name_the_state_file() {
+        let f = Frontier {
+            path: PathBuf::from("/no/such/directory/state"),
+            pending: Vec::new(),
+        };
+        let message = f.save().unwrap_err().to_string();
+        assert!(
+            message.starts_with("/no/such/directory/state: "),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn taking() {
+        let mut f = frontier(&["c", "b", "a"]);
+        assert_eq!(
+            f.take_pending(),
+            vec![PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c")]
+        );
+        assert_eq!(f.pending, Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn children_come_first() {
+        let mut f = frontier(&[]);
+        f.push([Path::new("a"), Path::new("b")].into_iter());
+        let _ = f.pending.pop();
+        f.push([Path::new("a/x"), Path::new("a/y")].into_iter());
+        assert_eq!(
+            f.take_pending(),
+            vec![
+                PathBuf::from("a/x"),
+                PathBuf::from("a/y"),
+                PathBuf::from("b"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,433 @@
+//! Manifests: snapshots of the files under the listed paths, written by
+//! `--manifest-write` and compared with by `--manifest-check`.
+//!
+//! A manifest is a text file with one entry per line, each being fields
+//! separated by tabs (shown here as spaces):
+//!
+//! ```text
+//! # eza manifest
+//! f  1024  1700000000  5d2f0e3c8ab19a47  src/main.rs
+//! d  -     -           -                 src
+//! l  -     -           -                 current  releases/42
+//! ```
+//!
+//! These are the kind of file (`f`ile, `d`irectory, `l`ink, or `o`ther),
+//! its size and modification time in seconds for files, a hash of its
+//! contents if one was asked for, and its path relative to the directory it
+//! was found in. Links have their target as a sixth field. Tabs, newlines,
+//! and backslashes in paths are escaped with backslashes.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::fs::compare::CompareMethod;
+use crate::fs::fields::DiffStatus;
+
+/// The first line of every manifest.
+static HEADER: &str = "# eza manifest";
+
+/// Where to write a manifest, and whether to hash the files in it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ManifestWrite {
+    pub path: PathBuf,
+    pub method: CompareMethod,
+}
+
+/// The kinds of file a manifest tells apart.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum Kind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+/// What a manifest records about one file.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Entry {
+    kind: Kind,
+    size: Option<u64>,
+    modified: Option<u64>,
+    hash: Option<u64>,
+    target: Option<String>,
+}
+
+/// A set of entries, keyed by their path relative to the listed directory
+/// they were found in.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Manifest {
+    entries: BTreeMap<PathBuf, Entry>,
+}
+
+impl Entry {
+    /// Reads the entry for the file at the given path, without following
+    /// it if it’s a symlink.
+    fn read(path: &Path, method: CompareMethod) -> io::Result<Self> {
+        let metadata = fs::symlink_metadata(path)?;
+        let file_type = metadata.file_type();
+
+        let mut entry = Self {
+            kind: Kind::Other,
+            size: None,
+            modified: None,
+            hash: None,
+            target: None,
+        };
+
+        if file_type.is_dir() {
+            entry.kind = Kind::Directory;
+        } else if file_type.is_symlink() {
+            entry.kind = Kind::Symlink;
+            entry.target = Some(fs::read_link(path)?.to_string_lossy().into_owned());
+        } else if file_type.is_file() {
+            entry.kind = Kind::File;
+            entry.size = Some(metadata.len());
+            entry.modified = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs());
+            if method == CompareMethod::Contents {
+                entry.hash = Some(hash_contents(path)?);
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Whether the file at the given path, which this entry was found for,
+    /// still looks the same.
+    fn matches(&self, path: &Path, method: CompareMethod) -> io::Result<bool> {
+        let now = Self::read(path, CompareMethod::Metadata)?;
+
+        if now.kind != self.kind || now.size != self.size || now.target != self.target {
+            return Ok(false);
+        }
+
+        match self.hash {
+            Some(hash) if method == CompareMethod::Contents => Ok(hash_contents(path)? == hash),
+            _ => Ok(now.modified == self.modified),
+        }
+    }
+}
+
+impl Manifest {
+    /// Records every file under the given paths. Directories are recorded
+    /// along with everything in them, with paths relative to themselves,
+    /// and other files under their own names.
+    pub fn of(args: &[PathBuf], method: CompareMethod) -> io::Result<Self> {
+        let mut manifest = Self::default();
+
+        for arg in args {
+            if arg.is_dir() {
+                manifest.add_dir(arg, Path::new(""), method)?;
+            } else if let Some(name) = arg.file_name() {
+                let entry = Entry::read(arg, method).map_err(|e| annotate(arg, &e))?;
+                manifest.entries.insert(PathBuf::from(name), entry);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    fn add_dir(&mut self, dir: &Path, prefix: &Path, method: CompareMethod) -> io::Result<()> {
+        for child in fs::read_dir(dir).map_err(|e| annotate(dir, &e))? {
+            let child = child.map_err(|e| annotate(dir, &e))?;
+            let path = child.path();
+            let key = prefix.join(child.file_name());
+            let entry = Entry::read(&path, method).map_err(|e| annotate(&path, &e))?;
+
+            if entry.kind == Kind::Directory {
+                self.add_dir(&path, &key, method)?;
+            }
+            self.entries.insert(key, entry);
+        }
+
+        Ok(())
+    }
+
+    /// Reads a manifest written by `write`.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| annotate(path, &e))?;
+        Self::parse(&contents).map_err(|(line, msg)| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{line}: {msg}", path.display()),
+            )
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string()).map_err(|e| annotate(path, &e))
+    }
+
+    /// Parses the contents of a manifest, returning the line number and a
+    /// description of the first line that couldn’t be parsed.
+    fn parse(contents: &str) -> Result<Self, (usize, String)> {
+        let mut manifest = Self::default();
+
+        for (index, line) in contents.lines().enumerate() {
+            let number = index + 1;
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (kind, size, modified, hash, path, target) = match fields[..] {
+                [kind, size, modified, hash, path] => (kind, size, modified, hash, path, None),
+                [kind, size, modified, hash, path, target] => {
+                    (kind, size, modified, hash, path, Some(target))
+                }
+                _ => return Err((number, "expected five or six tab-separated fields".into())),
+            };
+
+            let kind = match kind {
+                "f" => Kind::File,
+                "d" => Kind::Directory,
+                "l" => Kind::Symlink,
+                "o" => Kind::Other,
+                _ => return Err((number, format!("unknown kind of file {kind:?}"))),
+            };
+
+            let number_field = |field: &str, radix| match field {
+                "-" => Ok(None),
+                _ => u64::from_str_radix(field, radix)
+                    .map(Some)
+                    .map_err(|_| (number, format!("invalid number {field:?}"))),
+            };
+
+            let entry = Entry {
+                kind,
+                size: number_field(size, 10)?,
+                modified: number_field(modified, 10)?,
+                hash: number_field(hash, 16)?,
+                target: target.map(unescape),
+            };
+            manifest
+                .entries
+                .insert(PathBuf::from(unescape(path)), entry);
+        }
+
+        Ok(manifest)
+    }
+
+    /// How the file at the given path compares with the entry for the given
+    /// key: files that weren’t there are only here, and directories match
+    /// any directory, including the listed directory itself.
+    pub fn status(&self, key: &Path, path: &Path, method: CompareMethod) -> DiffStatus {
+        if key.as_os_str().is_empty() {
+            return DiffStatus::Same;
+        }
+
+        let Some(entry) = self.entries.get(key) else {
+            return DiffStatus::OnlyHere;
+        };
+
+        match entry.matches(path, method) {
+            Ok(true) => DiffStatus::Same,
+            Ok(false) => DiffStatus::Different,
+            Err(_) => DiffStatus::Unknown,
+        }
+    }
+
+    /// The keys of every entry, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(PathBuf::as_path)
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+
+        for (key, entry) in &self.entries {
+            let kind = match entry.kind {
+                Kind::File => 'f',
+                Kind::Directory => 'd',
+                Kind::Symlink => 'l',
+                Kind::Other => 'o',
+            };
+            let or_dash = |n: Option<String>| n.unwrap_or_else(|| "-".into());
+
+            let mut line = format!(
+                "{kind}\t{}\t{}\t{}\t{}",
+                or_dash(entry.size.map(|s| s.to_string())),
+                or_dash(entry.modified.map(|m| m.to_string())),
+                or_dash(entry.hash.map(|h| format!("{h:016x}"))),
+                escape(&key_string(key)),
+            );
+            if let Some(target) = &entry.target {
+                let _ = write!(line, "\t{}", escape(target));
+            }
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a key with forward slashes, so manifests can be checked on other
+/// platforms than the one they were written on.
+fn key_string(key: &Path) -> String {
+    key.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub(crate) fn escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Hashes a file’s contents with 64-bit FNV-1a, which is simple enough to
+/// stay the same across versions and platforms.
+pub(crate) fn hash_contents(path: &Path) -> io::Result<u64> {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut buf = [0; 8192];
+    let mut hash = OFFSET;
+
+    loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            return Ok(hash);
+        }
+
+        for byte in buf.iter().take(read) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+}
+
+/// Adds the path that an error happened at to its message.
+fn annotate(path: &Path, e: &io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn file(size: u64, modified: u64, hash: Option<u64>) -> Entry {
+        Entry {
+            kind: Kind::File,
+            size: Some(size),
+            modified: Some(modified),
+            hash,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Manifest::parse(""), Ok(Manifest::default()));
+        assert_eq!(Manifest::default().to_string(), "# eza manifest\n");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut manifest = Manifest::default();
+        manifest.entries.insert(
+            "src".into(),
+            Entry {
+                kind: Kind::Directory,
+                size: None,
+                modified: None,
+                hash: None,
+                target: None,
+            },
+        );
+        manifest
+            .entries
+            .insert("src/main.rs".into(), file(1024, 1_700_000_000, Some(0xabc)));
+        manifest
+            .entries
+            .insert("tab\there".into(), file(0, 1, None));
+        manifest.entries.insert(
+            "current".into(),
+            Entry {
+                kind: Kind::Symlink,
+                size: None,
+                modified: None,
+                hash: None,
+                target: Some("releases/42".into()),
+            },
+        );
+
+        let written = manifest.to_string();
+        assert!(written.contains("f\t1024\t1700000000\t0000000000000abc\tsrc/main.rs\n"));
+        assert!(written.contains("f\t0\t1\t-\ttab\\there\n"));
+        assert!(written.contains("l\t-\t-\t-\tcurrent\treleases/42\n"));
+        assert_eq!(Manifest::parse(&written), Ok(manifest));
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(unescape(&escape("a\\b\tc\nd")), "a\\b\tc\nd");
+    }
+
+    #[test]
+    fn bad_kind() {
+        assert!(matches!(
+            Manifest::parse("# eza manifest\nx\t-\t-\t-\tfoo"),
+            Err((2, _))
+        ));
+    }
+
+    #[test]
+    fn too_few_fields() {
+        assert!(matches!(Manifest::parse("f\t1\t2"), Err((1, _))));
+    }
+
+    #[test]
+    fn bad_number() {
+        assert!(Manifest::parse("f\tbig\t2\t-\tfoo").is_err());
+    }
+
+    #[test]
+    fn missing_is_only_here() {
+        let manifest = Manifest::default();
+        assert_eq!(
+            manifest.status(
+                Path::new("new"),
+                Path::new("dir/new"),
+                CompareMethod::Metadata
+            ),
+            DiffStatus::OnlyHere
+        );
+    }
+}
@@ -4,9 +4,14 @@ pub use self::dir::{Dir, DotFilter};
 mod file;
 pub use self::file::{File, FileTarget};
 
+pub mod cache;
+pub mod compare;
+pub mod contents;
 pub mod dir_action;
 pub mod feature;
 pub mod fields;
 pub mod filter;
+pub mod frontier;
+pub mod manifest;
 pub mod mounts;
 pub mod recursive_size;
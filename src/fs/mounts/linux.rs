@@ -11,6 +11,7 @@ pub fn mounts() -> Result<Vec<MountedFs>, Error> {
             dest: mount.dest.clone(),
             fstype: mount.fstype.clone(),
             source: mount.source.to_string_lossy().into(),
+            options: mount.options.clone(),
         })
         .collect())
 }
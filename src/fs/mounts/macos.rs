@@ -51,6 +51,7 @@ pub fn mounts() -> Result<Vec<MountedFs>, Error> {
             dest,
             fstype,
             source,
+            options: Vec::new(),
         });
     }
 
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 #[cfg(target_os = "linux")]
@@ -18,6 +18,74 @@ pub struct MountedFs {
     pub dest: PathBuf,
     pub fstype: String,
     pub source: String,
+    pub options: Vec<String>,
+}
+
+/// How much space a mounted filesystem has, in bytes.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Usage {
+    /// The size of the filesystem.
+    pub total: u64,
+
+    /// How much isn’t in use, including any that’s reserved for root.
+    pub free: u64,
+
+    /// How much can be used by anyone, which doesn’t include the space
+    /// reserved for root.
+    pub available: u64,
+}
+
+impl Usage {
+    /// How much of the filesystem is in use.
+    pub fn used(self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    /// How much of the space that can be used is in use, between 0 and 1.
+    /// Like `df`, this leaves out the reserved space, so a filesystem that
+    /// only root can write to any more counts as full.
+    pub fn fraction(self) -> f32 {
+        let usable = self.used() + self.available;
+        if usable == 0 {
+            0.0
+        } else {
+            (self.used() as f64 / usable as f64) as f32
+        }
+    }
+}
+
+/// Asks the filesystem mounted at the given path how much space it has.
+#[cfg(unix)]
+pub fn usage(path: &Path) -> Option<Usage> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: the path is NUL-terminated, and `statvfs` fills in the
+    // struct when it succeeds, which is the only time it gets read.
+    let stats = unsafe {
+        if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stats.assume_init()
+    };
+
+    #[allow(clippy::useless_conversion)]
+    let block_size = u64::from(stats.f_frsize);
+    #[allow(clippy::useless_conversion)]
+    Some(Usage {
+        total: u64::from(stats.f_blocks) * block_size,
+        free: u64::from(stats.f_bfree) * block_size,
+        available: u64::from(stats.f_bavail) * block_size,
+    })
+}
+
+/// Filesystem usage is only read on Unix.
+#[cfg(not(unix))]
+pub fn usage(_path: &Path) -> Option<Usage> {
+    None
 }
 
 #[derive(Debug)]
@@ -73,3 +141,29 @@ pub(super) fn all_mounts() -> &'static HashMap<PathBuf, MountedFs> {
         mount_map
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fraction_leaves_out_reserved() {
+        let usage = Usage {
+            total: 100,
+            free: 50,
+            available: 45,
+        };
+        assert_eq!(usage.used(), 50);
+        assert!((usage.fraction() - 50.0 / 95.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn empty_filesystem() {
+        let usage = Usage {
+            total: 0,
+            free: 0,
+            available: 0,
+        };
+        assert!(usage.fraction().abs() < f32::EPSILON);
+    }
+}
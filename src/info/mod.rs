@@ -4,4 +4,5 @@
 //! (This counts the file name as metadata.)
 
 pub mod filetype;
+pub mod shortcuts;
 mod sources;
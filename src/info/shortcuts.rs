@@ -0,0 +1,367 @@
+//! Reading what shortcut files point to: the name a `.desktop` file gives
+//! its application, or the path a Windows `.lnk` file links to.
+//!
+//! These get read from the files’ contents, so only as much of each file as
+//! a shortcut could reasonably need is read, in case something large has
+//! been given one of their extensions.
+
+use std::env;
+use std::fs;
+use std::io::Read;
+
+use once_cell::sync::Lazy;
+
+use crate::fs::File;
+
+/// The most of a shortcut file that gets read.
+const MAX_SHORTCUT_BYTES: u64 = 64 * 1024;
+
+/// The user’s locale for messages, which picks the translation of a
+/// `.desktop` file’s name to show.
+static LOCALE: Lazy<Option<String>> = Lazy::new(|| {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .find(|value| !value.is_empty())
+        .map(|value| value.to_string_lossy().into_owned())
+});
+
+/// What a shortcut file resolves to.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Shortcut {
+    /// The name of the application or link in a `.desktop` file.
+    Name(String),
+
+    /// The path a `.lnk` file points to.
+    Target(String),
+}
+
+impl File<'_> {
+    /// If this is a shortcut file, read what it resolves to, returning
+    /// `None` for any other file, or for a shortcut that can’t be read.
+    pub fn resolve_shortcut(&self) -> Option<Shortcut> {
+        match self.ext.as_deref() {
+            Some("desktop") if self.is_file() => {
+                let bytes = self.read_shortcut()?;
+                desktop_name(&String::from_utf8_lossy(&bytes), LOCALE.as_deref())
+            }
+            Some("lnk") if self.is_file() => lnk_target(&self.read_shortcut()?),
+            _ => None,
+        }
+    }
+
+    /// Reads the start of this file, up to as much as a shortcut needs.
+    fn read_shortcut(&self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        fs::File::open(&self.path)
+            .ok()?
+            .take(MAX_SHORTCUT_BYTES)
+            .read_to_end(&mut bytes)
+            .ok()?;
+        Some(bytes)
+    }
+}
+
+/// Finds the name in a `.desktop` file’s `[Desktop Entry]` group, in the
+/// given locale if it has a translation for it.
+fn desktop_name(contents: &str, locale: Option<&str>) -> Option<Shortcut> {
+    let wanted = locale.map(locale_keys).unwrap_or_default();
+    let mut best: Option<(usize, &str)> = None;
+    let mut in_entry = false;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        // Lower ranks are better matches, with the untranslated name
+        // coming after every translation.
+        let rank = match key.trim().strip_prefix("Name") {
+            Some("") => wanted.len(),
+            Some(suffix) => {
+                let Some(lang) = suffix.strip_prefix('[').and_then(|s| s.strip_suffix(']')) else {
+                    continue;
+                };
+                match wanted.iter().position(|w| w == lang) {
+                    Some(rank) => rank,
+                    None => continue,
+                }
+            }
+            None => continue,
+        };
+
+        if best.map_or(true, |(best_rank, _)| rank < best_rank) {
+            best = Some((rank, value.trim()));
+        }
+    }
+
+    best.map(|(_, name)| Shortcut::Name(unescape_desktop_value(name)))
+}
+
+/// The keys a translated name could have in the given locale, best match
+/// first, as per the Desktop Entry specification: a locale such as
+/// `sr_RS.UTF-8@latin` matches `sr_RS@latin`, `sr_RS`, `sr@latin`, and `sr`.
+fn locale_keys(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let rest = rest.split('.').next().unwrap_or(rest);
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+
+    let mut keys = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        keys.push(format!("{lang}_{country}@{modifier}"));
+    }
+    if let Some(country) = country {
+        keys.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        keys.push(format!("{lang}@{modifier}"));
+    }
+    if !lang.is_empty() && lang != "C" && lang != "POSIX" {
+        keys.push(lang.to_string());
+    }
+    keys
+}
+
+/// Undoes the backslash escapes that string values can have.
+fn unescape_desktop_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Finds the local path a Windows shell link points to, from the `LinkInfo`
+/// structure described in the MS-SHLLINK specification.
+fn lnk_target(bytes: &[u8]) -> Option<Shortcut> {
+    const HEADER_SIZE: u32 = 0x4C;
+    const HAS_LINK_TARGET_ID_LIST: u32 = 0x1;
+    const HAS_LINK_INFO: u32 = 0x2;
+    const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+
+    if u32_at(bytes, 0)? != HEADER_SIZE {
+        return None;
+    }
+
+    let link_flags = u32_at(bytes, 0x14)?;
+    if link_flags & HAS_LINK_INFO == 0 {
+        return None;
+    }
+
+    let mut offset = HEADER_SIZE as usize;
+    if link_flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        offset += 2 + usize::from(u16_at(bytes, offset)?);
+    }
+
+    let info = bytes.get(offset..)?;
+    let info_header_size = u32_at(info, 4)?;
+    if u32_at(info, 8)? & VOLUME_ID_AND_LOCAL_BASE_PATH == 0 {
+        return None;
+    }
+
+    // Newer links also have the paths in UTF-16, which are preferred, as
+    // the others are in whichever code page the link was made with.
+    let (base, suffix) = if info_header_size >= 0x24 {
+        (
+            utf16_string_at(info, u32_at(info, 0x1C)? as usize)?,
+            utf16_string_at(info, u32_at(info, 0x20)? as usize)?,
+        )
+    } else {
+        (
+            ansi_string_at(info, u32_at(info, 0x10)? as usize)?,
+            ansi_string_at(info, u32_at(info, 0x18)? as usize)?,
+        )
+    };
+
+    let target = base + &suffix;
+    if target.is_empty() {
+        None
+    } else {
+        Some(Shortcut::Target(target))
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Reads a NUL-terminated string of single-byte characters, treating them
+/// as Latin-1, which most Windows code pages agree with.
+fn ansi_string_at(bytes: &[u8], offset: usize) -> Option<String> {
+    let bytes = bytes.get(offset..)?;
+    let string = bytes.split(|b| *b == 0).next()?;
+    Some(string.iter().map(|b| char::from(*b)).collect())
+}
+
+/// Reads a NUL-terminated UTF-16 string.
+fn utf16_string_at(bytes: &[u8], offset: usize) -> Option<String> {
+    let units: Vec<u16> = bytes
+        .get(offset..)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FIREFOX: &str = "\
+[Desktop Entry]
+Name=Firefox Web Browser
+Name[de]=Firefox-Webbrowser
+Name[sr@latin]=Firefox veb pregledač
+Exec=firefox %u
+
+[Desktop Action new-window]
+Name=New Window
+";
+
+    fn name(name: &str) -> Shortcut {
+        Shortcut::Name(name.into())
+    }
+
+    #[test]
+    fn untranslated() {
+        assert_eq!(
+            desktop_name(FIREFOX, None),
+            Some(name("Firefox Web Browser"))
+        );
+        assert_eq!(
+            desktop_name(FIREFOX, Some("C")),
+            Some(name("Firefox Web Browser"))
+        );
+    }
+
+    #[test]
+    fn translated() {
+        assert_eq!(
+            desktop_name(FIREFOX, Some("de_DE.UTF-8")),
+            Some(name("Firefox-Webbrowser"))
+        );
+        assert_eq!(
+            desktop_name(FIREFOX, Some("sr_RS@latin")),
+            Some(name("Firefox veb pregledač"))
+        );
+    }
+
+    #[test]
+    fn no_translation() {
+        assert_eq!(
+            desktop_name(FIREFOX, Some("fr_FR")),
+            Some(name("Firefox Web Browser"))
+        );
+    }
+
+    #[test]
+    fn other_groups_ignored() {
+        assert_eq!(desktop_name("[Desktop Action x]\nName=Nope\n", None), None);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            desktop_name("[Desktop Entry]\nName=a\\sb\\\\c\n", None),
+            Some(name("a b\\c"))
+        );
+    }
+
+    #[test]
+    fn keys_for_locale() {
+        assert_eq!(
+            locale_keys("sr_RS.UTF-8@latin"),
+            vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]
+        );
+    }
+
+    /// Builds a link with just a header and a `LinkInfo` structure holding
+    /// the given base path and suffix.
+    fn lnk(base: &str, suffix: &str, id_list: bool) -> Vec<u8> {
+        let mut bytes = vec![0; 0x4C];
+        bytes[0] = 0x4C;
+        bytes[0x14] = if id_list { 0x3 } else { 0x2 };
+
+        if id_list {
+            bytes.extend([4, 0, 0xAA, 0xBB, 0xCC, 0xDD]);
+        }
+
+        let header_size = 0x1Cu32;
+        let base_offset = header_size;
+        let suffix_offset = base_offset + base.len() as u32 + 1;
+        let size = suffix_offset + suffix.len() as u32 + 1;
+        for field in [size, header_size, 1, 0, base_offset, 0, suffix_offset] {
+            bytes.extend(field.to_le_bytes());
+        }
+        bytes.extend(base.bytes().chain([0]));
+        bytes.extend(suffix.bytes().chain([0]));
+        bytes
+    }
+
+    #[test]
+    fn link_target() {
+        let bytes = lnk("C:\\Windows\\", "notepad.exe", false);
+        assert_eq!(
+            lnk_target(&bytes),
+            Some(Shortcut::Target("C:\\Windows\\notepad.exe".into()))
+        );
+    }
+
+    #[test]
+    fn link_target_after_id_list() {
+        let bytes = lnk("D:\\Games\\run.exe", "", true);
+        assert_eq!(
+            lnk_target(&bytes),
+            Some(Shortcut::Target("D:\\Games\\run.exe".into()))
+        );
+    }
+
+    #[test]
+    fn not_a_link() {
+        assert_eq!(lnk_target(b"MZ\x90\x00"), None);
+        assert_eq!(lnk_target(&[]), None);
+    }
+
+    #[test]
+    fn truncated_link() {
+        let bytes = lnk("C:\\a.exe", "", false);
+        assert_eq!(lnk_target(&bytes[..0x50]), None);
+    }
+}
@@ -0,0 +1,37 @@
+//! Quoting strings as JSON values, for both the `--json` output and the
+//! JSON lines written to `EZA_DEBUG_FILE`.
+//!
+//! Neither needs anything more than strings, numbers and nulls, which are
+//! simple enough to write by hand, so there’s no JSON library for this.
+
+/// Quotes and escapes a string for use as a JSON value.
+pub fn json_string(input: &str) -> String {
+    let mut output = String::with_capacity(input.len() + 2);
+    output.push('"');
+
+    for c in input.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(json_string("a \"b\"\n\\"), "\"a \\\"b\\\"\\n\\\\\"");
+        assert_eq!(json_string("\u{1b}[1m"), "\"\\u001b[1m\"");
+    }
+}
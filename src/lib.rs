@@ -1,12 +1,20 @@
 #[allow(unused)]
+pub mod diagnostics;
+#[allow(unused)]
 pub mod fs;
 #[allow(unused)]
 pub mod info;
 #[allow(unused)]
+pub mod json_string;
+#[allow(unused)]
 pub mod logger;
 #[allow(unused)]
+pub mod notify;
+#[allow(unused)]
 pub mod options;
 #[allow(unused)]
 pub mod output;
 #[allow(unused)]
+pub mod pager;
+#[allow(unused)]
 pub mod theme;
@@ -1,12 +1,28 @@
 //! Debug error logging.
+//!
+//! The `EZA_DEBUG` environment variable holds a comma-separated list of
+//! directives, each either a bare level that applies everywhere, or a
+//! `module=level` pair that applies to one module and its children, such as
+//! `EZA_DEBUG=info,fs::dir=trace`. Any other non-empty value turns on debug
+//! logging everywhere, as it always has.
+//!
+//! Logs are written to standard error, or as JSON lines to the file named by
+//! `EZA_DEBUG_FILE` if it’s set, which is easier to attach to a bug report.
 
 use std::ffi::OsStr;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Mutex;
 
 use ansiterm::{ANSIString, Colour};
+use log::LevelFilter;
 
-/// Sets the internal logger, changing the log level based on the value of an
-/// environment variable.
-pub fn configure<T: AsRef<OsStr>>(ev: Option<T>) {
+use crate::json_string::json_string;
+
+/// Sets the internal logger, changing the log levels based on the value of
+/// an environment variable, and sending the logs to a file if one is given.
+pub fn configure<T: AsRef<OsStr>, P: AsRef<OsStr>>(ev: Option<T>, file: Option<P>) {
     let Some(ev) = ev else { return };
 
     let env_var = ev.as_ref();
@@ -14,45 +30,150 @@ pub fn configure<T: AsRef<OsStr>>(ev: Option<T>) {
         return;
     }
 
-    if env_var == "trace" {
-        log::set_max_level(log::LevelFilter::Trace);
-    } else {
-        log::set_max_level(log::LevelFilter::Debug);
-    }
+    let filter = Filter::parse(&env_var.to_string_lossy());
+
+    let output = match file {
+        Some(path) => {
+            let path = path.as_ref();
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => Output::Json(Mutex::new(file)),
+                Err(e) => {
+                    eprintln!("Failed to open log file {}: {e}", path.to_string_lossy());
+                    Output::Terminal
+                }
+            }
+        }
+        None => Output::Terminal,
+    };
+
+    log::set_max_level(filter.max_level());
 
-    let result = log::set_logger(GLOBAL_LOGGER);
+    let logger: &'static Logger = Box::leak(Box::new(Logger { filter, output }));
+    let result = log::set_logger(logger);
     if let Err(e) = result {
         eprintln!("Failed to initialise logger: {e}");
     }
 }
 
+/// Which log levels are enabled for which modules.
+#[derive(PartialEq, Eq, Debug)]
+struct Filter {
+    /// The level for modules without a directive of their own.
+    default: LevelFilter,
+
+    /// Module paths, relative to the crate root, and their levels.
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    fn parse(input: &str) -> Self {
+        let mut default = None;
+        let mut modules = Vec::new();
+
+        for directive in input.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            if let Some((module, level)) = directive.split_once('=') {
+                if let Ok(level) = LevelFilter::from_str(level.trim()) {
+                    let module = module.trim();
+                    let module = module.strip_prefix("eza::").unwrap_or(module);
+                    modules.push((module.to_string(), level));
+                }
+            } else {
+                default = Some(LevelFilter::from_str(directive).unwrap_or(LevelFilter::Debug));
+            }
+        }
+
+        // Naming only some modules means only those modules are of interest.
+        let default = default.unwrap_or(if modules.is_empty() {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Off
+        });
+
+        Self { default, modules }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+
+    /// The level for the given log target, taken from the most specific
+    /// module that contains it.
+    fn level_for(&self, target: &str) -> LevelFilter {
+        let target = target.strip_prefix("eza::").unwrap_or(target);
+
+        self.modules
+            .iter()
+            .filter(|(module, _)| {
+                target == module
+                    || (target.starts_with(module.as_str())
+                        && target[module.len()..].starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+}
+
 #[derive(Debug)]
-struct Logger;
+enum Output {
+    /// Coloured lines on standard error.
+    Terminal,
+
+    /// One JSON object per line in a file.
+    Json(Mutex<File>),
+}
 
-const GLOBAL_LOGGER: &Logger = &Logger;
+#[derive(Debug)]
+struct Logger {
+    filter: Filter,
+    output: Output,
+}
 
 impl log::Log for Logger {
-    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
-        true // no need to filter after using ‘set_max_level’.
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
     }
 
     fn log(&self, record: &log::Record<'_>) {
-        let open = Colour::Fixed(243).paint("[");
-        let level = level(record.level());
-        let close = Colour::Fixed(243).paint("]");
-
-        eprintln!(
-            "{}{} {}{} {}",
-            open,
-            level,
-            record.target(),
-            close,
-            record.args()
-        );
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match &self.output {
+            Output::Terminal => {
+                let open = Colour::Fixed(243).paint("[");
+                let level = level(record.level());
+                let close = Colour::Fixed(243).paint("]");
+
+                eprintln!(
+                    "{}{} {}{} {}",
+                    open,
+                    level,
+                    record.target(),
+                    close,
+                    record.args()
+                );
+            }
+            Output::Json(file) => {
+                let line = format!(
+                    "{{\"time\":{},\"level\":{},\"target\":{},\"message\":{}}}",
+                    json_string(&chrono::Local::now().to_rfc3339()),
+                    json_string(record.level().as_str()),
+                    json_string(record.target()),
+                    json_string(&record.args().to_string()),
+                );
+
+                if let Ok(mut file) = file.lock() {
+                    let _ = writeln!(file, "{line}");
+                }
+            }
+        }
     }
 
     fn flush(&self) {
-        // no need to flush with ‘eprintln!’.
+        // no need to flush with ‘eprintln!’, and the file is unbuffered.
     }
 }
 
@@ -66,3 +187,33 @@ fn level(level: log::Level) -> ANSIString<'static> {
         log::Level::Trace => Colour::Fixed(245).paint("TRACE"),
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn legacy_values() {
+        assert_eq!(Filter::parse("1").default, LevelFilter::Debug);
+        assert_eq!(Filter::parse("trace").default, LevelFilter::Trace);
+    }
+
+    #[test]
+    fn module_directives() {
+        let filter = Filter::parse("info, fs::dir=trace, eza::output=off");
+
+        assert_eq!(filter.level_for("eza::main"), LevelFilter::Info);
+        assert_eq!(filter.level_for("eza::fs::dir"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("eza::fs::dir_action"), LevelFilter::Info);
+        assert_eq!(filter.level_for("eza::output::details"), LevelFilter::Off);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn only_modules() {
+        let filter = Filter::parse("fs=debug");
+
+        assert_eq!(filter.level_for("eza::fs::file"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("eza::options"), LevelFilter::Off);
+    }
+}
@@ -21,28 +21,46 @@
 #![allow(clippy::upper_case_acronyms)]
 #![allow(clippy::wildcard_imports)]
 
+use std::collections::HashSet;
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::io::{self, stdin, ErrorKind, IsTerminal, Read, Write};
 use std::path::{Component, PathBuf};
 use std::process::exit;
+use std::time::Instant;
 
 use ansiterm::{ANSIStrings, Style};
 
-use crate::fs::feature::git::GitCache;
+use crate::fs::cache;
+use crate::fs::compare::Comparison;
+use crate::fs::feature::git::{self, GitCache};
+use crate::fs::feature::sparse;
+use crate::fs::feature::special_dirs::{self, UserDirsFile};
+use crate::fs::feature::trash::{self, Trash, TrashEntry};
 use crate::fs::filter::GitIgnore;
+use crate::fs::frontier::Frontier;
+use crate::fs::manifest::Manifest;
 use crate::fs::{Dir, File};
 use crate::options::stdin::FilesInput;
-use crate::options::{vars, Options, OptionsResult, Vars};
-use crate::output::{details, escape, file_name, grid, grid_details, lines, Mode, View};
+use crate::options::{vars, views, Options, OptionsResult, Vars};
+use crate::output::{
+    blocks_map, capabilities, color_scale, delimited, details, escape, file_name, grid,
+    grid_details, json, layout_debug, lines, relative_root, stats, Mode, TerminalHeight,
+    TerminalWidth, View,
+};
+use crate::pager::{Flush, Output};
 use crate::theme::Theme;
 use log::*;
 
+mod diagnostics;
 mod fs;
 mod info;
+mod json_string;
 mod logger;
+mod notify;
 mod options;
 mod output;
+mod pager;
 mod theme;
 
 fn main() {
@@ -51,59 +69,183 @@ fn main() {
         libc::signal(libc::SIGPIPE, libc::SIG_DFL);
     }
 
-    logger::configure(env::var_os(vars::EZA_DEBUG).or_else(|| env::var_os(vars::EXA_DEBUG)));
+    logger::configure(
+        env::var_os(vars::EZA_DEBUG).or_else(|| env::var_os(vars::EXA_DEBUG)),
+        env::var_os(vars::EZA_DEBUG_FILE),
+    );
 
     #[cfg(windows)]
     if let Err(e) = ansiterm::enable_ansi_support() {
         warn!("Failed to enable ANSI support: {}", e);
     }
 
+    let started = Instant::now();
     let stdout_istty = io::stdout().is_terminal();
 
     let mut input = String::new();
-    let args: Vec<_> = env::args_os().skip(1).collect();
+    let resumed: Vec<PathBuf>;
+    let args = match views::expand(env::args_os().skip(1).collect(), &LiveVars) {
+        Ok(args) => args,
+        Err(e) => {
+            diagnostics::error(format_args!("eza: {e}"));
+            exit(exits::OPTIONS_ERROR);
+        }
+    };
     match Options::parse(args.iter().map(std::convert::AsRef::as_ref), &LiveVars) {
         OptionsResult::Ok(options, mut input_paths) => {
-            // List the current directory by default.
+            diagnostics::configure(options.verbosity);
+            layout_debug::configure(options.debug_layout);
+            color_scale::configure_dump(options.dump_scale);
+            capabilities::configure(options.capabilities);
+
+            let trash_entries = match options.trash.as_ref().map(Trash::entries).transpose() {
+                Ok(entries) => entries.unwrap_or_default(),
+                Err(e) => {
+                    diagnostics::warning(format_args!("could not read the trash: {e}"));
+                    Vec::new()
+                }
+            };
+            trash::configure(&trash_entries);
+
+            let user_dirs = match options
+                .user_dirs
+                .as_ref()
+                .map(UserDirsFile::entries)
+                .transpose()
+            {
+                Ok(dirs) => dirs.unwrap_or_default(),
+                Err(e) => {
+                    diagnostics::warning(format_args!("could not read the user directories: {e}"));
+                    Vec::new()
+                }
+            };
+            special_dirs::configure(&user_dirs);
+            cache::configure(options.cache.as_ref());
+            git::configure_cache(options.cache.as_ref());
+            stats::configure(options.stats);
+
+            // Paths piped in are listed after the ones given as arguments, and
+            // the current directory is listed if there are neither.
             // (This has to be done here, otherwise git_options won’t see it.)
-            if input_paths.is_empty() {
-                match &options.stdin {
-                    FilesInput::Args => {
-                        input_paths = vec![OsStr::new(".")];
-                    }
-                    FilesInput::Stdin(separator) => {
-                        stdin()
-                            .read_to_string(&mut input)
-                            .expect("Failed to read from stdin");
-                        input_paths.extend(
-                            input
-                                .split(&separator.clone().into_string().unwrap_or("\n".to_string()))
-                                .map(std::ffi::OsStr::new)
-                                .filter(|s| !s.is_empty())
-                                .collect::<Vec<_>>(),
-                        );
-                    }
+            let mut piped_paths = HashSet::new();
+            if let FilesInput::ArgsAndStdin { .. } = options.stdin {
+                stdin()
+                    .read_to_string(&mut input)
+                    .expect("Failed to read from stdin");
+                let arg_paths = input_paths.clone();
+                input_paths = options.stdin.merge(input_paths, &input);
+                piped_paths = input_paths
+                    .iter()
+                    .filter(|path| !arg_paths.contains(path))
+                    .copied()
+                    .collect();
+            } else if input_paths.is_empty() {
+                input_paths = vec![OsStr::new(".")];
+            }
+
+            // A run being resumed lists the directories the last one didn’t
+            // get to, instead of the ones it was given.
+            let mut frontier = match options.resume.clone().map(Frontier::load).transpose() {
+                Ok(frontier) => frontier,
+                Err(e) => {
+                    diagnostics::error(format_args!("eza: {e}"));
+                    exit(exits::RUNTIME_ERROR);
                 }
+            };
+            resumed = frontier
+                .as_mut()
+                .map(Frontier::take_pending)
+                .unwrap_or_default();
+            if !resumed.is_empty() {
+                input_paths = resumed.iter().map(|path| path.as_os_str()).collect();
+                piped_paths.clear();
             }
 
             let git = git_options(&options, &input_paths);
-            let writer = io::stdout();
+            let diff = match write_manifest(&options, &input_paths)
+                .and_then(|()| diff_options(&options, &input_paths))
+            {
+                Ok(diff) => diff,
+                Err(e) => {
+                    diagnostics::error(format_args!("eza: {e}"));
+                    exit(exits::RUNTIME_ERROR);
+                }
+            };
+            // Output going to a terminal is shown as it’s listed, and output
+            // going anywhere else is written in blocks, unless told otherwise.
+            let flush = options.flush.unwrap_or(if stdout_istty {
+                Flush::Line
+            } else {
+                Flush::Block
+            });
+            let writer = Output::new(flush, options.paginate.is_some() && stdout_istty);
             let git_repos = git_repos(&options, &input_paths);
 
-            let console_width = options.view.width.actual_terminal_width();
+            let geometry = options.view.geometry();
+            let console_width = geometry.width;
+            match (options.view.width, console_width) {
+                (TerminalWidth::Set(width), _) => layout_debug::note(format_args!(
+                    "terminal width is {width} columns, from --width or $COLUMNS"
+                )),
+                (TerminalWidth::Automatic, Some(width)) => layout_debug::note(format_args!(
+                    "terminal width is {width} columns, from the terminal"
+                )),
+                (TerminalWidth::Automatic, None) => layout_debug::note(
+                    "terminal width is unknown, as output is not a terminal, so no grid will be drawn",
+                ),
+            }
+            match (options.view.height, geometry.height) {
+                (TerminalHeight::Set(height), _) => layout_debug::note(format_args!(
+                    "terminal height is {height} rows, from --height or $LINES"
+                )),
+                (TerminalHeight::Automatic, Some(height)) => layout_debug::note(format_args!(
+                    "terminal height is {height} rows, from the terminal"
+                )),
+                (TerminalHeight::Automatic, None) => {
+                    layout_debug::note("terminal height is unknown");
+                }
+            }
+            let notify = options.notify;
+            let pager = options.paginate.clone();
             let theme = options.theme.to_theme(stdout_istty);
-            let exa = Exa {
+            let mut exa = Exa {
                 options,
                 writer,
                 input_paths,
+                piped_paths,
                 theme,
                 console_width,
                 git,
+                diff,
                 git_repos,
+                trash: trash_entries,
+                frontier,
             };
 
             info!("matching on exa.run");
-            match exa.run() {
+            let mut result = exa.run();
+
+            if let Err(e) = cache::save() {
+                diagnostics::warning(format_args!("eza: Couldn’t save the cache: {e}"));
+            }
+
+            if let (Some(pager), Output::Buffer(listing)) = (pager, &exa.writer) {
+                result = result
+                    .and_then(|status| pager.print(listing, geometry.height).map(|()| status));
+            } else {
+                result = result.and_then(|status| exa.writer.finish().map(|()| status));
+            }
+
+            if let Some(notify) = notify {
+                notify.finished(started.elapsed());
+            }
+
+            match result {
+                Ok(exit_status) if exit_status == exits::SUCCESS && diagnostics::escalated() => {
+                    trace!("exa.run: exit RUNTIME_ERROR after escalated warnings");
+                    exit(exits::RUNTIME_ERROR);
+                }
+
                 Ok(exit_status) => {
                     trace!("exa.run: exit Ok(exit_status)");
                     exit(exit_status);
@@ -115,7 +257,7 @@ fn main() {
                 }
 
                 Err(e) => {
-                    eprintln!("{e}");
+                    diagnostics::error(format_args!("eza: {e}"));
                     trace!("exa.run: exit RUNTIME_ERROR");
                     exit(exits::RUNTIME_ERROR);
                 }
@@ -130,11 +272,15 @@ fn main() {
             print!("{version_str}");
         }
 
+        OptionsResult::ListOptions(list) => {
+            print!("{list}");
+        }
+
         OptionsResult::InvalidOptions(error) => {
-            eprintln!("eza: {error}");
+            diagnostics::error(format_args!("eza: {error}"));
 
             if let Some(s) = error.suggestion() {
-                eprintln!("{s}");
+                diagnostics::error(format_args!("{s}"));
             }
 
             exit(exits::OPTIONS_ERROR);
@@ -148,12 +294,16 @@ pub struct Exa<'args> {
     pub options: Options,
 
     /// The output handle that we write to.
-    pub writer: io::Stdout,
+    pub writer: Output,
 
     /// List of the free command-line arguments that should correspond to file
     /// names (anything that isn’t an option).
     pub input_paths: Vec<&'args OsStr>,
 
+    /// The paths that were read from standard input rather than given as
+    /// arguments, which get reported together if they don’t exist.
+    pub piped_paths: HashSet<&'args OsStr>,
+
     /// The theme that has been configured from the command-line options and
     /// environment variables. If colours are disabled, this is a theme with
     /// every style set to the default.
@@ -168,7 +318,19 @@ pub struct Exa<'args> {
     /// want to list several directories in the same repository.
     pub git: Option<GitCache>,
 
+    /// The comparison with another directory, if `--diff-against` was
+    /// passed in. Like the Git cache, it’s made from the arguments, as
+    /// they’re what get compared with the other directory.
+    pub diff: Option<Comparison>,
+
     pub git_repos: bool,
+
+    /// Everything in the trash, if it was read.
+    pub trash: Vec<TrashEntry>,
+
+    /// The directories a recursive listing still has to get to, if they’re
+    /// being saved with `--resume`.
+    pub frontier: Option<Frontier>,
 }
 
 /// The “real” environment variables type.
@@ -191,9 +353,30 @@ fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
     }
 }
 
+/// Pair up the arguments with the directory they’re to be compared with,
+/// if the options demand it.
+fn diff_options(options: &Options, args: &[&OsStr]) -> io::Result<Option<Comparison>> {
+    let args: Vec<PathBuf> = args.iter().map(PathBuf::from).collect();
+    options
+        .diff_against()
+        .map(|diff| Comparison::new(diff, &args))
+        .transpose()
+}
+
+/// Write a manifest of everything under the arguments, if the options ask
+/// for one, before they get listed.
+fn write_manifest(options: &Options, args: &[&OsStr]) -> io::Result<()> {
+    let Some(write) = &options.manifest_write else {
+        return Ok(());
+    };
+
+    let args: Vec<PathBuf> = args.iter().map(PathBuf::from).collect();
+    Manifest::of(&args, write.method)?.write(&write.path)
+}
+
 #[cfg(not(feature = "git"))]
 fn git_repos(_options: &Options, _args: &[&OsStr]) -> bool {
-    return false;
+    false
 }
 
 #[cfg(feature = "git")]
@@ -251,11 +434,21 @@ impl<'args> Exa<'args> {
     /// # Errors
     ///
     /// Will return `Err` if printing to stderr fails.
-    pub fn run(mut self) -> io::Result<i32> {
+    pub fn run(&mut self) -> io::Result<i32> {
         debug!("Running with options: {:#?}", self.options);
 
+        if self.options.list_trash {
+            self.print_trash()?;
+            return Ok(exits::SUCCESS);
+        }
+
+        if self.options.blocks_map {
+            return self.print_blocks_map();
+        }
+
         let mut files = Vec::new();
         let mut dirs = Vec::new();
+        let mut missing = Vec::new();
         let mut exit_status = 0;
 
         for file_path in &self.input_paths {
@@ -266,9 +459,18 @@ impl<'args> Exa<'args> {
                 self.options.view.deref_links,
                 self.options.view.total_size,
             ) {
+                // Paths read from stdin are reported together at the end,
+                // as there could be any number of them that don’t exist.
+                // Arguments get the usual error, even when stdin is read.
+                Err(e)
+                    if e.kind() == ErrorKind::NotFound && self.piped_paths.contains(file_path) =>
+                {
+                    missing.push(*file_path);
+                }
+
                 Err(e) => {
                     exit_status = 2;
-                    writeln!(io::stderr(), "{file_path:?}: {e}")?;
+                    diagnostics::error(format_args!("{file_path:?}: {e}"));
                 }
 
                 Ok(f) => {
@@ -277,10 +479,10 @@ impl<'args> Exa<'args> {
                         match f.to_dir() {
                             Ok(d) => dirs.push(d),
                             Err(e) if e.kind() == ErrorKind::PermissionDenied => {
-                                eprintln!("{file_path:?}: {e}");
+                                diagnostics::error(format_args!("{file_path:?}: {e}"));
                                 exit(exits::PERMISSION_DENIED);
                             }
-                            Err(e) => writeln!(io::stderr(), "{file_path:?}: {e}")?,
+                            Err(e) => diagnostics::error(format_args!("{file_path:?}: {e}")),
                         }
                     } else {
                         files.push(f);
@@ -296,10 +498,236 @@ impl<'args> Exa<'args> {
         let no_files = files.is_empty();
         let is_only_dir = dirs.len() == 1 && no_files;
 
-        self.options.filter.filter_argument_files(&mut files);
+        self.options
+            .filter
+            .filter_argument_files(&mut files, self.git.as_ref());
+
+        if self.options.view.mode == Mode::Json {
+            self.print_json(files, dirs)?;
+            return Ok(self.report_missing(&missing).unwrap_or(exit_status));
+        }
+
+        if let Mode::Delimited(_) = self.options.view.mode {
+            self.print_delimited(files, dirs)?;
+            return Ok(self.report_missing(&missing).unwrap_or(exit_status));
+        }
+
+        let merge = self.options.merge && dirs.len() > 1;
+        let index = self.options.index && !merge && dirs.len() > 1 && self.has_headings();
+        if index {
+            self.print_index(&dirs)?;
+        }
+
         self.print_files(None, files)?;
 
-        self.print_dirs(dirs, no_files, is_only_dir, exit_status)
+        if merge {
+            if !no_files {
+                writeln!(&mut self.writer)?;
+            }
+            self.print_merged(&dirs)?;
+        } else {
+            if let Some(frontier) = &mut self.frontier {
+                frontier.push(dirs.iter().map(|dir| dir.path.as_path()));
+            }
+
+            exit_status =
+                self.print_dirs(dirs, no_files && !index, is_only_dir, index, exit_status)?;
+        }
+        self.print_removed()?;
+        stats::render(&mut self.writer, &self.theme)?;
+
+        if let Some(frontier) = &self.frontier {
+            frontier.finish()?;
+        }
+        Ok(self.report_missing(&missing).unwrap_or(exit_status))
+    }
+
+    /// Reports the paths read from stdin that don’t exist, all at once,
+    /// unless they’re being skipped. Returns the exit status to use instead
+    /// if any were reported.
+    fn report_missing(&self, missing: &[&OsStr]) -> Option<i32> {
+        let FilesInput::ArgsAndStdin {
+            skip_missing: false,
+            ..
+        } = self.options.stdin
+        else {
+            return None;
+        };
+
+        if missing.is_empty() {
+            return None;
+        }
+
+        let mut report = match missing.len() {
+            1 => String::from("eza: 1 path does not exist:"),
+            n => format!("eza: {n} paths do not exist:"),
+        };
+        for path in missing {
+            report.push_str(&format!("\n  {path:?}"));
+        }
+        diagnostics::warning(report);
+
+        Some(2)
+    }
+
+    /// Lists everything in the trash, oldest first, with when it was trashed
+    /// and where from.
+    fn print_trash(&mut self) -> io::Result<()> {
+        let quote_style = self.options.view.file_style.quote_style;
+
+        for entry in &self.trash {
+            let deleted = entry
+                .deleted
+                .as_deref()
+                .map_or_else(|| "-".into(), |d| d.replacen('T', " ", 1));
+
+            let mut bits = Vec::new();
+            escape(
+                entry.original.display().to_string(),
+                &mut bits,
+                Style::default(),
+                self.theme.ui.control_char,
+                quote_style,
+            );
+
+            writeln!(
+                &mut self.writer,
+                "{}  {}",
+                self.theme.ui.date.paint(format!("{deleted:19}")),
+                ANSIStrings(&bits)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Draws the map of where the data is in the single file argument.
+    fn print_blocks_map(&mut self) -> io::Result<i32> {
+        let [path] = self.input_paths[..] else {
+            diagnostics::error(format_args!("eza: Option --blocks-map needs exactly one file to map"));
+            return Ok(exits::OPTIONS_ERROR);
+        };
+
+        let map = std::fs::File::open(path).and_then(|file| {
+            let metadata = file.metadata()?;
+            if !metadata.is_file() {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "not a regular file",
+                ));
+            }
+            let extents = sparse::extents(&file, metadata.len())?;
+            let width = self.console_width.unwrap_or(64);
+            Ok(blocks_map::render(
+                &extents,
+                metadata.len(),
+                width,
+                &self.theme,
+            ))
+        });
+
+        match map {
+            Ok(map) => {
+                writeln!(&mut self.writer, "{map}")?;
+                Ok(exits::SUCCESS)
+            }
+            Err(e) => {
+                diagnostics::error(format_args!("{path:?}: {e}"));
+                Ok(exits::RUNTIME_ERROR)
+            }
+        }
+    }
+
+    /// Lists the entries in the manifest being checked against that aren’t
+    /// there any more, as they can’t be marked in the listing itself.
+    fn print_removed(&mut self) -> io::Result<()> {
+        let Some(diff) = &self.diff else {
+            return Ok(());
+        };
+
+        let removed = diff.removed();
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        let style = self.theme.ui.git.deleted;
+        let root = self.options.view.file_style.relative_root.as_deref();
+        writeln!(&mut self.writer)?;
+        writeln!(&mut self.writer, "Removed since the manifest:")?;
+        for path in removed {
+            writeln!(
+                &mut self.writer,
+                "{} {}",
+                style.paint("-"),
+                relative_root::display(root, path).display()
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether directories get headings before their contents. fzf reads
+    /// every entry as one list, so it mustn’t get any.
+    fn has_headings(&self) -> bool {
+        !matches!(
+            self.options.view.mode,
+            Mode::Lines(lines::Options { fzf: true })
+        )
+    }
+
+    /// Prints a numbered list of the directories that are about to be
+    /// listed, with how many entries each of them has, for `--index`.
+    fn print_index(&mut self, dirs: &[Dir]) -> io::Result<()> {
+        let quote_style = self.options.view.file_style.quote_style;
+        let root = self.options.view.file_style.relative_root.clone();
+        let number_width = dirs.len().to_string().len();
+
+        writeln!(&mut self.writer, "Index:")?;
+        for (number, dir) in dirs.iter().enumerate() {
+            let entries = self.count_entries(dir);
+            let plural = if entries == 1 { "y" } else { "ies" };
+
+            let mut bits = Vec::new();
+            escape(
+                relative_root::display(root.as_deref(), &dir.path)
+                    .display()
+                    .to_string(),
+                &mut bits,
+                Style::default(),
+                Style::default(),
+                quote_style,
+            );
+            writeln!(
+                &mut self.writer,
+                "{:>number_width$}. {} ({entries} entr{plural})",
+                number + 1,
+                ANSIStrings(&bits),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// How many entries will be listed in a directory once it’s been
+    /// filtered. Any errors get reported when it’s listed, so they’re
+    /// ignored here.
+    fn count_entries(&self, dir: &Dir) -> usize {
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        let mut children: Vec<_> = dir
+            .files(
+                self.options.filter.dot_filter,
+                self.git.as_ref(),
+                git_ignore,
+                self.options.view.deref_links,
+                false,
+            )
+            .filter_map(Result::ok)
+            .collect();
+
+        self.options
+            .filter
+            .filter_child_files(&mut children, self.git.as_ref());
+        children.len()
     }
 
     fn print_dirs(
@@ -307,50 +735,49 @@ impl<'args> Exa<'args> {
         dir_files: Vec<Dir>,
         mut first: bool,
         is_only_dir: bool,
+        numbered: bool,
         exit_status: i32,
     ) -> io::Result<i32> {
         let View {
             file_style: file_name::Options { quote_style, .. },
             ..
         } = self.options.view;
-        for dir in dir_files {
-            // Put a gap between directories, or between the list of files and
-            // the first directory.
-            if first {
-                first = false;
-            } else {
-                writeln!(&mut self.writer)?;
-            }
+        let root = self.options.view.file_style.relative_root.clone();
 
-            if !is_only_dir {
-                let mut bits = Vec::new();
-                escape(
-                    dir.path.display().to_string(),
-                    &mut bits,
-                    Style::default(),
-                    Style::default(),
-                    quote_style,
-                );
-                writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
-            }
+        let headings = self.has_headings();
+        let number_width = dir_files.len().to_string().len();
 
-            let mut children = Vec::new();
-            let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
-            for file in dir.files(
-                self.options.filter.dot_filter,
-                self.git.as_ref(),
-                git_ignore,
-                self.options.view.deref_links,
-                self.options.view.total_size,
-            ) {
-                match file {
-                    Ok(file) => children.push(file),
-                    Err((path, e)) => writeln!(io::stderr(), "[{}: {}]", path.display(), e)?,
+        for (number, dir) in dir_files.into_iter().enumerate() {
+            if headings {
+                // Put a gap between directories, or between the list of files
+                // and the first directory.
+                if first {
+                    first = false;
+                } else {
+                    writeln!(&mut self.writer)?;
+                }
+
+                if !is_only_dir {
+                    let mut bits = Vec::new();
+                    if numbered {
+                        bits.push(
+                            Style::default().paint(format!("{:>number_width$}. ", number + 1)),
+                        );
+                    }
+                    escape(
+                        relative_root::display(root.as_deref(), &dir.path)
+                            .display()
+                            .to_string(),
+                        &mut bits,
+                        Style::default(),
+                        Style::default(),
+                        quote_style,
+                    );
+                    writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
                 }
             }
 
-            self.options.filter.filter_child_files(&mut children);
-            self.options.filter.sort_files(&mut children);
+            let children = self.children(&dir);
 
             if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
                 let depth = dir
@@ -363,18 +790,23 @@ impl<'args> Exa<'args> {
                     let mut child_dirs = Vec::new();
                     for child_dir in children
                         .iter()
-                        .filter(|f| f.is_directory() && !f.is_all_all)
+                        .filter(|f| recurse_opts.descends_into(f) && !f.is_all_all)
                     {
                         match child_dir.to_dir() {
                             Ok(d) => child_dirs.push(d),
                             Err(e) => {
-                                writeln!(io::stderr(), "{}: {}", child_dir.path.display(), e)?;
+                                diagnostics::warning(format_args!(
+                                    "{}: {}",
+                                    child_dir.path.display(),
+                                    e
+                                ));
                             }
                         }
                     }
 
                     self.print_files(Some(&dir), children)?;
-                    match self.print_dirs(child_dirs, false, false, exit_status) {
+                    self.advance_frontier(&child_dirs)?;
+                    match self.print_dirs(child_dirs, false, false, false, exit_status) {
                         Ok(_) => (),
                         Err(e) => return Err(e),
                     }
@@ -383,17 +815,130 @@ impl<'args> Exa<'args> {
             }
 
             self.print_files(Some(&dir), children)?;
+            self.advance_frontier(&[])?;
         }
 
         Ok(exit_status)
     }
 
+    /// The files in a directory, filtered and sorted.
+    fn children<'dir>(&self, dir: &'dir Dir) -> Vec<File<'dir>> {
+        let mut children = Vec::new();
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        for file in dir.files(
+            self.options.filter.dot_filter,
+            self.git.as_ref(),
+            git_ignore,
+            self.options.view.deref_links,
+            self.options.view.total_size,
+        ) {
+            match file {
+                Ok(file) => children.push(file),
+                Err((path, e)) => {
+                    diagnostics::warning(format_args!("[{}: {}]", path.display(), e));
+                }
+            }
+        }
+
+        self.options
+            .filter
+            .filter_child_files(&mut children, self.git.as_ref());
+        self.options
+            .filter
+            .sort_files(&mut children, self.git.as_ref());
+        children
+    }
+
+    /// Lists the contents of several directories together, sorted as one,
+    /// for `--merge`, rather than one directory after another.
+    fn print_merged(&mut self, dirs: &[Dir]) -> io::Result<()> {
+        let mut children = Vec::new();
+        for dir in dirs {
+            children.extend(self.children(dir));
+        }
+
+        self.options
+            .filter
+            .sort_files(&mut children, self.git.as_ref());
+        self.print_files(None, children)
+    }
+
+    /// Saves that the directory being listed is done with, and that its
+    /// subdirectories are to be listed next, if the frontier’s being saved.
+    fn advance_frontier(&mut self, child_dirs: &[Dir]) -> io::Result<()> {
+        match &mut self.frontier {
+            Some(frontier) => frontier.advance(child_dirs.iter().map(|dir| dir.path.as_path())),
+            None => Ok(()),
+        }
+    }
+
+    /// Prints the files and directories given as arguments as one JSON
+    /// document, which can’t be split up with headings like the other views.
+    fn print_json(&mut self, files: Vec<File<'_>>, dirs: Vec<Dir>) -> io::Result<()> {
+        let r = json::Render {
+            files,
+            dirs,
+            filter: &self.options.filter,
+            recurse: self.options.dir_action.recurse_options(),
+            git: self.git.as_ref(),
+            git_ignoring: self.options.filter.git_ignore == GitIgnore::CheckAndIgnore,
+            deref_links: self.options.view.deref_links,
+            total_size: self.options.view.total_size,
+            relative_root: self.options.view.file_style.relative_root.as_deref(),
+            metadata: self
+                .options
+                .metadata
+                .as_ref()
+                .map(|filters| json::Metadata {
+                    roots: &self.input_paths,
+                    filters,
+                }),
+            #[cfg(unix)]
+            users: uzers::UsersCache::new(),
+        };
+        r.render(&mut self.writer)
+    }
+
+    /// Prints the files and directories given as arguments as CSV or TSV,
+    /// with one header row for all of them.
+    fn print_delimited(&mut self, files: Vec<File<'_>>, dirs: Vec<Dir>) -> io::Result<()> {
+        let Mode::Delimited(ref opts) = self.options.view.mode else {
+            unreachable!("Tried to print delimited output in another view");
+        };
+
+        let r = delimited::Render {
+            files,
+            dirs,
+            opts,
+            filter: &self.options.filter,
+            recurse: self.options.dir_action.recurse_options(),
+            git: self.git.as_ref(),
+            git_ignoring: self.options.filter.git_ignore == GitIgnore::CheckAndIgnore,
+            deref_links: self.options.view.deref_links,
+            total_size: self.options.view.total_size,
+            relative_root: self.options.view.file_style.relative_root.as_deref(),
+            metadata: self
+                .options
+                .metadata
+                .as_ref()
+                .map(|filters| json::Metadata {
+                    roots: &self.input_paths,
+                    filters,
+                }),
+            #[cfg(unix)]
+            users: uzers::UsersCache::new(),
+        };
+        r.render(&mut self.writer)
+    }
+
     /// Prints the list of files using whichever view is selected.
     fn print_files(&mut self, dir: Option<&Dir>, files: Vec<File<'_>>) -> io::Result<()> {
         if files.is_empty() {
             return Ok(());
         }
 
+        stats::record(&files);
+
         let theme = &self.theme;
         let View {
             ref mode,
@@ -404,6 +949,7 @@ impl<'args> Exa<'args> {
         match (mode, self.console_width) {
             (Mode::Grid(ref opts), Some(console_width)) => {
                 let filter = &self.options.filter;
+                let git = self.git.as_ref();
                 let r = grid::Render {
                     files,
                     theme,
@@ -411,17 +957,39 @@ impl<'args> Exa<'args> {
                     opts,
                     console_width,
                     filter,
+                    git,
                 };
                 r.render(&mut self.writer)
             }
 
-            (Mode::Grid(_), None) | (Mode::Lines, _) => {
+            (Mode::Json, _) => self.print_json(files, Vec::new()),
+            (Mode::Delimited(_), _) => self.print_delimited(files, Vec::new()),
+
+            (Mode::Grid(_), None) => {
                 let filter = &self.options.filter;
+                let opts = &lines::Options::default();
+                let git = self.git.as_ref();
                 let r = lines::Render {
                     files,
                     theme,
                     file_style,
+                    opts,
                     filter,
+                    git,
+                };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Lines(ref opts), _) => {
+                let filter = &self.options.filter;
+                let git = self.git.as_ref();
+                let r = lines::Render {
+                    files,
+                    theme,
+                    file_style,
+                    opts,
+                    filter,
+                    git,
                 };
                 r.render(&mut self.writer)
             }
@@ -432,6 +1000,7 @@ impl<'args> Exa<'args> {
 
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
+                let diff = self.diff.as_ref();
                 let git_repos = self.git_repos;
                 let r = details::Render {
                     dir,
@@ -443,6 +1012,7 @@ impl<'args> Exa<'args> {
                     filter,
                     git_ignoring,
                     git,
+                    diff,
                     git_repos,
                 };
                 r.render(&mut self.writer)
@@ -456,6 +1026,7 @@ impl<'args> Exa<'args> {
                 let filter = &self.options.filter;
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
+                let diff = self.diff.as_ref();
                 let git_repos = self.git_repos;
 
                 let r = grid_details::Render {
@@ -469,6 +1040,7 @@ impl<'args> Exa<'args> {
                     row_threshold,
                     git_ignoring,
                     git,
+                    diff,
                     console_width,
                     git_repos,
                 };
@@ -481,6 +1053,7 @@ impl<'args> Exa<'args> {
                 let recurse = self.options.dir_action.recurse_options();
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
+                let diff = self.diff.as_ref();
                 let git_repos = self.git_repos;
 
                 let r = details::Render {
@@ -493,6 +1066,7 @@ impl<'args> Exa<'args> {
                     filter,
                     git_ignoring,
                     git,
+                    diff,
                     git_repos,
                 };
                 r.render(&mut self.writer)
@@ -0,0 +1,109 @@
+//! Letting the user know that a long listing has finished, for when it was
+//! started in another pane or window and left to run.
+//!
+//! Once eza has printed everything, if it took at least as long as the user
+//! asked for, it either rings the terminal bell or sends an OSC 9 escape
+//! sequence, which many terminals turn into a desktop notification.
+
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+use log::*;
+
+/// When and how to notify the user that eza has finished.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Notify {
+    /// How long a run has to take before it’s worth notifying about.
+    pub after: Duration,
+
+    /// What to send to the terminal.
+    pub style: NotifyStyle,
+}
+
+/// The ways of getting the user’s attention.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum NotifyStyle {
+    /// Ring the terminal bell.
+    #[default]
+    Bell,
+
+    /// Send an OSC 9 notification with a message in it.
+    Osc9,
+}
+
+impl Notify {
+    /// Notifies the user that a run has finished, if it took long enough.
+    /// The notification is sent to whichever of standard error or standard
+    /// output is a terminal, and not at all if neither are, so it never ends
+    /// up in a file or a pipe.
+    pub fn finished(self, elapsed: Duration) {
+        let Some(sequence) = self.sequence(elapsed) else {
+            return;
+        };
+
+        let result = if io::stderr().is_terminal() {
+            io::stderr().write_all(sequence.as_bytes())
+        } else if io::stdout().is_terminal() {
+            let mut stdout = io::stdout();
+            stdout
+                .write_all(sequence.as_bytes())
+                .and_then(|()| stdout.flush())
+        } else {
+            debug!("Not notifying, as there’s no terminal to notify");
+            Ok(())
+        };
+
+        if let Err(e) = result {
+            debug!("Failed to notify: {e}");
+        }
+    }
+
+    /// The escape sequence to send after a run that took the given time, if
+    /// it took long enough to send one.
+    fn sequence(self, elapsed: Duration) -> Option<String> {
+        if elapsed < self.after {
+            return None;
+        }
+
+        match self.style {
+            NotifyStyle::Bell => Some("\x07".into()),
+            NotifyStyle::Osc9 => Some(format!("\x1b]9;eza finished in {}s\x07", elapsed.as_secs())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn notify(style: NotifyStyle) -> Notify {
+        Notify {
+            after: Duration::from_secs(5),
+            style,
+        }
+    }
+
+    #[test]
+    fn too_quick() {
+        assert_eq!(
+            notify(NotifyStyle::Bell).sequence(Duration::from_millis(4999)),
+            None
+        );
+    }
+
+    #[test]
+    fn bell() {
+        assert_eq!(
+            notify(NotifyStyle::Bell).sequence(Duration::from_secs(5)),
+            Some("\x07".into())
+        );
+    }
+
+    #[test]
+    fn osc9() {
+        assert_eq!(
+            notify(NotifyStyle::Osc9).sequence(Duration::from_millis(12_500)),
+            Some("\x1b]9;eza finished in 12s\x07".into())
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Parsing the options for `CacheOptions`.
+
+use std::path::PathBuf;
+
+use crate::fs::cache::{CacheOptions, CACHE_FILE};
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, OptionsError, Vars};
+
+impl CacheOptions {
+    /// Find the cache, if one is going to be used: the `metadata` file in
+    /// the `eza` directory of the XDG cache directory, which defaults to
+    /// `~/.cache`. Git statuses are cached in the `git` directory next to
+    /// it, unless `EZA_GIT_CACHE` says otherwise.
+    pub fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<Option<Self>, OptionsError> {
+        let refresh = match matches.get(&flags::CACHE)? {
+            None => return Ok(None),
+            Some(word) => match word.to_str() {
+                Some("on") => false,
+                Some("refresh") => true,
+                Some("off") => return Ok(None),
+                _ => return Err(OptionsError::BadArgument(&flags::CACHE, word.into())),
+            },
+        };
+
+        let non_empty = |name| vars.get(name).filter(|v| !v.is_empty());
+        let cache_dir = non_empty(vars::XDG_CACHE_HOME)
+            .map(PathBuf::from)
+            .or_else(|| non_empty(vars::HOME).map(|home| PathBuf::from(home).join(".cache")));
+
+        Ok(cache_dir.map(|dir| {
+            let dir = dir.join("eza");
+            Self {
+                path: dir.join(CACHE_FILE),
+                git_dir: non_empty(vars::EZA_GIT_CACHE)
+                    .map_or_else(|| dir.join("git"), PathBuf::from),
+                refresh,
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::config::test::Env;
+    use crate::options::parser::Arg;
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::Both;
+
+    fn deduce(args: &[&str], env: &Env) -> Result<Option<CacheOptions>, OptionsError> {
+        static TEST_ARGS: &[&Arg] = &[&flags::CACHE];
+        let mut results = parse_for_test(args, TEST_ARGS, Both, |mf| CacheOptions::deduce(mf, env));
+        results.pop().unwrap()
+    }
+
+    #[test]
+    fn not_asked_for() {
+        assert_eq!(deduce(&[], &Env(&[("HOME", "/home/u")])), Ok(None));
+    }
+
+    #[test]
+    fn home() {
+        assert_eq!(
+            deduce(&["--cache"], &Env(&[("HOME", "/home/u")])),
+            Ok(Some(CacheOptions {
+                path: PathBuf::from("/home/u/.cache/eza/metadata"),
+                git_dir: PathBuf::from("/home/u/.cache/eza/git"),
+                refresh: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn xdg_refresh() {
+        let env = Env(&[("HOME", "/home/u"), ("XDG_CACHE_HOME", "/cache")]);
+        assert_eq!(
+            deduce(&["--cache=refresh"], &env),
+            Ok(Some(CacheOptions {
+                path: PathBuf::from("/cache/eza/metadata"),
+                git_dir: PathBuf::from("/cache/eza/git"),
+                refresh: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn git_dir() {
+        let env = Env(&[("HOME", "/home/u"), ("EZA_GIT_CACHE", "/tmp/git")]);
+        assert_eq!(
+            deduce(&["--cache"], &env),
+            Ok(Some(CacheOptions {
+                path: PathBuf::from("/home/u/.cache/eza/metadata"),
+                git_dir: PathBuf::from("/tmp/git"),
+                refresh: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn off() {
+        assert_eq!(
+            deduce(&["--cache=off"], &Env(&[("HOME", "/home/u")])),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn no_home() {
+        assert_eq!(deduce(&["--cache"], &Env(&[])), Ok(None));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::options::vars::{self, Vars};
+use crate::output::capabilities::{Capabilities, ColourDepth};
+
+impl Capabilities {
+    /// Determine what the terminal can draw. RGB colours are only used when
+    /// `COLORTERM` says they can be, and only sixteen colours are used when
+    /// `TERM` names a terminal that can’t draw any more. `EZA_TERM_CAPS` can
+    /// turn off any of the capabilities with a comma-separated list such as
+    /// `no-unicode,no-hyperlinks`. Names it doesn’t know are ignored.
+    pub fn deduce<V: Vars>(vars: &V) -> Self {
+        let mut capabilities = Self {
+            colours: ColourDepth::deduce(vars),
+            ..Self::default()
+        };
+
+        if let Some(caps) = vars.get(vars::EZA_TERM_CAPS) {
+            for cap in caps.to_string_lossy().split(',') {
+                match cap.trim() {
+                    "no-unicode" => capabilities.unicode = false,
+                    "no-truecolor" => {
+                        capabilities.colours = capabilities.colours.min(ColourDepth::Ansi256);
+                    }
+                    "no-256color" => capabilities.colours = ColourDepth::Ansi16,
+                    "no-hyperlinks" => capabilities.hyperlinks = false,
+                    _ => {}
+                }
+            }
+        }
+
+        capabilities
+    }
+}
+
+impl ColourDepth {
+    fn deduce<V: Vars>(vars: &V) -> Self {
+        let colorterm = vars.get(vars::COLORTERM);
+        if colorterm.is_some_and(|c| c == "truecolor" || c == "24bit") {
+            return Self::Truecolor;
+        }
+
+        // The Linux console, and terminal types named after how many colours
+        // they have, are the ones known to stop at sixteen.
+        let term = vars
+            .get(vars::TERM)
+            .map(|t| t.to_string_lossy().to_string());
+        match term.as_deref() {
+            Some(t) if t == "linux" || t.ends_with("-16color") || t.ends_with("-8color") => {
+                Self::Ansi16
+            }
+            _ => Self::Ansi256,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::config::test::Env;
+
+    #[test]
+    fn everything() {
+        let env = Env(&[("COLORTERM", "truecolor")]);
+        assert_eq!(Capabilities::deduce(&env), Capabilities::default());
+    }
+
+    #[test]
+    fn no_colorterm() {
+        let caps = Capabilities::deduce(&Env(&[]));
+        assert_eq!(caps.colours, ColourDepth::Ansi256);
+        assert!(caps.unicode);
+    }
+
+    #[test]
+    fn sixteen_colour_terms() {
+        for env in [
+            Env(&[("TERM", "linux")]),
+            Env(&[("TERM", "xterm-16color")]),
+            Env(&[("TERM", "rxvt-8color")]),
+        ] {
+            let caps = Capabilities::deduce(&env);
+            assert_eq!(caps.colours, ColourDepth::Ansi16, "{:?}", env.0);
+        }
+
+        let caps = Capabilities::deduce(&Env(&[("TERM", "xterm-256color")]));
+        assert_eq!(caps.colours, ColourDepth::Ansi256);
+    }
+
+    #[test]
+    fn colorterm_beats_term() {
+        let env = Env(&[("COLORTERM", "truecolor"), ("TERM", "linux")]);
+        assert_eq!(Capabilities::deduce(&env).colours, ColourDepth::Truecolor);
+    }
+
+    #[test]
+    fn no_256color() {
+        let env = Env(&[("COLORTERM", "truecolor"), ("EZA_TERM_CAPS", "no-256color")]);
+        assert_eq!(Capabilities::deduce(&env).colours, ColourDepth::Ansi16);
+    }
+
+    #[test]
+    fn overridden() {
+        let env = Env(&[
+            ("COLORTERM", "24bit"),
+            (
+                "EZA_TERM_CAPS",
+                "no-unicode, no-truecolor,no-hyperlinks,no-sixel",
+            ),
+        ]);
+        assert_eq!(
+            Capabilities::deduce(&env),
+            Capabilities {
+                unicode: false,
+                colours: ColourDepth::Ansi256,
+                hyperlinks: false,
+            }
+        );
+    }
+}
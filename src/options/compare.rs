@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use crate::fs::compare::{Baseline, CompareMethod, DiffAgainst};
+use crate::fs::manifest::ManifestWrite;
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, OptionsError};
+
+impl CompareMethod {
+    /// Determine whether files get compared by their size and modification
+    /// time, or by their contents.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::DIFF_BY)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("meta") => Ok(Self::Metadata),
+            Some("content") => Ok(Self::Contents),
+            _ => Err(OptionsError::BadArgument(&flags::DIFF_BY, word.into())),
+        }
+    }
+}
+
+impl DiffAgainst {
+    /// Determine which directory or manifest to compare files with, if any.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let method = CompareMethod::deduce(matches)?;
+        let directory = matches.get(&flags::DIFF_AGAINST)?;
+        let manifest = matches.get(&flags::MANIFEST_CHECK)?;
+
+        let baseline = match (directory, manifest) {
+            (Some(_), Some(_)) => {
+                return Err(OptionsError::Conflict(
+                    &flags::DIFF_AGAINST,
+                    &flags::MANIFEST_CHECK,
+                ))
+            }
+            (Some(directory), None) => Baseline::Directory(PathBuf::from(directory)),
+            (None, Some(manifest)) => Baseline::Manifest(PathBuf::from(manifest)),
+            (None, None) => {
+                let useless = matches.is_strict()
+                    && matches.get(&flags::DIFF_BY)?.is_some()
+                    && matches.get(&flags::MANIFEST_WRITE)?.is_none();
+                if useless {
+                    return Err(OptionsError::Useless(
+                        &flags::DIFF_BY,
+                        false,
+                        &flags::DIFF_AGAINST,
+                    ));
+                }
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(Self { baseline, method }))
+    }
+}
+
+impl ManifestWrite {
+    /// Determine where to write a manifest to, if anywhere, and whether to
+    /// hash each file’s contents in it.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(path) = matches.get(&flags::MANIFEST_WRITE)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            path: PathBuf::from(path),
+            method: CompareMethod::deduce(matches)?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsString;
+
+    macro_rules! test {
+        ($name:ident: $type:ident <- $inputs:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                use crate::options::parser::Arg;
+                use crate::options::test::parse_for_test;
+                use crate::options::test::Strictnesses::*;
+
+                static TEST_ARGS: &[&Arg] = &[
+                    &flags::DIFF_AGAINST,
+                    &flags::DIFF_BY,
+                    &flags::MANIFEST_WRITE,
+                    &flags::MANIFEST_CHECK,
+                ];
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    fn directory(path: &str, method: CompareMethod) -> DiffAgainst {
+        DiffAgainst {
+            baseline: Baseline::Directory(PathBuf::from(path)),
+            method,
+        }
+    }
+
+    test!(empty:     DiffAgainst <- [];                                         Both => Ok(None));
+    test!(other:     DiffAgainst <- ["--diff-against", "../b"];                 Both => Ok(Some(directory("../b", CompareMethod::Metadata))));
+    test!(content:   DiffAgainst <- ["--diff-against=b", "--diff-by=content"];  Both => Ok(Some(directory("b", CompareMethod::Contents))));
+    test!(bad:       DiffAgainst <- ["--diff-against=b", "--diff-by=hash"];     Both => Err(OptionsError::BadArgument(&flags::DIFF_BY, OsString::from("hash"))));
+    test!(no_dir:    DiffAgainst <- ["--diff-by=meta"];                         Last => Ok(None));
+    test!(no_dir_2:  DiffAgainst <- ["--diff-by=meta"];                         Complain => Err(OptionsError::Useless(&flags::DIFF_BY, false, &flags::DIFF_AGAINST)));
+    test!(manifest:  DiffAgainst <- ["--manifest-check=m.txt"];                 Both => Ok(Some(DiffAgainst { baseline: Baseline::Manifest(PathBuf::from("m.txt")), method: CompareMethod::Metadata })));
+    test!(both:      DiffAgainst <- ["--manifest-check=m", "--diff-against=b"]; Both => Err(OptionsError::Conflict(&flags::DIFF_AGAINST, &flags::MANIFEST_CHECK)));
+    test!(writing:   DiffAgainst <- ["--manifest-write=m", "--diff-by=content"]; Both => Ok(None));
+
+    test!(no_write:  ManifestWrite <- [];                                       Both => Ok(None));
+    test!(write:     ManifestWrite <- ["--manifest-write", "m.txt"];            Both => Ok(Some(ManifestWrite { path: PathBuf::from("m.txt"), method: CompareMethod::Metadata })));
+    test!(hashed:    ManifestWrite <- ["--manifest-write=m", "--diff-by=content"]; Both => Ok(Some(ManifestWrite { path: PathBuf::from("m"), method: CompareMethod::Contents })));
+}
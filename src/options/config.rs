@@ -0,0 +1,202 @@
+//! Finding the directory that eza’s configuration files live in.
+//!
+//! eza has no single configuration file: each feature that can be configured
+//! reads its own file from this directory, if it exists.
+//!
+//! Configuration files can be split into sections headed by a platform name
+//! in square brackets, such as `[windows]` or `[macos]`, so the same file can
+//! be shared between machines. The lines in a section are only used on that
+//! platform; lines before the first section, or in an `[all]` section, are
+//! used everywhere.
+
+use std::env;
+use std::path::PathBuf;
+
+use crate::options::vars::{self, Vars};
+
+/// The directory to read configuration files from. This is `$EZA_CONFIG_DIR`
+/// if it’s set, otherwise the `eza` directory inside `$XDG_CONFIG_HOME`, or
+/// inside `~/.config` if that isn’t set either.
+pub fn config_dir<V: Vars>(vars: &V) -> Option<PathBuf> {
+    let non_empty = |name| vars.get(name).filter(|v| !v.is_empty());
+
+    if let Some(dir) = non_empty(vars::EZA_CONFIG_DIR) {
+        return Some(PathBuf::from(dir));
+    }
+
+    if let Some(dir) = non_empty(vars::XDG_CONFIG_HOME) {
+        return Some(PathBuf::from(dir).join("eza"));
+    }
+
+    non_empty(vars::HOME).map(|home| PathBuf::from(home).join(".config").join("eza"))
+}
+
+/// The lines of a configuration file that apply to this platform, along
+/// with their line numbers, skipping blank lines and `#` comments.
+pub fn platform_lines(contents: &str) -> Vec<(usize, &str)> {
+    lines_for(contents, env::consts::OS, env::consts::FAMILY)
+}
+
+/// The lines of a configuration file that apply to the given operating
+/// system (such as `linux` or `macos`) or family (`unix` or `windows`).
+fn lines_for<'a>(contents: &'a str, os: &str, family: &str) -> Vec<(usize, &'a str)> {
+    let mut applies = true;
+    let mut lines = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let section = section.trim();
+            applies = section == "all" || section == os || section == family;
+            continue;
+        }
+
+        if applies {
+            lines.push((number + 1, line));
+        }
+    }
+
+    lines
+}
+
+/// Splits a line of a configuration file into words the way a shell would,
+/// so values with spaces in can be wrapped in single or double quotes.
+/// Returns `None` if a quote is left unclosed.
+pub fn split_words(line: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.get_or_insert_with(String::new).push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => words.extend(word.take()),
+            None => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+
+    words.extend(word);
+    Some(words)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use std::ffi::OsString;
+    use std::path::Path;
+
+    /// A set of environment variables for tests that look up more than one.
+    pub struct Env(pub &'static [(&'static str, &'static str)]);
+
+    impl Vars for Env {
+        fn get(&self, name: &'static str) -> Option<OsString> {
+            self.0
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| OsString::from(v))
+        }
+    }
+
+    #[test]
+    fn eza_config_dir() {
+        let env = Env(&[("EZA_CONFIG_DIR", "/etc/eza"), ("HOME", "/home/me")]);
+        assert_eq!(config_dir(&env).as_deref(), Some(Path::new("/etc/eza")));
+    }
+
+    #[test]
+    fn xdg_config_home() {
+        let env = Env(&[("XDG_CONFIG_HOME", "/home/me/.cfg"), ("HOME", "/home/me")]);
+        assert_eq!(
+            config_dir(&env).as_deref(),
+            Some(Path::new("/home/me/.cfg/eza"))
+        );
+    }
+
+    #[test]
+    fn home() {
+        let env = Env(&[("EZA_CONFIG_DIR", ""), ("HOME", "/home/me")]);
+        assert_eq!(
+            config_dir(&env).as_deref(),
+            Some(Path::new("/home/me/.config/eza"))
+        );
+    }
+
+    #[test]
+    fn nowhere() {
+        assert_eq!(config_dir(&Env(&[])), None);
+    }
+
+    static SECTIONED: &str = "\
+*.bak
+
+[windows]
+desktop.ini
+Thumbs.db
+
+[macos]
+.DS_Store
+
+# Both of these are Unix
+[unix]
+*~
+[all]
+*.tmp
+";
+
+    #[test]
+    fn windows_sections() {
+        assert_eq!(
+            lines_for(SECTIONED, "windows", "windows"),
+            vec![
+                (1, "*.bak"),
+                (4, "desktop.ini"),
+                (5, "Thumbs.db"),
+                (14, "*.tmp")
+            ]
+        );
+    }
+
+    #[test]
+    fn macos_sections() {
+        assert_eq!(
+            lines_for(SECTIONED, "macos", "unix"),
+            vec![(1, "*.bak"), (8, ".DS_Store"), (12, "*~"), (14, "*.tmp")]
+        );
+    }
+
+    #[test]
+    fn unknown_section() {
+        assert_eq!(lines_for("[plan9]\nnamespace", "linux", "unix"), vec![]);
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(
+            split_words("--long  --ignore-glob='*.o|*.a' \"two words\" ''"),
+            Some(vec![
+                "--long".into(),
+                "--ignore-glob=*.o|*.a".into(),
+                "two words".into(),
+                String::new(),
+            ])
+        );
+    }
+
+    #[test]
+    fn unclosed_quote() {
+        assert_eq!(split_words("--sort='name"), None);
+    }
+}
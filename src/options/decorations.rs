@@ -0,0 +1,104 @@
+//! Reading the order of the parts drawn around file names from the
+//! `decorations` file in the configuration directory, one per line:
+//!
+//! ```text
+//! # put classify characters before icons
+//! classify
+//! icon
+//! name
+//! ```
+//!
+//! Decorations that aren’t listed aren’t drawn, even when the option that
+//! turns them on is given. The name has to be listed.
+
+use std::fs;
+use std::io;
+
+use crate::options::config;
+use crate::options::{OptionsError, Vars};
+use crate::output::decorations::Decoration;
+
+/// The name of the file, in the configuration directory, that the order of
+/// decorations is read from.
+pub static DECORATIONS_FILE: &str = "decorations";
+
+/// Determine the order to draw decorations in from the `decorations`
+/// configuration file, using the default order if it doesn’t exist.
+pub fn deduce<V: Vars>(vars: &V) -> Result<Vec<Decoration>, OptionsError> {
+    let Some(path) = config::config_dir(vars).map(|dir| dir.join(DECORATIONS_FILE)) else {
+        return Ok(Decoration::default_order());
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Decoration::default_order()),
+        Err(e) => return Err(OptionsError::BadConfig(path, 0, e.to_string())),
+    };
+
+    parse_decorations(&contents).map_err(|(line, msg)| OptionsError::BadConfig(path, line, msg))
+}
+
+/// Parses the contents of a decorations file, returning the line number and
+/// a description of the first line that couldn’t be parsed.
+fn parse_decorations(contents: &str) -> Result<Vec<Decoration>, (usize, String)> {
+    let mut decorations = Vec::new();
+
+    for (number, line) in config::platform_lines(contents) {
+        let Some(decoration) = Decoration::from_name(line) else {
+            return Err((number, format!("unknown decoration {line:?}")));
+        };
+
+        if decorations.contains(&decoration) {
+            return Err((number, format!("decoration {line:?} is listed twice")));
+        }
+
+        decorations.push(decoration);
+    }
+
+    if !decorations.contains(&Decoration::Name) {
+        return Err((0, "the name has to be listed".into()));
+    }
+
+    Ok(decorations)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reordered() {
+        assert_eq!(
+            parse_decorations("classify\n# comment\nicon\nname\n"),
+            Ok(vec![
+                Decoration::Classify,
+                Decoration::Icon,
+                Decoration::Name
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            parse_decorations("name\ngit\n"),
+            Err((2, "unknown decoration \"git\"".into()))
+        );
+    }
+
+    #[test]
+    fn twice() {
+        assert_eq!(
+            parse_decorations("icon\nname\nicon\n"),
+            Err((3, "decoration \"icon\" is listed twice".into()))
+        );
+    }
+
+    #[test]
+    fn no_name() {
+        assert_eq!(
+            parse_decorations("icon\n"),
+            Err((0, "the name has to be listed".into()))
+        );
+    }
+}
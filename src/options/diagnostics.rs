@@ -0,0 +1,56 @@
+//! Parsing the options for `Verbosity`.
+
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, OptionsError};
+
+use crate::diagnostics::Verbosity;
+
+impl Verbosity {
+    /// Determine how warnings should be treated. `--quiet` and
+    /// `--strict-warnings` pull in opposite directions, so the last one given
+    /// wins, unless in strict mode, where giving both is an error.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let flag = matches
+            .has_where(|f| f.matches(&flags::QUIET) || f.matches(&flags::STRICT_WARNINGS))?;
+
+        match flag {
+            Some(f) if f.matches(&flags::QUIET) => Ok(Self::Quiet),
+            Some(_) => Ok(Self::Strict),
+            None => Ok(Self::Normal),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::flags;
+    use crate::options::parser::Flag;
+
+    macro_rules! test {
+        ($name:ident: $type:ident <- $inputs:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                use crate::options::parser::Arg;
+                use crate::options::test::parse_for_test;
+                use crate::options::test::Strictnesses::*;
+
+                static TEST_ARGS: &[&Arg] = &[&flags::QUIET, &flags::STRICT_WARNINGS];
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    test!(empty:         Verbosity <- [];                                   Both => Ok(Verbosity::Normal));
+    test!(quiet_short:   Verbosity <- ["-q"];                               Both => Ok(Verbosity::Quiet));
+    test!(quiet_long:    Verbosity <- ["--quiet"];                          Both => Ok(Verbosity::Quiet));
+    test!(strict:        Verbosity <- ["--strict-warnings"];                Both => Ok(Verbosity::Strict));
+
+    test!(quiet_strict:  Verbosity <- ["--quiet", "--strict-warnings"];     Last => Ok(Verbosity::Strict));
+    test!(strict_quiet:  Verbosity <- ["--strict-warnings", "-q"];          Last => Ok(Verbosity::Quiet));
+    test!(both_complain: Verbosity <- ["-q", "--strict-warnings"];      Complain => Err(OptionsError::Duplicate(Flag::Short(b'q'), Flag::Long("strict-warnings"))));
+}
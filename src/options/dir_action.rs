@@ -23,10 +23,26 @@ impl DirAction {
                     &flags::RECURSE,
                     &flags::TREE,
                 ));
+            } else if !recurse && !tree && matches.has(&flags::FOLLOW_SYMLINKS)? {
+                return Err(OptionsError::Useless2(
+                    &flags::FOLLOW_SYMLINKS,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
             } else if recurse && as_file {
                 return Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS));
             } else if tree && as_file {
                 return Err(OptionsError::Conflict(&flags::TREE, &flags::LIST_DIRS));
+            } else if recurse && matches.has(&flags::MERGE)? {
+                return Err(OptionsError::Conflict(&flags::MERGE, &flags::RECURSE));
+            } else if tree && matches.has(&flags::MERGE)? {
+                return Err(OptionsError::Conflict(&flags::MERGE, &flags::TREE));
+            } else if !tree && matches.has(&flags::TREE_SUMMARY)? {
+                return Err(OptionsError::Useless(
+                    &flags::TREE_SUMMARY,
+                    false,
+                    &flags::TREE,
+                ));
             }
         }
 
@@ -46,16 +62,20 @@ impl DirAction {
 
 impl RecurseOptions {
     /// Determine which files should be recursed into, based on the `--level`
-    /// flag’s value, and whether the `--tree` flag was passed, which was
-    /// determined earlier. The maximum level should be a number, and this
-    /// will fail with an `Err` if it isn’t.
+    /// flag’s value, whether symlinks should be followed, and whether the
+    /// `--tree` flag was passed, which was determined earlier. The maximum
+    /// level should be a number, and this will fail with an `Err` if it
+    /// isn’t.
     pub fn deduce(matches: &MatchedFlags<'_>, tree: bool) -> Result<Self, OptionsError> {
+        let follow_symlinks = matches.has(&flags::FOLLOW_SYMLINKS)?;
+
         if let Some(level) = matches.get(&flags::LEVEL)? {
             let arg_str = level.to_string_lossy();
             match arg_str.parse() {
                 Ok(l) => Ok(Self {
                     tree,
                     max_depth: Some(l),
+                    follow_symlinks,
                 }),
                 Err(e) => {
                     let source = NumberSource::Arg(&flags::LEVEL);
@@ -66,6 +86,7 @@ impl RecurseOptions {
             Ok(Self {
                 tree,
                 max_depth: None,
+                follow_symlinks,
             })
         }
     }
@@ -90,6 +111,9 @@ mod test {
                     &flags::LIST_DIRS,
                     &flags::TREE,
                     &flags::LEVEL,
+                    &flags::TREE_SUMMARY,
+                    &flags::FOLLOW_SYMLINKS,
+                    &flags::MERGE,
                 ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
                     $type::deduce(mf, true)
@@ -109,26 +133,40 @@ mod test {
 
     // Recursing
     use self::DirAction::Recurse;
-    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4) })));
-    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5) })));
-    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666) })));
-    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118) })));
-    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, follow_symlinks: false })));
+    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, follow_symlinks: false })));
+    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4), follow_symlinks: false })));
+    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5), follow_symlinks: false })));
+    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666), follow_symlinks: false })));
+    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118), follow_symlinks: false })));
+    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: false })));
+    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: false })));
+    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: false })));
 
     // Overriding --list-dirs, --recurse, and --tree
-    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, follow_symlinks: false })));
+    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: false })));
     test!(just_level:      DirAction <- ["--level=4"];                    Last => Ok(DirAction::List));
 
     test!(dirs_recurse_2:  DirAction <- ["--list-dirs", "--recurse"]; Complain => Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS)));
     test!(dirs_tree_2:     DirAction <- ["--list-dirs", "--tree"];    Complain => Err(OptionsError::Conflict(&flags::TREE,    &flags::LIST_DIRS)));
     test!(just_level_2:    DirAction <- ["--level=4"];                Complain => Err(OptionsError::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE)));
+    test!(tree_summary:    DirAction <- ["--tree", "--tree-summary"];     Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: false })));
+    test!(just_summary:    DirAction <- ["--tree-summary"];               Last => Ok(DirAction::List));
+    test!(just_summary_2:  DirAction <- ["--tree-summary"];           Complain => Err(OptionsError::Useless(&flags::TREE_SUMMARY, false, &flags::TREE)));
+
+    // Following symlinks
+    test!(follow:          DirAction <- ["-R", "--follow-symlinks"];      Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, follow_symlinks: true })));
+    test!(follow_tree:     DirAction <- ["-T", "--follow-symlinks"];      Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: true })));
+    test!(just_follow:     DirAction <- ["--follow-symlinks"];            Last => Ok(DirAction::List));
+    test!(just_follow_2:   DirAction <- ["--follow-symlinks"];        Complain => Err(OptionsError::Useless2(&flags::FOLLOW_SYMLINKS, &flags::RECURSE, &flags::TREE)));
+
+    // Merging several directories
+    test!(merge_recurse:   DirAction <- ["-R", "--merge"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, follow_symlinks: false })));
+    test!(merge_recurse_2: DirAction <- ["-R", "--merge"];            Complain => Err(OptionsError::Conflict(&flags::MERGE, &flags::RECURSE)));
+    test!(merge_tree_2:    DirAction <- ["-T", "--merge"];            Complain => Err(OptionsError::Conflict(&flags::MERGE, &flags::TREE)));
 
     // Overriding levels
-    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7) })));
+    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7), follow_symlinks: false })));
     test!(overriding_2:    DirAction <- ["-RL=6", "-L=7"];            Complain => Err(OptionsError::Duplicate(Flag::Short(b'L'), Flag::Short(b'L'))));
 }
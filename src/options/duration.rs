@@ -0,0 +1,80 @@
+//! Parsing lengths of time given as command-line arguments.
+
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use crate::options::parser::Arg;
+use crate::options::OptionsError;
+
+/// Parses a length of time such as `90s`, `30m`, `24h`, `7d`, or `2w`. A
+/// number on its own is a number of seconds.
+pub fn parse_duration(arg: &'static Arg, input: &OsStr) -> Result<Duration, OptionsError> {
+    let bad = || OptionsError::BadArgument(arg, input.into());
+    let text = input.to_str().ok_or_else(bad)?.trim();
+
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().map_err(|_| bad())?;
+
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => return Err(bad()),
+    };
+
+    number
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(bad)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::flags;
+
+    fn parse(input: &str) -> Result<Duration, OptionsError> {
+        parse_duration(&flags::HIGHLIGHT_RECENT, OsStr::new(input))
+    }
+
+    #[test]
+    fn seconds() {
+        assert_eq!(parse("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse("90s"), Ok(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(parse("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse("24h"), Ok(Duration::from_secs(24 * 60 * 60)));
+        assert_eq!(parse("7d"), Ok(Duration::from_secs(7 * 24 * 60 * 60)));
+        assert_eq!(parse("2w"), Ok(Duration::from_secs(14 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn bad_unit() {
+        assert_eq!(
+            parse("3y"),
+            Err(OptionsError::BadArgument(
+                &flags::HIGHLIGHT_RECENT,
+                "3y".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn no_number() {
+        assert!(parse("h").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(parse("99999999999999999w").is_err());
+    }
+}
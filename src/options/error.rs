@@ -1,6 +1,7 @@
 use std::ffi::OsString;
 use std::fmt;
 use std::num::ParseIntError;
+use std::path::PathBuf;
 
 use crate::options::flags;
 use crate::options::parser::{Arg, Flag, ParseError};
@@ -39,6 +40,24 @@ pub enum OptionsError {
 
     /// A glob ignore was given that failed to be parsed as a pattern.
     FailedGlobPattern(String),
+
+    /// A highlight pattern was given that failed to be parsed as a regular
+    /// expression.
+    FailedRegexPattern(String),
+
+    /// A user or group was given that doesn’t exist.
+    NoSuchOwner(&'static Arg, OsString),
+
+    /// An option was given that needs a feature this build was compiled
+    /// without.
+    FeatureDisabled(&'static Arg, &'static str),
+
+    /// `eza view` was given a view that doesn’t exist, or none at all,
+    /// along with the names of the views that do.
+    NoSuchView(Option<OsString>, Vec<String>),
+
+    /// A configuration file couldn’t be read or had a bad line in it.
+    BadConfig(PathBuf, usize, String),
 }
 
 /// The source of a string that failed to be parsed as a number.
@@ -57,6 +76,12 @@ impl From<glob::PatternError> for OptionsError {
     }
 }
 
+impl From<regex::Error> for OptionsError {
+    fn from(error: regex::Error) -> Self {
+        Self::FailedRegexPattern(error.to_string())
+    }
+}
+
 impl fmt::Display for NumberSource {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -96,6 +121,13 @@ impl fmt::Display for OptionsError {
             Self::TreeAllAll                 => write!(f, "Option --tree is useless given --all --all"),
             Self::FailedParse(s, n, e)       => write!(f, "Value {s:?} not valid for {n}: {e}"),
             Self::FailedGlobPattern(ref e)   => write!(f, "Failed to parse glob pattern: {e}"),
+            Self::FailedRegexPattern(ref e)  => write!(f, "Failed to parse regular expression: {e}"),
+            Self::NoSuchOwner(a, name)       => write!(f, "Option {a} has no user or group {name:?}"),
+            Self::FeatureDisabled(a, feat)   => write!(f, "Option {a} is unavailable because eza was built without the {feat:?} feature"),
+            Self::NoSuchView(None, ref v)    => write!(f, "Subcommand view needs a view name (choices: {})", v.join(", ")),
+            Self::NoSuchView(Some(ref n), ref v) => write!(f, "No view named {n:?} (choices: {})", v.join(", ")),
+            Self::BadConfig(ref p, 0, ref e) => write!(f, "Failed to read {}: {e}", p.display()),
+            Self::BadConfig(ref p, l, ref e) => write!(f, "{}:{l}: {e}", p.display()),
         };
     }
 }
@@ -103,20 +135,64 @@ impl fmt::Display for OptionsError {
 impl OptionsError {
     /// Try to second-guess what the user was trying to do, depending on what
     /// went wrong.
-    pub fn suggestion(&self) -> Option<&'static str> {
+    pub fn suggestion(&self) -> Option<String> {
         // ‘ls -lt’ and ‘ls -ltr’ are common combinations
         match self {
             Self::BadArgument(time, r) if *time == &flags::TIME && r == "r" => {
-                Some("To sort oldest files last, try \"--sort oldest\", or just \"-sold\"")
+                Some("To sort oldest files last, try \"--sort oldest\", or just \"-sold\"".into())
             }
             Self::Parse(ParseError::NeedsValue { ref flag, .. }) if *flag == Flag::Short(b't') => {
-                Some("To sort newest files last, try \"--sort newest\", or just \"-snew\"")
+                Some("To sort newest files last, try \"--sort newest\", or just \"-snew\"".into())
+            }
+            Self::Parse(ParseError::UnknownArgument { ref attempt }) => {
+                suggest_long(&attempt.to_string_lossy())
             }
             _ => None,
         }
     }
 }
 
+/// Builds the “did you mean” hint for an unknown long option, mentioning
+/// when the option in question needs a feature this build was compiled
+/// without.
+fn suggest_long(attempt: &str) -> Option<String> {
+    let disabled_feature = |long: &str| {
+        flags::FEATURE_GATED
+            .iter()
+            .find(|(arg, _, enabled)| arg.long == long && !enabled)
+            .map(|(_, feature, _)| *feature)
+    };
+
+    if let Some(feature) = disabled_feature(attempt) {
+        return Some(format!(
+            "Option --{attempt} is only available when eza is built with the \"{feature}\" feature"
+        ));
+    }
+
+    let similar = flags::ALL_ARGS.similar_longs(attempt);
+    let mut hint = match similar.as_slice() {
+        [] => return None,
+        [only] => format!("Did you mean --{only}?"),
+        several => {
+            let names = several
+                .iter()
+                .map(|long| format!("--{long}"))
+                .collect::<Vec<_>>();
+            format!("Did you mean one of {}?", names.join(", "))
+        }
+    };
+
+    for long in &similar {
+        if let Some(feature) = disabled_feature(long) {
+            hint.push_str(&format!(
+                " (--{long} needs eza to be built with the \"{feature}\" feature)"
+            ));
+        }
+    }
+
+    Some(hint)
+}
+
 /// A list of legal choices for an argument-taking option.
 #[derive(PartialEq, Eq, Debug)]
 pub struct Choices(pub &'static [&'static str]);
@@ -126,3 +202,35 @@ impl fmt::Display for Choices {
         write!(f, "choices: {}", self.0.join(", "))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn unknown(attempt: &str) -> OptionsError {
+        OptionsError::Parse(ParseError::UnknownArgument {
+            attempt: OsString::from(attempt),
+        })
+    }
+
+    #[test]
+    fn suggests_single_flag() {
+        assert_eq!(
+            unknown("revrese").suggestion(),
+            Some("Did you mean --reverse?".into())
+        );
+    }
+
+    #[test]
+    fn suggests_several_flags() {
+        assert_eq!(
+            unknown("colour-scal").suggestion(),
+            Some("Did you mean one of --colour-scale, --color-scale, --colour-scale-mode?".into())
+        );
+    }
+
+    #[test]
+    fn nothing_similar() {
+        assert_eq!(unknown("xyzzy-plugh").suggestion(), None);
+    }
+}
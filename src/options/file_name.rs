@@ -1,8 +1,18 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::options::duration::parse_duration;
 use crate::options::parser::MatchedFlags;
 use crate::options::vars::{self, Vars};
-use crate::options::{flags, NumberSource, OptionsError};
+use crate::options::{decorations, flags, NumberSource, OptionsError};
+use crate::output::capabilities::Capabilities;
+use crate::output::glyphs::Glyphs;
 
-use crate::output::file_name::{Classify, EmbedHyperlinks, Options, QuoteStyle, ShowIcons};
+use crate::output::file_name::{
+    Classify, DepthColours, HyperlinkMode, Options, QuoteStyle, ShowIcons, Truncate,
+};
 
 impl Options {
     pub fn deduce<V: Vars>(
@@ -14,7 +24,26 @@ impl Options {
         let show_icons = ShowIcons::deduce(matches, vars)?;
 
         let quote_style = QuoteStyle::deduce(matches)?;
-        let embed_hyperlinks = EmbedHyperlinks::deduce(matches)?;
+        let embed_hyperlinks = HyperlinkMode::deduce(matches)?;
+        let highlight_recent = Self::deduce_highlight_recent(matches)?;
+        let name_clashes = matches.has(&flags::NAME_CLASHES)?;
+        let show_trash = matches.has(&flags::SHOW_TRASH)?;
+        let resolve_shortcuts = matches.has(&flags::RESOLVE_SHORTCUTS)?;
+        let finder_tags = matches.has(&flags::FINDER_TAGS)?;
+        if finder_tags && !cfg!(target_os = "macos") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Showing Finder tags is only supported on macOS",
+            )));
+        }
+        let mount_usage = matches.has(&flags::MOUNT_USAGE)?;
+        let special_dirs = matches.has(&flags::SPECIAL_DIRS)?;
+        let depth_colours = DepthColours::deduce(matches)?;
+        let truncate = Truncate::deduce(matches)?;
+        let highlight = Self::deduce_highlight(matches)?;
+        let relative_root = Self::deduce_relative_root(matches)?;
+        let badges = Self::deduce_badges(matches)?;
+        let glyphs = Glyphs::deduce(vars, Capabilities::deduce(vars))?;
+        let decorations = decorations::deduce(vars)?;
 
         Ok(Self {
             classify,
@@ -22,8 +51,71 @@ impl Options {
             quote_style,
             embed_hyperlinks,
             is_a_tty,
+            highlight_recent,
+            name_clashes,
+            show_trash,
+            resolve_shortcuts,
+            finder_tags,
+            mount_usage,
+            special_dirs,
+            depth_colours,
+            truncate,
+            highlight,
+            relative_root,
+            badges,
+            glyphs,
+            decorations,
         })
     }
+
+    /// How recently modified a file has to be to be highlighted, from the
+    /// optional duration given to `--highlight-recent`.
+    fn deduce_highlight_recent(
+        matches: &MatchedFlags<'_>,
+    ) -> Result<Option<Duration>, OptionsError> {
+        matches
+            .get(&flags::HIGHLIGHT_RECENT)?
+            .map(|window| parse_duration(&flags::HIGHLIGHT_RECENT, window))
+            .transpose()
+    }
+
+    /// Compile the pattern given to `--highlight`, if there is one.
+    fn deduce_highlight(matches: &MatchedFlags<'_>) -> Result<Option<Regex>, OptionsError> {
+        let Some(pattern) = matches.get(&flags::HIGHLIGHT)? else {
+            return Ok(None);
+        };
+
+        let Some(pattern) = pattern.to_str() else {
+            return Err(OptionsError::BadArgument(&flags::HIGHLIGHT, pattern.into()));
+        };
+
+        Ok(Some(Regex::new(pattern)?))
+    }
+
+    /// The attribute given to `--badges`, which defaults to `user.eza.badge`.
+    fn deduce_badges(matches: &MatchedFlags<'_>) -> Result<Option<String>, OptionsError> {
+        let Some(attribute) = matches.get(&flags::BADGES)? else {
+            return Ok(None);
+        };
+
+        match attribute.to_str() {
+            Some(name) if !name.is_empty() => Ok(Some(name.to_owned())),
+            _ => Err(OptionsError::BadArgument(&flags::BADGES, attribute.into())),
+        }
+    }
+
+    /// The directory given to `--relative-root`, which can’t be empty, as
+    /// that would match nothing.
+    fn deduce_relative_root(matches: &MatchedFlags<'_>) -> Result<Option<PathBuf>, OptionsError> {
+        match matches.get(&flags::RELATIVE_ROOT)? {
+            None => Ok(None),
+            Some(root) if root.is_empty() => Err(OptionsError::BadArgument(
+                &flags::RELATIVE_ROOT,
+                root.into(),
+            )),
+            Some(root) => Ok(Some(PathBuf::from(root))),
+        }
+    }
 }
 
 impl Classify {
@@ -102,14 +194,135 @@ impl QuoteStyle {
     }
 }
 
-impl EmbedHyperlinks {
+impl DepthColours {
+    /// Determine which parts of a tree to colour by depth, which only makes
+    /// sense when there’s a tree to colour.
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        let flagged = matches.has(&flags::HYPERLINK)?;
+        let Some(word) =
+            matches.get_where(|f| f.matches(&flags::DEPTH_COLORS) || f.matches(&flags::DEPTH_COLOURS))?
+        else {
+            return Ok(Self::Off);
+        };
+
+        if matches.is_strict() && !matches.has(&flags::TREE)? {
+            return Err(OptionsError::Useless(
+                &flags::DEPTH_COLORS,
+                false,
+                &flags::TREE,
+            ));
+        }
 
-        if flagged {
-            Ok(Self::On)
+        if word == "lines" {
+            Ok(Self::Lines)
+        } else if word == "names" {
+            Ok(Self::Names)
+        } else if word == "all" {
+            Ok(Self::All)
         } else {
-            Ok(Self::Off)
+            Err(OptionsError::BadArgument(&flags::DEPTH_COLORS, word.into()))
+        }
+    }
+}
+
+impl Truncate {
+    /// Determine how to shorten long names from the `--truncate` and
+    /// `--max-name-width` options. Giving just a width cuts the ends off
+    /// names, but giving just a mode does nothing, as there’s no width to
+    /// shorten them to.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let mode = matches.get(&flags::TRUNCATE)?;
+
+        let Some(width) = matches.get(&flags::MAX_NAME_WIDTH)? else {
+            if mode.is_some_and(|m| m != "off") && matches.is_strict() {
+                return Err(OptionsError::Useless(
+                    &flags::TRUNCATE,
+                    false,
+                    &flags::MAX_NAME_WIDTH,
+                ));
+            }
+            return Ok(Self::Off);
+        };
+
+        let arg_str = width.to_string_lossy();
+        let width = arg_str.parse().map_err(|e| {
+            let source = NumberSource::Arg(&flags::MAX_NAME_WIDTH);
+            OptionsError::FailedParse(arg_str.to_string(), source, e)
+        })?;
+
+        match mode {
+            None => Ok(Self::End(width)),
+            Some(m) if m == "end" => Ok(Self::End(width)),
+            Some(m) if m == "middle" => Ok(Self::Middle(width)),
+            Some(m) if m == "off" => Ok(Self::Off),
+            Some(m) => Err(OptionsError::BadArgument(&flags::TRUNCATE, m.into())),
+        }
+    }
+}
+
+impl HyperlinkMode {
+    /// Determines which parts of the output to make hyperlinks from the
+    /// `--hyperlink` option, which makes file names hyperlinks when it’s
+    /// given without a value.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::HYPERLINK)? else {
+            return Ok(Self::Never);
+        };
+
+        match word.to_str() {
+            Some("never") => Ok(Self::Never),
+            Some("auto") => Ok(Self::Auto),
+            Some("always") => Ok(Self::Always),
+            Some("all-cells") => Ok(Self::AllCells),
+            _ => Err(OptionsError::BadArgument(&flags::HYPERLINK, word.into())),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::flags;
+    use crate::options::parser::Flag;
+
+    macro_rules! test {
+        ($name:ident: $type:ident <- $inputs:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                use crate::options::parser::Arg;
+                use crate::options::test::parse_for_test;
+                use crate::options::test::Strictnesses::*;
+
+                static TEST_ARGS: &[&Arg] = &[
+                    &flags::TRUNCATE,
+                    &flags::MAX_NAME_WIDTH,
+                    &flags::DEPTH_COLORS,
+                    &flags::DEPTH_COLOURS,
+                    &flags::TREE,
+                ];
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    test!(none:         Truncate <- [];                                          Both => Ok(Truncate::Off));
+    test!(width_only:   Truncate <- ["--max-name-width=20"];                     Both => Ok(Truncate::End(20)));
+    test!(end:          Truncate <- ["--truncate=end", "--max-name-width=20"];   Both => Ok(Truncate::End(20)));
+    test!(middle:       Truncate <- ["--truncate=middle", "--max-name-width=9"]; Both => Ok(Truncate::Middle(9)));
+    test!(off:          Truncate <- ["--truncate=off", "--max-name-width=9"];    Both => Ok(Truncate::Off));
+    test!(no_width:     Truncate <- ["--truncate=middle"];                       Last => Ok(Truncate::Off));
+    test!(no_width_2:   Truncate <- ["--truncate=middle"];                       Complain => Err(OptionsError::Useless(&flags::TRUNCATE, false, &flags::MAX_NAME_WIDTH)));
+    test!(bad_mode:     Truncate <- ["--truncate=start", "--max-name-width=9"];  Both => Err(OptionsError::BadArgument(&flags::TRUNCATE, "start".into())));
+    test!(overridden:   Truncate <- ["--max-name-width=9", "--max-name-width=20"]; Complain => Err(OptionsError::Duplicate(Flag::Long("max-name-width"), Flag::Long("max-name-width"))));
+
+    test!(depth_none:   DepthColours <- ["--tree"];                              Both => Ok(DepthColours::Off));
+    test!(depth_bare:   DepthColours <- ["--tree", "--depth-colors"];            Both => Ok(DepthColours::Lines));
+    test!(depth_names:  DepthColours <- ["--tree", "--depth-colours=names"];     Both => Ok(DepthColours::Names));
+    test!(depth_all:    DepthColours <- ["--tree", "--depth-colors=all"];        Both => Ok(DepthColours::All));
+    test!(depth_bad:    DepthColours <- ["--tree", "--depth-colors=rainbow"];    Both => Err(OptionsError::BadArgument(&flags::DEPTH_COLORS, "rainbow".into())));
+    test!(depth_flat:   DepthColours <- ["--depth-colors"];                      Last => Ok(DepthColours::Lines));
+    test!(depth_flat_2: DepthColours <- ["--depth-colors"];                      Complain => Err(OptionsError::Useless(&flags::DEPTH_COLORS, false, &flags::TREE)));
+}
@@ -1,16 +1,26 @@
 //! Parsing the options for `FileFilter`.
 
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::fs::feature::sort_command::SortCommand;
 use crate::fs::filter::{
-    FileFilter, FileFilterFlags, GitIgnore, IgnorePatterns, SortCase, SortField,
+    EmptyFilter, ExtFilter, FileFilter, FileFilterFlags, GitIgnore, GitTracking, IgnorePatterns,
+    OwnerFilter, PermFilter, PermMatch, SizeFilter, SortCase, SortField, SortKey, TimeFilter,
 };
 use crate::fs::DotFilter;
 
-use crate::options::parser::MatchedFlags;
-use crate::options::{flags, OptionsError};
+use crate::options::duration::parse_duration;
+use crate::options::parser::{Arg, MatchedFlags};
+use crate::options::{config, flags, OptionsError, Vars};
+use crate::output::table::{TimeType, TimeTypes};
 
 impl FileFilter {
     /// Determines which of all the file filter options to use.
-    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+    pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         use FileFilterFlags as FFF;
         let mut filter_flags: Vec<FileFilterFlags> = vec![];
 
@@ -28,22 +38,35 @@ impl FileFilter {
         return Ok(Self {
             list_dirs_first:  matches.has(&flags::DIRS_FIRST)?,
             flags: filter_flags,
-            sort_field:       SortField::deduce(matches)?,
+            sort_fields:      SortKey::deduce(matches)?,
+            sort_command:     SortCommand::deduce(matches)?,
             dot_filter:       DotFilter::deduce(matches)?,
             ignore_patterns:  IgnorePatterns::deduce(matches)?,
+            only_patterns:    IgnorePatterns::deduce_only(matches)?,
+            hidden_patterns:  IgnorePatterns::deduce_hidden(vars)?,
             git_ignore:       GitIgnore::deduce(matches)?,
+            git_tracking:     GitTracking::deduce(matches)?,
+            owner:            OwnerFilter::deduce(matches)?,
+            perm:             PermFilter::deduce(matches)?,
+            emptiness:        EmptyFilter::deduce(matches)?,
+            recursing:        matches.has(&flags::RECURSE)? || matches.has(&flags::TREE)?,
+            extensions:       ExtFilter::deduce(matches)?,
+            size:             SizeFilter::deduce(matches)?,
+            time:             TimeFilter::deduce(matches)?,
         });
     }
 }
 
-impl SortField {
-    /// Determines which sort field to use based on the `--sort` argument.
-    /// This argument’s value can be one of several flags, listed above.
-    /// Returns the default sort field if none is given, or `Err` if the
-    /// value doesn’t correspond to a sort field we know about.
-    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+impl SortKey {
+    /// Determines which fields to sort by based on the `--sort` argument.
+    /// This argument’s value can be one of several fields, listed below, or
+    /// a comma-separated list of them, with files that sort the same by one
+    /// field being sorted by the next. A field with a `-` in front of it is
+    /// sorted backwards. Returns the default sort field if none is given, or
+    /// `Err` if a value doesn’t correspond to a sort field we know about.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Vec<Self>, OptionsError> {
         let Some(word) = matches.get(&flags::SORT)? else {
-            return Ok(Self::default());
+            return Ok(vec![Self::from(SortField::default())]);
         };
 
         // Get String because we can’t match an OsStr
@@ -51,6 +74,38 @@ impl SortField {
             return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
         };
 
+        // The command itself is checked by `SortCommand::deduce`, and can
+        // have commas in it, so it can only be sorted by on its own.
+        if word.starts_with("cmd:") {
+            return Ok(vec![Self::from(SortField::Command)]);
+        }
+
+        let keys = word
+            .split(',')
+            .map(|part| {
+                let (reverse, name) = match part.strip_prefix('-') {
+                    Some(name) => (true, name),
+                    None => (false, part),
+                };
+                let field = SortField::from_word(name)
+                    .ok_or_else(|| OptionsError::BadArgument(&flags::SORT, part.into()))?;
+                Ok(Self { field, reverse })
+            })
+            .collect::<Result<Vec<_>, OptionsError>>()?;
+
+        // Leaving files unsorted only makes sense if nothing else sorts them.
+        if keys.len() > 1 && keys.iter().any(|key| key.field == SortField::Unsorted) {
+            return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
+        }
+
+        Ok(keys)
+    }
+}
+
+impl SortField {
+    /// Finds the sort field with the given name, or `None` if there isn’t
+    /// one.
+    fn from_word(word: &str) -> Option<Self> {
         let field = match word {
             "name" | "filename" => Self::Name(SortCase::AaBbCc),
             "Name" | "Filename" => Self::Name(SortCase::ABCabc),
@@ -76,14 +131,64 @@ impl SortField {
             "cr" | "created" => Self::CreatedDate,
             #[cfg(unix)]
             "inode" => Self::FileInode,
+            #[cfg(unix)]
+            "dev" | "device" => Self::DeviceID,
             "type" => Self::FileType,
+            "bytes" => Self::Bytes,
+            "git" => Self::GitStatus,
             "none" => Self::Unsorted,
-            _ => {
-                return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
+            _ => return None,
+        };
+
+        Some(field)
+    }
+}
+
+impl SortCommand {
+    /// Determines the command to sort by from a `--sort=cmd:COMMAND`
+    /// argument, which is split into words like a shell would. As it runs a
+    /// program, it has to be allowed with `--allow-sort-command` too.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let allowed = matches.has(&flags::ALLOW_SORT_COMMAND)?;
+
+        let command = matches
+            .get(&flags::SORT)?
+            .and_then(OsStr::to_str)
+            .and_then(|word| word.strip_prefix("cmd:"));
+
+        let Some(command) = command else {
+            if allowed && matches.is_strict() {
+                return Err(OptionsError::Useless(
+                    &flags::ALLOW_SORT_COMMAND,
+                    false,
+                    &flags::SORT,
+                ));
             }
+            return Ok(None);
         };
 
-        Ok(field)
+        if !cfg!(feature = "sort-command") {
+            return Err(OptionsError::FeatureDisabled(&flags::SORT, "sort-command"));
+        }
+
+        if !allowed {
+            return Err(OptionsError::Unsupported(String::from(
+                "Sorting with a command needs --allow-sort-command",
+            )));
+        }
+
+        let bad_command =
+            || OptionsError::BadArgument(&flags::SORT, format!("cmd:{command}").into());
+        let mut words = config::split_words(command).ok_or_else(bad_command)?;
+        if words.is_empty() {
+            return Err(bad_command());
+        }
+
+        let program = words.remove(0);
+        Ok(Some(Self {
+            program,
+            args: words,
+        }))
     }
 }
 
@@ -170,6 +275,20 @@ impl IgnorePatterns {
             return Ok(Self::empty());
         };
 
+        Self::parse_pipe_separated(inputs)
+    }
+
+    /// Determines the set of glob patterns that files have to match from
+    /// the `--only-glob` argument’s value, which is separated by pipes in
+    /// the same way. Unlike with ignoring, no patterns means no filter.
+    pub fn deduce_only(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        match matches.get(&flags::ONLY_GLOB)? {
+            Some(inputs) => Self::parse_pipe_separated(inputs).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn parse_pipe_separated(inputs: &OsStr) -> Result<Self, OptionsError> {
         // Awkwardly, though, a glob pattern can be invalid, and we need to
         // deal with invalid patterns somehow.
         let (patterns, mut errors) = Self::parse_from_iter(inputs.to_string_lossy().split('|'));
@@ -183,9 +302,383 @@ impl IgnorePatterns {
     }
 }
 
+/// The name of the file, in the configuration directory, that lists the
+/// patterns of files to hide like dotfiles.
+pub static HIDDEN_FILE: &str = "hidden";
+
+impl IgnorePatterns {
+    /// Reads the glob patterns of files to hide like dotfiles from the
+    /// `hidden` configuration file, one per line, using only the ones in
+    /// sections for this platform. There are no such patterns if the file
+    /// doesn’t exist.
+    pub fn deduce_hidden<V: Vars>(vars: &V) -> Result<Self, OptionsError> {
+        let Some(path) = config::config_dir(vars).map(|dir| dir.join(HIDDEN_FILE)) else {
+            return Ok(Self::empty());
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::empty()),
+            Err(e) => return Err(OptionsError::BadConfig(path, 0, e.to_string())),
+        };
+
+        let lines = config::platform_lines(&contents);
+        for &(number, line) in &lines {
+            if let Err(e) = glob::Pattern::new(line) {
+                return Err(OptionsError::BadConfig(path, number, e.to_string()));
+            }
+        }
+
+        Ok(Self::parse_from_iter(lines.into_iter().map(|(_, line)| line)).0)
+    }
+}
+
+impl EmptyFilter {
+    /// Determines whether to list only empty files, or hide them, from the
+    /// `--empty` and `--non-empty` options. These contradict each other, so
+    /// the last one given wins, unless in strict mode.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let flag =
+            matches.has_where(|f| f.matches(&flags::EMPTY) || f.matches(&flags::NON_EMPTY))?;
+
+        match flag {
+            Some(f) if f.matches(&flags::EMPTY) => Ok(Self::Empty),
+            Some(_) => Ok(Self::NonEmpty),
+            None => Ok(Self::Any),
+        }
+    }
+}
+
+impl ExtFilter {
+    /// Determines which extensions files need to have from the `--ext`
+    /// argument’s value, a comma-separated list with or without leading
+    /// dots.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(value) = matches.get(&flags::EXT)? else {
+            return Ok(None);
+        };
+
+        let bad_argument = || OptionsError::BadArgument(&flags::EXT, value.into());
+        let input = value.to_str().ok_or_else(bad_argument)?;
+
+        let extensions = input
+            .split(',')
+            .map(|ext| ext.trim().trim_start_matches('.'))
+            .map(|ext| (!ext.is_empty()).then(|| ext.to_string()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(bad_argument)?;
+
+        Ok(Some(Self { extensions }))
+    }
+}
+
+impl SizeFilter {
+    /// Determines which sizes files need to be between from the `--size`
+    /// argument’s value: either `+SIZE` for files at least that big,
+    /// `-SIZE` for files at most that big, or `MIN..MAX` for files in
+    /// between, where either end can be left off.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(value) = matches.get(&flags::SIZE)? else {
+            return Ok(None);
+        };
+
+        let bad_argument = || OptionsError::BadArgument(&flags::SIZE, value.into());
+        let input = value.to_str().ok_or_else(bad_argument)?;
+
+        let (min, max) = if let Some(min) = input.strip_prefix('+') {
+            (Some(min), None)
+        } else if let Some(max) = input.strip_prefix('-') {
+            (None, Some(max))
+        } else if let Some((min, max)) = input.split_once("..") {
+            (
+                Some(min).filter(|min| !min.is_empty()),
+                Some(max).filter(|max| !max.is_empty()),
+            )
+        } else {
+            return Err(bad_argument());
+        };
+
+        let size = |end: Option<&str>| {
+            end.map(|size| parse_size(size).ok_or_else(bad_argument))
+                .transpose()
+        };
+        let filter = Self {
+            min: size(min)?,
+            max: size(max)?,
+        };
+
+        match (filter.min, filter.max) {
+            (Some(min), Some(max)) if min > max => Err(bad_argument()),
+            (None, None) => Err(bad_argument()),
+            _ => Ok(Some(filter)),
+        }
+    }
+}
+
+impl TimeFilter {
+    /// Determines which times files need to be between from the
+    /// `--newer-than` and `--older-than` arguments, which are checked
+    /// against the timestamp picked with `--time`, or the first of them if
+    /// several are picked, or the modified time if none are.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let now = Utc::now().naive_utc();
+        let newer_than = matches
+            .get(&flags::NEWER_THAN)?
+            .map(|value| parse_time(&flags::NEWER_THAN, value, now))
+            .transpose()?;
+        let older_than = matches
+            .get(&flags::OLDER_THAN)?
+            .map(|value| parse_time(&flags::OLDER_THAN, value, now))
+            .transpose()?;
+
+        if newer_than.is_none() && older_than.is_none() {
+            return Ok(None);
+        }
+
+        let types = TimeTypes::deduce(matches)?;
+        let time_type = [
+            (types.modified, TimeType::Modified),
+            (types.changed, TimeType::Changed),
+            (types.accessed, TimeType::Accessed),
+            (types.created, TimeType::Created),
+        ]
+        .into_iter()
+        .find(|(picked, _)| *picked)
+        .map_or(TimeType::Modified, |(_, time_type)| time_type);
+
+        Ok(Some(Self {
+            time_type,
+            newer_than,
+            older_than,
+        }))
+    }
+}
+
+/// Parses a point in time given to `--newer-than` or `--older-than`, as a
+/// length of time before now, such as `2d`, or as a local date and time in
+/// ISO 8601 format, such as `2024-01-01` or `2024-01-01T12:00`, returning
+/// it in UTC to compare with file timestamps.
+fn parse_time(
+    arg: &'static Arg,
+    input: &OsStr,
+    now: NaiveDateTime,
+) -> Result<NaiveDateTime, OptionsError> {
+    let bad = || OptionsError::BadArgument(arg, input.into());
+    let text = input.to_str().ok_or_else(bad)?.trim();
+
+    if !text.contains('-') {
+        let ago = parse_duration(arg, input)?;
+        return chrono::Duration::from_std(ago)
+            .ok()
+            .and_then(|ago| now.checked_sub_signed(ago))
+            .ok_or_else(bad);
+    }
+
+    let local = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN))
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M"))
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M"))
+        .map_err(|_| bad())?;
+
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.naive_utc())
+        .ok_or_else(bad)
+}
+
+/// Parses a size written the way the size column shows them, such as
+/// `1.5M` or `20Ki`, with an optional trailing `B`, into a number of bytes.
+/// Decimal prefixes are powers of 1000, binary ones powers of 1024, and a
+/// number without a prefix is already in bytes.
+fn parse_size(input: &str) -> Option<u64> {
+    let input = input.strip_suffix('B').unwrap_or(input);
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (number, prefix) = input.split_at(split);
+
+    #[rustfmt::skip]
+    let (base, exponent): (u64, u32) = match prefix {
+        ""          => (1, 0),
+        "k" | "K"   => (1000, 1),
+        "M"         => (1000, 2),
+        "G"         => (1000, 3),
+        "T"         => (1000, 4),
+        "P"         => (1000, 5),
+        "E"         => (1000, 6),
+        "Ki"        => (1024, 1),
+        "Mi"        => (1024, 2),
+        "Gi"        => (1024, 3),
+        "Ti"        => (1024, 4),
+        "Pi"        => (1024, 5),
+        "Ei"        => (1024, 6),
+        _           => return None,
+    };
+    let multiplier = base.pow(exponent);
+
+    if number.contains('.') {
+        let number: f64 = number.parse().ok()?;
+        let bytes = (number * multiplier as f64).round();
+        (bytes < u64::MAX as f64).then_some(bytes as u64)
+    } else {
+        number.parse::<u64>().ok()?.checked_mul(multiplier)
+    }
+}
+
+impl PermFilter {
+    /// Determines which permission bits files need to have from the
+    /// `--perm` option, which takes a mode in the same form as `find -perm`:
+    /// either octal, such as `644`, or symbolic, such as `u=rw,go=r`, with an
+    /// optional `-` or `/` prefix to match all or any of the bits.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(value) = matches.get(&flags::PERM)? else {
+            return Ok(None);
+        };
+
+        if cfg!(windows) {
+            return Err(OptionsError::Unsupported(String::from(
+                "Filtering by permissions is not supported on Windows",
+            )));
+        }
+
+        let bad_argument = || OptionsError::BadArgument(&flags::PERM, value.into());
+        let input = value.to_str().ok_or_else(bad_argument)?;
+
+        let (matching, mode) = if let Some(mode) = input.strip_prefix('-') {
+            (PermMatch::All, mode)
+        } else if let Some(mode) = input.strip_prefix('/') {
+            (PermMatch::Any, mode)
+        } else {
+            (PermMatch::Exact, input)
+        };
+
+        let mode = parse_mode(mode).ok_or_else(bad_argument)?;
+        Ok(Some(Self { mode, matching }))
+    }
+}
+
+/// Parses a mode given to `--perm`, either as up to four octal digits or
+/// as comma-separated symbolic clauses that start from no bits set.
+fn parse_mode(input: &str) -> Option<u32> {
+    if input.is_empty() {
+        return None;
+    }
+
+    if input.bytes().all(|b| b.is_ascii_digit()) {
+        return u32::from_str_radix(input, 8).ok().filter(|&m| m <= 0o7777);
+    }
+
+    let mut mode = 0;
+    for clause in input.split(',') {
+        let ops_start = clause.find(['+', '-', '='])?;
+        let (who, mut rest) = clause.split_at(ops_start);
+
+        let mut mask = 0;
+        for c in who.chars() {
+            mask |= match c {
+                'u' => 0o4700,
+                'g' => 0o2070,
+                'o' => 0o1007,
+                'a' => 0o7777,
+                _ => return None,
+            };
+        }
+        if who.is_empty() {
+            mask = 0o7777;
+        }
+
+        while let Some(op) = rest.chars().next() {
+            let perms_end = rest[1..]
+                .find(['+', '-', '='])
+                .map_or(rest.len(), |i| i + 1);
+            let mut bits = 0;
+            for c in rest[1..perms_end].chars() {
+                bits |= match c {
+                    'r' => 0o0444,
+                    'w' => 0o0222,
+                    'x' => 0o0111,
+                    's' => 0o6000,
+                    't' => 0o1000,
+                    _ => return None,
+                };
+            }
+
+            match op {
+                '+' => mode |= bits & mask,
+                '-' => mode &= !(bits & mask),
+                '=' => mode = (mode & !mask) | (bits & mask),
+                _ => return None,
+            }
+
+            rest = &rest[perms_end..];
+        }
+    }
+
+    Some(mode)
+}
+
+impl OwnerFilter {
+    /// Determines which user and group files need to be owned by, from the
+    /// `--owner` and `--in-group` options. Either can be a name, a numeric
+    /// ID, or `me` for the current user or their group.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let user = matches.get(&flags::OWNER)?;
+        let group = matches.get(&flags::IN_GROUP)?;
+
+        if cfg!(windows) && (user.is_some() || group.is_some()) {
+            return Err(OptionsError::Unsupported(String::from(
+                "Filtering by owner is not supported on Windows",
+            )));
+        }
+
+        Ok(Self {
+            user: user
+                .map(|name| lookup_owner(&flags::OWNER, name))
+                .transpose()?,
+            group: group
+                .map(|name| lookup_owner(&flags::IN_GROUP, name))
+                .transpose()?,
+        })
+    }
+}
+
+/// Turns the name or numeric ID given to `--owner` or `--in-group` into an ID.
+#[cfg(unix)]
+fn lookup_owner(arg: &'static Arg, name: &OsStr) -> Result<u32, OptionsError> {
+    let is_group = arg == &flags::IN_GROUP;
+
+    if name == "me" {
+        return Ok(if is_group {
+            uzers::get_current_gid()
+        } else {
+            uzers::get_current_uid()
+        });
+    }
+
+    if let Some(id) = name.to_str().and_then(|n| n.parse().ok()) {
+        return Ok(id);
+    }
+
+    let id = if is_group {
+        uzers::get_group_by_name(name).map(|g| g.gid())
+    } else {
+        uzers::get_user_by_name(name).map(|u| u.uid())
+    };
+
+    id.ok_or_else(|| OptionsError::NoSuchOwner(arg, name.into()))
+}
+
+#[cfg(windows)]
+fn lookup_owner(arg: &'static Arg, name: &OsStr) -> Result<u32, OptionsError> {
+    Err(OptionsError::NoSuchOwner(arg, name.into()))
+}
+
 impl GitIgnore {
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        if matches.has(&flags::GIT_IGNORE)? {
+        if cfg!(feature = "git") && matches.has(&flags::GIT_IGNORE)? {
             Ok(Self::CheckAndIgnore)
         } else {
             Ok(Self::Off)
@@ -193,6 +686,24 @@ impl GitIgnore {
     }
 }
 
+impl GitTracking {
+    /// Determines whether to list only files Git is tracking, or only ones
+    /// it isn’t, from the `--git-tracked` and `--git-untracked` options.
+    /// These contradict each other, so the last one given wins, unless in
+    /// strict mode.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let flag = matches
+            .has_where(|f| f.matches(&flags::GIT_TRACKED) || f.matches(&flags::GIT_UNTRACKED))?;
+
+        match flag {
+            _ if !cfg!(feature = "git") => Ok(Self::Any),
+            Some(f) if f.matches(&flags::GIT_TRACKED) => Ok(Self::Tracked),
+            Some(_) => Ok(Self::Untracked),
+            None => Ok(Self::Any),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -210,11 +721,30 @@ mod test {
 
                 static TEST_ARGS: &[&Arg] = &[
                     &flags::SORT,
+                    &flags::ALLOW_SORT_COMMAND,
                     &flags::ALL,
                     &flags::ALMOST_ALL,
                     &flags::TREE,
                     &flags::IGNORE_GLOB,
+                    &flags::ONLY_GLOB,
                     &flags::GIT_IGNORE,
+                    &flags::OWNER,
+                    &flags::IN_GROUP,
+                    &flags::PERM,
+                    &flags::EMPTY,
+                    &flags::NON_EMPTY,
+                    &flags::EXT,
+                    &flags::SIZE,
+                    &flags::NEWER_THAN,
+                    &flags::OLDER_THAN,
+                    &flags::TIME,
+                    &flags::MODIFIED,
+                    &flags::CHANGED,
+                    &flags::ACCESSED,
+                    &flags::CREATED,
+                    &flags::NO_TIME,
+                    &flags::GIT_TRACKED,
+                    &flags::GIT_UNTRACKED,
                 ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
                     $type::deduce(mf)
@@ -228,32 +758,77 @@ mod test {
     mod sort_fields {
         use super::*;
 
+        fn by(field: SortField) -> Vec<SortKey> {
+            vec![SortKey::from(field)]
+        }
+
+        fn backwards(field: SortField) -> SortKey {
+            SortKey {
+                field,
+                reverse: true,
+            }
+        }
+
         // Default behaviour
-        test!(empty:         SortField <- [];                  Both => Ok(SortField::default()));
+        test!(empty:         SortKey <- [];                  Both => Ok(by(SortField::default())));
 
         // Sort field arguments
-        test!(one_arg:       SortField <- ["--sort=mod"];      Both => Ok(SortField::ModifiedDate));
-        test!(one_long:      SortField <- ["--sort=size"];     Both => Ok(SortField::Size));
-        test!(one_short:     SortField <- ["-saccessed"];      Both => Ok(SortField::AccessedDate));
-        test!(lowercase:     SortField <- ["--sort", "name"];  Both => Ok(SortField::Name(SortCase::AaBbCc)));
-        test!(uppercase:     SortField <- ["--sort", "Name"];  Both => Ok(SortField::Name(SortCase::ABCabc)));
-        test!(old:           SortField <- ["--sort", "new"];   Both => Ok(SortField::ModifiedDate));
-        test!(oldest:        SortField <- ["--sort=newest"];   Both => Ok(SortField::ModifiedDate));
-        test!(new:           SortField <- ["--sort", "old"];   Both => Ok(SortField::ModifiedAge));
-        test!(newest:        SortField <- ["--sort=oldest"];   Both => Ok(SortField::ModifiedAge));
-        test!(age:           SortField <- ["-sage"];           Both => Ok(SortField::ModifiedAge));
-
-        test!(mix_hidden_lowercase:     SortField <- ["--sort", ".name"];  Both => Ok(SortField::NameMixHidden(SortCase::AaBbCc)));
-        test!(mix_hidden_uppercase:     SortField <- ["--sort", ".Name"];  Both => Ok(SortField::NameMixHidden(SortCase::ABCabc)));
+        test!(one_arg:       SortKey <- ["--sort=mod"];      Both => Ok(by(SortField::ModifiedDate)));
+        test!(one_long:      SortKey <- ["--sort=size"];     Both => Ok(by(SortField::Size)));
+        test!(one_short:     SortKey <- ["-saccessed"];      Both => Ok(by(SortField::AccessedDate)));
+        test!(lowercase:     SortKey <- ["--sort", "name"];  Both => Ok(by(SortField::Name(SortCase::AaBbCc))));
+        test!(uppercase:     SortKey <- ["--sort", "Name"];  Both => Ok(by(SortField::Name(SortCase::ABCabc))));
+        test!(old:           SortKey <- ["--sort", "new"];   Both => Ok(by(SortField::ModifiedDate)));
+        test!(oldest:        SortKey <- ["--sort=newest"];   Both => Ok(by(SortField::ModifiedDate)));
+        test!(new:           SortKey <- ["--sort", "old"];   Both => Ok(by(SortField::ModifiedAge)));
+        test!(newest:        SortKey <- ["--sort=oldest"];   Both => Ok(by(SortField::ModifiedAge)));
+        test!(age:           SortKey <- ["-sage"];           Both => Ok(by(SortField::ModifiedAge)));
+        #[cfg(unix)]
+        test!(device:        SortKey <- ["--sort=dev"];      Both => Ok(by(SortField::DeviceID)));
+        test!(bytes:         SortKey <- ["--sort=bytes"];    Both => Ok(by(SortField::Bytes)));
+        test!(git:           SortKey <- ["--sort=git"];      Both => Ok(by(SortField::GitStatus)));
+
+        test!(mix_hidden_lowercase:     SortKey <- ["--sort", ".name"];  Both => Ok(by(SortField::NameMixHidden(SortCase::AaBbCc))));
+        test!(mix_hidden_uppercase:     SortKey <- ["--sort", ".Name"];  Both => Ok(by(SortField::NameMixHidden(SortCase::ABCabc))));
+
+        test!(command:       SortKey <- ["--sort=cmd:cut -f2"];  Both => Ok(by(SortField::Command)));
+
+        // Several fields
+        test!(two:           SortKey <- ["--sort=ext,name"];   Both => Ok(vec![SortKey::from(SortField::Extension(SortCase::AaBbCc)), SortKey::from(SortField::Name(SortCase::AaBbCc))]));
+        test!(reversed:      SortKey <- ["--sort=-size"];      Both => Ok(vec![backwards(SortField::Size)]));
+        test!(git_then_name: SortKey <- ["--sort=-git,name"];  Both => Ok(vec![backwards(SortField::GitStatus), SortKey::from(SortField::Name(SortCase::AaBbCc))]));
+        test!(two_reversed:  SortKey <- ["--sort=size,-mod"];  Both => Ok(vec![SortKey::from(SortField::Size), backwards(SortField::ModifiedDate)]));
 
         // Errors
-        test!(error:         SortField <- ["--sort=colour"];   Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
+        test!(error:         SortKey <- ["--sort=colour"];   Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
+        test!(bad_second:    SortKey <- ["--sort=size,colour"];  Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
+        test!(trailing:      SortKey <- ["--sort=size,"];        Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from(""))));
+        test!(none_and_more: SortKey <- ["--sort=none,size"];    Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("none,size"))));
 
         // Overriding
-        test!(overridden:    SortField <- ["--sort=cr",       "--sort", "mod"];     Last => Ok(SortField::ModifiedDate));
-        test!(overridden_2:  SortField <- ["--sort", "none",  "--sort=Extension"];  Last => Ok(SortField::Extension(SortCase::ABCabc)));
-        test!(overridden_3:  SortField <- ["--sort=cr",       "--sort", "mod"];     Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
-        test!(overridden_4:  SortField <- ["--sort", "none",  "--sort=Extension"];  Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
+        test!(overridden:    SortKey <- ["--sort=cr",       "--sort", "mod"];     Last => Ok(by(SortField::ModifiedDate)));
+        test!(overridden_2:  SortKey <- ["--sort", "none",  "--sort=Extension"];  Last => Ok(by(SortField::Extension(SortCase::ABCabc))));
+        test!(overridden_3:  SortKey <- ["--sort=cr",       "--sort", "mod"];     Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
+        test!(overridden_4:  SortKey <- ["--sort", "none",  "--sort=Extension"];  Complain => Err(OptionsError::Duplicate(Flag::Long("sort"), Flag::Long("sort"))));
+    }
+
+    mod sort_commands {
+        use super::*;
+
+        test!(none:          SortCommand <- [];                           Both => Ok(None));
+        #[cfg(feature = "sort-command")]
+        test!(not_allowed:   SortCommand <- ["--sort=cmd:cut"];           Both => Err(OptionsError::Unsupported(String::from("Sorting with a command needs --allow-sort-command"))));
+        test!(useless:       SortCommand <- ["--allow-sort-command"];     Last => Ok(None));
+        test!(useless_2:     SortCommand <- ["--allow-sort-command"];     Complain => Err(OptionsError::Useless(&flags::ALLOW_SORT_COMMAND, false, &flags::SORT)));
+
+        #[cfg(feature = "sort-command")]
+        test!(allowed:       SortCommand <- ["--sort=cmd:cut -d'-' -f2", "--allow-sort-command"];  Both => Ok(Some(SortCommand { program: "cut".into(), args: vec!["-d-".into(), "-f2".into()] })));
+
+        #[cfg(feature = "sort-command")]
+        test!(empty:         SortCommand <- ["--sort=cmd:", "--allow-sort-command"];  Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("cmd:"))));
+
+        #[cfg(not(feature = "sort-command"))]
+        test!(disabled:      SortCommand <- ["--sort=cmd:cut", "--allow-sort-command"];  Both => Err(OptionsError::FeatureDisabled(&flags::SORT, "sort-command")));
     }
 
     mod dot_filters {
@@ -302,10 +877,196 @@ mod test {
         test!(overridden_4: IgnorePatterns <- ["-I", "*.OGG", "-I*.MP3"];      Complain => Err(OptionsError::Duplicate(Flag::Short(b'I'), Flag::Short(b'I'))));
     }
 
+    mod only_patterns {
+        use super::*;
+        use crate::options::parser::Arg;
+        use crate::options::test::parse_for_test;
+        use crate::options::test::Strictnesses::*;
+        use std::iter::FromIterator;
+
+        fn deduce(args: &[&str]) -> Result<Option<IgnorePatterns>, OptionsError> {
+            static TEST_ARGS: &[&Arg] = &[&flags::ONLY_GLOB];
+            let mut results = parse_for_test(args, TEST_ARGS, Both, IgnorePatterns::deduce_only);
+            results.pop().unwrap()
+        }
+
+        #[test]
+        fn none() {
+            assert_eq!(deduce(&[]), Ok(None));
+        }
+
+        #[test]
+        fn two() {
+            let pats = ["*.rs", "Cargo.*"].map(|p| glob::Pattern::new(p).unwrap());
+            assert_eq!(
+                deduce(&["--only-glob=*.rs|Cargo.*"]),
+                Ok(Some(IgnorePatterns::from_iter(pats)))
+            );
+        }
+
+        #[test]
+        fn bad_glob() {
+            assert!(deduce(&["--only-glob=[a"]).is_err());
+        }
+    }
+
+    mod hidden_patterns {
+        use super::*;
+        use crate::options::config::test::Env;
+
+        #[test]
+        fn no_config_file() {
+            let env = Env(&[("EZA_CONFIG_DIR", "/nonexistent/eza")]);
+            assert_eq!(
+                IgnorePatterns::deduce_hidden(&env),
+                Ok(IgnorePatterns::empty())
+            );
+        }
+    }
+
+    mod sizes {
+        use super::*;
+
+        fn size(min: Option<u64>, max: Option<u64>) -> SizeFilter {
+            SizeFilter { min, max }
+        }
+
+        test!(none:       SizeFilter <- [];                       Both => Ok(None));
+        test!(at_least:   SizeFilter <- ["--size=+10M"];          Both => Ok(Some(size(Some(10_000_000), None))));
+        test!(at_most:    SizeFilter <- ["--size=-1k"];           Both => Ok(Some(size(None, Some(1000)))));
+        test!(range:      SizeFilter <- ["--size=1M..100M"];      Both => Ok(Some(size(Some(1_000_000), Some(100_000_000)))));
+        test!(open_end:   SizeFilter <- ["--size=512.."];         Both => Ok(Some(size(Some(512), None))));
+        test!(binary:     SizeFilter <- ["--size=+4KiB"];         Both => Ok(Some(size(Some(4096), None))));
+        test!(fraction:   SizeFilter <- ["--size=-1.5M"];         Both => Ok(Some(size(None, Some(1_500_000)))));
+        test!(no_sign:    SizeFilter <- ["--size=10M"];           Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from("10M"))));
+        test!(bad_unit:   SizeFilter <- ["--size=+10X"];          Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from("+10X"))));
+        test!(backwards:  SizeFilter <- ["--size=2M..1M"];        Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from("2M..1M"))));
+        test!(no_ends:    SizeFilter <- ["--size=.."];            Both => Err(OptionsError::BadArgument(&flags::SIZE, OsString::from(".."))));
+    }
+
+    mod times {
+        use super::*;
+
+        fn local(text: &str) -> NaiveDateTime {
+            let time = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+            Local.from_local_datetime(&time).unwrap().naive_utc()
+        }
+
+        fn filter(
+            time_type: TimeType,
+            newer_than: Option<&str>,
+            older_than: Option<&str>,
+        ) -> TimeFilter {
+            TimeFilter {
+                time_type,
+                newer_than: newer_than.map(local),
+                older_than: older_than.map(local),
+            }
+        }
+
+        test!(none:       TimeFilter <- [];                                     Both => Ok(None));
+        test!(date:       TimeFilter <- ["--newer-than=2024-01-01"];            Both => Ok(Some(filter(TimeType::Modified, Some("2024-01-01 00:00"), None))));
+        test!(date_time:  TimeFilter <- ["--older-than=2024-01-01T12:30"];      Both => Ok(Some(filter(TimeType::Modified, None, Some("2024-01-01 12:30")))));
+        test!(accessed:   TimeFilter <- ["--older-than=2024-01-01", "-u"];      Both => Ok(Some(filter(TimeType::Accessed, None, Some("2024-01-01 00:00")))));
+        test!(time_word:  TimeFilter <- ["--newer-than=2024-01-01", "--time=created"];  Both => Ok(Some(filter(TimeType::Created, Some("2024-01-01 00:00"), None))));
+        test!(bad_date:   TimeFilter <- ["--newer-than=2024-13-01"];            Both => Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("2024-13-01"))));
+        test!(bad_unit:   TimeFilter <- ["--older-than=2y"];                    Both => Err(OptionsError::BadArgument(&flags::OLDER_THAN, OsString::from("2y"))));
+
+        #[test]
+        fn relative() {
+            let now = local("2024-01-10 12:00");
+            assert_eq!(
+                parse_time(&flags::NEWER_THAN, OsStr::new("2d"), now),
+                Ok(now - chrono::Duration::days(2))
+            );
+        }
+    }
+
     mod git_ignores {
         use super::*;
 
         test!(off:  GitIgnore <- [];                Both => Ok(GitIgnore::Off));
+
+        #[cfg(feature = "git")]
         test!(on:   GitIgnore <- ["--git-ignore"];  Both => Ok(GitIgnore::CheckAndIgnore));
+
+        #[cfg(not(feature = "git"))]
+        test!(on:   GitIgnore <- ["--git-ignore"];  Both => Ok(GitIgnore::Off));
+    }
+
+    #[cfg(unix)]
+    mod owners {
+        use super::*;
+
+        test!(nobody:       OwnerFilter <- [];                                 Both => Ok(OwnerFilter::default()));
+        test!(numeric_user: OwnerFilter <- ["--owner=0"];                      Both => Ok(OwnerFilter { user: Some(0), group: None }));
+        test!(numeric_both: OwnerFilter <- ["--owner", "0", "--in-group=20"];  Both => Ok(OwnerFilter { user: Some(0), group: Some(20) }));
+        test!(me:           OwnerFilter <- ["--owner=me"];                     Both => Ok(OwnerFilter { user: Some(uzers::get_current_uid()), group: None }));
+        test!(my_group:     OwnerFilter <- ["--in-group=me"];                  Both => Ok(OwnerFilter { user: None, group: Some(uzers::get_current_gid()) }));
+        test!(no_such_user: OwnerFilter <- ["--owner=no-such-user-here"];      Both => Err(OptionsError::NoSuchOwner(&flags::OWNER, OsString::from("no-such-user-here"))));
+    }
+
+    mod extensions {
+        use super::*;
+
+        fn exts(extensions: &[&str]) -> ExtFilter {
+            ExtFilter {
+                extensions: extensions.iter().map(|e| (*e).to_string()).collect(),
+            }
+        }
+
+        test!(none:      ExtFilter <- [];                      Both => Ok(None));
+        test!(one:       ExtFilter <- ["--ext=rs"];            Both => Ok(Some(exts(&["rs"]))));
+        test!(several:   ExtFilter <- ["--ext", "rs,toml,md"]; Both => Ok(Some(exts(&["rs", "toml", "md"]))));
+        test!(dots:      ExtFilter <- ["--ext=.rs, .tar.gz"];  Both => Ok(Some(exts(&["rs", "tar.gz"]))));
+        test!(empty:     ExtFilter <- ["--ext=rs,,md"];        Both => Err(OptionsError::BadArgument(&flags::EXT, OsString::from("rs,,md"))));
+    }
+
+    mod git_tracking {
+        use super::*;
+
+        test!(any:        GitTracking <- [];                                    Both => Ok(GitTracking::Any));
+
+        #[cfg(feature = "git")]
+        test!(tracked:    GitTracking <- ["--git-tracked"];                     Both => Ok(GitTracking::Tracked));
+        #[cfg(feature = "git")]
+        test!(untracked:  GitTracking <- ["--git-untracked"];                   Both => Ok(GitTracking::Untracked));
+        #[cfg(feature = "git")]
+        test!(last:       GitTracking <- ["--git-tracked", "--git-untracked"];  Last => Ok(GitTracking::Untracked));
+        test!(both:       GitTracking <- ["--git-tracked", "--git-untracked"];  Complain => Err(OptionsError::Duplicate(Flag::Long("git-tracked"), Flag::Long("git-untracked"))));
+
+        #[cfg(not(feature = "git"))]
+        test!(tracked:    GitTracking <- ["--git-tracked"];                     Both => Ok(GitTracking::Any));
+    }
+
+    mod perms {
+        use super::*;
+
+        fn perm(mode: u32, matching: PermMatch) -> PermFilter {
+            PermFilter { mode, matching }
+        }
+
+        test!(none:       PermFilter <- [];                       Both => Ok(None));
+        test!(octal:      PermFilter <- ["--perm=644"];           Both => Ok(Some(perm(0o644, PermMatch::Exact))));
+        test!(all_bits:   PermFilter <- ["--perm", "-4000"];      Both => Ok(Some(perm(0o4000, PermMatch::All))));
+        test!(any_bits:   PermFilter <- ["--perm=/111"];          Both => Ok(Some(perm(0o111, PermMatch::Any))));
+        test!(symbolic:   PermFilter <- ["--perm=u=rw,go=r"];     Both => Ok(Some(perm(0o644, PermMatch::Exact))));
+        test!(sym_all:    PermFilter <- ["--perm=-g+w"];          Both => Ok(Some(perm(0o020, PermMatch::All))));
+        test!(sym_chain:  PermFilter <- ["--perm=/a+rwx-w"];      Both => Ok(Some(perm(0o555, PermMatch::Any))));
+        test!(setuid:     PermFilter <- ["--perm=-u+s"];          Both => Ok(Some(perm(0o4000, PermMatch::All))));
+        test!(too_big:    PermFilter <- ["--perm=17777"];         Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("17777"))));
+        test!(not_octal:  PermFilter <- ["--perm=648"];           Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("648"))));
+        test!(bad_who:    PermFilter <- ["--perm=z+w"];           Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("z+w"))));
+        test!(empty:      PermFilter <- ["--perm=/"];             Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("/"))));
+    }
+
+    mod emptiness {
+        use super::*;
+
+        test!(any:        EmptyFilter <- [];                             Both => Ok(EmptyFilter::Any));
+        test!(empty:      EmptyFilter <- ["--empty"];                    Both => Ok(EmptyFilter::Empty));
+        test!(non_empty:  EmptyFilter <- ["--non-empty"];                Both => Ok(EmptyFilter::NonEmpty));
+        test!(last_wins:  EmptyFilter <- ["--non-empty", "--empty"];     Last => Ok(EmptyFilter::Empty));
+        test!(both:       EmptyFilter <- ["--empty", "--non-empty"]; Complain => Err(OptionsError::Duplicate(Flag::Long("empty"), Flag::Long("non-empty"))));
     }
 }
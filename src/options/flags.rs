@@ -11,6 +11,21 @@ pub static HELP: Arg = Arg {
     long: "help",
     takes_value: TakesValue::Forbidden,
 };
+pub static LIST_OPTIONS: Arg = Arg {
+    short: None,
+    long: "list-options",
+    takes_value: TakesValue::Optional(None, ""),
+};
+pub static QUIET: Arg = Arg {
+    short: Some(b'q'),
+    long: "quiet",
+    takes_value: TakesValue::Forbidden,
+};
+pub static STRICT_WARNINGS: Arg = Arg {
+    short: None,
+    long: "strict-warnings",
+    takes_value: TakesValue::Forbidden,
+};
 
 // display options
 pub static ONE_LINE: Arg = Arg {
@@ -43,6 +58,11 @@ pub static TREE: Arg = Arg {
     long: "tree",
     takes_value: TakesValue::Forbidden,
 };
+pub static TREE_SUMMARY: Arg = Arg {
+    short: None,
+    long: "tree-summary",
+    takes_value: TakesValue::Forbidden,
+};
 pub static CLASSIFY: Arg = Arg {
     short: Some(b'F'),
     long: "classify",
@@ -58,6 +78,22 @@ pub static WIDTH: Arg = Arg {
     long: "width",
     takes_value: TakesValue::Necessary(None),
 };
+pub static HEIGHT: Arg = Arg {
+    short: None,
+    long: "height",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static PAGINATE: Arg = Arg {
+    short: None,
+    long: "paginate",
+    takes_value: TakesValue::Forbidden,
+};
+pub static FLUSH: Arg = Arg {
+    short: None,
+    long: "flush",
+    takes_value: TakesValue::Necessary(Some(FLUSH_MODES)),
+};
+const FLUSH_MODES: Values = &["line", "block", "end"];
 pub static NO_QUOTES: Arg = Arg {
     short: None,
     long: "no-quotes",
@@ -96,8 +132,127 @@ pub static COLOUR_SCALE_MODE: Arg = Arg {
     long: "colour-scale-mode",
     takes_value: TakesValue::Necessary(Some(COLOR_SCALE_MODES)),
 };
+pub static COLOR_BY: Arg = Arg {
+    short: None,
+    long: "color-by",
+    takes_value: TakesValue::Necessary(Some(COLOR_BY_VALUES)),
+};
+pub static COLOUR_BY: Arg = Arg {
+    short: None,
+    long: "colour-by",
+    takes_value: TakesValue::Necessary(Some(COLOR_BY_VALUES)),
+};
+const COLOR_BY_VALUES: Values = &["owner", "owner-names", "git-age"];
+pub static COLOR_GLOB: Arg = Arg {
+    short: None,
+    long: "color-glob",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static COLOUR_GLOB: Arg = Arg {
+    short: None,
+    long: "colour-glob",
+    takes_value: TakesValue::Necessary(None),
+};
 const SCALES: Values = &["all", "size", "age"];
 const COLOR_SCALE_MODES: Values = &["fixed", "gradient"];
+pub static HIGHLIGHT_RECENT: Arg = Arg {
+    short: None,
+    long: "highlight-recent",
+    takes_value: TakesValue::Optional(None, "24h"),
+};
+pub static HIGHLIGHT: Arg = Arg {
+    short: None,
+    long: "highlight",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static BADGES: Arg = Arg {
+    short: None,
+    long: "badges",
+    takes_value: TakesValue::Optional(None, "user.eza.badge"),
+};
+pub static RELATIVE_ROOT: Arg = Arg {
+    short: None,
+    long: "relative-root",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static NAME_CLASHES: Arg = Arg {
+    short: None,
+    long: "name-clashes",
+    takes_value: TakesValue::Forbidden,
+};
+pub static SHOW_TRASH: Arg = Arg {
+    short: None,
+    long: "show-trash",
+    takes_value: TakesValue::Forbidden,
+};
+pub static TRASH: Arg = Arg {
+    short: None,
+    long: "trash",
+    takes_value: TakesValue::Forbidden,
+};
+pub static RESOLVE_SHORTCUTS: Arg = Arg {
+    short: None,
+    long: "resolve-shortcuts",
+    takes_value: TakesValue::Forbidden,
+};
+pub static FINDER_TAGS: Arg = Arg {
+    short: None,
+    long: "finder-tags",
+    takes_value: TakesValue::Forbidden,
+};
+pub static MOUNT_USAGE: Arg = Arg {
+    short: None,
+    long: "mount-usage",
+    takes_value: TakesValue::Forbidden,
+};
+pub static INDEX: Arg = Arg {
+    short: None,
+    long: "index",
+    takes_value: TakesValue::Forbidden,
+};
+pub static MERGE: Arg = Arg {
+    short: None,
+    long: "merge",
+    takes_value: TakesValue::Forbidden,
+};
+pub static STATS: Arg = Arg {
+    short: None,
+    long: "stats",
+    takes_value: TakesValue::Necessary(Some(STATISTICS)),
+};
+const STATISTICS: Values = &["ext"];
+pub static SPECIAL_DIRS: Arg = Arg {
+    short: None,
+    long: "special-dirs",
+    takes_value: TakesValue::Forbidden,
+};
+pub static BLOCKS_MAP: Arg = Arg {
+    short: None,
+    long: "blocks-map",
+    takes_value: TakesValue::Forbidden,
+};
+pub static DEPTH_COLORS: Arg = Arg {
+    short: None,
+    long: "depth-colors",
+    takes_value: TakesValue::Optional(Some(DEPTH_PARTS), "lines"),
+};
+pub static DEPTH_COLOURS: Arg = Arg {
+    short: None,
+    long: "depth-colours",
+    takes_value: TakesValue::Optional(Some(DEPTH_PARTS), "lines"),
+};
+const DEPTH_PARTS: Values = &["lines", "names", "all"];
+pub static TRUNCATE: Arg = Arg {
+    short: None,
+    long: "truncate",
+    takes_value: TakesValue::Necessary(Some(TRUNCATE_MODES)),
+};
+pub static MAX_NAME_WIDTH: Arg = Arg {
+    short: None,
+    long: "max-name-width",
+    takes_value: TakesValue::Necessary(None),
+};
+const TRUNCATE_MODES: Values = &["end", "middle", "off"];
 
 // filtering and sorting options
 pub static ALL: Arg = Arg {
@@ -120,6 +275,16 @@ pub static LEVEL: Arg = Arg {
     long: "level",
     takes_value: TakesValue::Necessary(None),
 };
+pub static FOLLOW_SYMLINKS: Arg = Arg {
+    short: None,
+    long: "follow-symlinks",
+    takes_value: TakesValue::Forbidden,
+};
+pub static RESUME: Arg = Arg {
+    short: None,
+    long: "resume",
+    takes_value: TakesValue::Necessary(None),
+};
 pub static REVERSE: Arg = Arg {
     short: Some(b'r'),
     long: "reverse",
@@ -130,11 +295,21 @@ pub static SORT: Arg = Arg {
     long: "sort",
     takes_value: TakesValue::Necessary(Some(SORTS)),
 };
+pub static ALLOW_SORT_COMMAND: Arg = Arg {
+    short: None,
+    long: "allow-sort-command",
+    takes_value: TakesValue::Forbidden,
+};
 pub static IGNORE_GLOB: Arg = Arg {
     short: Some(b'I'),
     long: "ignore-glob",
     takes_value: TakesValue::Necessary(None),
 };
+pub static ONLY_GLOB: Arg = Arg {
+    short: None,
+    long: "only-glob",
+    takes_value: TakesValue::Necessary(None),
+};
 pub static GIT_IGNORE: Arg = Arg {
     short: None,
     long: "git-ignore",
@@ -155,6 +330,61 @@ pub static ONLY_FILES: Arg = Arg {
     long: "only-files",
     takes_value: TakesValue::Forbidden,
 };
+pub static EMPTY: Arg = Arg {
+    short: None,
+    long: "empty",
+    takes_value: TakesValue::Forbidden,
+};
+pub static NON_EMPTY: Arg = Arg {
+    short: None,
+    long: "non-empty",
+    takes_value: TakesValue::Forbidden,
+};
+pub static PERM: Arg = Arg {
+    short: None,
+    long: "perm",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static GIT_TRACKED: Arg = Arg {
+    short: None,
+    long: "git-tracked",
+    takes_value: TakesValue::Forbidden,
+};
+pub static GIT_UNTRACKED: Arg = Arg {
+    short: None,
+    long: "git-untracked",
+    takes_value: TakesValue::Forbidden,
+};
+pub static EXT: Arg = Arg {
+    short: None,
+    long: "ext",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static SIZE: Arg = Arg {
+    short: None,
+    long: "size",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static NEWER_THAN: Arg = Arg {
+    short: None,
+    long: "newer-than",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static OLDER_THAN: Arg = Arg {
+    short: None,
+    long: "older-than",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static OWNER: Arg = Arg {
+    short: None,
+    long: "owner",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static IN_GROUP: Arg = Arg {
+    short: None,
+    long: "in-group",
+    takes_value: TakesValue::Necessary(None),
+};
 const SORTS: Values = &[
     "name",
     "Name",
@@ -166,7 +396,10 @@ const SORTS: Values = &[
     "accessed",
     "created",
     "inode",
+    "device",
     "type",
+    "bytes",
+    "git",
     "none",
 ];
 
@@ -209,7 +442,7 @@ pub static INODE: Arg = Arg {
 pub static LINKS: Arg = Arg {
     short: Some(b'H'),
     long: "links",
-    takes_value: TakesValue::Forbidden,
+    takes_value: TakesValue::OptionalAfterEquals(Some(LINKS_MODES), "always"),
 };
 pub static MODIFIED: Arg = Arg {
     short: Some(b'm'),
@@ -231,6 +464,11 @@ pub static TOTAL_SIZE: Arg = Arg {
     long: "total-size",
     takes_value: TakesValue::Forbidden,
 };
+pub static CACHE: Arg = Arg {
+    short: None,
+    long: "cache",
+    takes_value: TakesValue::Optional(Some(CACHE_MODES), "on"),
+};
 pub static TIME: Arg = Arg {
     short: Some(b't'),
     long: "time",
@@ -251,11 +489,17 @@ pub static TIME_STYLE: Arg = Arg {
     long: "time-style",
     takes_value: TakesValue::Necessary(Some(TIME_STYLES)),
 };
+pub static RELATIVE_TO: Arg = Arg {
+    short: None,
+    long: "relative-to",
+    takes_value: TakesValue::Necessary(None),
+};
 pub static HYPERLINK: Arg = Arg {
     short: None,
     long: "hyperlink",
-    takes_value: TakesValue::Forbidden,
+    takes_value: TakesValue::Optional(Some(HYPERLINK_MODES), "always"),
 };
+const HYPERLINK_MODES: Values = &["never", "auto", "always", "all-cells"];
 pub static MOUNTS: Arg = Arg {
     short: Some(b'M'),
     long: "mounts",
@@ -266,7 +510,9 @@ pub static SMART_GROUP: Arg = Arg {
     long: "smart-group",
     takes_value: TakesValue::Forbidden,
 };
-const TIMES: Values = &["modified", "changed", "accessed", "created"];
+const TIMES: Values = &["modified", "changed", "accessed", "created", "all"];
+const LINKS_MODES: Values = &["always", "auto"];
+const CACHE_MODES: Values = &["on", "refresh", "off"];
 const TIME_STYLES: Values = &["default", "long-iso", "full-iso", "iso", "relative"];
 
 // suppressing columns
@@ -322,31 +568,173 @@ pub static OCTAL: Arg = Arg {
     long: "octal-permissions",
     takes_value: TakesValue::Forbidden,
 };
+const CONTEXT_MODES: &[&str] = &["full", "smart"];
 pub static SECURITY_CONTEXT: Arg = Arg {
     short: Some(b'Z'),
     long: "context",
-    takes_value: TakesValue::Forbidden,
+    takes_value: TakesValue::Optional(Some(CONTEXT_MODES), "full"),
 };
 pub static STDIN: Arg = Arg {
     short: None,
     long: "stdin",
     takes_value: TakesValue::Forbidden,
 };
+pub static SKIP_MISSING: Arg = Arg {
+    short: None,
+    long: "skip-missing",
+    takes_value: TakesValue::Forbidden,
+};
 pub static FILE_FLAGS: Arg = Arg {
     short: Some(b'O'),
     long: "flags",
     takes_value: TakesValue::Forbidden,
 };
+pub static VIRTUAL_FILES: Arg = Arg {
+    short: None,
+    long: "virtual-files",
+    takes_value: TakesValue::Necessary(Some(VIRTUAL_FILES_MODES)),
+};
+const VIRTUAL_FILES_MODES: Values = &["show", "mark", "read"];
+pub static ALIGN: Arg = Arg {
+    short: None,
+    long: "align",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static FIELDS: Arg = Arg {
+    short: None,
+    long: "fields",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static GRID_LAYOUT: Arg = Arg {
+    short: None,
+    long: "grid-layout",
+    takes_value: TakesValue::Necessary(Some(GRID_LAYOUTS)),
+};
+const GRID_LAYOUTS: Values = &["auto", "exact", "fast"];
+pub static NOTIFY: Arg = Arg {
+    short: None,
+    long: "notify",
+    takes_value: TakesValue::Optional(None, "5"),
+};
+pub static OUTPUT: Arg = Arg {
+    short: None,
+    long: "output",
+    takes_value: TakesValue::Necessary(Some(OUTPUTS)),
+};
+const OUTPUTS: Values = &["fzf"];
+pub static JSON: Arg = Arg {
+    short: None,
+    long: "json",
+    takes_value: TakesValue::Forbidden,
+};
+pub static FORMAT: Arg = Arg {
+    short: None,
+    long: "format",
+    takes_value: TakesValue::Necessary(Some(FORMATS)),
+};
+const FORMATS: Values = &["csv", "tsv"];
+pub static NO_METADATA: Arg = Arg {
+    short: None,
+    long: "no-metadata",
+    takes_value: TakesValue::Forbidden,
+};
+pub static DEBUG_LAYOUT: Arg = Arg {
+    short: None,
+    long: "debug-layout",
+    takes_value: TakesValue::Forbidden,
+};
+pub static DUMP_SCALE: Arg = Arg {
+    short: None,
+    long: "dump-scale",
+    takes_value: TakesValue::Forbidden,
+};
+pub static SOCKET_INFO: Arg = Arg {
+    short: None,
+    long: "socket-info",
+    takes_value: TakesValue::Forbidden,
+};
+pub static AGE_RANK: Arg = Arg {
+    short: None,
+    long: "age-rank",
+    takes_value: TakesValue::Forbidden,
+};
+pub static TEXT_SIZE: Arg = Arg {
+    short: None,
+    long: "text-size",
+    takes_value: TakesValue::Forbidden,
+};
+pub static ENCODING: Arg = Arg {
+    short: None,
+    long: "encoding",
+    takes_value: TakesValue::Forbidden,
+};
+pub static PREVIEW: Arg = Arg {
+    short: None,
+    long: "preview",
+    takes_value: TakesValue::Optional(None, "40"),
+};
+pub static SIMULATE_UMASK: Arg = Arg {
+    short: None,
+    long: "simulate-umask",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static FIND_DUPES: Arg = Arg {
+    short: None,
+    long: "find-dupes",
+    takes_value: TakesValue::Optional(Some(DUPE_CHECKS), "content"),
+};
+const DUPE_CHECKS: Values = &["size", "content"];
+pub static FILE_VERSION: Arg = Arg {
+    short: None,
+    long: "file-version",
+    takes_value: TakesValue::Optional(Some(VERSION_KINDS), "file"),
+};
+const VERSION_KINDS: Values = &["file", "product"];
+pub static OVERLAY: Arg = Arg {
+    short: None,
+    long: "overlay",
+    takes_value: TakesValue::Forbidden,
+};
+pub static NAME_FIRST: Arg = Arg {
+    short: None,
+    long: "name-first",
+    takes_value: TakesValue::Forbidden,
+};
+pub static DIFF_AGAINST: Arg = Arg {
+    short: None,
+    long: "diff-against",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static MANIFEST_WRITE: Arg = Arg {
+    short: None,
+    long: "manifest-write",
+    takes_value: TakesValue::Necessary(None),
+};
+pub static MANIFEST_CHECK: Arg = Arg {
+    short: None,
+    long: "manifest-check",
+    takes_value: TakesValue::Necessary(None),
+};
+const DIFF_METHODS: &[&str] = &["meta", "content"];
+pub static DIFF_BY: Arg = Arg {
+    short: None,
+    long: "diff-by",
+    takes_value: TakesValue::Necessary(Some(DIFF_METHODS)),
+};
 
 pub static ALL_ARGS: Args = Args(&[
     &VERSION,
     &HELP,
+    &LIST_OPTIONS,
+    &QUIET,
+    &STRICT_WARNINGS,
     &ONE_LINE,
     &LONG,
     &GRID,
     &ACROSS,
     &RECURSE,
     &TREE,
+    &TREE_SUMMARY,
     &CLASSIFY,
     &DEREF_LINKS,
     &COLOR,
@@ -355,19 +743,60 @@ pub static ALL_ARGS: Args = Args(&[
     &COLOUR_SCALE,
     &COLOR_SCALE_MODE,
     &COLOUR_SCALE_MODE,
+    &COLOR_BY,
+    &COLOUR_BY,
+    &COLOR_GLOB,
+    &COLOUR_GLOB,
+    &HIGHLIGHT_RECENT,
+    &HIGHLIGHT,
+    &BADGES,
+    &RELATIVE_ROOT,
+    &NAME_CLASHES,
+    &SHOW_TRASH,
+    &TRASH,
+    &RESOLVE_SHORTCUTS,
+    &FINDER_TAGS,
+    &MOUNT_USAGE,
+    &SPECIAL_DIRS,
+    &BLOCKS_MAP,
+    &INDEX,
+    &MERGE,
+    &STATS,
+    &DEPTH_COLORS,
+    &DEPTH_COLOURS,
+    &TRUNCATE,
+    &MAX_NAME_WIDTH,
     &WIDTH,
+    &HEIGHT,
+    &PAGINATE,
+    &FLUSH,
     &NO_QUOTES,
     &ALL,
     &ALMOST_ALL,
     &LIST_DIRS,
     &LEVEL,
+    &FOLLOW_SYMLINKS,
+    &RESUME,
     &REVERSE,
     &SORT,
+    &ALLOW_SORT_COMMAND,
     &DIRS_FIRST,
     &IGNORE_GLOB,
+    &ONLY_GLOB,
     &GIT_IGNORE,
     &ONLY_DIRS,
     &ONLY_FILES,
+    &OWNER,
+    &IN_GROUP,
+    &PERM,
+    &EXT,
+    &SIZE,
+    &NEWER_THAN,
+    &OLDER_THAN,
+    &GIT_TRACKED,
+    &GIT_UNTRACKED,
+    &EMPTY,
+    &NON_EMPTY,
     &BINARY,
     &BYTES,
     &GROUP,
@@ -380,10 +809,12 @@ pub static ALL_ARGS: Args = Args(&[
     &CHANGED,
     &BLOCKSIZE,
     &TOTAL_SIZE,
+    &CACHE,
     &TIME,
     &ACCESSED,
     &CREATED,
     &TIME_STYLE,
+    &RELATIVE_TO,
     &HYPERLINK,
     &MOUNTS,
     &NO_PERMISSIONS,
@@ -399,5 +830,73 @@ pub static ALL_ARGS: Args = Args(&[
     &OCTAL,
     &SECURITY_CONTEXT,
     &STDIN,
+    &SKIP_MISSING,
     &FILE_FLAGS,
+    &SOCKET_INFO,
+    &OVERLAY,
+    &TEXT_SIZE,
+    &ENCODING,
+    &PREVIEW,
+    &AGE_RANK,
+    &FIND_DUPES,
+    &FILE_VERSION,
+    &SIMULATE_UMASK,
+    &NAME_FIRST,
+    &DIFF_AGAINST,
+    &DIFF_BY,
+    &MANIFEST_WRITE,
+    &MANIFEST_CHECK,
+    &VIRTUAL_FILES,
+    &ALIGN,
+    &FIELDS,
+    &GRID_LAYOUT,
+    &NOTIFY,
+    &DEBUG_LAYOUT,
+    &DUMP_SCALE,
+    &JSON,
+    &FORMAT,
+    &NO_METADATA,
+    &OUTPUT,
 ]);
+
+/// Options that narrow down which files get listed, which are recorded in
+/// the metadata of JSON output when they’re given.
+pub static FILTERS: &[&Arg] = &[
+    &ALL,
+    &ALMOST_ALL,
+    &LEVEL,
+    &IGNORE_GLOB,
+    &ONLY_GLOB,
+    &GIT_IGNORE,
+    &ONLY_DIRS,
+    &ONLY_FILES,
+    &OWNER,
+    &IN_GROUP,
+    &PERM,
+    &EXT,
+    &SIZE,
+    &NEWER_THAN,
+    &OLDER_THAN,
+    &GIT_TRACKED,
+    &GIT_UNTRACKED,
+    &EMPTY,
+    &NON_EMPTY,
+];
+
+/// Options that only do anything when eza was built with a particular Cargo
+/// feature, along with that feature’s name and whether it was enabled in
+/// this build. They are registered either way, so a build without the
+/// feature can explain itself instead of calling them unknown.
+pub static FEATURE_GATED: &[(&Arg, &str, bool)] = &[
+    (&GIT, "git", cfg!(feature = "git")),
+    (&GIT_IGNORE, "git", cfg!(feature = "git")),
+    (&GIT_REPOS, "git", cfg!(feature = "git")),
+    (&GIT_REPOS_NO_STAT, "git", cfg!(feature = "git")),
+    (&GIT_TRACKED, "git", cfg!(feature = "git")),
+    (&GIT_UNTRACKED, "git", cfg!(feature = "git")),
+    (
+        &ALLOW_SORT_COMMAND,
+        "sort-command",
+        cfg!(feature = "sort-command"),
+    ),
+];
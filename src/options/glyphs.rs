@@ -0,0 +1,168 @@
+//! Reading the punctuation glyphs from the `glyphs` file in the
+//! configuration directory, one per line:
+//!
+//! ```text
+//! # name = glyph
+//! set        = ascii
+//! arrow      = ⇒
+//! tree-blank = "   "
+//! ```
+//!
+//! The `set` line picks the glyphs to start from, either `unicode` (the
+//! default, unless the terminal can’t draw it) or `ascii`, and the other
+//! lines replace single glyphs in it.
+//! Glyphs with spaces in have to be quoted.
+
+use std::fs;
+use std::io;
+
+use crate::options::config;
+use crate::options::{OptionsError, Vars};
+use crate::output::capabilities::Capabilities;
+use crate::output::glyphs::Glyphs;
+
+/// The name of the file, in the configuration directory, that glyphs are
+/// read from.
+pub static GLYPHS_FILE: &str = "glyphs";
+
+impl Glyphs {
+    /// Determine which glyphs to draw with from the `glyphs` configuration
+    /// file, using the default ones if it doesn’t exist. The default ones
+    /// are ASCII if the terminal can’t draw anything else.
+    pub fn deduce<V: Vars>(vars: &V, capabilities: Capabilities) -> Result<Self, OptionsError> {
+        let default = if capabilities.unicode {
+            Self::unicode()
+        } else {
+            Self::ascii()
+        };
+
+        let Some(path) = config::config_dir(vars).map(|dir| dir.join(GLYPHS_FILE)) else {
+            return Ok(default);
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(default),
+            Err(e) => return Err(OptionsError::BadConfig(path, 0, e.to_string())),
+        };
+
+        parse_glyphs(&contents, default)
+            .map_err(|(line, msg)| OptionsError::BadConfig(path, line, msg))
+    }
+}
+
+/// Parses the contents of a glyphs file on top of the given default glyphs,
+/// returning the line number and a description of the first line that
+/// couldn’t be parsed.
+fn parse_glyphs(contents: &str, default: Glyphs) -> Result<Glyphs, (usize, String)> {
+    let lines = config::platform_lines(contents);
+    let mut glyphs = default;
+
+    // The set has to be picked before any single glyphs are replaced, so
+    // it doesn’t matter which line it’s on.
+    for &(number, line) in &lines {
+        if let Some(("set", value)) = parse_line(number, line)? {
+            glyphs = match value.as_str() {
+                "unicode" => Glyphs::unicode(),
+                "ascii" => Glyphs::ascii(),
+                _ => return Err((number, format!("unknown glyph set {value:?}"))),
+            };
+        }
+    }
+
+    for &(number, line) in &lines {
+        let Some((name, value)) = parse_line(number, line)? else {
+            continue;
+        };
+
+        let glyph = match name {
+            "set" => continue,
+            "arrow" => &mut glyphs.link_arrow,
+            "xattr" => &mut glyphs.xattr,
+            "tree-edge" => &mut glyphs.tree_edge,
+            "tree-line" => &mut glyphs.tree_line,
+            "tree-corner" => &mut glyphs.tree_corner,
+            "tree-blank" => &mut glyphs.tree_blank,
+            _ => return Err((number, format!("unknown glyph {name:?}"))),
+        };
+        *glyph = value;
+    }
+
+    Ok(glyphs)
+}
+
+/// Splits a line into the glyph’s name and its value.
+fn parse_line(number: usize, line: &str) -> Result<Option<(&str, String)>, (usize, String)> {
+    let Some((name, rest)) = line.split_once('=') else {
+        return Err((number, "expected ‘NAME = GLYPH’".into()));
+    };
+
+    match config::split_words(rest).as_deref() {
+        Some([value]) => Ok(Some((name.trim(), value.clone()))),
+        Some(_) => Err((
+            number,
+            "expected a single glyph, quoted if it has spaces".into(),
+        )),
+        None => Err((number, "unclosed quote".into())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(parse_glyphs("", Glyphs::unicode()), Ok(Glyphs::unicode()));
+    }
+
+    #[test]
+    fn ascii_set() {
+        assert_eq!(
+            parse_glyphs("set = ascii", Glyphs::unicode()),
+            Ok(Glyphs::ascii())
+        );
+    }
+
+    #[test]
+    fn unicode_set_over_ascii_default() {
+        assert_eq!(
+            parse_glyphs("set = unicode", Glyphs::ascii()),
+            Ok(Glyphs::unicode())
+        );
+    }
+
+    #[test]
+    fn single_glyphs() {
+        let glyphs = parse_glyphs("arrow = ⇒\ntree-line = '¦  '\n", Glyphs::unicode()).unwrap();
+        assert_eq!(glyphs.link_arrow, "⇒");
+        assert_eq!(glyphs.tree_line, "¦  ");
+        assert_eq!(glyphs.tree_edge, "├──");
+    }
+
+    #[test]
+    fn set_applies_first() {
+        let glyphs = parse_glyphs("arrow = →\nset = ascii\n", Glyphs::unicode()).unwrap();
+        assert_eq!(glyphs.link_arrow, "→");
+        assert_eq!(glyphs.tree_edge, "|--");
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        assert!(matches!(
+            parse_glyphs("\n\nspinner = *", Glyphs::unicode()),
+            Err((3, _))
+        ));
+    }
+
+    #[test]
+    fn unknown_set() {
+        assert!(parse_glyphs("set = emoji", Glyphs::unicode()).is_err());
+    }
+
+    #[test]
+    fn unquoted_spaces() {
+        assert!(parse_glyphs("tree-blank =    ", Glyphs::unicode()).is_err());
+        assert!(parse_glyphs("arrow = - >", Glyphs::unicode()).is_err());
+    }
+}
@@ -6,27 +6,64 @@ use crate::options::parser::MatchedFlags;
 
 static USAGE_PART1: &str = "Usage:
   eza [options] [files...]
+  eza view NAME [options] [files...]
+  eza [options] @NAME [options] [files...]
 
 META OPTIONS
   --help                     show list of command-line options
   -v, --version              show version of eza
+  -q, --quiet                don't print warnings
+  --strict-warnings          exit with an error if any warnings were printed
+  --notify[=SECS]            ring the bell when a run of SECS or more finishes
+  --debug-layout             explain column widths and terminal width on stderr
+  --dump-scale               print the colour scales' ranges and stops on stderr
 
 DISPLAY OPTIONS
   -1, --oneline              display one entry per line
   -l, --long                 display extended file metadata as a table
   -G, --grid                 display entries as a grid (default)
   -x, --across               sort the grid across, rather than downwards
+  --grid-layout=LAYOUT       how to size the grid's columns (auto, exact, fast)
   -R, --recurse              recurse into directories
   -T, --tree                 recurse into directories as a tree
+  --tree-summary             show the number and size of files under each directory
   -X, --dereference          dereference symbolic links when displaying information
   -F, --classify=WHEN        display type indicator by file names (always, auto, never)
   --colo[u]r=WHEN            when to use terminal colours (always, auto, never)
   --colo[u]r-scale           highlight levels of 'field' distinctly(all, age, size)
   --colo[u]r-scale-mode      use gradient or fixed colors in --color-scale (fixed, gradient)
+  --colo[u]r-by=WHAT         colour by owner or last commit (owner, owner-names, git-age)
+  --colo[u]r-glob=RULE       paint files matching a GLOB=STYLE rule, over EZA_COLORS
+  --highlight-recent[=TIME]  highlight files modified within TIME (default 24h)
+  --highlight REGEX          highlight the parts of names that match REGEX
+  --name-clashes             flag names that differ only by case or normalization
+  --show-trash               mark files that something was trashed from the same path as
+  --badges[=ATTRIBUTE]       show the user.eza.badge (or ATTRIBUTE) xattr after names
+  --relative-root=DIR        show paths inside DIR as if DIR were the root
+  --trash                    list what's in the trash, with where it was trashed from
+  --resolve-shortcuts        show the names of .desktop files and targets of .lnk files
+  --finder-tags              show Finder tags as coloured dots after file names (macOS)
+  --mount-usage              draw how full the filesystem on each mount point is
+  --special-dirs             decorate XDG user dirs and dirs like node_modules
+  --blocks-map               draw where a single sparse file's data and holes are
+  --index                    start with a numbered list of the directories given
+  --merge                    list the contents of all the directories given as one
+  --stats=WHAT               finish with statistics, such as sizes by extension (ext)
+  --depth-colo[u]rs[=PARTS]  colour the tree by depth (lines, names, all)
+  --truncate=MODE            how to shorten long names (end, middle, off)
+  --max-name-width COLS      shorten names wider than COLS columns
   --icons=WHEN               when to display icons (always, auto, never)
   --no-quotes                don't quote file names with spaces
-  --hyperlink                display entries as hyperlinks
+  --hyperlink[=WHEN]         display entries as hyperlinks (always, auto, never,
+                             all-cells)
+  --json                     print the listing as JSON, nested with --tree
+  --no-metadata              leave out how the listing was made from JSON, CSV, TSV
+  --format FORMAT            print one row per file as csv or tsv
+  --output=fzf               write names and paths for fzf, null-separated
   -w, --width COLS           set screen width in columns
+  --height ROWS              set screen height in rows
+  --paginate                 page listings taller than the screen
+  --flush=WHEN               when to write output (line, block, end)
 
 
 FILTERING AND SORTING OPTIONS
@@ -35,20 +72,38 @@ FILTERING AND SORTING OPTIONS
   -A, --almost-all           equivalent to --all; included for compatibility with `ls -A`
   -d, --list-dirs            list directories as files; don't list their contents
   -L, --level DEPTH          limit the depth of recursion
+  --follow-symlinks          recurse into symlinks to directories, stopping at loops
+  --resume STATE             save where a recursive listing is up to in STATE, and carry on from it
   -r, --reverse              reverse the sort order
   -s, --sort SORT_FIELD      which field to sort by
+  --allow-sort-command       allow sorting by keys from a command (--sort=cmd:COMMAND)
   --group-directories-first  list directories before other files
   -D, --only-dirs            list only directories
   -f, --only-files           list only files
-  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore";
+  --owner USER               list only files owned by USER (a name, ID, or 'me')
+  --in-group GROUP           list only files in GROUP (a name, ID, or 'me')
+  --empty                    list only empty files and directories
+  --non-empty                list only files and directories with something in them
+  --perm MODE                list only files with these permissions ('-' for all, '/' for any)
+  --ext EXTS                 list only files with these extensions (comma-separated)
+  --size RANGE               list only files with sizes in a range (+10M, -1k, 1M..100M)
+  --newer-than TIME          list only files modified after a time (2d, 2024-01-01)
+  --older-than TIME          list only files modified before a time
+  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore
+  --only-glob GLOBS          glob patterns (pipe-separated) of files to list";
 
 static GIT_FILTER_HELP: &str = "  \
-  --git-ignore               ignore files mentioned in '.gitignore'";
+  --git-ignore               ignore files mentioned in '.gitignore'
+  --git-tracked              list only files tracked by Git
+  --git-untracked            list only files not tracked by Git";
 
 static USAGE_PART2: &str = "  \
   Valid sort fields:         name, Name, extension, Extension, size, type,
-                             modified, accessed, created, inode, and none.
+                             modified, accessed, created, inode, device, git,
+                             and none.
                              date, time, old, and new all refer to modified.
+                             Several can be separated by commas, and a field
+                             after a '-' is sorted backwards: size,-modified.
 
 LONG VIEW OPTIONS
   -b, --binary               list file sizes with binary prefixes
@@ -56,29 +111,51 @@ LONG VIEW OPTIONS
   -g, --group                list each file's group
   --smart-group              only show group if it has a different name from owner
   -h, --header               add a header row to each column
-  -H, --links                list each file's number of hard links
+  -H, --links[=WHEN]         list each file's number of hard links (always, auto)
   -i, --inode                list each file's inode number
   -m, --modified             use the modified timestamp field
   -M, --mounts               show mount details (Linux and Mac only)
   -n, --numeric              list numeric user and group IDs
   -O, --flags                list file flags (Mac, BSD, and Windows only)
+  --virtual-files MODE       how to size files in /proc and /sys (show, mark, read)
+  --socket-info              list whether sockets are listening or connected (Linux only)
+  --overlay                  list which overlayfs layer each file comes from (Linux only)
+  --age-rank                 list each file's rank by modified time, 1 being the newest
+  --find-dupes[=CHECK]       number groups of duplicate files (size, content)
+  --file-version[=KIND]      list the version of each executable and DLL (file, product)
+  --text-size                list each text file's size with its line endings as LF
+  --encoding                 list each text file's line endings, and any byte order mark
+  --preview[=N]              list the first N characters (default 40) of each text file
+  --name-first               list file names before the other columns
+  --diff-against DIR         mark each file as the same as, different from, or not in DIR
+  --diff-by WHAT             how to compare files with DIR or a manifest (meta, content)
+  --manifest-write FILE      write a manifest of every file under the listed paths to FILE
+  --manifest-check FILE      mark each file as the same as, different from, or not in FILE
+  --align COLUMN:SIDE,...    align columns to the left or right (e.g. size:left)
+  --fields COLUMN,...        list just these columns, in order (e.g. name,size,git)
   -S, --blocksize            show size of allocated file system blocks
-  -t, --time FIELD           which timestamp field to list (modified, accessed, created)
+  -t, --time FIELD           which timestamp field to list (modified, accessed, created, all)
   -u, --accessed             use the accessed timestamp field
   -U, --created              use the created timestamp field
   --changed                  use the changed timestamp field
   --time-style               how to format timestamps (default, iso, long-iso,
                              full-iso, relative, or a custom style '+<FORMAT>'
                              like '+%Y-%m-%d %H:%M')
+  --relative-to TIME         measure relative times and the age colour scale from
+                             TIME ('2024-01-01 09:30', or '@FILE' for its mtime)
   --total-size               show the size of a directory as the size of all
                              files and directories inside (unix only)
+  --cache[=MODE]             cache xattrs and Git statuses between runs
+                             (on, refresh, off)
   --no-permissions           suppress the permissions field
   -o, --octal-permissions    list each file's permission in octal format
+  --simulate-umask=MASK      list the permissions new files in each directory would get
   --no-filesize              suppress the filesize field
   --no-user                  suppress the user field
   --no-time                  suppress the time field
   --stdin                    read file names from stdin, one per line or other separator 
-                             specified in environment";
+                             specified in environment
+  --skip-missing             don't report file names from stdin that don't exist";
 
 static GIT_VIEW_HELP: &str = "  \
   --git                      list each file's Git status, if tracked or ignored
@@ -88,7 +165,7 @@ static GIT_VIEW_HELP: &str = "  \
 static EXTENDED_HELP: &str = "  \
   -@, --extended             list each file's extended attributes and sizes";
 static SECATTR_HELP: &str = "  \
-  -Z, --context              list each file's security context";
+  -Z, --context[=MODE]       list each file's security context (full, smart)";
 
 /// All the information needed to display the help text, which depends
 /// on which features are enabled and whether the user only wants to
@@ -0,0 +1,132 @@
+//! Printing the values that options accept, for `--list-options`, so shell
+//! completion scripts and other programs that run eza can ask it rather
+//! than having their own copy of the lists.
+//!
+//! `--list-options` on its own prints the long name of each option with a
+//! fixed set of values, and `--list-options=FLAG` prints that option’s
+//! values, each on their own line. Both come from the values the options
+//! are declared with in `flags`.
+
+use std::fmt;
+
+use crate::options::flags;
+use crate::options::parser::{Arg, MatchedFlags, TakesValue, Values};
+use crate::options::OptionsError;
+
+/// What to print for `--list-options`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ListOptions {
+    /// The options that have a fixed set of values.
+    Flags,
+
+    /// The values of one option.
+    Values(Values),
+}
+
+impl ListOptions {
+    /// Determines what to list, if anything, based on the user’s
+    /// command-line arguments. Like `--help`, this takes over from any
+    /// other options, but an option that doesn’t exist or has no fixed
+    /// values is an error.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Option<Result<Self, OptionsError>> {
+        let word = matches.get(&flags::LIST_OPTIONS).ok()??;
+        let word = word.to_string_lossy();
+
+        if word.is_empty() {
+            return Some(Ok(Self::Flags));
+        }
+
+        let name = word.trim_start_matches('-');
+        let arg = flags::ALL_ARGS
+            .0
+            .iter()
+            .find(|arg| arg.long == name || arg.short.is_some_and(|s| [s] == name.as_bytes()));
+
+        Some(match arg.and_then(|arg| values(arg)) {
+            Some(values) => Ok(Self::Values(values)),
+            None => Err(OptionsError::Unsupported(format!(
+                "Option --{name} has no values to list"
+            ))),
+        })
+    }
+}
+
+/// The fixed set of values the given option takes, if it has one.
+fn values(arg: &Arg) -> Option<Values> {
+    match arg.takes_value {
+        TakesValue::Necessary(values)
+        | TakesValue::Optional(values, _)
+        | TakesValue::OptionalAfterEquals(values, _) => values,
+        TakesValue::Forbidden => None,
+    }
+}
+
+impl fmt::Display for ListOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flags => {
+                for arg in flags::ALL_ARGS.0 {
+                    if values(arg).is_some() {
+                        writeln!(f, "{}", arg.long)?;
+                    }
+                }
+            }
+            Self::Values(values) => {
+                for value in *values {
+                    writeln!(f, "{value}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::options::{Options, OptionsResult};
+    use std::ffi::OsStr;
+
+    fn list(args: &[&str]) -> Result<String, String> {
+        match Options::parse(args.iter().map(OsStr::new), &None) {
+            OptionsResult::ListOptions(list) => Ok(list.to_string()),
+            OptionsResult::InvalidOptions(e) => Err(e.to_string()),
+            _ => Err(String::from("not listed")),
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let listed = list(&["--list-options"]).unwrap();
+        assert!(listed.lines().any(|l| l == "sort"));
+        assert!(listed.lines().any(|l| l == "time-style"));
+        assert!(!listed.lines().any(|l| l == "long"));
+    }
+
+    #[test]
+    fn long() {
+        assert_eq!(
+            list(&["--list-options=color"]),
+            Ok(String::from("always\nauto\nnever\n"))
+        );
+    }
+
+    #[test]
+    fn dashes_and_short() {
+        assert_eq!(
+            list(&["--list-options=--time"]),
+            list(&["--list-options=t"])
+        );
+    }
+
+    #[test]
+    fn no_values() {
+        assert!(list(&["--list-options=long"]).is_err());
+        assert!(list(&["--list-options=nonexistent"]).is_err());
+    }
+
+    #[test]
+    fn not_listed() {
+        assert_eq!(list(&["--long"]), Err(String::from("not listed")));
+    }
+}
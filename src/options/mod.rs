@@ -69,19 +69,43 @@
 //! it’s clear what the user wants.
 
 use std::ffi::OsStr;
+use std::path::PathBuf;
 
+use crate::diagnostics::Verbosity;
+use crate::fs::cache::CacheOptions;
+use crate::fs::compare::DiffAgainst;
 use crate::fs::dir_action::DirAction;
-use crate::fs::filter::{FileFilter, GitIgnore};
+use crate::fs::feature::special_dirs::UserDirsFile;
+use crate::fs::feature::trash::Trash;
+use crate::fs::filter::{FileFilter, GitIgnore, GitTracking, SortField};
+use crate::fs::manifest::ManifestWrite;
+use crate::notify::Notify;
 use crate::options::stdin::FilesInput;
-use crate::output::{details, grid_details, Mode, View};
+use crate::output::capabilities::Capabilities;
+use crate::output::stats::Options as StatsOptions;
+use crate::output::{delimited, details, grid_details, Mode, View};
+use crate::pager::{Flush, Pager};
 use crate::theme::Options as ThemeOptions;
 
+mod cache;
+mod capabilities;
+mod compare;
+mod decorations;
+mod diagnostics;
 mod dir_action;
+mod duration;
 mod file_name;
 mod filter;
 #[rustfmt::skip] // this module becomes unreadable with rustfmt
 mod flags;
+mod glyphs;
+mod notify;
+mod pager;
+mod special_dirs;
+mod stats;
 mod theme;
+mod time_anchor;
+mod trash;
 mod view;
 
 mod error;
@@ -90,6 +114,9 @@ pub use self::error::{NumberSource, OptionsError};
 mod help;
 use self::help::HelpString;
 
+mod list_options;
+use self::list_options::ListOptions;
+
 mod parser;
 use self::parser::MatchedFlags;
 
@@ -99,11 +126,15 @@ pub use self::vars::Vars;
 pub mod stdin;
 mod version;
 
+mod config;
+pub mod views;
+
 use self::version::VersionString;
 
 /// These **options** represent a parsed, error-checked versions of the
 /// user’s command-line options.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Options {
     /// The action to perform when encountering a directory rather than a
     /// regular file.
@@ -121,8 +152,68 @@ pub struct Options {
     /// The options to make up the styles of the UI and file names.
     pub theme: ThemeOptions,
 
-    /// Whether to read file names from stdin instead of the command-line
+    /// Whether to read file names from stdin as well as the command-line
     pub stdin: FilesInput,
+
+    /// Whether warnings should be hidden, shown, or treated as errors.
+    pub verbosity: Verbosity,
+
+    /// Whether to notify the user once a long run has finished.
+    pub notify: Option<Notify>,
+
+    /// Whether to explain the widths of grids and tables on standard error.
+    pub debug_layout: bool,
+
+    /// Whether to print the colour scales worked out for the listing on
+    /// standard error.
+    pub dump_scale: bool,
+
+    /// Whether to send listings too tall for the terminal through a pager.
+    pub paginate: Option<Pager>,
+
+    /// When to pass output on to standard output, if it was chosen.
+    pub flush: Option<Flush>,
+
+    /// What the terminal is able to draw.
+    pub capabilities: Capabilities,
+
+    /// Where to write a manifest of the listed files, if anywhere.
+    pub manifest_write: Option<ManifestWrite>,
+
+    /// Where the trash is, if it needs reading.
+    pub trash: Option<Trash>,
+
+    /// Whether to list what’s in the trash instead of any files.
+    pub list_trash: bool,
+
+    /// Where the XDG user directories are listed, if they need reading.
+    pub user_dirs: Option<UserDirsFile>,
+
+    /// Whether to draw a map of where a single file’s data is instead of
+    /// listing it.
+    pub blocks_map: bool,
+
+    /// Whether to start with a numbered list of the directories being
+    /// listed, when there are several of them.
+    pub index: bool,
+
+    /// Whether to list the contents of several directories together, in
+    /// one sorted listing, rather than one after the other.
+    pub merge: bool,
+
+    /// The state file to save the directories a recursive listing still
+    /// has to get to in, and to carry on from, if there is one.
+    pub resume: Option<PathBuf>,
+
+    /// Where to cache slow-to-gather metadata between runs, if anywhere.
+    pub cache: Option<CacheOptions>,
+
+    /// Which statistics to print after the listing, if any.
+    pub stats: Option<StatsOptions>,
+
+    /// The filtering options that were given, to record alongside JSON
+    /// output, or `None` if the metadata is being left out.
+    pub metadata: Option<Vec<String>>,
 }
 
 impl Options {
@@ -157,6 +248,13 @@ impl Options {
             return OptionsResult::Version(version);
         }
 
+        if let Some(list) = ListOptions::deduce(&flags) {
+            return match list {
+                Ok(list) => OptionsResult::ListOptions(list),
+                Err(oe) => OptionsResult::InvalidOptions(oe),
+            };
+        }
+
         match Self::deduce(&flags, vars) {
             Ok(options) => OptionsResult::Ok(options, frees),
             Err(oe) => OptionsResult::InvalidOptions(oe),
@@ -167,7 +265,14 @@ impl Options {
     /// status column. It’s only worth trying to discover a repository if the
     /// results will end up being displayed.
     pub fn should_scan_for_git(&self) -> bool {
-        if self.filter.git_ignore == GitIgnore::CheckAndIgnore {
+        if self.filter.git_ignore == GitIgnore::CheckAndIgnore
+            || self.filter.git_tracking != GitTracking::Any
+            || self
+                .filter
+                .sort_fields
+                .iter()
+                .any(|key| key.field == SortField::GitStatus)
+        {
             return true;
         }
 
@@ -184,28 +289,73 @@ impl Options {
                     },
                 ..
             }) => table.columns.git,
+            // JSON output includes every file’s Git status whenever there is
+            // one to include.
+            Mode::Json => true,
+            Mode::Delimited(ref delimited) => delimited.fields.contains(&delimited::Field::Git),
             _ => false,
         }
     }
 
+    /// The directory to compare the listed files with, if the diff column
+    /// is going to be displayed.
+    pub fn diff_against(&self) -> Option<&DiffAgainst> {
+        match self.view.mode {
+            Mode::Details(details::Options {
+                table: Some(ref table),
+                ..
+            })
+            | Mode::GridDetails(grid_details::Options {
+                details:
+                    details::Options {
+                        table: Some(ref table),
+                        ..
+                    },
+                ..
+            }) => table.diff_against.as_ref(),
+            _ => None,
+        }
+    }
+
     /// Determines the complete set of options based on the given command-line
     /// arguments, after they’ve been parsed.
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        if cfg!(not(feature = "git"))
-            && matches
-                .has_where_any(|f| f.matches(&flags::GIT) || f.matches(&flags::GIT_IGNORE))
-                .is_some()
-        {
-            return Err(OptionsError::Unsupported(String::from(
-                "Options --git and --git-ignore can't be used because `git` feature was disabled in this build of exa"
-            )));
+        let verbosity = Verbosity::deduce(matches)?;
+
+        for &(arg, feature, enabled) in flags::FEATURE_GATED {
+            if !enabled && matches.has_where_any(|f| f.matches(arg)).is_some() {
+                let error = OptionsError::FeatureDisabled(arg, feature);
+                if matches.is_strict() || verbosity == Verbosity::Strict {
+                    return Err(error);
+                } else if verbosity == Verbosity::Normal {
+                    crate::diagnostics::warning(format_args!("eza: {error}; ignoring it"));
+                }
+            }
         }
 
         let view = View::deduce(matches, vars)?;
-        let dir_action = DirAction::deduce(matches, matches!(view.mode, Mode::Details(_)))?;
-        let filter = FileFilter::deduce(matches)?;
+        let can_tree = matches!(view.mode, Mode::Details(_) | Mode::Json);
+        let dir_action = DirAction::deduce(matches, can_tree)?;
+        let filter = FileFilter::deduce(matches, vars)?;
         let theme = ThemeOptions::deduce(matches, vars)?;
         let stdin = FilesInput::deduce(matches, vars)?;
+        let notify = Notify::deduce(matches, vars)?;
+        let debug_layout = matches.has(&flags::DEBUG_LAYOUT)?;
+        let dump_scale = matches.has(&flags::DUMP_SCALE)?;
+        let paginate = Pager::deduce(matches, vars)?;
+        let flush = Flush::deduce(matches)?;
+        let capabilities = Capabilities::deduce(vars);
+        let manifest_write = ManifestWrite::deduce(matches)?;
+        let trash = Trash::deduce(matches, vars)?;
+        let list_trash = matches.has(&flags::TRASH)?;
+        let user_dirs = UserDirsFile::deduce(matches, vars)?;
+        let blocks_map = matches.has(&flags::BLOCKS_MAP)?;
+        let index = matches.has(&flags::INDEX)?;
+        let merge = matches.has(&flags::MERGE)? && dir_action.recurse_options().is_none();
+        let resume = Self::deduce_resume(matches, &dir_action)?;
+        let cache = CacheOptions::deduce(matches, vars)?;
+        let stats = StatsOptions::deduce(matches)?;
+        let metadata = Self::deduce_metadata(matches, &view.mode)?;
 
         Ok(Self {
             dir_action,
@@ -213,8 +363,85 @@ impl Options {
             view,
             theme,
             stdin,
+            verbosity,
+            notify,
+            debug_layout,
+            dump_scale,
+            paginate,
+            flush,
+            capabilities,
+            manifest_write,
+            trash,
+            list_trash,
+            user_dirs,
+            blocks_map,
+            index,
+            merge,
+            resume,
+            cache,
+            stats,
+            metadata,
         })
     }
+
+    /// Determine which filtering options to record in the metadata of JSON,
+    /// CSV, or TSV output, written out in full the way they were given, unless the
+    /// metadata is turned off with `--no-metadata`.
+    fn deduce_metadata(
+        matches: &MatchedFlags<'_>,
+        mode: &Mode,
+    ) -> Result<Option<Vec<String>>, OptionsError> {
+        if matches.has(&flags::NO_METADATA)? {
+            if matches.is_strict() && !matches!(mode, Mode::Json | Mode::Delimited(_)) {
+                return Err(OptionsError::Useless(
+                    &flags::NO_METADATA,
+                    false,
+                    &flags::JSON,
+                ));
+            }
+            return Ok(None);
+        }
+
+        let mut filters = Vec::new();
+        for (flag, value) in matches.all_where(|f| flags::FILTERS.iter().any(|a| f.matches(a))) {
+            let Some(arg) = flags::FILTERS.iter().find(|a| flag.matches(a)) else {
+                continue;
+            };
+
+            match value {
+                None => filters.push(format!("--{}", arg.long)),
+                Some(value) => {
+                    filters.push(format!("--{}={}", arg.long, value.to_string_lossy()));
+                }
+            }
+        }
+
+        Ok(Some(filters))
+    }
+
+    /// Determine which state file to resume a recursive listing from, which
+    /// only makes sense when recursing without drawing a tree, as a tree
+    /// can’t be drawn from part-way through.
+    fn deduce_resume(
+        matches: &MatchedFlags<'_>,
+        dir_action: &DirAction,
+    ) -> Result<Option<PathBuf>, OptionsError> {
+        let Some(path) = matches.get(&flags::RESUME)? else {
+            return Ok(None);
+        };
+
+        match dir_action.recurse_options() {
+            Some(recurse) if recurse.tree => {
+                Err(OptionsError::Useless(&flags::RESUME, true, &flags::TREE))
+            }
+            Some(_) => Ok(Some(PathBuf::from(path))),
+            None => Err(OptionsError::Useless(
+                &flags::RESUME,
+                false,
+                &flags::RECURSE,
+            )),
+        }
+    }
 }
 
 /// The result of the `Options::parse` function.
@@ -240,6 +467,10 @@ pub enum OptionsResult<'args> {
 
     /// One of the arguments was `--version`, so display the version number.
     Version(VersionString),
+
+    /// One of the arguments was `--list-options`, so display the options
+    /// with fixed values, or the values of one of them.
+    ListOptions(ListOptions),
 }
 
 #[cfg(test)]
@@ -247,7 +478,7 @@ pub mod test {
     use crate::options::parser::{Arg, MatchedFlags};
     use std::ffi::OsStr;
 
-    #[derive(PartialEq, Eq, Debug)]
+    #[derive(PartialEq, Eq, Debug, Copy, Clone)]
     pub enum Strictnesses {
         Last,
         Complain,
@@ -287,4 +518,73 @@ pub mod test {
 
         result
     }
+
+    #[cfg(test)]
+    mod metadata {
+        use super::*;
+        use crate::options::flags;
+        use crate::options::{Options, OptionsError};
+        use crate::output::{delimited, lines, Mode};
+
+        static TEST_ARGS: &[&Arg] = &[&flags::NO_METADATA, &flags::ONLY_DIRS];
+
+        fn csv() -> Mode {
+            Mode::Delimited(delimited::Options {
+                format: delimited::Format::Csv,
+                fields: Vec::new(),
+            })
+        }
+
+        #[test]
+        fn filters() {
+            for result in parse_for_test(&["--only-dirs"], TEST_ARGS, Strictnesses::Both, |mf| {
+                Options::deduce_metadata(mf, &Mode::Json)
+            }) {
+                assert_eq!(result, Ok(Some(vec![String::from("--only-dirs")])));
+            }
+        }
+
+        #[test]
+        fn none_for_json() {
+            for result in parse_for_test(
+                &["--no-metadata"],
+                TEST_ARGS,
+                Strictnesses::Complain,
+                |mf| Options::deduce_metadata(mf, &Mode::Json),
+            ) {
+                assert_eq!(result, Ok(None));
+            }
+        }
+
+        #[test]
+        fn none_for_csv() {
+            for result in parse_for_test(
+                &["--no-metadata"],
+                TEST_ARGS,
+                Strictnesses::Complain,
+                |mf| Options::deduce_metadata(mf, &csv()),
+            ) {
+                assert_eq!(result, Ok(None));
+            }
+        }
+
+        #[test]
+        fn useless_for_lines() {
+            for result in parse_for_test(
+                &["--no-metadata"],
+                TEST_ARGS,
+                Strictnesses::Complain,
+                |mf| Options::deduce_metadata(mf, &Mode::Lines(lines::Options::default())),
+            ) {
+                assert_eq!(
+                    result,
+                    Err(OptionsError::Useless(
+                        &flags::NO_METADATA,
+                        false,
+                        &flags::JSON
+                    ))
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,80 @@
+//! Parsing the options for `Notify`.
+
+use std::time::Duration;
+
+use crate::notify::{Notify, NotifyStyle};
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, NumberSource, OptionsError, Vars};
+
+impl Notify {
+    /// Determine whether to notify the user when eza finishes, from the
+    /// `--notify` argument’s number of seconds, and how to from the
+    /// `EZA_NOTIFY_STYLE` environment variable.
+    pub fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<Option<Self>, OptionsError> {
+        let Some(seconds) = matches.get(&flags::NOTIFY)? else {
+            return Ok(None);
+        };
+
+        let seconds = seconds.to_string_lossy();
+        let after = match seconds.parse() {
+            Ok(s) => Duration::from_secs(s),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::NOTIFY);
+                return Err(OptionsError::FailedParse(seconds.into(), source, e));
+            }
+        };
+
+        let style = NotifyStyle::deduce(vars);
+        Ok(Some(Self { after, style }))
+    }
+}
+
+impl NotifyStyle {
+    fn deduce<V: Vars>(vars: &V) -> Self {
+        match vars.get(vars::EZA_NOTIFY_STYLE) {
+            Some(s) if s == "osc9" => Self::Osc9,
+            _ => Self::Bell,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsString;
+
+    macro_rules! test {
+        ($name:ident: $inputs:expr, $env:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                use crate::options::parser::Arg;
+                use crate::options::test::parse_for_test;
+                use crate::options::test::Strictnesses::*;
+
+                static TEST_ARGS: &[&Arg] = &[&flags::NOTIFY];
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    Notify::deduce(mf, &$env)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    fn after(seconds: u64, style: NotifyStyle) -> Notify {
+        Notify {
+            after: Duration::from_secs(seconds),
+            style,
+        }
+    }
+
+    test!(off:     [],                   None; Both => Ok(None));
+    test!(default: ["--notify"],         None; Both => Ok(Some(after(5, NotifyStyle::Bell))));
+    test!(seconds: ["--notify=30"],      None; Both => Ok(Some(after(30, NotifyStyle::Bell))));
+    test!(osc9:    ["--notify=0"],       Some(OsString::from("osc9")); Both => Ok(Some(after(0, NotifyStyle::Osc9))));
+    test!(other:   ["--notify=0"],       Some(OsString::from("beep")); Both => Ok(Some(after(0, NotifyStyle::Bell))));
+    test!(bad:     ["--notify=soon"],    None; Both => Err(OptionsError::FailedParse("soon".into(), NumberSource::Arg(&flags::NOTIFY), "soon".parse::<u64>().unwrap_err())));
+}
@@ -0,0 +1,87 @@
+//! Parsing the options for `Pager`.
+
+use crate::options::config::split_words;
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, OptionsError, Vars};
+use crate::pager::{Flush, Pager};
+
+/// The pager to use if neither `EZA_PAGER` nor `PAGER` are set. The `-R`
+/// lets colours through, `-F` quits straight away if everything fits on
+/// one screen after all, and `-X` leaves the listing on screen afterwards.
+const DEFAULT_PAGER: &str = "less -RFX";
+
+impl Pager {
+    /// Determine whether to page long listings from the `--paginate` flag,
+    /// and which pager to use from the `EZA_PAGER` or `PAGER` environment
+    /// variables.
+    pub fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<Option<Self>, OptionsError> {
+        if !matches.has(&flags::PAGINATE)? {
+            return Ok(None);
+        }
+
+        let command = vars
+            .get_with_fallback(vars::EZA_PAGER, vars::PAGER)
+            .and_then(|p| p.into_string().ok())
+            .and_then(|p| split_words(&p))
+            .filter(|words| !words.is_empty())
+            .unwrap_or_else(|| split_words(DEFAULT_PAGER).unwrap_or_default());
+
+        Ok(Some(Self { command }))
+    }
+}
+
+impl Flush {
+    /// Determine when to pass output on from the `--flush` option. Without
+    /// it, this depends on where the output is going, so it’s left to be
+    /// decided later.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::FLUSH)? else {
+            return Ok(None);
+        };
+
+        match word.to_str() {
+            Some("line") => Ok(Some(Self::Line)),
+            Some("block") => Ok(Some(Self::Block)),
+            Some("end") => Ok(Some(Self::End)),
+            _ => Err(OptionsError::BadArgument(&flags::FLUSH, word.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsString;
+
+    macro_rules! test {
+        ($name:ident: $inputs:expr, $env:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                use crate::options::parser::Arg;
+                use crate::options::test::parse_for_test;
+                use crate::options::test::Strictnesses::*;
+
+                static TEST_ARGS: &[&Arg] = &[&flags::PAGINATE];
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    Pager::deduce(mf, &$env)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    fn pager(command: &[&str]) -> Pager {
+        Pager {
+            command: command.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    test!(off:         [],               None;                                      Both => Ok(None));
+    test!(default:     ["--paginate"],   None;                                      Both => Ok(Some(pager(&["less", "-RFX"]))));
+    test!(from_env:    ["--paginate"],   Some(OsString::from("most -s"));           Both => Ok(Some(pager(&["most", "-s"]))));
+    test!(empty_env:   ["--paginate"],   Some(OsString::new());                     Both => Ok(Some(pager(&["less", "-RFX"]))));
+}
@@ -100,6 +100,11 @@ pub enum TakesValue {
 
     /// This flag may be followed by a value to override its defaults
     Optional(Option<Values>, &'static str),
+
+    /// This flag may be given a value, but only after an equals sign in
+    /// its long form, such as `--links=auto`. Anywhere else it gets its
+    /// default, so the string after it is never taken as its value.
+    OptionalAfterEquals(Option<Values>, &'static str),
 }
 
 /// An **argument** can be matched by one of the user’s input strings.
@@ -176,7 +181,9 @@ impl Args {
                     let arg = self.lookup_long(before)?;
                     let flag = Flag::Long(arg.long);
                     match arg.takes_value {
-                        TakesValue::Necessary(_) | TakesValue::Optional(_, _) => {
+                        TakesValue::Necessary(_)
+                        | TakesValue::Optional(_, _)
+                        | TakesValue::OptionalAfterEquals(_, _) => {
                             result_flags.push((flag, Some(after)));
                         }
                         TakesValue::Forbidden => return Err(ParseError::ForbiddenValue { flag }),
@@ -207,6 +214,9 @@ impl Args {
                                     .push((flag, Some(bytes_to_os_str(default.as_bytes()))));
                             }
                         },
+                        TakesValue::OptionalAfterEquals(_, default) => {
+                            result_flags.push((flag, Some(bytes_to_os_str(default.as_bytes()))));
+                        }
                     }
                 }
             }
@@ -239,7 +249,8 @@ impl Args {
                             TakesValue::Forbidden => {
                                 result_flags.push((flag, None));
                             }
-                            TakesValue::Optional(_, default) => {
+                            TakesValue::Optional(_, default)
+                            | TakesValue::OptionalAfterEquals(_, default) => {
                                 result_flags
                                     .push((flag, Some(bytes_to_os_str(default.as_bytes()))));
                             }
@@ -256,7 +267,7 @@ impl Args {
                         TakesValue::Necessary(_) | TakesValue::Optional(_, _) => {
                             result_flags.push((flag, Some(after)));
                         }
-                        TakesValue::Forbidden => {
+                        TakesValue::Forbidden | TakesValue::OptionalAfterEquals(_, _) => {
                             return Err(ParseError::ForbiddenValue { flag });
                         }
                     }
@@ -281,6 +292,9 @@ impl Args {
                             TakesValue::Forbidden => {
                                 result_flags.push((flag, None));
                             }
+                            TakesValue::OptionalAfterEquals(_, default) => {
+                                result_flags.push((flag, Some(OsStr::new(default))));
+                            }
                             TakesValue::Necessary(values) => {
                                 if index < bytes.len() - 1 {
                                     let remnants = &bytes[index + 1..];
@@ -290,7 +304,9 @@ impl Args {
                                     result_flags.push((flag, Some(next_arg)));
                                 } else {
                                     match arg.takes_value {
-                                        TakesValue::Forbidden | TakesValue::Optional(_, _) => {
+                                        TakesValue::Forbidden
+                                        | TakesValue::Optional(_, _)
+                                        | TakesValue::OptionalAfterEquals(_, _) => {
                                             unreachable!()
                                         }
                                         TakesValue::Necessary(_) => {
@@ -301,9 +317,17 @@ impl Args {
                             }
                             TakesValue::Optional(values, default) => {
                                 if index < bytes.len() - 1 {
-                                    let remnants = bytes_to_os_str(&bytes[index + 1..]);
-                                    if is_optional_arg(remnants, values) {
-                                        result_flags.push((flag, Some(remnants)));
+                                    let remnants = &bytes[index + 1..];
+                                    if is_optional_arg(bytes_to_os_str(remnants), values) {
+                                        result_flags.push((flag, Some(bytes_to_os_str(remnants))));
+                                    }
+                                    // If the rest of the cluster is made up of
+                                    // other flags, such as ‘-Hl’, then this
+                                    // one gets its default value instead.
+                                    else if remnants.iter().all(|b| self.lookup_short(*b).is_ok())
+                                    {
+                                        result_flags.push((flag, Some(OsStr::new(default))));
+                                        continue;
                                     } else {
                                         return Err(ParseError::ForbiddenValue { flag });
                                     }
@@ -316,7 +340,9 @@ impl Args {
                                     }
                                 } else {
                                     match arg.takes_value {
-                                        TakesValue::Forbidden | TakesValue::Necessary(_) => {
+                                        TakesValue::Forbidden
+                                        | TakesValue::Necessary(_)
+                                        | TakesValue::OptionalAfterEquals(_, _) => {
                                             unreachable!()
                                         }
                                         TakesValue::Optional(_, default) => {
@@ -351,6 +377,34 @@ impl Args {
         }
     }
 
+    /// Whether the given input is a flag, or a cluster of short flags,
+    /// whose value is the input after it, the way `--relative-to DIR` and
+    /// `-L 2` are parsed.
+    pub fn value_follows(&self, input: &OsStr) -> bool {
+        let bytes = os_str_to_bytes(input);
+
+        if let Some(long) = bytes.strip_prefix(b"--") {
+            return !long.contains(&b'=')
+                && self
+                    .lookup_long(bytes_to_os_str(long))
+                    .is_ok_and(|arg| matches!(arg.takes_value, TakesValue::Necessary(_)));
+        }
+
+        if bytes.len() < 2 || bytes[0] != b'-' || bytes.contains(&b'=') {
+            return false;
+        }
+
+        for (index, byte) in bytes.iter().enumerate().skip(1) {
+            match self.lookup_short(*byte).map(|arg| arg.takes_value) {
+                Ok(TakesValue::Forbidden | TakesValue::OptionalAfterEquals(_, _)) => {}
+                Ok(TakesValue::Necessary(_)) => return index == bytes.len() - 1,
+                Ok(TakesValue::Optional(_, _)) | Err(_) => return false,
+            }
+        }
+
+        false
+    }
+
     fn lookup_long(&self, long: &OsStr) -> Result<&Arg, ParseError> {
         match self.0.iter().find(|arg| arg.long == long) {
             Some(arg) => Ok(arg),
@@ -359,6 +413,79 @@ impl Args {
             }),
         }
     }
+
+    /// Returns the long names of the arguments that look most like the
+    /// given unknown one, closest first, so the user can be told what they
+    /// probably meant to type.
+    ///
+    /// An argument counts as similar if it’s only a few edits away from the
+    /// attempt, or if the attempt is an abbreviation of it.
+    pub fn similar_longs(&self, attempt: &str) -> Vec<LongArg> {
+        const MAX_SUGGESTIONS: usize = 3;
+
+        let threshold = (attempt.chars().count() / 3).max(1);
+
+        let mut candidates = self
+            .0
+            .iter()
+            .filter_map(|arg| {
+                let distance = edit_distance(attempt, arg.long);
+                let is_prefix = attempt.len() >= 3 && arg.long.starts_with(attempt);
+
+                if distance <= threshold || is_prefix {
+                    Some((distance, arg.long))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        candidates.sort_unstable();
+        candidates.dedup_by_key(|c| c.1);
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|c| c.1)
+            .collect()
+    }
+}
+
+/// The number of single-character insertions, deletions, substitutions, or
+/// adjacent transpositions needed to turn one string into the other (the
+/// “optimal string alignment” distance).
+///
+/// Transpositions are counted as one edit because swapping two letters is
+/// by far the most common way to mistype an option name.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // Three rows of the full matrix are enough: the current one, the
+    // previous one, and the one before that for transpositions.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
 }
 
 fn is_optional_arg(value: &OsStr, values: Option<&[&str]>) -> bool {
@@ -486,6 +613,34 @@ impl<'a> MatchedFlags<'a> {
         }
     }
 
+    /// Returns every value given to arguments that match the predicate, in
+    /// the order they were given, for arguments that can be repeated. Strict
+    /// mode has no effect.
+    pub fn get_all_where<P>(&self, predicate: P) -> Vec<&OsStr>
+    where
+        P: Fn(&Flag) -> bool,
+    {
+        self.flags
+            .iter()
+            .filter(|tuple| predicate(&tuple.0))
+            .filter_map(|tuple| tuple.1)
+            .collect()
+    }
+
+    /// Returns every argument that matches the predicate, along with its
+    /// value if it has one, in the order they were given. Strict mode has
+    /// no effect.
+    pub fn all_where<P>(&self, predicate: P) -> Vec<(&Flag, Option<&OsStr>)>
+    where
+        P: Fn(&Flag) -> bool,
+    {
+        self.flags
+            .iter()
+            .filter(|tuple| predicate(&tuple.0))
+            .map(|tuple| (&tuple.0, tuple.1))
+            .collect()
+    }
+
     // It’s annoying that ‘has’ and ‘get’ won’t work when accidentally given
     // flags that do/don’t take values, but this should be caught by tests.
 
@@ -668,7 +823,8 @@ mod parse_test {
         &Arg { short: Some(b'v'), long: "verbose",  takes_value: TakesValue::Forbidden },
         &Arg { short: Some(b'c'), long: "count",    takes_value: TakesValue::Necessary(None) },
         &Arg { short: Some(b't'), long: "type",     takes_value: TakesValue::Necessary(Some(SUGGESTIONS))},
-        &Arg { short: Some(b'o'), long: "optional", takes_value: TakesValue::Optional(Some(&["all", "some", "none"]), "all")} 
+        &Arg { short: Some(b'o'), long: "optional", takes_value: TakesValue::Optional(Some(&["all", "some", "none"]), "all")},
+        &Arg { short: Some(b'e'), long: "equals",   takes_value: TakesValue::OptionalAfterEquals(Some(&["auto", "never"]), "always")},
     ];
 
     // Just filenames
@@ -736,7 +892,75 @@ mod parse_test {
     test!(short_opt:        ["-o"]                 => frees: [], flags: [(Flag::Short(b'o'), Some(OsStr::new("all")))]);
     test!(short_opt_value:  ["-onone"]             => frees: [], flags: [(Flag::Short(b'o'), Some(OsStr::new("none")))]);
     test!(short_forbidden:  ["-opath"]             => error ForbiddenValue  { flag: Flag::Short(b'o') });
+    test!(short_opt_first:  ["-olv"]               => frees: [], flags: [(Flag::Short(b'o'), Some(OsStr::new("all"))), (Flag::Short(b'l'), None), (Flag::Short(b'v'), None)]);
     test!(short_allowed:    ["-o","path"]          => frees: ["path"], flags: [(Flag::Short(b'o'), Some(OsStr::new("all")))]);
+
+    // Optional args that only take a value after an equals sign
+    test!(equals:           ["--equals"]           => frees: [], flags: [(Flag::Long("equals"), Some(OsStr::new("always")))]);
+    test!(equals_with_eq:   ["--equals=auto"]      => frees: [], flags: [(Flag::Long("equals"), Some(OsStr::new("auto")))]);
+    test!(equals_wo_eq:     ["--equals", "auto"]   => frees: ["auto"], flags: [(Flag::Long("equals"), Some(OsStr::new("always")))]);
+    test!(short_equals:     ["-e", "auto"]         => frees: ["auto"], flags: [(Flag::Short(b'e'), Some(OsStr::new("always")))]);
+    test!(short_equals_two: ["-el"]                => frees: [], flags: [(Flag::Short(b'e'), Some(OsStr::new("always"))), (Flag::Short(b'l'), None)]);
+    test!(short_equals_eq:  ["-e=auto"]            => error ForbiddenValue { flag: Flag::Short(b'e') });
+
+    #[test]
+    fn links_short_takes_no_value() {
+        use crate::options::flags;
+        static ARGS: &[&Arg] = &[&flags::LINKS];
+
+        let inputs = ["-H", "auto"].into_iter().map(OsStr::new);
+        let got = Args(ARGS).parse(inputs, Strictness::UseLastArguments);
+
+        let flags = MatchedFlags {
+            flags: vec![(Flag::Short(b'H'), Some(OsStr::new("always")))],
+            strictness: Strictness::UseLastArguments,
+        };
+        let expected = Ok(Matches {
+            frees: vec![OsStr::new("auto")],
+            flags,
+        });
+        assert_eq!(got, expected);
+    }
+}
+
+#[cfg(test)]
+mod similar_test {
+    use super::*;
+
+    #[rustfmt::skip]
+    static TEST_ARGS: &[&Arg] = &[
+        &Arg { short: Some(b'r'), long: "reverse",  takes_value: TakesValue::Forbidden },
+        &Arg { short: Some(b'R'), long: "recurse",  takes_value: TakesValue::Forbidden },
+        &Arg { short: None,       long: "git",      takes_value: TakesValue::Forbidden },
+        &Arg { short: None,       long: "no-git",   takes_value: TakesValue::Forbidden },
+        &Arg { short: Some(b'l'), long: "long",     takes_value: TakesValue::Forbidden },
+    ];
+
+    macro_rules! test {
+        ($name:ident: $attempt:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                let expected: &[&str] = &$result;
+                assert_eq!(Args(TEST_ARGS).similar_longs($attempt), expected);
+            }
+        };
+    }
+
+    test!(transposed:   "revrese"  => ["reverse"]);
+    test!(missing:      "recuse"   => ["recurse"]);
+    test!(swapped_git:  "gti"      => ["git"]);
+    test!(abbreviated:  "rev"      => ["reverse"]);
+    test!(ambiguous:    "re"       => []);
+    test!(exact:        "long"     => ["long"]);
+    test!(nothing:      "quiet"    => []);
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("sort", "srot"), 1);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use crate::fs::feature::special_dirs::UserDirsFile;
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, OptionsError, Vars};
+
+impl UserDirsFile {
+    /// Find the user directories file, if special directories are going to
+    /// be decorated at all: `user-dirs.dirs` in the XDG configuration
+    /// directory, which defaults to `~/.config`.
+    pub fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<Option<Self>, OptionsError> {
+        if !matches.has(&flags::SPECIAL_DIRS)? {
+            return Ok(None);
+        }
+
+        let non_empty = |name| vars.get(name).filter(|v| !v.is_empty());
+        let Some(home) = non_empty(vars::HOME).map(PathBuf::from) else {
+            return Ok(None);
+        };
+        let config_dir = non_empty(vars::XDG_CONFIG_HOME)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".config"));
+
+        Ok(Some(Self {
+            path: config_dir.join("user-dirs.dirs"),
+            home,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::config::test::Env;
+    use crate::options::parser::Arg;
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::Both;
+
+    fn deduce(args: &[&str], env: &Env) -> Option<UserDirsFile> {
+        static TEST_ARGS: &[&Arg] = &[&flags::SPECIAL_DIRS];
+        let mut results = parse_for_test(args, TEST_ARGS, Both, |mf| UserDirsFile::deduce(mf, env));
+        results.pop().unwrap().unwrap()
+    }
+
+    #[test]
+    fn not_asked_for() {
+        assert_eq!(deduce(&[], &Env(&[("HOME", "/home/u")])), None);
+    }
+
+    #[test]
+    fn home() {
+        let file = deduce(&["--special-dirs"], &Env(&[("HOME", "/home/u")])).unwrap();
+        assert_eq!(file.path, PathBuf::from("/home/u/.config/user-dirs.dirs"));
+        assert_eq!(file.home, PathBuf::from("/home/u"));
+    }
+
+    #[test]
+    fn xdg_config_home() {
+        let env = Env(&[("HOME", "/home/u"), ("XDG_CONFIG_HOME", "/cfg")]);
+        assert_eq!(
+            deduce(&["--special-dirs"], &env).unwrap().path,
+            PathBuf::from("/cfg/user-dirs.dirs")
+        );
+    }
+
+    #[test]
+    fn nowhere() {
+        assert_eq!(deduce(&["--special-dirs"], &Env(&[])), None);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, OptionsError};
+use crate::output::stats::{Options, Statistic};
+use crate::output::table::SizeFormat;
+
+impl Options {
+    /// Determine which statistics to print after the listing, if any, with
+    /// sizes in the same format as the size column.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::STATS)? else {
+            return Ok(None);
+        };
+
+        let statistic = match word.to_str() {
+            Some("ext") => Statistic::Extensions,
+            _ => return Err(OptionsError::BadArgument(&flags::STATS, word.into())),
+        };
+
+        let size_format = SizeFormat::deduce(matches)?;
+        Ok(Some(Self {
+            statistic,
+            size_format,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::parser::Arg;
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::*;
+    use std::ffi::OsString;
+
+    static TEST_ARGS: &[&Arg] = &[&flags::STATS, &flags::BINARY, &flags::BYTES];
+
+    macro_rules! test {
+        ($name:ident: $inputs:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                for result in
+                    parse_for_test($inputs.as_ref(), TEST_ARGS, Both, |mf| Options::deduce(mf))
+                {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    test!(none:   []                             => Ok(None));
+    test!(ext:    ["--stats=ext"]                => Ok(Some(Options { statistic: Statistic::Extensions, size_format: SizeFormat::DecimalBytes })));
+    test!(binary: ["--stats", "ext", "--binary"] => Ok(Some(Options { statistic: Statistic::Extensions, size_format: SizeFormat::BinaryBytes })));
+    test!(bad:    ["--stats=dirs"]               => Err(OptionsError::BadArgument(&flags::STATS, OsString::from("dirs"))));
+}
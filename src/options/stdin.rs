@@ -1,18 +1,39 @@
 use crate::options::parser::MatchedFlags;
 use crate::options::vars::EZA_STDIN_SEPARATOR;
 use crate::options::{flags, OptionsError, Vars};
-use std::ffi::OsString;
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
 use std::io;
 use std::io::IsTerminal;
 
+/// Where the paths to list come from.
 #[derive(Debug, PartialEq)]
 pub enum FilesInput {
-    Stdin(OsString),
+    /// Only the arguments on the command line.
     Args,
+
+    /// The arguments on the command line, followed by the paths read from
+    /// standard input.
+    ArgsAndStdin {
+        /// What the paths from standard input are split up with.
+        separator: OsString,
+
+        /// Whether to say nothing about paths that don’t exist.
+        skip_missing: bool,
+    },
 }
 
 impl FilesInput {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        let skip_missing = matches.has(&flags::SKIP_MISSING)?;
+        if skip_missing && matches.is_strict() && !matches.has(&flags::STDIN)? {
+            return Err(OptionsError::Useless(
+                &flags::SKIP_MISSING,
+                false,
+                &flags::STDIN,
+            ));
+        }
+
         Ok(
             if io::stdin().is_terminal() || !matches.has(&flags::STDIN)? {
                 FilesInput::Args
@@ -20,10 +41,70 @@ impl FilesInput {
                 let separator = vars
                     .get(EZA_STDIN_SEPARATOR)
                     .unwrap_or(OsString::from("\n"));
-                FilesInput::Stdin(separator)
+                FilesInput::ArgsAndStdin {
+                    separator,
+                    skip_missing,
+                }
             } else {
                 FilesInput::Args
             },
         )
     }
+
+    /// Adds the paths read from standard input to the ones given as
+    /// arguments, leaving out empty paths and any path that’s already been
+    /// given, so nothing gets listed twice.
+    pub fn merge<'a>(&self, args: Vec<&'a OsStr>, input: &'a str) -> Vec<&'a OsStr> {
+        let Self::ArgsAndStdin { separator, .. } = self else {
+            return args;
+        };
+
+        let separator = separator.to_str().unwrap_or("\n");
+        let piped = input
+            .split(separator)
+            .filter(|s| !s.is_empty())
+            .map(OsStr::new);
+
+        let mut seen = HashSet::new();
+        args.into_iter()
+            .chain(piped)
+            .filter(|path| seen.insert(*path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stdin(separator: &str) -> FilesInput {
+        FilesInput::ArgsAndStdin {
+            separator: OsString::from(separator),
+            skip_missing: false,
+        }
+    }
+
+    #[test]
+    fn args_only() {
+        let args = vec![OsStr::new("a")];
+        assert_eq!(FilesInput::Args.merge(args, "b\n"), vec![OsStr::new("a")]);
+    }
+
+    #[test]
+    fn args_then_stdin() {
+        let args = vec![OsStr::new("a")];
+        assert_eq!(
+            stdin("\n").merge(args, "b\nc\n"),
+            vec![OsStr::new("a"), OsStr::new("b"), OsStr::new("c")]
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        let args = vec![OsStr::new("a"), OsStr::new("b")];
+        assert_eq!(
+            stdin(",").merge(args, "b,,a,c,c"),
+            vec![OsStr::new("a"), OsStr::new("b"), OsStr::new("c")]
+        );
+    }
 }
@@ -1,27 +1,61 @@
+use std::fs;
+use std::io;
+
+use crate::options::config;
 use crate::options::parser::MatchedFlags;
 use crate::options::{flags, vars, OptionsError, Vars};
+use crate::output::color_by::ColorBy;
 use crate::output::color_scale::ColorScaleOptions;
+use crate::theme::config_file::{ThemeFile, THEME_FILE};
 use crate::theme::{Definitions, Options, UseColours};
 
 impl Options {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let use_colours = UseColours::deduce(matches, vars)?;
         let colour_scale = ColorScaleOptions::deduce(matches, vars)?;
+        let color_by = ColorBy::deduce(matches)?;
 
+        // Icons come from the same places as colours, and are still shown
+        // when colours are turned off.
         let definitions = if use_colours == UseColours::Never {
-            Definitions::default()
+            Definitions {
+                theme_file: Definitions::deduce_theme_file(vars)?,
+                icons: Definitions::deduce_icons(vars),
+                ..Definitions::default()
+            }
         } else {
-            Definitions::deduce(vars)
+            Definitions::deduce(matches, vars)?
         };
 
         Ok(Self {
             use_colours,
             colour_scale,
+            color_by,
             definitions,
         })
     }
 }
 
+impl ColorBy {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) =
+            matches.get_where(|f| f.matches(&flags::COLOR_BY) || f.matches(&flags::COLOUR_BY))?
+        else {
+            return Ok(Self::Off);
+        };
+
+        if word == "owner" {
+            Ok(Self::Owner)
+        } else if word == "owner-names" {
+            Ok(Self::OwnerNames)
+        } else if word == "git-age" {
+            Ok(Self::GitAge)
+        } else {
+            Err(OptionsError::BadArgument(&flags::COLOR_BY, word.into()))
+        }
+    }
+}
+
 impl UseColours {
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let default_value = match vars.get(vars::NO_COLOR) {
@@ -48,14 +82,62 @@ impl UseColours {
 }
 
 impl Definitions {
-    fn deduce<V: Vars>(vars: &V) -> Self {
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let ls = vars
             .get(vars::LS_COLORS)
             .map(|e| e.to_string_lossy().to_string());
         let exa = vars
             .get_with_fallback(vars::EZA_COLORS, vars::EXA_COLORS)
             .map(|e| e.to_string_lossy().to_string());
-        Self { ls, exa }
+        let globs = Self::deduce_globs(matches)?;
+        let theme_file = Self::deduce_theme_file(vars)?;
+        let icons = Self::deduce_icons(vars);
+        Ok(Self {
+            theme_file,
+            ls,
+            exa,
+            icons,
+            globs,
+        })
+    }
+
+    /// The icon overrides from the `EZA_ICONS` variable, which get checked
+    /// when the theme is put together, as with the colour variables.
+    fn deduce_icons<V: Vars>(vars: &V) -> Option<String> {
+        vars.get(vars::EZA_ICONS)
+            .map(|e| e.to_string_lossy().to_string())
+    }
+
+    /// The colours from the `theme.yml` file in the configuration directory,
+    /// or nothing if there isn’t one.
+    fn deduce_theme_file<V: Vars>(vars: &V) -> Result<Option<ThemeFile>, OptionsError> {
+        let Some(path) = config::config_dir(vars).map(|dir| dir.join(THEME_FILE)) else {
+            return Ok(None);
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(OptionsError::BadConfig(path, 0, e.to_string())),
+        };
+
+        ThemeFile::parse(&contents)
+            .map(Some)
+            .map_err(|(line, msg)| OptionsError::BadConfig(path, line, msg))
+    }
+
+    /// The glob colour rules given with `--color-glob`, in order. Each one
+    /// has to be a glob and a style separated by an equals sign, in the
+    /// same form as in `LS_COLORS`.
+    fn deduce_globs(matches: &MatchedFlags<'_>) -> Result<Vec<String>, OptionsError> {
+        matches
+            .get_all_where(|f| f.matches(&flags::COLOR_GLOB) || f.matches(&flags::COLOUR_GLOB))
+            .into_iter()
+            .map(|rule| match rule.to_str() {
+                Some(rule) if rule.split(':').all(|r| r.contains('=')) => Ok(rule.to_string()),
+                _ => Err(OptionsError::BadArgument(&flags::COLOR_GLOB, rule.into())),
+            })
+            .collect()
     }
 }
 
@@ -74,6 +156,10 @@ mod terminal_test {
         &flags::COLOUR,
         &flags::COLOR_SCALE,
         &flags::COLOUR_SCALE,
+        &flags::COLOR_BY,
+        &flags::COLOUR_BY,
+        &flags::COLOR_GLOB,
+        &flags::COLOUR_GLOB,
     ];
 
     #[allow(unused_macro_rules)]
@@ -192,4 +278,17 @@ mod terminal_test {
     test!(overridden_6:  UseColours <- ["--color=auto",  "--colour=never"], MockVars::empty();  Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("colour")));
     test!(overridden_7:  UseColours <- ["--colour=auto", "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("colour"), Flag::Long("color")));
     test!(overridden_8:  UseColours <- ["--color=auto",  "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("color")));
+
+    // --color-glob can be repeated, in either spelling
+    test!(glob_none:     Definitions <- [], MockVars::empty();                                        Both => Ok(Definitions::default()));
+    test!(glob_one:      Definitions <- ["--color-glob=*.log=2;90"], MockVars::empty();               Both => Ok(Definitions { globs: vec!["*.log=2;90".into()], ..Definitions::default() }));
+    test!(glob_many:     Definitions <- ["--color-glob=*.log=31", "--colour-glob", "*.tmp=32"], MockVars::empty();  Both => Ok(Definitions { globs: vec!["*.log=31".into(), "*.tmp=32".into()], ..Definitions::default() }));
+    test!(glob_no_style: Definitions <- ["--color-glob=*.log"], MockVars::empty();                    Both => err OptionsError::BadArgument(&flags::COLOR_GLOB, OsString::from("*.log")));
+
+    // --color-by
+    test!(by_none:       ColorBy <- [];                                    Both => Ok(ColorBy::Off));
+    test!(by_owner:      ColorBy <- ["--color-by=owner"];                  Both => Ok(ColorBy::Owner));
+    test!(by_names:      ColorBy <- ["--colour-by", "owner-names"];        Both => Ok(ColorBy::OwnerNames));
+    test!(by_git_age:    ColorBy <- ["--color-by=git-age"];                Both => Ok(ColorBy::GitAge));
+    test!(by_bad:        ColorBy <- ["--color-by=group"];                  Both => err OptionsError::BadArgument(&flags::COLOR_BY, OsString::from("group")));
 }
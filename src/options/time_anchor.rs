@@ -0,0 +1,136 @@
+//! Parsing the moment given to `--relative-to`, which relative times and
+//! the age colour scale are measured from instead of the current time.
+
+use std::ffi::OsStr;
+use std::fs;
+
+use chrono::prelude::*;
+
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, OptionsError};
+use crate::output::time::TimeAnchor;
+
+/// The formats a local date and time can be given in, other than RFC 3339.
+static LOCAL_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+impl TimeAnchor {
+    /// Determines the moment to measure times from, if one was given.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        matches
+            .get(&flags::RELATIVE_TO)?
+            .map(Self::parse)
+            .transpose()
+    }
+
+    /// Parses either a date and time, which is taken to be in the local time
+    /// zone unless it says otherwise, or `@` followed by the path to a file
+    /// whose modification time to use.
+    fn parse(input: &OsStr) -> Result<Self, OptionsError> {
+        let bad = || OptionsError::BadArgument(&flags::RELATIVE_TO, input.into());
+
+        if let Some(path) = input.to_str().and_then(|s| s.strip_prefix('@')) {
+            return fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .map(|modified| Self(DateTime::<Utc>::from(modified).naive_utc()))
+                .map_err(|e| {
+                    OptionsError::Unsupported(format!(
+                        "Option {} could not read the modification time of {path:?}: {e}",
+                        flags::RELATIVE_TO
+                    ))
+                });
+        }
+
+        let text = input.to_str().ok_or_else(bad)?.trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return Ok(Self(time.naive_utc()));
+        }
+
+        let local = LOCAL_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+            .ok_or_else(bad)?;
+
+        // A time skipped by the clocks going forward doesn’t exist, so it
+        // isn’t a moment that can be measured from.
+        let time = Local
+            .from_local_datetime(&local)
+            .earliest()
+            .ok_or_else(bad)?;
+        Ok(Self(time.naive_utc()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(input: &str) -> Result<TimeAnchor, OptionsError> {
+        TimeAnchor::parse(OsStr::new(input))
+    }
+
+    fn local(date: &str) -> NaiveDateTime {
+        let naive = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .unwrap()
+            .naive_utc()
+    }
+
+    #[test]
+    fn rfc_3339() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|d| d.and_hms_opt(10, 0, 0))
+            .unwrap();
+        assert_eq!(parse("2024-01-01T12:00:00+02:00"), Ok(TimeAnchor(expected)));
+    }
+
+    #[test]
+    fn local_date() {
+        assert_eq!(
+            parse("2024-01-01"),
+            Ok(TimeAnchor(local("2024-01-01 00:00:00")))
+        );
+    }
+
+    #[test]
+    fn local_time() {
+        assert_eq!(
+            parse("2024-01-01 09:30"),
+            Ok(TimeAnchor(local("2024-01-01 09:30:00")))
+        );
+        assert_eq!(
+            parse("2024-01-01T09:30:15"),
+            Ok(TimeAnchor(local("2024-01-01 09:30:15")))
+        );
+    }
+
+    #[test]
+    fn garbage() {
+        assert_eq!(
+            parse("yesterday"),
+            Err(OptionsError::BadArgument(
+                &flags::RELATIVE_TO,
+                "yesterday".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(matches!(
+            parse("@/this/does/not/exist"),
+            Err(OptionsError::Unsupported(_))
+        ));
+    }
+}
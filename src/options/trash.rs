@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use crate::fs::feature::trash::Trash;
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, OptionsError, Vars};
+
+impl Trash {
+    /// Find the trash directory, if the trash is going to be read at all:
+    /// `Trash` in the XDG data directory, which defaults to
+    /// `~/.local/share`.
+    pub fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<Option<Self>, OptionsError> {
+        if !matches.has(&flags::SHOW_TRASH)? && !matches.has(&flags::TRASH)? {
+            return Ok(None);
+        }
+
+        let non_empty = |name| vars.get(name).filter(|v| !v.is_empty());
+        let data_dir = non_empty(vars::XDG_DATA_HOME)
+            .map(PathBuf::from)
+            .or_else(|| non_empty(vars::HOME).map(|home| PathBuf::from(home).join(".local/share")));
+
+        Ok(data_dir.map(|dir| Self {
+            dir: dir.join("Trash"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::config::test::Env;
+    use crate::options::parser::Arg;
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::Both;
+
+    fn deduce(args: &[&str], env: &Env) -> Option<Trash> {
+        static TEST_ARGS: &[&Arg] = &[&flags::SHOW_TRASH, &flags::TRASH];
+        let mut results = parse_for_test(args, TEST_ARGS, Both, |mf| Trash::deduce(mf, env));
+        results.pop().unwrap().unwrap()
+    }
+
+    #[test]
+    fn not_asked_for() {
+        assert_eq!(deduce(&[], &Env(&[("HOME", "/home/u")])), None);
+    }
+
+    #[test]
+    fn home() {
+        let trash = deduce(&["--show-trash"], &Env(&[("HOME", "/home/u")]));
+        assert_eq!(
+            trash.unwrap().dir,
+            PathBuf::from("/home/u/.local/share/Trash")
+        );
+    }
+
+    #[test]
+    fn xdg_data_home() {
+        let env = Env(&[("HOME", "/home/u"), ("XDG_DATA_HOME", "/data")]);
+        assert_eq!(
+            deduce(&["--trash"], &env).unwrap().dir,
+            PathBuf::from("/data/Trash")
+        );
+    }
+
+    #[test]
+    fn nowhere() {
+        assert_eq!(deduce(&["--trash"], &Env(&[])), None);
+    }
+}
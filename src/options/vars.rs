@@ -11,6 +11,13 @@ pub static LS_COLORS: &str = "LS_COLORS";
 /// characters.
 pub static COLUMNS: &str = "COLUMNS";
 
+/// Environment variable used to override the height of the terminal, in
+/// lines.
+pub static LINES: &str = "LINES";
+
+/// Environment variable used to pick the program to page output through.
+pub static PAGER: &str = "PAGER";
+
 /// Environment variable used to datetime format.
 pub static TIME_STYLE: &str = "TIME_STYLE";
 
@@ -35,10 +42,15 @@ pub static EXA_STRICT: &str = "EXA_STRICT";
 pub static EZA_STRICT: &str = "EZA_STRICT";
 
 /// Environment variable used to make exa print out debugging information as
-/// it runs. Any non-empty value will turn debug mode on.
+/// it runs. Any non-empty value will turn debug mode on, and a list of
+/// levels and `module=level` directives picks what gets logged.
 pub static EXA_DEBUG: &str = "EXA_DEBUG";
 pub static EZA_DEBUG: &str = "EZA_DEBUG";
 
+/// Environment variable used to write the debugging information as JSON
+/// lines to a file, rather than to standard error.
+pub static EZA_DEBUG_FILE: &str = "EZA_DEBUG_FILE";
+
 /// Environment variable used to limit the grid-details view
 /// (`--grid --long`) so it’s only activated if there’s at least the given
 /// number of rows of output.
@@ -64,6 +76,10 @@ pub static EZA_MIN_LUMINANCE: &str = "EZA_MIN_LUMINANCE";
 /// Any explicit use of `--icons=WHEN` overrides this behavior.
 pub static EZA_ICONS_AUTO: &str = "EZA_ICONS_AUTO";
 
+/// Environment variable used to override the icons shown for files whose
+/// names match a glob, or for each kind of file, such as `*.rs=λ:di=\uf115`.
+pub static EZA_ICONS: &str = "EZA_ICONS";
+
 pub static EZA_STDIN_SEPARATOR: &str = "EZA_STDIN_SEPARATOR";
 
 /// Environment variable used to choose how windows attributes are displayed.
@@ -71,6 +87,61 @@ pub static EZA_STDIN_SEPARATOR: &str = "EZA_STDIN_SEPARATOR";
 /// display a comma separated list of descriptions.
 pub static EZA_WINDOWS_ATTRIBUTES: &str = "EZA_WINDOWS_ATTRIBUTES";
 
+/// Environment variable used to choose how the flags column is displayed on
+/// every platform, taking precedence over `EZA_WINDOWS_ATTRIBUTES`. Short
+/// will display a single character for each flag, long their names.
+pub static EZA_FILE_FLAGS: &str = "EZA_FILE_FLAGS";
+
+/// Environment variable used to pick the separator between the major and
+/// minor numbers of device files, which is a comma by default.
+pub static EZA_DEVICE_SEPARATOR: &str = "EZA_DEVICE_SEPARATOR";
+
+/// Environment variable used to choose how `--notify` gets the user’s
+/// attention: `bell` rings the terminal bell, and `osc9` sends a desktop
+/// notification in terminals that support them.
+pub static EZA_NOTIFY_STYLE: &str = "EZA_NOTIFY_STYLE";
+
+/// Environment variable used to pick the program `--paginate` pages output
+/// through, taking precedence over `PAGER`.
+pub static EZA_PAGER: &str = "EZA_PAGER";
+
+/// Environment variable used to pick the directory eza reads its
+/// configuration files from, taking precedence over the XDG location.
+pub static EZA_CONFIG_DIR: &str = "EZA_CONFIG_DIR";
+
+/// Environment variable used to find the user’s configuration directory, as
+/// per the XDG Base Directory specification.
+pub static XDG_CONFIG_HOME: &str = "XDG_CONFIG_HOME";
+
+/// Environment variable used to find the user’s data directory, which the
+/// trash is in, as per the XDG Base Directory specification.
+pub static XDG_DATA_HOME: &str = "XDG_DATA_HOME";
+
+/// Environment variable used to find the user’s cache directory, which
+/// `--cache` keeps its cache in, as per the XDG Base Directory specification.
+pub static XDG_CACHE_HOME: &str = "XDG_CACHE_HOME";
+
+/// Environment variable used to choose the directory that `--cache` keeps
+/// the Git statuses of each repository in, instead of the cache directory.
+pub static EZA_GIT_CACHE: &str = "EZA_GIT_CACHE";
+
+/// Environment variable that terminals set to `truecolor` or `24bit` when
+/// they can draw RGB colours.
+pub static COLORTERM: &str = "COLORTERM";
+
+/// Environment variable holding the name of the terminal type, which is
+/// used to spot terminals that can only draw sixteen colours.
+pub static TERM: &str = "TERM";
+
+/// Environment variable used to turn off things the terminal can’t draw,
+/// as a comma-separated list of `no-unicode`, `no-truecolor`,
+/// `no-256color`, and `no-hyperlinks`.
+pub static EZA_TERM_CAPS: &str = "EZA_TERM_CAPS";
+
+/// Environment variable used to find the user’s home directory, when
+/// neither of the configuration directory variables are set.
+pub static HOME: &str = "HOME";
+
 /// Mockable wrapper for `std::env::var_os`.
 pub trait Vars {
     fn get(&self, name: &'static str) -> Option<OsString>;
@@ -1,16 +1,22 @@
 use std::ffi::OsString;
 
+use crate::fs::compare::DiffAgainst;
+use crate::fs::feature::version_info::VersionKind;
 use crate::fs::feature::xattr;
 use crate::options::parser::MatchedFlags;
 use crate::options::{flags, vars, NumberSource, OptionsError, Vars};
-use crate::output::color_scale::{ColorScaleMode, ColorScaleOptions};
-use crate::output::file_name::Options as FileStyle;
+use crate::output::color_scale::{ColorScaleMode, ColorScaleOptions, DEFAULT_MIN_LUMINANCE};
+use crate::output::dupes::DupeCheck;
+use crate::output::file_name::{HyperlinkMode, Options as FileStyle};
 use crate::output::grid_details::{self, RowThreshold};
+use crate::output::render::DEFAULT_DEVICE_SEPARATOR;
 use crate::output::table::{
-    Columns, FlagsFormat, GroupFormat, Options as TableOptions, SizeFormat, TimeTypes, UserFormat,
+    Alignment, Alignments, Column, Columns, ContextFormat, FlagsFormat, GroupFormat, LinksFormat,
+    Options as TableOptions, SizeFormat, TimeTypes, UserFormat, VirtualFiles, ALIGNABLE_COLUMNS,
+    DEFAULT_PREVIEW_LENGTH, DEFAULT_UMASK,
 };
-use crate::output::time::TimeFormat;
-use crate::output::{details, grid, Mode, TerminalWidth, View};
+use crate::output::time::{TimeAnchor, TimeFormat};
+use crate::output::{delimited, details, grid, lines, Mode, TerminalHeight, TerminalWidth, View};
 
 impl View {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
@@ -18,10 +24,12 @@ impl View {
         let deref_links = matches.has(&flags::DEREF_LINKS)?;
         let total_size = matches.has(&flags::TOTAL_SIZE)?;
         let width = TerminalWidth::deduce(matches, vars)?;
+        let height = TerminalHeight::deduce(matches, vars)?;
         let file_style = FileStyle::deduce(matches, vars, width.actual_terminal_width().is_some())?;
         Ok(Self {
             mode,
             width,
+            height,
             file_style,
             deref_links,
             total_size,
@@ -39,6 +47,25 @@ impl Mode {
     /// This is complicated a little by the fact that `--grid` and `--tree`
     /// can also combine with `--long`, so care has to be taken to use the
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        // JSON output includes every detail, so it overrides the other views
+        // rather than combining with them, apart from --tree.
+        if matches.has(&flags::JSON)? {
+            return Ok(Self::Json);
+        }
+
+        // So does CSV and TSV output, which prints plain text fields.
+        if let Some(delimited) = delimited::Options::deduce(matches)? {
+            return Ok(Self::Delimited(delimited));
+        }
+
+        // fzf reads one name per entry, so its output is always a list.
+        if let Some(output) = matches.get(&flags::OUTPUT)? {
+            if output == "fzf" {
+                return Ok(Self::Lines(lines::Options { fzf: true }));
+            }
+            return Err(OptionsError::BadArgument(&flags::OUTPUT, output.into()));
+        }
+
         let flag = matches.has_where_any(|f| {
             f.matches(&flags::LONG)
                 || f.matches(&flags::ONE_LINE)
@@ -88,7 +115,7 @@ impl Mode {
 
         if flag.matches(&flags::ONE_LINE) {
             let _ = matches.has(&flags::ONE_LINE)?;
-            return Ok(Self::Lines);
+            return Ok(Self::Lines(lines::Options::default()));
         }
 
         let grid = grid::Options::deduce(matches)?;
@@ -103,19 +130,58 @@ impl Mode {
                 &flags::BINARY,
                 &flags::BYTES,
                 &flags::INODE,
-                &flags::LINKS,
                 &flags::HEADER,
                 &flags::BLOCKSIZE,
                 &flags::TIME,
                 &flags::GROUP,
                 &flags::NUMERIC,
                 &flags::MOUNTS,
+                &flags::SOCKET_INFO,
+                &flags::OVERLAY,
+                &flags::AGE_RANK,
+                &flags::TEXT_SIZE,
+                &flags::ENCODING,
+                &flags::NAME_FIRST,
             ] {
                 if matches.has(option)? {
                     return Err(OptionsError::Useless(option, false, &flags::LONG));
                 }
             }
 
+            if matches.get(&flags::LINKS)?.is_some() {
+                return Err(OptionsError::Useless(&flags::LINKS, false, &flags::LONG));
+            }
+
+            if matches.get(&flags::PREVIEW)?.is_some() {
+                return Err(OptionsError::Useless(&flags::PREVIEW, false, &flags::LONG));
+            }
+
+            for option in &[
+                &flags::FIND_DUPES,
+                &flags::FILE_VERSION,
+                &flags::SIMULATE_UMASK,
+                &flags::DIFF_AGAINST,
+                &flags::MANIFEST_CHECK,
+            ] {
+                if matches.get(option)?.is_some() {
+                    return Err(OptionsError::Useless(option, false, &flags::LONG));
+                }
+            }
+
+            for option in &[&flags::ALIGN, &flags::FIELDS] {
+                if matches.get(option)?.is_some() {
+                    return Err(OptionsError::Useless(option, false, &flags::LONG));
+                }
+            }
+
+            if matches.get(&flags::VIRTUAL_FILES)?.is_some() {
+                return Err(OptionsError::Useless(
+                    &flags::VIRTUAL_FILES,
+                    false,
+                    &flags::LONG,
+                ));
+            }
+
             if matches.has(&flags::GIT)? && !matches.has(&flags::NO_GIT)? {
                 return Err(OptionsError::Useless(&flags::GIT, false, &flags::LONG));
             } else if matches.has(&flags::LEVEL)?
@@ -138,12 +204,30 @@ impl grid::Options {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let grid = grid::Options {
             across: matches.has(&flags::ACROSS)?,
+            layout: grid::Layout::deduce(matches)?,
         };
 
         Ok(grid)
     }
 }
 
+impl grid::Layout {
+    /// Determine how to work out the widths of the grid’s columns, which
+    /// depends on how many files there are unless `--grid-layout` says
+    /// otherwise.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get(&flags::GRID_LAYOUT)? {
+            Some(word) => match word.to_str() {
+                Some("auto") => Ok(Self::Auto),
+                Some("exact") => Ok(Self::Exact),
+                Some("fast") => Ok(Self::Fast),
+                _ => Err(OptionsError::BadArgument(&flags::GRID_LAYOUT, word.into())),
+            },
+            None => Ok(Self::Auto),
+        }
+    }
+}
+
 impl details::Options {
     fn deduce_tree<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let details = details::Options {
@@ -152,6 +236,7 @@ impl details::Options {
             xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
             secattr: xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?,
             mounts: matches.has(&flags::MOUNTS)?,
+            tree_summary: matches.has(&flags::TREE_SUMMARY)?,
             color_scale: ColorScaleOptions::deduce(matches, vars)?,
         };
 
@@ -173,6 +258,7 @@ impl details::Options {
             xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
             secattr: xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?,
             mounts: matches.has(&flags::MOUNTS)?,
+            tree_summary: matches.has(&flags::TREE_SUMMARY)?,
             color_scale: ColorScaleOptions::deduce(matches, vars)?,
         })
     }
@@ -209,6 +295,32 @@ impl TerminalWidth {
     }
 }
 
+impl TerminalHeight {
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if let Some(height) = matches.get(&flags::HEIGHT)? {
+            let arg_str = height.to_string_lossy();
+            match arg_str.parse() {
+                Ok(0) => Ok(Self::Automatic),
+                Ok(h) => Ok(Self::Set(h)),
+                Err(e) => {
+                    let source = NumberSource::Arg(&flags::HEIGHT);
+                    Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+                }
+            }
+        } else if let Some(lines) = vars.get(vars::LINES).and_then(|s| s.into_string().ok()) {
+            match lines.parse() {
+                Ok(height) => Ok(Self::Set(height)),
+                Err(e) => {
+                    let source = NumberSource::Env(vars::LINES);
+                    Err(OptionsError::FailedParse(lines, source, e))
+                }
+            }
+        } else {
+            Ok(Self::Automatic)
+        }
+    }
+}
+
 impl RowThreshold {
     fn deduce<V: Vars>(vars: &V) -> Result<Self, OptionsError> {
         if let Some(columns) = vars
@@ -238,41 +350,196 @@ impl TableOptions {
         let user_format = UserFormat::deduce(matches)?;
         let group_format = GroupFormat::deduce(matches)?;
         let flags_format = FlagsFormat::deduce(vars);
-        let columns = Columns::deduce(matches, vars)?;
+        let links_format = LinksFormat::deduce(matches)?;
+        let context_format = ContextFormat::deduce(matches)?;
+        let virtual_files = VirtualFiles::deduce(matches)?;
+        let device_separator = vars.get(vars::EZA_DEVICE_SEPARATOR).map_or_else(
+            || DEFAULT_DEVICE_SEPARATOR.into(),
+            |s| s.to_string_lossy().into(),
+        );
+        let alignments = Alignments::deduce(matches)?;
+        let fields = Fields::deduce(matches)?;
+        let columns = Columns::deduce(matches, vars, fields.as_ref())?;
+        let name_first =
+            matches.has(&flags::NAME_FIRST)? || fields.as_ref().is_some_and(|f| f.name_first);
+        let diff_against = DiffAgainst::deduce(matches)?;
+        let hyperlink_cells = HyperlinkMode::deduce(matches)?.links_cells();
         Ok(Self {
             size_format,
             time_format,
             user_format,
             group_format,
             flags_format,
+            links_format,
+            context_format,
+            virtual_files,
+            device_separator,
+            alignments,
             columns,
+            fields: fields.map(|f| f.columns),
+            name_first,
+            diff_against,
+            hyperlink_cells,
         })
     }
 }
 
+impl delimited::Options {
+    /// Determine the format from the `--format` argument, and which fields
+    /// to print from `--fields`, which defaults to all of them. Fields can
+    /// be given in any order, but only once each.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::FORMAT)? else {
+            return Ok(None);
+        };
+
+        let format = match word.to_str() {
+            Some("csv") => delimited::Format::Csv,
+            Some("tsv") => delimited::Format::Tsv,
+            _ => return Err(OptionsError::BadArgument(&flags::FORMAT, word.into())),
+        };
+
+        let Some(value) = matches.get(&flags::FIELDS)? else {
+            return Ok(Some(Self {
+                format,
+                fields: delimited::ALL_FIELDS.to_vec(),
+            }));
+        };
+
+        let bad_argument = || OptionsError::BadArgument(&flags::FIELDS, value.into());
+        let mut fields = Vec::new();
+        for name in value.to_str().ok_or_else(bad_argument)?.split(',') {
+            let field = delimited::Field::named(name).ok_or_else(bad_argument)?;
+            if fields.contains(&field) {
+                return Err(bad_argument());
+            }
+            fields.push(field);
+        }
+
+        Ok(Some(Self { format, fields }))
+    }
+}
+
+/// The columns picked with `--fields`, in order, and whether the file name
+/// was picked to come before them rather than after.
+#[derive(PartialEq, Eq, Debug)]
+struct Fields {
+    columns: Vec<Column>,
+    name_first: bool,
+}
+
+impl Fields {
+    /// Determine which columns to display, and in which order, from the
+    /// `--fields` argument’s value: a comma-separated list of column names,
+    /// which can include `name` as long as it comes first or last.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(value) = matches.get(&flags::FIELDS)? else {
+            return Ok(None);
+        };
+
+        let bad_argument = || OptionsError::BadArgument(&flags::FIELDS, value.into());
+        let input = value.to_str().ok_or_else(bad_argument)?;
+        let names = input.split(',').collect::<Vec<_>>();
+
+        let mut columns = Vec::new();
+        let mut name_first = false;
+        for (index, name) in names.iter().enumerate() {
+            if *name == "name" {
+                if index == 0 {
+                    name_first = true;
+                } else if index != names.len() - 1 {
+                    return Err(bad_argument());
+                }
+                continue;
+            }
+
+            let column = Column::named(name).ok_or_else(bad_argument)?;
+            if columns.contains(&column) {
+                return Err(bad_argument());
+            }
+            columns.push(column);
+        }
+
+        Ok(Some(Self {
+            columns,
+            name_first,
+        }))
+    }
+}
+
 impl Columns {
-    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        let time_types = TimeTypes::deduce(matches)?;
+    fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+        fields: Option<&Fields>,
+    ) -> Result<Self, OptionsError> {
+        let compact_time_headers = matches.get(&flags::TIME)?.is_some_and(|word| word == "all");
 
         let no_git_env = vars
             .get_with_fallback(vars::EXA_OVERRIDE_GIT, vars::EZA_OVERRIDE_GIT)
             .is_some();
+        let git_enabled = cfg!(feature = "git") && !matches.has(&flags::NO_GIT)? && !no_git_env;
+
+        // Columns picked with --fields replace the ones the other flags pick.
+        if let Some(fields) = fields {
+            let picked = |name| fields.columns.iter().any(|c| c.name() == name);
+            return Ok(Self {
+                time_types: TimeTypes {
+                    modified: picked("modified"),
+                    changed: picked("changed"),
+                    accessed: picked("accessed"),
+                    created: picked("created"),
+                },
+                compact_time_headers,
+                inode: picked("inode"),
+                links: picked("links"),
+                blocksize: picked("blocksize"),
+                group: picked("group"),
+                git: git_enabled && picked("git"),
+                subdir_git_repos: git_enabled && picked("repo"),
+                subdir_git_repos_no_stat: false,
+                octal: picked("octal"),
+                security_context: xattr::ENABLED && picked("context"),
+                file_flags: picked("flags"),
+                socket_info: picked("socket"),
+                overlay_layer: picked("layer"),
+                age_rank: picked("rank"),
+                text_size: picked("text"),
+                encoding: picked("encoding"),
+                preview: picked("preview").then_some(DEFAULT_PREVIEW_LENGTH),
+                root: picked("root"),
+                dupes: picked("dupe").then_some(DupeCheck::Contents),
+                file_version: picked("version").then_some(VersionKind::File),
+                simulate_umask: picked("new").then_some(DEFAULT_UMASK),
+                permissions: picked("permissions"),
+                filesize: picked("size"),
+                user: picked("user"),
+            });
+        }
 
-        let git = matches.has(&flags::GIT)? && !matches.has(&flags::NO_GIT)? && !no_git_env;
-        let subdir_git_repos =
-            matches.has(&flags::GIT_REPOS)? && !matches.has(&flags::NO_GIT)? && !no_git_env;
-        let subdir_git_repos_no_stat = !subdir_git_repos
-            && matches.has(&flags::GIT_REPOS_NO_STAT)?
-            && !matches.has(&flags::NO_GIT)?
-            && !no_git_env;
+        let time_types = TimeTypes::deduce(matches)?;
+        let git = git_enabled && matches.has(&flags::GIT)?;
+        let subdir_git_repos = git_enabled && matches.has(&flags::GIT_REPOS)?;
+        let subdir_git_repos_no_stat =
+            git_enabled && !subdir_git_repos && matches.has(&flags::GIT_REPOS_NO_STAT)?;
 
         let blocksize = matches.has(&flags::BLOCKSIZE)?;
         let group = matches.has(&flags::GROUP)?;
         let inode = matches.has(&flags::INODE)?;
-        let links = matches.has(&flags::LINKS)?;
+        let links = matches.get(&flags::LINKS)?.is_some();
         let octal = matches.has(&flags::OCTAL)?;
         let security_context = xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?;
         let file_flags = matches.has(&flags::FILE_FLAGS)?;
+        let socket_info = matches.has(&flags::SOCKET_INFO)?;
+        let overlay_layer = matches.has(&flags::OVERLAY)?;
+        let age_rank = matches.has(&flags::AGE_RANK)?;
+        let text_size = matches.has(&flags::TEXT_SIZE)?;
+        let encoding = matches.has(&flags::ENCODING)?;
+        let preview = Self::deduce_preview(matches)?;
+        let root = matches.has(&flags::MERGE)?;
+        let dupes = Self::deduce_dupes(matches)?;
+        let file_version = Self::deduce_file_version(matches)?;
+        let simulate_umask = Self::deduce_umask(matches)?;
 
         let permissions = !matches.has(&flags::NO_PERMISSIONS)?;
         let filesize = !matches.has(&flags::NO_FILESIZE)?;
@@ -280,6 +547,7 @@ impl Columns {
 
         Ok(Self {
             time_types,
+            compact_time_headers,
             inode,
             links,
             blocksize,
@@ -290,11 +558,88 @@ impl Columns {
             octal,
             security_context,
             file_flags,
+            socket_info,
+            overlay_layer,
+            age_rank,
+            text_size,
+            encoding,
+            preview,
+            root,
+            dupes,
+            file_version,
+            simulate_umask,
             permissions,
             filesize,
             user,
         })
     }
+
+    /// How many characters of each text file to preview, which has to be a
+    /// number above zero.
+    fn deduce_preview(matches: &MatchedFlags<'_>) -> Result<Option<usize>, OptionsError> {
+        let Some(word) = matches.get(&flags::PREVIEW)? else {
+            return Ok(None);
+        };
+
+        match word.to_str().and_then(|w| w.parse().ok()) {
+            Some(length) if length > 0 => Ok(Some(length)),
+            _ => Err(OptionsError::BadArgument(&flags::PREVIEW, word.into())),
+        }
+    }
+
+    /// The umask to show the permissions of new files with, which has to
+    /// be up to four octal digits, with nothing above the permission bits.
+    fn deduce_umask(matches: &MatchedFlags<'_>) -> Result<Option<u32>, OptionsError> {
+        let Some(word) = matches.get(&flags::SIMULATE_UMASK)? else {
+            return Ok(None);
+        };
+
+        let umask = word
+            .to_str()
+            .filter(|w| (1..=4).contains(&w.len()))
+            .and_then(|w| u32::from_str_radix(w, 8).ok());
+        match umask {
+            Some(umask) if umask <= 0o777 => Ok(Some(umask)),
+            _ => Err(OptionsError::BadArgument(
+                &flags::SIMULATE_UMASK,
+                word.into(),
+            )),
+        }
+    }
+
+    /// Whether to look for duplicate files, and whether to go by their
+    /// sizes alone or to hash their contents as well.
+    fn deduce_dupes(matches: &MatchedFlags<'_>) -> Result<Option<DupeCheck>, OptionsError> {
+        let Some(word) = matches.get(&flags::FIND_DUPES)? else {
+            return Ok(None);
+        };
+
+        if word == "size" {
+            Ok(Some(DupeCheck::Size))
+        } else if word == "content" {
+            Ok(Some(DupeCheck::Contents))
+        } else {
+            Err(OptionsError::BadArgument(&flags::FIND_DUPES, word.into()))
+        }
+    }
+
+    /// Whether to show the versions of executables and DLLs, and whether to
+    /// show the version of the file or of the product it comes with.
+    fn deduce_file_version(
+        matches: &MatchedFlags<'_>,
+    ) -> Result<Option<VersionKind>, OptionsError> {
+        let Some(word) = matches.get(&flags::FILE_VERSION)? else {
+            return Ok(None);
+        };
+
+        if word == "file" {
+            Ok(Some(VersionKind::File))
+        } else if word == "product" {
+            Ok(Some(VersionKind::Product))
+        } else {
+            Err(OptionsError::BadArgument(&flags::FILE_VERSION, word.into()))
+        }
+    }
 }
 
 impl SizeFormat {
@@ -306,7 +651,7 @@ impl SizeFormat {
     /// strings of digits in your head. Changing the format to anything else
     /// involves the `--binary` or `--bytes` flags, and these conflict with
     /// each other.
-    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+    pub(super) fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let flag = matches.has_where(|f| f.matches(&flags::BINARY) || f.matches(&flags::BYTES))?;
 
         Ok(match flag {
@@ -317,6 +662,91 @@ impl SizeFormat {
     }
 }
 
+impl LinksFormat {
+    /// Determine when to show the hard links column. An `--links=auto` only
+    /// shows the count for files with more than one link, and leaves out the
+    /// column entirely when there aren’t any.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get(&flags::LINKS)? {
+            Some(word) => match word.to_str() {
+                Some("always") => Ok(Self::Always),
+                Some("auto") => Ok(Self::Auto),
+                _ => Err(OptionsError::BadArgument(&flags::LINKS, word.into())),
+            },
+            None => Ok(Self::Always),
+        }
+    }
+}
+
+impl ContextFormat {
+    /// Determine how much of each file’s security context to show. An
+    /// `--context=smart` leaves out the parts most files have in common.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get(&flags::SECURITY_CONTEXT)? {
+            Some(word) => match word.to_str() {
+                Some("full") => Ok(Self::Full),
+                Some("smart") => Ok(Self::Smart),
+                _ => Err(OptionsError::BadArgument(
+                    &flags::SECURITY_CONTEXT,
+                    word.into(),
+                )),
+            },
+            None => Ok(Self::Full),
+        }
+    }
+}
+
+impl VirtualFiles {
+    /// Determine how to show the sizes of files on virtual filesystems,
+    /// which are shown like any other file unless `--virtual-files` says
+    /// otherwise.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get(&flags::VIRTUAL_FILES)? {
+            Some(word) => match word.to_str() {
+                Some("show") => Ok(Self::Show),
+                Some("mark") => Ok(Self::Mark),
+                Some("read") => Ok(Self::Read),
+                _ => Err(OptionsError::BadArgument(
+                    &flags::VIRTUAL_FILES,
+                    word.into(),
+                )),
+            },
+            None => Ok(Self::Show),
+        }
+    }
+}
+
+impl Alignments {
+    /// Determine which columns should be aligned differently from usual,
+    /// from the `--align` argument’s value: a comma-separated list of
+    /// `column:left` or `column:right` pairs.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(value) = matches.get(&flags::ALIGN)? else {
+            return Ok(Self::default());
+        };
+
+        let bad_argument = || OptionsError::BadArgument(&flags::ALIGN, value.into());
+        let input = value.to_str().ok_or_else(bad_argument)?;
+
+        let mut alignments = Vec::new();
+        for pair in input.split(',') {
+            let (column, alignment) = pair.split_once(':').ok_or_else(bad_argument)?;
+            let column = ALIGNABLE_COLUMNS
+                .iter()
+                .find(|c| **c == column)
+                .ok_or_else(bad_argument)?;
+            let alignment = match alignment {
+                "left" => Alignment::Left,
+                "right" => Alignment::Right,
+                _ => return Err(bad_argument()),
+            };
+            alignments.push((*column, alignment));
+        }
+
+        Ok(Self(alignments))
+    }
+}
+
 impl TimeFormat {
     /// Determine how time should be formatted in timestamp columns.
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
@@ -331,7 +761,7 @@ impl TimeFormat {
 
         match word.to_string_lossy().as_ref() {
             "default" => Ok(Self::DefaultFormat),
-            "relative" => Ok(Self::Relative),
+            "relative" => Ok(Self::Relative(TimeAnchor::deduce(matches)?)),
             "iso" => Ok(Self::ISOFormat),
             "long-iso" => Ok(Self::LongISO),
             "full-iso" => Ok(Self::FullISO),
@@ -398,7 +828,7 @@ impl TimeTypes {
     /// It’s valid to show more than one column by passing in more than one
     /// option, but passing *no* options means that the user just wants to
     /// see the default set.
-    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+    pub(super) fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let possible_word = matches.get(&flags::TIME)?;
         let modified = matches.has(&flags::MODIFIED)?;
         let changed = matches.has(&flags::CHANGED)?;
@@ -432,6 +862,8 @@ impl TimeTypes {
                 Self { modified: false, changed: false, accessed: true,  created: false }
             } else if word == "cr" || word == "created" {
                 Self { modified: false, changed: false, accessed: false, created: true  }
+            } else if word == "all" {
+                Self { modified: true,  changed: true,  accessed: true,  created: true  }
             } else {
                 return Err(OptionsError::BadArgument(&flags::TIME, word.into()));
             }
@@ -456,9 +888,9 @@ impl ColorScaleOptions {
             match vars.get_with_fallback(vars::EZA_MIN_LUMINANCE, vars::EXA_MIN_LUMINANCE) {
                 Some(var) => match var.to_string_lossy().parse() {
                     Ok(luminance) if (-100..=100).contains(&luminance) => luminance,
-                    _ => 40,
+                    _ => DEFAULT_MIN_LUMINANCE,
                 },
-                None => 40,
+                None => DEFAULT_MIN_LUMINANCE,
             };
 
         let mode = if let Some(w) = matches
@@ -482,6 +914,7 @@ impl ColorScaleOptions {
             min_luminance,
             size: false,
             age: false,
+            anchor: TimeAnchor::deduce(matches)?,
         };
 
         let words = if let Some(w) = matches
@@ -526,6 +959,7 @@ mod test {
         &flags::BINARY,
         &flags::BYTES,
         &flags::TIME_STYLE,
+        &flags::RELATIVE_TO,
         &flags::TIME,
         &flags::MODIFIED,
         &flags::CHANGED,
@@ -536,6 +970,11 @@ mod test {
         &flags::INODE,
         &flags::GIT,
         &flags::LINKS,
+        &flags::SECURITY_CONTEXT,
+        &flags::VIRTUAL_FILES,
+        &flags::ALIGN,
+        &flags::FIELDS,
+        &flags::GRID_LAYOUT,
         &flags::BLOCKSIZE,
         &flags::LONG,
         &flags::LEVEL,
@@ -544,6 +983,23 @@ mod test {
         &flags::ONE_LINE,
         &flags::TREE,
         &flags::NUMERIC,
+        &flags::JSON,
+        &flags::FORMAT,
+        &flags::OUTPUT,
+        &flags::HEIGHT,
+        &flags::OVERLAY,
+        &flags::AGE_RANK,
+        &flags::TEXT_SIZE,
+        &flags::ENCODING,
+        &flags::PREVIEW,
+        &flags::FIND_DUPES,
+        &flags::FILE_VERSION,
+        &flags::SIMULATE_UMASK,
+        &flags::NAME_FIRST,
+        &flags::DIFF_AGAINST,
+        &flags::DIFF_BY,
+        &flags::MERGE,
+        &flags::MANIFEST_CHECK,
     ];
 
     #[allow(unused_macro_rules)]
@@ -642,6 +1098,80 @@ mod test {
         test!(both_8:  SizeFormat <- ["--bytes",  "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("bytes")));
     }
 
+    mod links_formats {
+        use super::*;
+
+        test!(empty:   LinksFormat <- [];                     Both => Ok(LinksFormat::Always));
+        test!(plain:   LinksFormat <- ["--links"];            Both => Ok(LinksFormat::Always));
+        test!(short:   LinksFormat <- ["-H"];                 Both => Ok(LinksFormat::Always));
+        test!(auto:    LinksFormat <- ["--links=auto"];       Both => Ok(LinksFormat::Auto));
+        test!(auto_2:  LinksFormat <- ["-H", "auto"];         Both => Ok(LinksFormat::Always));
+        test!(bad:     LinksFormat <- ["--links=sometimes"];  Both => Err(OptionsError::BadArgument(&flags::LINKS, OsString::from("sometimes"))));
+    }
+
+    mod context_formats {
+        use super::*;
+
+        test!(empty:   ContextFormat <- [];                   Both => Ok(ContextFormat::Full));
+        test!(plain:   ContextFormat <- ["--context"];        Both => Ok(ContextFormat::Full));
+        test!(short:   ContextFormat <- ["-Z"];               Both => Ok(ContextFormat::Full));
+        test!(smart:   ContextFormat <- ["--context=smart"];  Both => Ok(ContextFormat::Smart));
+        test!(smart_2: ContextFormat <- ["-Z", "smart"];      Both => Ok(ContextFormat::Smart));
+        test!(bad:     ContextFormat <- ["--context=brief"];  Both => Err(OptionsError::BadArgument(&flags::SECURITY_CONTEXT, OsString::from("brief"))));
+    }
+
+    mod grid_layouts {
+        use super::*;
+        use crate::output::grid::Layout;
+
+        test!(empty:   Layout <- [];                          Both => Ok(Layout::Auto));
+        test!(exact:   Layout <- ["--grid-layout=exact"];     Both => Ok(Layout::Exact));
+        test!(fast:    Layout <- ["--grid-layout", "fast"];   Both => Ok(Layout::Fast));
+        test!(bad:     Layout <- ["--grid-layout=slow"];      Both => Err(OptionsError::BadArgument(&flags::GRID_LAYOUT, OsString::from("slow"))));
+    }
+
+    mod virtual_files {
+        use super::*;
+
+        test!(empty:   VirtualFiles <- [];                          Both => Ok(VirtualFiles::Show));
+        test!(mark:    VirtualFiles <- ["--virtual-files=mark"];    Both => Ok(VirtualFiles::Mark));
+        test!(read:    VirtualFiles <- ["--virtual-files", "read"]; Both => Ok(VirtualFiles::Read));
+        test!(bad:     VirtualFiles <- ["--virtual-files=hide"];    Both => Err(OptionsError::BadArgument(&flags::VIRTUAL_FILES, OsString::from("hide"))));
+    }
+
+    mod alignments {
+        use super::*;
+
+        test!(empty:     Alignments <- [];                                 Both => Ok(Alignments::default()));
+        test!(one:       Alignments <- ["--align=size:left"];              Both => Ok(Alignments(vec![("size", Alignment::Left)])));
+        test!(two:       Alignments <- ["--align", "name:right,git:left"]; Both => Ok(Alignments(vec![("name", Alignment::Right), ("git", Alignment::Left)])));
+        test!(column:    Alignments <- ["--align=Size:left"];              Both => Err(OptionsError::BadArgument(&flags::ALIGN, OsString::from("Size:left"))));
+        test!(direction: Alignments <- ["--align=size:centre"];            Both => Err(OptionsError::BadArgument(&flags::ALIGN, OsString::from("size:centre"))));
+        test!(no_colon:  Alignments <- ["--align=size"];                   Both => Err(OptionsError::BadArgument(&flags::ALIGN, OsString::from("size"))));
+
+        #[test]
+        fn last_wins() {
+            let alignments =
+                Alignments(vec![("size", Alignment::Left), ("size", Alignment::Right)]);
+            assert_eq!(alignments.get("size"), Some(Alignment::Right));
+            assert_eq!(alignments.get("user"), None);
+        }
+    }
+
+    mod fields {
+        use super::*;
+        use crate::output::table::TimeType;
+
+        test!(empty:     Fields <- [];                            Both => Ok(None));
+        test!(ordered:   Fields <- ["--fields=size,permissions"]; Both => Ok(Some(Fields { columns: vec![Column::FileSize, Column::Permissions], name_first: false })));
+        test!(name_last: Fields <- ["--fields", "git,name"];      Both => Ok(Some(Fields { columns: vec![Column::GitStatus], name_first: false })));
+        test!(name_one:  Fields <- ["--fields=name,modified"];    Both => Ok(Some(Fields { columns: vec![Column::Timestamp(TimeType::Modified)], name_first: true })));
+        test!(name_mid:  Fields <- ["--fields=size,name,git"];    Both => Err(OptionsError::BadArgument(&flags::FIELDS, OsString::from("size,name,git"))));
+        test!(unknown:   Fields <- ["--fields=size,colour"];      Both => Err(OptionsError::BadArgument(&flags::FIELDS, OsString::from("size,colour"))));
+        test!(twice:     Fields <- ["--fields=size,size"];        Both => Err(OptionsError::BadArgument(&flags::FIELDS, OsString::from("size,size"))));
+        test!(empty_one: Fields <- ["--fields=size,"];            Both => Err(OptionsError::BadArgument(&flags::FIELDS, OsString::from("size,"))));
+    }
+
     mod time_formats {
         use super::*;
 
@@ -654,7 +1184,9 @@ mod test {
         // Individual settings
         test!(default:                TimeFormat <- ["--time-style=default"], None;               Both => like Ok(TimeFormat::DefaultFormat));
         test!(iso:                    TimeFormat <- ["--time-style", "iso"], None;                Both => like Ok(TimeFormat::ISOFormat));
-        test!(relative:               TimeFormat <- ["--time-style", "relative"], None;           Both => like Ok(TimeFormat::Relative));
+        test!(relative:               TimeFormat <- ["--time-style", "relative"], None;           Both => like Ok(TimeFormat::Relative(None)));
+        test!(relative_to:            TimeFormat <- ["--time-style", "relative", "--relative-to", "2024-01-01T00:00:00Z"], None; Both => like Ok(TimeFormat::Relative(Some(_))));
+        test!(relative_to_bad:        TimeFormat <- ["--time-style", "relative", "--relative-to", "soon"], None; Both => err OptionsError::BadArgument(&flags::RELATIVE_TO, OsString::from("soon")));
         test!(long_iso:               TimeFormat <- ["--time-style=long-iso"], None;              Both => like Ok(TimeFormat::LongISO));
         test!(full_iso:               TimeFormat <- ["--time-style", "full-iso"], None;           Both => like Ok(TimeFormat::FullISO));
         test!(custom_style:           TimeFormat <- ["--time-style", "+%Y/%m/%d"], None;          Both => like Ok(TimeFormat::Custom { recent: None, .. }));
@@ -713,6 +1245,8 @@ mod test {
 
         // Multiples
         test!(time_uu:   TimeTypes <- ["-u", "--modified"];    Both => Ok(TimeTypes { modified: true,  changed: false, accessed: true,  created: false }));
+        test!(time_all:  TimeTypes <- ["--time=all"];          Both => Ok(TimeTypes { modified: true,  changed: true,  accessed: true,  created: true  }));
+        test!(all_flag:  TimeTypes <- ["--time=all", "-m"];    Both => err OptionsError::Useless(&flags::MODIFIED, true, &flags::TIME));
 
         // Errors
         test!(time_tea:  TimeTypes <- ["--time=tea"];          Both => err OptionsError::BadArgument(&flags::TIME, OsString::from("tea")));
@@ -723,6 +1257,17 @@ mod test {
         test!(overridden_2: TimeTypes <- ["-tcr", "-tmod"];    Complain => err OptionsError::Duplicate(Flag::Short(b't'), Flag::Short(b't')));
     }
 
+    mod heights {
+        use super::*;
+
+        test!(automatic:  TerminalHeight <- [], None;                                   Both => like Ok(TerminalHeight::Automatic));
+        test!(arg:        TerminalHeight <- ["--height=40"], None;                      Both => like Ok(TerminalHeight::Set(40)));
+        test!(zero:       TerminalHeight <- ["--height=0"], None;                       Both => like Ok(TerminalHeight::Automatic));
+        test!(env:        TerminalHeight <- [], Some(OsString::from("30"));             Both => like Ok(TerminalHeight::Set(30)));
+        test!(arg_wins:   TerminalHeight <- ["--height=40"], Some(OsString::from("30")); Both => like Ok(TerminalHeight::Set(40)));
+        test!(bad_arg:    TerminalHeight <- ["--height=tall"], None;                    Both => like Err(OptionsError::FailedParse(..)));
+    }
+
     mod views {
         use super::*;
 
@@ -738,8 +1283,8 @@ mod test {
         test!(gracross:      Mode <- ["-xG"], None;       Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
 
         // Lines views
-        test!(lines:         Mode <- ["--oneline"], None;     Both => like Ok(Mode::Lines));
-        test!(prima:         Mode <- ["-1"], None;            Both => like Ok(Mode::Lines));
+        test!(lines:         Mode <- ["--oneline"], None;     Both => like Ok(Mode::Lines(_)));
+        test!(prima:         Mode <- ["-1"], None;            Both => like Ok(Mode::Lines(_)));
 
         // Details views
         test!(long:          Mode <- ["--long"], None;    Both => like Ok(Mode::Details(_)));
@@ -749,7 +1294,34 @@ mod test {
         test!(lid:           Mode <- ["--long", "--grid"], None;  Both => like Ok(Mode::GridDetails(_)));
         test!(leg:           Mode <- ["-lG"], None;               Both => like Ok(Mode::GridDetails(_)));
 
+        // JSON view
+        test!(json:          Mode <- ["--json"], None;            Both => like Ok(Mode::Json));
+        test!(json_long:     Mode <- ["--json", "--long"], None;  Both => like Ok(Mode::Json));
+        test!(json_tree:     Mode <- ["--tree", "--json"], None;  Both => like Ok(Mode::Json));
+
+        // CSV and TSV views
+        test!(csv:           Mode <- ["--format=csv"], None;               Both => like Ok(Mode::Delimited(delimited::Options { format: delimited::Format::Csv, .. })));
+        test!(tsv_long:      Mode <- ["--long", "--format", "tsv"], None;  Both => like Ok(Mode::Delimited(delimited::Options { format: delimited::Format::Tsv, .. })));
+        test!(csv_twice:     Mode <- ["--format=csv", "--fields=name,name"], None;  Both => err OptionsError::BadArgument(&flags::FIELDS, OsString::from("name,name")));
+        test!(bad_format:    Mode <- ["--format=xml"], None;               Both => err OptionsError::BadArgument(&flags::FORMAT, OsString::from("xml")));
+
+        // fzf output
+        test!(fzf:           Mode <- ["--output=fzf"], None;          Both => like Ok(Mode::Lines(lines::Options { fzf: true })));
+        test!(fzf_long:      Mode <- ["--output=fzf", "-l"], None;    Both => like Ok(Mode::Lines(lines::Options { fzf: true })));
+        test!(fzf_oneline:   Mode <- ["-1"], None;                    Both => like Ok(Mode::Lines(lines::Options { fzf: false })));
+
         // Options that do nothing with --long
+        test!(preview:       Mode <- ["--long", "--preview"],      None;  Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { preview: Some(DEFAULT_PREVIEW_LENGTH), .. }, .. }), .. })));
+        test!(preview_20:    Mode <- ["--long", "--preview=20"],   None;  Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { preview: Some(20), .. }, .. }), .. })));
+        test!(merge:         Mode <- ["--long", "--merge"],        None;  Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { root: true, .. }, .. }), .. })));
+        test!(dupes:         Mode <- ["--long", "--find-dupes"],   None;  Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { dupes: Some(DupeCheck::Contents), .. }, .. }), .. })));
+        test!(dupes_size:    Mode <- ["--long", "--find-dupes=size"], None; Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { dupes: Some(DupeCheck::Size), .. }, .. }), .. })));
+        test!(version:       Mode <- ["--long", "--file-version"], None; Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { file_version: Some(VersionKind::File), .. }, .. }), .. })));
+        test!(version_prod:  Mode <- ["--long", "--file-version=product"], None; Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { file_version: Some(VersionKind::Product), .. }, .. }), .. })));
+        test!(umask:         Mode <- ["--long", "--simulate-umask=027"], None; Both => like Ok(Mode::Details(details::Options { table: Some(TableOptions { columns: Columns { simulate_umask: Some(0o027), .. }, .. }), .. })));
+        test!(umask_bad:     Mode <- ["--long", "--simulate-umask=9"], None; Both => err OptionsError::BadArgument(&flags::SIMULATE_UMASK, OsString::from("9")));
+        test!(umask_big:     Mode <- ["--long", "--simulate-umask=1777"], None; Both => err OptionsError::BadArgument(&flags::SIMULATE_UMASK, OsString::from("1777")));
+        test!(preview_0:     Mode <- ["--long", "--preview=0"],    None;  Both => err OptionsError::BadArgument(&flags::PREVIEW, OsString::from("0")));
         test!(long_across:   Mode <- ["--long", "--across"],   None;  Last => like Ok(Mode::Details(_)));
 
         // Options that do nothing without --long
@@ -761,6 +1333,7 @@ mod test {
         test!(just_binary:   Mode <- ["--binary"],    None;  Last => like Ok(Mode::Grid(_)));
         test!(just_bytes:    Mode <- ["--bytes"],     None;  Last => like Ok(Mode::Grid(_)));
         test!(just_numeric:  Mode <- ["--numeric"],   None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_name_1st: Mode <- ["--name-first"], None; Last => like Ok(Mode::Grid(_)));
 
         #[cfg(feature = "git")]
         test!(just_git:      Mode <- ["--git"],       None;  Last => like Ok(Mode::Grid(_)));
@@ -773,12 +1346,22 @@ mod test {
         test!(just_binary_2: Mode <- ["--binary"],    None;  Complain => err OptionsError::Useless(&flags::BINARY,  false, &flags::LONG));
         test!(just_bytes_2:  Mode <- ["--bytes"],     None;  Complain => err OptionsError::Useless(&flags::BYTES,   false, &flags::LONG));
         test!(just_numeric2: Mode <- ["--numeric"],   None;  Complain => err OptionsError::Useless(&flags::NUMERIC, false, &flags::LONG));
+        test!(just_name_1st2: Mode <- ["--name-first"], None; Complain => err OptionsError::Useless(&flags::NAME_FIRST, false, &flags::LONG));
+        test!(just_overlay:   Mode <- ["--overlay"],    None; Complain => err OptionsError::Useless(&flags::OVERLAY, false, &flags::LONG));
+        test!(just_age_rank:  Mode <- ["--age-rank"],   None; Complain => err OptionsError::Useless(&flags::AGE_RANK, false, &flags::LONG));
+        test!(just_text_size: Mode <- ["--text-size"],  None; Complain => err OptionsError::Useless(&flags::TEXT_SIZE, false, &flags::LONG));
+        test!(just_encoding:  Mode <- ["--encoding"],   None; Complain => err OptionsError::Useless(&flags::ENCODING, false, &flags::LONG));
+        test!(just_preview:   Mode <- ["--preview"],    None; Complain => err OptionsError::Useless(&flags::PREVIEW, false, &flags::LONG));
+        test!(just_umask:     Mode <- ["--simulate-umask=022"], None; Complain => err OptionsError::Useless(&flags::SIMULATE_UMASK, false, &flags::LONG));
+        test!(just_dupes:     Mode <- ["--find-dupes"], None; Complain => err OptionsError::Useless(&flags::FIND_DUPES, false, &flags::LONG));
+        test!(just_version:   Mode <- ["--file-version"], None; Complain => err OptionsError::Useless(&flags::FILE_VERSION, false, &flags::LONG));
+        test!(just_diff_2:   Mode <- ["--diff-against", "b"], None; Complain => err OptionsError::Useless(&flags::DIFF_AGAINST, false, &flags::LONG));
 
         #[cfg(feature = "git")]
         test!(just_git_2:    Mode <- ["--git"],    None;  Complain => err OptionsError::Useless(&flags::GIT,    false, &flags::LONG));
 
         // Contradictions and combinations
-        test!(lgo:           Mode <- ["--long", "--grid", "--oneline"], None;  Both => like Ok(Mode::Lines));
+        test!(lgo:           Mode <- ["--long", "--grid", "--oneline"], None;  Both => like Ok(Mode::Lines(_)));
         test!(lgt:           Mode <- ["--long", "--grid", "--tree"],    None;  Both => like Ok(Mode::Details(_)));
         test!(tgl:           Mode <- ["--tree", "--grid", "--long"],    None;  Both => like Ok(Mode::GridDetails(_)));
         test!(tlg:           Mode <- ["--tree", "--long", "--grid"],    None;  Both => like Ok(Mode::GridDetails(_)));
@@ -0,0 +1,371 @@
+//! Named views, which are bundles of options picked with `eza view NAME`.
+//!
+//! A few views are built in, and more can be defined in the `views` file in
+//! the configuration directory, one per line:
+//!
+//! ```text
+//! # name = options
+//! big    = --long --sort=size --reverse --only-files
+//! photos = --grid --icons --ignore-glob='*.xmp'
+//! ```
+//!
+//! A view defined in the file replaces a built-in view with the same name.
+//! The view’s options are put in place of `view NAME` before the rest of the
+//! arguments are parsed, so any options after it can override them.
+//!
+//! Views can also be used as aliases anywhere in the arguments by writing
+//! their names after an `@`, as in `eza -a @big src`, which lets more than
+//! one of them be combined.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::*;
+
+use crate::options::config;
+use crate::options::{flags, OptionsError, Vars};
+
+/// The word that introduces a view as the first argument.
+pub static SUBCOMMAND: &str = "view";
+
+/// What an argument starts with to be replaced by the view it names.
+pub static ALIAS_PREFIX: &str = "@";
+
+/// The name of the file, in the configuration directory, that views are
+/// read from.
+pub static VIEWS_FILE: &str = "views";
+
+/// The views that exist even without a views file. The `code` view lists
+/// Git statuses, so it only exists in builds with Git support.
+#[rustfmt::skip]
+pub static BUILTIN_VIEWS: &[(&str, &str)] = &[
+    ("big",    "--long --sort=size --reverse --only-files"),
+    #[cfg(feature = "git")]
+    ("code",   "--long --git --git-ignore --group-directories-first"),
+    ("media",  "--grid --icons --sort=extension --only-files"),
+    ("recent", "--long --sort=modified --reverse"),
+];
+
+/// A named bundle of options.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct View {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+/// Replaces a leading `view NAME` in the arguments, and any `@NAME` aliases
+/// after it, with the options of the views with those names. The views are
+/// only read if they’re needed.
+pub fn expand<V: Vars>(args: Vec<OsString>, vars: &V) -> Result<Vec<OsString>, OptionsError> {
+    let mut views = None;
+    let args = expand_subcommand(args, vars, &mut views)?;
+    expand_aliases(args, vars, &mut views)
+}
+
+/// Reads every view the first time one is needed.
+fn load<'a, V: Vars>(
+    views: &'a mut Option<Vec<View>>,
+    vars: &V,
+) -> Result<&'a [View], OptionsError> {
+    if views.is_none() {
+        *views = Some(all_views(vars)?);
+    }
+
+    Ok(views.as_deref().unwrap_or_default())
+}
+
+/// Finds the view with the given name, complaining if there isn’t one.
+fn find<'a>(views: &'a [View], name: &OsString) -> Result<&'a View, OptionsError> {
+    views
+        .iter()
+        .find(|v| name == v.name.as_str())
+        .ok_or_else(|| OptionsError::NoSuchView(Some(name.clone()), view_names(views)))
+}
+
+/// Replaces a leading `view NAME`. Arguments that don’t start with `view`
+/// are returned untouched, as are ones where `view` is the name of a file in
+/// the current directory, which the user more likely meant to list.
+fn expand_subcommand<V: Vars>(
+    args: Vec<OsString>,
+    vars: &V,
+    views: &mut Option<Vec<View>>,
+) -> Result<Vec<OsString>, OptionsError> {
+    if args.first().map_or(true, |a| a != SUBCOMMAND) || Path::new(SUBCOMMAND).exists() {
+        return Ok(args);
+    }
+
+    let views = load(views, vars)?;
+    let Some(name) = args.get(1) else {
+        return Err(OptionsError::NoSuchView(None, view_names(views)));
+    };
+
+    let view = find(views, name)?;
+    debug!("Expanding view {:?} to {:?}", view.name, view.args);
+    Ok(view
+        .args
+        .iter()
+        .map(OsString::from)
+        .chain(args.into_iter().skip(2))
+        .collect())
+}
+
+/// Replaces each `@NAME` argument, up to a `--`, with the options of the
+/// view with that name. Like `view`, an argument that’s the name of a file
+/// is left alone, as is one that’s the value of the flag before it, such as
+/// `--relative-to @file`, and the options in the views themselves, so one
+/// alias can’t expand into another.
+fn expand_aliases<V: Vars>(
+    args: Vec<OsString>,
+    vars: &V,
+    views: &mut Option<Vec<View>>,
+) -> Result<Vec<OsString>, OptionsError> {
+    let mut expanded = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            expanded.push(arg);
+            expanded.extend(args);
+            break;
+        }
+
+        if flags::ALL_ARGS.value_follows(&arg) {
+            expanded.push(arg);
+            expanded.extend(args.next());
+            continue;
+        }
+
+        let name = arg
+            .to_str()
+            .and_then(|a| a.strip_prefix(ALIAS_PREFIX))
+            .filter(|name| !name.is_empty() && !Path::new(&arg).exists());
+
+        match name {
+            Some(name) => {
+                let view = find(load(views, vars)?, &OsString::from(name))?;
+                debug!("Expanding alias {:?} to {:?}", view.name, view.args);
+                expanded.extend(view.args.iter().map(OsString::from));
+            }
+            None => expanded.push(arg),
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// The built-in views, with the ones from the views file replacing or added
+/// to them.
+fn all_views<V: Vars>(vars: &V) -> Result<Vec<View>, OptionsError> {
+    let mut views: Vec<View> = BUILTIN_VIEWS
+        .iter()
+        .map(|(name, args)| View {
+            name: (*name).into(),
+            args: args.split_whitespace().map(String::from).collect(),
+        })
+        .collect();
+
+    let Some(path) = config::config_dir(vars).map(|dir| dir.join(VIEWS_FILE)) else {
+        return Ok(views);
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(views),
+        Err(e) => return Err(OptionsError::BadConfig(path, 0, e.to_string())),
+    };
+
+    for view in parse_views(&contents)
+        .map_err(|(line, msg)| OptionsError::BadConfig(path.clone(), line, msg))?
+    {
+        views.retain(|v| v.name != view.name);
+        views.push(view);
+    }
+
+    Ok(views)
+}
+
+/// Parses the contents of a views file, returning the line number and a
+/// description of the first line that couldn’t be parsed.
+fn parse_views(contents: &str) -> Result<Vec<View>, (usize, String)> {
+    let mut views = Vec::new();
+
+    for (number, line) in config::platform_lines(contents) {
+        let Some((name, rest)) = line.split_once('=') else {
+            return Err((number, "expected ‘NAME = OPTIONS’".into()));
+        };
+
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err((number, format!("invalid view name {name:?}")));
+        }
+
+        let Some(args) = config::split_words(rest) else {
+            return Err((number, "unclosed quote".into()));
+        };
+
+        views.push(View {
+            name: name.into(),
+            args,
+        });
+    }
+
+    Ok(views)
+}
+
+fn view_names(views: &[View]) -> Vec<String> {
+    let mut names: Vec<String> = views.iter().map(|v| v.name.clone()).collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::config::test::Env;
+
+    fn args(strs: &[&str]) -> Vec<OsString> {
+        strs.iter().map(OsString::from).collect()
+    }
+
+    static NO_CONFIG: Env = Env(&[("EZA_CONFIG_DIR", "/nonexistent/eza")]);
+
+    #[test]
+    fn not_a_view() {
+        let given = args(&["--long", "view", "big"]);
+        assert_eq!(expand(given.clone(), &NO_CONFIG), Ok(given));
+    }
+
+    #[test]
+    fn builtin() {
+        assert_eq!(
+            expand(args(&["view", "recent", "-a", "src"]), &NO_CONFIG),
+            Ok(args(&[
+                "--long",
+                "--sort=modified",
+                "--reverse",
+                "-a",
+                "src"
+            ]))
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            expand(args(&["view", "nope"]), &NO_CONFIG),
+            Err(OptionsError::NoSuchView(
+                Some("nope".into()),
+                BUILTIN_VIEWS
+                    .iter()
+                    .map(|(name, _)| (*name).into())
+                    .collect()
+            ))
+        );
+    }
+
+    #[test]
+    fn missing_name() {
+        assert!(matches!(
+            expand(args(&["view"]), &NO_CONFIG),
+            Err(OptionsError::NoSuchView(None, _))
+        ));
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(
+            expand(args(&["-a", "@recent", "src", "@big"]), &NO_CONFIG),
+            Ok(args(&[
+                "-a",
+                "--long",
+                "--sort=modified",
+                "--reverse",
+                "src",
+                "--long",
+                "--sort=size",
+                "--reverse",
+                "--only-files",
+            ]))
+        );
+    }
+
+    #[test]
+    fn view_and_alias() {
+        assert_eq!(
+            expand(args(&["view", "recent", "@media"]), &NO_CONFIG),
+            Ok(args(&[
+                "--long",
+                "--sort=modified",
+                "--reverse",
+                "--grid",
+                "--icons",
+                "--sort=extension",
+                "--only-files",
+            ]))
+        );
+    }
+
+    #[test]
+    fn aliases_stop_at_double_dash() {
+        let given = args(&["-l", "--", "@big", "@"]);
+        assert_eq!(expand(given.clone(), &NO_CONFIG), Ok(given));
+    }
+
+    #[test]
+    fn flag_value_is_not_an_alias() {
+        let given = args(&["--relative-to", "@file"]);
+        assert_eq!(expand(given.clone(), &NO_CONFIG), Ok(given));
+    }
+
+    #[test]
+    fn short_flag_value_is_not_an_alias() {
+        let given = args(&["-TL", "@2", "-I@big"]);
+        assert_eq!(expand(given.clone(), &NO_CONFIG), Ok(given));
+    }
+
+    #[test]
+    fn unknown_alias() {
+        assert!(matches!(
+            expand(args(&["@nope"]), &NO_CONFIG),
+            Err(OptionsError::NoSuchView(Some(name), _)) if name == "nope"
+        ));
+    }
+
+    #[test]
+    fn parse_file() {
+        let contents = "\
+# my views
+big = --long --sort=size
+
+photos = --grid --ignore-glob='*.xmp *.thm'
+";
+        assert_eq!(
+            parse_views(contents),
+            Ok(vec![
+                View {
+                    name: "big".into(),
+                    args: vec!["--long".into(), "--sort=size".into()],
+                },
+                View {
+                    name: "photos".into(),
+                    args: vec!["--grid".into(), "--ignore-glob=*.xmp *.thm".into()],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_bad_line() {
+        assert_eq!(
+            parse_views("big = -l\njust some words\n"),
+            Err((2, "expected ‘NAME = OPTIONS’".into()))
+        );
+    }
+
+    #[test]
+    fn parse_bad_name() {
+        assert!(parse_views("two words = -l").is_err());
+    }
+}
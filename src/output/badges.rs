@@ -0,0 +1,61 @@
+//! Badges drawn after file names, such as “KEEP” or “WIP”, for `--badges`,
+//! so that people can tag files by setting an extended attribute on them.
+//!
+//! The attribute’s name is carried in the file name options, so it gets read
+//! from wherever names get drawn.
+
+use crate::fs::File;
+
+/// The most characters of a badge that get drawn, so that a long value
+/// can’t push everything else out of the way.
+pub static MAX_LENGTH: usize = 12;
+
+/// The badge to draw after the given file’s name, read from the given
+/// attribute, if badges are being drawn and the file has one.
+pub fn badge(attribute: Option<&str>, file: &File<'_>) -> Option<String> {
+    text(file.extended_attribute(attribute?)?)
+}
+
+/// The text of a badge from its attribute’s value, with surrounding
+/// whitespace and control characters taken out, cut down to `MAX_LENGTH`
+/// characters. Empty values have no badge.
+fn text(value: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(value)
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_LENGTH)
+        .collect::<String>();
+
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        assert_eq!(text(b"KEEP"), Some("KEEP".into()));
+    }
+
+    #[test]
+    fn trimmed() {
+        assert_eq!(text(b"  WIP\n"), Some("WIP".into()));
+    }
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(text(b"W\x1b[1mIP"), Some("W[1mIP".into()));
+    }
+
+    #[test]
+    fn too_long() {
+        assert_eq!(text(b"do not delete this"), Some("do not delet".into()));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(text(b" \n"), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! The map of where a sparse file’s data is, for `--blocks-map`, drawn as a
+//! bar with each character standing for an equal share of the file.
+
+use ansiterm::{ANSIString, Style};
+use number_prefix::NumberPrefix;
+
+use crate::fs::feature::sparse::Extent;
+use crate::output::capabilities::capabilities;
+
+/// What the bytes covered by one character of the bar are.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Cell {
+    /// Every byte is data.
+    Data,
+
+    /// Every byte is in a hole.
+    Hole,
+
+    /// There’s some of both.
+    Mixed,
+}
+
+impl Cell {
+    fn paint(self, colours: &dyn Colours) -> ANSIString<'static> {
+        let unicode = capabilities().unicode;
+
+        #[rustfmt::skip]
+        return match self {
+            Self::Data   => colours.data().paint(if unicode { "█" } else { "#" }),
+            Self::Hole   => colours.hole().paint(if unicode { "░" } else { "-" }),
+            Self::Mixed  => colours.data().paint(if unicode { "▒" } else { "+" }),
+        };
+    }
+}
+
+pub trait Colours {
+    fn data(&self) -> Style;
+    fn hole(&self) -> Style;
+}
+
+/// Splits a file `len` bytes long into `width` equal shares, and works out
+/// what each of them has in it. A file shorter than the bar gets a shorter
+/// bar, rather than having bytes split between characters.
+pub fn cells(extents: &[Extent], len: u64, width: usize) -> Vec<Cell> {
+    let width = (width as u64).min(len);
+    (0..width)
+        .map(|i| {
+            // Working in u128 keeps this from overflowing for huge files.
+            let start = (u128::from(len) * u128::from(i) / u128::from(width)) as u64;
+            let end = (u128::from(len) * u128::from(i + 1) / u128::from(width)) as u64;
+
+            let overlapping = extents.iter().filter(|e| e.start < end && e.end > start);
+            let (mut data, mut hole) = (false, false);
+            for extent in overlapping {
+                if extent.data {
+                    data = true;
+                } else {
+                    hole = true;
+                }
+            }
+
+            match (data, hole) {
+                (true, true) => Cell::Mixed,
+                (true, false) => Cell::Data,
+                _ => Cell::Hole,
+            }
+        })
+        .collect()
+}
+
+/// Draws the bar for a file, followed by how much of it is data.
+pub fn render(extents: &[Extent], len: u64, width: usize, colours: &dyn Colours) -> String {
+    let bar: String = cells(extents, len, width)
+        .into_iter()
+        .map(|cell| cell.paint(colours).to_string())
+        .collect();
+
+    let data_extents = extents.iter().filter(|e| e.data).count();
+    let data: u64 = extents
+        .iter()
+        .filter(|e| e.data)
+        .map(|e| e.end - e.start)
+        .sum();
+    let plural = if data_extents == 1 { "" } else { "s" };
+
+    format!(
+        "{bar}\n{} of data in {data_extents} extent{plural}, {} of holes, {} in all",
+        human_size(data),
+        human_size(len - data),
+        human_size(len),
+    )
+}
+
+/// A size in bytes, in binary units with one decimal place, such as
+/// `1.5 KiB`.
+pub(crate) fn human_size(bytes: u64) -> String {
+    match NumberPrefix::binary(bytes as f64) {
+        NumberPrefix::Standalone(b) => format!("{b} B"),
+        NumberPrefix::Prefixed(prefix, n) => format!("{n:.1} {prefix}B"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn extent(start: u64, end: u64, data: bool) -> Extent {
+        Extent { start, end, data }
+    }
+
+    #[test]
+    fn all_data() {
+        let extents = [extent(0, 100, true)];
+        assert_eq!(cells(&extents, 100, 4), vec![Cell::Data; 4]);
+    }
+
+    #[test]
+    fn hole_in_the_middle() {
+        let extents = [
+            extent(0, 25, true),
+            extent(25, 75, false),
+            extent(75, 100, true),
+        ];
+        assert_eq!(
+            cells(&extents, 100, 4),
+            vec![Cell::Data, Cell::Hole, Cell::Hole, Cell::Data]
+        );
+    }
+
+    #[test]
+    fn mixed() {
+        let extents = [extent(0, 10, true), extent(10, 100, false)];
+        assert_eq!(cells(&extents, 100, 2), vec![Cell::Mixed, Cell::Hole]);
+    }
+
+    #[test]
+    fn short_file() {
+        let extents = [extent(0, 3, true)];
+        assert_eq!(cells(&extents, 3, 80).len(), 3);
+        assert_eq!(cells(&[], 0, 80), vec![]);
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+    }
+}
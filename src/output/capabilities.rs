@@ -0,0 +1,247 @@
+//! What the terminal that output goes to is able to draw.
+//!
+//! How many colours there are is detected from `COLORTERM` and `TERM`, and
+//! any capability can be turned off with `EZA_TERM_CAPS`, for terminals that
+//! claim more than they can do. As these are the same for the whole run,
+//! they’re set once at startup, and the theme and the renderers consult them
+//! from wherever they’re needed: colours get downgraded to the nearest one
+//! the terminal has, and box-drawing characters, icons, and hyperlinks are
+//! avoided.
+
+use std::sync::OnceLock;
+
+use ansiterm::Colour;
+
+static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+/// The things that not every terminal can draw.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Capabilities {
+    /// Whether characters outside ASCII, such as box-drawing characters and
+    /// icons, can be drawn.
+    pub unicode: bool,
+
+    /// How many colours can be drawn.
+    pub colours: ColourDepth,
+
+    /// Whether OSC 8 hyperlinks are understood.
+    pub hyperlinks: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            unicode: true,
+            colours: ColourDepth::Truecolor,
+            hyperlinks: true,
+        }
+    }
+}
+
+/// How many different colours a terminal can draw, from fewest to most.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
+pub enum ColourDepth {
+    /// Only the eight standard colours and their bright versions.
+    Ansi16,
+
+    /// The 256-colour palette.
+    Ansi256,
+
+    /// Any 24-bit RGB colour.
+    Truecolor,
+}
+
+/// Sets what the terminal can draw. This only has an effect the first time
+/// it’s called, before anything has been drawn.
+pub fn configure(capabilities: Capabilities) {
+    let _ = CAPABILITIES.set(capabilities);
+}
+
+/// What the terminal can draw: the capabilities that were configured, or
+/// everything if none were.
+pub fn capabilities() -> Capabilities {
+    *CAPABILITIES.get_or_init(Capabilities::default)
+}
+
+/// Replaces a colour the terminal can’t draw with the nearest one it can.
+/// Colours it can draw are left alone.
+pub fn downgrade(colour: Colour) -> Colour {
+    downgrade_to(colour, capabilities().colours)
+}
+
+/// Replaces a colour with the nearest one available at the given depth.
+fn downgrade_to(colour: Colour, depth: ColourDepth) -> Colour {
+    match (colour, depth) {
+        (Colour::RGB(r, g, b), ColourDepth::Ansi256) => Colour::Fixed(nearest_256(r, g, b)),
+        (Colour::RGB(r, g, b), ColourDepth::Ansi16) => nearest_16(r, g, b),
+        (Colour::Fixed(index), ColourDepth::Ansi16) => {
+            let (r, g, b) = palette_rgb(index);
+            nearest_16(r, g, b)
+        }
+        (colour, _) => colour,
+    }
+}
+
+/// The sixteen standard colours, in palette order, along with the RGB
+/// values xterm draws them with.
+#[rustfmt::skip]
+const BASIC_COLOURS: [(Colour, (u8, u8, u8)); 16] = [
+    (Colour::Black,        (  0,   0,   0)),
+    (Colour::Red,          (205,   0,   0)),
+    (Colour::Green,        (  0, 205,   0)),
+    (Colour::Yellow,       (205, 205,   0)),
+    (Colour::Blue,         (  0,   0, 238)),
+    (Colour::Purple,       (205,   0, 205)),
+    (Colour::Cyan,         (  0, 205, 205)),
+    (Colour::White,        (229, 229, 229)),
+    (Colour::DarkGray,     (127, 127, 127)),
+    (Colour::BrightRed,    (255,   0,   0)),
+    (Colour::BrightGreen,  (  0, 255,   0)),
+    (Colour::BrightYellow, (255, 255,   0)),
+    (Colour::BrightBlue,   ( 92,  92, 255)),
+    (Colour::BrightPurple, (255,   0, 255)),
+    (Colour::BrightCyan,   (  0, 255, 255)),
+    (Colour::BrightGray,   (255, 255, 255)),
+];
+
+/// The levels of each component of the 6×6×6 colour cube, which takes up
+/// colours 16 to 231 of the palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The index in the 256-colour palette of the colour closest to the given
+/// one, picking from the colour cube and the greyscale ramp.
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        v => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (
+        CUBE_LEVELS[ri as usize],
+        CUBE_LEVELS[gi as usize],
+        CUBE_LEVELS[bi as usize],
+    );
+
+    // The greyscale ramp runs from 8 to 238 in steps of 10, as colours 232
+    // to 255.
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let grey_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let grey_level = 8 + 10 * grey_index;
+
+    let rgb = (r, g, b);
+    if distance(rgb, (grey_level, grey_level, grey_level)) < distance(rgb, cube) {
+        232 + grey_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// The standard colour closest to the given one.
+fn nearest_16(r: u8, g: u8, b: u8) -> Colour {
+    BASIC_COLOURS
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map_or(Colour::White, |(colour, _)| *colour)
+}
+
+/// The RGB value of a colour in the 256-colour palette.
+fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_COLOURS[usize::from(index)].1,
+        16..=231 => {
+            let i = index - 16;
+            let level = |v: u8| CUBE_LEVELS[usize::from(v)];
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let grey = 8 + 10 * (index - 232);
+            (grey, grey, grey)
+        }
+    }
+}
+
+/// The squared distance between two colours.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cube_corners() {
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_256(255, 255, 255), 231);
+        assert_eq!(nearest_256(255, 0, 0), 196);
+    }
+
+    #[test]
+    fn cube_middle() {
+        assert_eq!(nearest_256(135, 175, 215), 16 + 36 * 2 + 6 * 3 + 4);
+    }
+
+    #[test]
+    fn grey() {
+        assert_eq!(nearest_256(128, 128, 128), 244);
+    }
+
+    #[test]
+    fn basic_exact() {
+        assert_eq!(nearest_16(0, 0, 0), Colour::Black);
+        assert_eq!(nearest_16(255, 0, 0), Colour::BrightRed);
+        assert_eq!(nearest_16(255, 255, 255), Colour::BrightGray);
+    }
+
+    #[test]
+    fn basic_nearest() {
+        assert_eq!(nearest_16(200, 20, 10), Colour::Red);
+        assert_eq!(nearest_16(120, 130, 125), Colour::DarkGray);
+        assert_eq!(nearest_16(255, 136, 0), Colour::Yellow);
+    }
+
+    #[test]
+    fn palette_to_basic() {
+        assert_eq!(
+            downgrade_to(Colour::Fixed(1), ColourDepth::Ansi16),
+            Colour::Red
+        );
+        assert_eq!(
+            downgrade_to(Colour::Fixed(196), ColourDepth::Ansi16),
+            Colour::BrightRed
+        );
+        assert_eq!(
+            downgrade_to(Colour::Fixed(244), ColourDepth::Ansi16),
+            Colour::DarkGray
+        );
+    }
+
+    #[test]
+    fn palette_rgb_round_trip() {
+        for index in 16..=255 {
+            let (r, g, b) = palette_rgb(index);
+            assert_eq!(nearest_256(r, g, b), index);
+        }
+    }
+
+    #[test]
+    fn depths() {
+        let orange = Colour::RGB(255, 136, 0);
+        assert_eq!(downgrade_to(orange, ColourDepth::Truecolor), orange);
+        assert_eq!(
+            downgrade_to(orange, ColourDepth::Ansi256),
+            Colour::Fixed(208)
+        );
+        assert_eq!(downgrade_to(orange, ColourDepth::Ansi16), Colour::Yellow);
+        assert_eq!(
+            downgrade_to(Colour::Fixed(208), ColourDepth::Ansi256),
+            Colour::Fixed(208)
+        );
+        assert_eq!(
+            downgrade_to(Colour::Blue, ColourDepth::Ansi16),
+            Colour::Blue
+        );
+    }
+}
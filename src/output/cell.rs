@@ -6,6 +6,8 @@ use std::ops::{Add, Deref, DerefMut};
 use ansiterm::{ANSIString, ANSIStrings, Style};
 use unicode_width::UnicodeWidthStr;
 
+use crate::theme::apply_overlay;
+
 /// An individual cell that holds text in a table, used in the details and
 /// lines views to store ANSI-terminal-formatted data before it is printed.
 ///
@@ -91,6 +93,21 @@ impl TextCell {
         (*self.width) += *other.width;
         self.contents.0.extend(other.contents.0);
     }
+
+    /// Surrounds the contents of this cell with strings that take up no
+    /// width, such as the escape sequences that make it a hyperlink.
+    pub fn wrap(&mut self, before: ANSIString<'static>, after: ANSIString<'static>) {
+        self.contents.0.insert(0, before);
+        self.contents.0.push(after);
+    }
+
+    /// Applies an overlay style on top of every string in this cell.
+    pub fn overlay(&mut self, overlay: Style) {
+        for string in &mut self.contents.0 {
+            let style = string.style_ref_mut();
+            *style = apply_overlay(*style, overlay);
+        }
+    }
 }
 
 // I’d like to eventually abstract cells so that instead of *every* cell
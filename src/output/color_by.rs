@@ -0,0 +1,128 @@
+//! Colouring files by who owns them, for `--color-by=owner`, or by when
+//! they were last committed, for `--color-by=git-age`.
+//!
+//! Each user ID is hashed to pick a colour from a fixed palette, so the same
+//! owner gets the same colour every time, on every machine, without anything
+//! having to be configured. Two owners can end up sharing a colour when there
+//! are more of them than there are colours, but neighbouring IDs, such as
+//! the users created one after the other on a server, are spread out.
+//!
+//! Commit ages brighten and darken names along the same scale as
+//! `--color-scale=age`, only going by when each file was last committed to
+//! its Git repository rather than when it was last modified on disk.
+
+use ansiterm::Colour::{self, Fixed, White};
+use ansiterm::Style;
+
+use crate::output::color_scale::scale_style;
+
+/// What to colour by the owner or commit age of each file.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum ColorBy {
+    /// Colour everything as usual.
+    #[default]
+    Off,
+
+    /// Colour the user column.
+    Owner,
+
+    /// Colour the user column and tint the names of files.
+    OwnerNames,
+
+    /// Brighten the names of the most recently committed files, and darken
+    /// the names of the least.
+    GitAge,
+}
+
+impl ColorBy {
+    /// Whether the user column gets coloured by owner.
+    pub fn users(self) -> bool {
+        matches!(self, Self::Owner | Self::OwnerNames)
+    }
+
+    /// Whether file names get tinted by owner.
+    pub fn names(self) -> bool {
+        matches!(self, Self::OwnerNames)
+    }
+
+    /// Whether file names get brightened by how recently they were
+    /// committed.
+    pub fn git_age(self) -> bool {
+        matches!(self, Self::GitAge)
+    }
+}
+
+/// The colours owners are picked from, chosen to be readable on both dark
+/// and light backgrounds, and to be told apart from each other.
+const PALETTE: [Colour; 12] = [
+    Fixed(33),
+    Fixed(37),
+    Fixed(71),
+    Fixed(136),
+    Fixed(166),
+    Fixed(167),
+    Fixed(133),
+    Fixed(98),
+    Fixed(67),
+    Fixed(107),
+    Fixed(173),
+    Fixed(168),
+];
+
+/// The style for files owned by the user with the given ID.
+pub fn style(uid: u32) -> Style {
+    PALETTE[palette_index(uid, PALETTE.len())].normal()
+}
+
+/// Brightens or darkens a style by how recently a file was committed, given
+/// as a ratio from 0 for the least recent to 1 for the most recent. Styles
+/// without a colour of their own are scaled from white.
+pub fn git_age_style(style: Style, ratio: f32, min_luminance: isize) -> Style {
+    let style = match style.foreground {
+        Some(_) => style,
+        None => Style {
+            foreground: Some(White),
+            ..style
+        },
+    };
+
+    scale_style(style, ratio, min_luminance)
+}
+
+/// Picks a place in a palette of the given length for a user ID, mixing its
+/// bits first so that neighbouring IDs don’t get neighbouring colours.
+fn palette_index(uid: u32, len: usize) -> usize {
+    let mut hash = uid;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+    hash as usize % len
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_every_time() {
+        assert_eq!(style(1000), style(1000));
+        assert_eq!(palette_index(0, 12), palette_index(0, 12));
+    }
+
+    #[test]
+    fn within_palette() {
+        for uid in [0, 1, 500, 1000, 65534, u32::MAX] {
+            assert!(palette_index(uid, PALETTE.len()) < PALETTE.len());
+        }
+    }
+
+    #[test]
+    fn neighbours_spread_out() {
+        let colours = (1000..1012)
+            .map(|uid| palette_index(uid, PALETTE.len()))
+            .collect::<std::collections::HashSet<_>>();
+        assert!(colours.len() > 4);
+    }
+}
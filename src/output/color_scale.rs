@@ -1,12 +1,34 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use ansiterm::{Colour, Style};
+use chrono::NaiveDateTime;
 use log::trace;
+use number_prefix::NumberPrefix;
 use palette::{FromColor, Oklab, Srgb};
 
 use crate::{
     fs::{dir_action::RecurseOptions, feature::git::GitCache, fields::Size, DotFilter, File},
-    output::{table::TimeType, tree::TreeDepth},
+    output::{
+        capabilities, render::SizeColours, table::TimeType, time::TimeAnchor, tree::TreeDepth,
+    },
+    theme::Theme,
 };
 
+/// How dark the lowest values on a scale get, as a percentage, unless
+/// `EZA_MIN_LUMINANCE` says otherwise.
+pub const DEFAULT_MIN_LUMINANCE: isize = 40;
+
+/// Whether to print each scale that gets worked out, for `--dump-scale`.
+static DUMP: AtomicBool = AtomicBool::new(false);
+
+/// The points along each scale that get printed by `--dump-scale`.
+const DUMP_STOPS: [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+/// Sets whether the scales worked out from now on should be printed.
+pub fn configure_dump(enabled: bool) {
+    DUMP.store(enabled, Ordering::Relaxed);
+}
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct ColorScaleOptions {
     pub mode: ColorScaleMode,
@@ -14,6 +36,24 @@ pub struct ColorScaleOptions {
 
     pub size: bool,
     pub age: bool,
+
+    /// The moment the age scale is measured from, so that the files
+    /// modified closest to it are the brightest, rather than the newest.
+    pub anchor: Option<TimeAnchor>,
+}
+
+impl ColorScaleOptions {
+    /// The value a time has on the age scale, which is higher the newer it
+    /// is, or the closer it is to the anchor if there is one.
+    fn age_value(&self, time: NaiveDateTime) -> f32 {
+        let millis = time.and_utc().timestamp_millis();
+        match self.anchor {
+            Some(TimeAnchor(anchor)) => {
+                -((millis - anchor.and_utc().timestamp_millis()).abs() as f32)
+            }
+            None => millis as f32,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -69,21 +109,87 @@ impl ColorScaleInformation {
         }
     }
 
-    pub fn adjust_style(&self, mut style: Style, value: f32, range: Option<Extremes>) -> Style {
-        if let (Some(fg), Some(range)) = (style.foreground, range) {
+    pub fn adjust_style(&self, style: Style, value: f32, range: Option<Extremes>) -> Style {
+        if let Some(range) = range {
             let mut ratio = ((value - range.min) / (range.max - range.min)).clamp(0.0, 1.0);
             if ratio.is_nan() {
                 ratio = 1.0;
             }
 
-            style.foreground = Some(adjust_luminance(
-                fg,
-                ratio,
-                self.options.min_luminance as f32 / 100.0,
-            ));
+            scale_style(style, ratio, self.options.min_luminance)
+        } else {
+            style
+        }
+    }
+
+    /// Prints the range of each scale to standard error, along with the
+    /// colour that values at a few points along it get, if the scales are
+    /// being dumped. Sizes are painted with the style for their decimal
+    /// prefix, which is what they get unless `--binary` or `--bytes` says
+    /// otherwise.
+    pub fn dump(&self, theme: &Theme) {
+        if !DUMP.load(Ordering::Relaxed) {
+            return;
+        }
+
+        eprintln!(
+            "eza scale: darkest at {}% luminance",
+            self.options.min_luminance
+        );
+
+        if let Some(range) = self.size {
+            eprintln!("eza scale: size, from {} to {} bytes", range.min, range.max);
+            for ratio in DUMP_STOPS {
+                let bytes = range.at(ratio);
+                let prefix = match NumberPrefix::decimal(bytes) {
+                    NumberPrefix::Standalone(_) => None,
+                    NumberPrefix::Prefixed(prefix, _) => Some(prefix),
+                };
+                self.dump_stop(ratio, &format!("{bytes:.0} bytes"), theme.size(prefix));
+            }
+        }
+
+        for (name, range) in [
+            ("modified", self.modified),
+            ("changed", self.changed),
+            ("accessed", self.accessed),
+            ("created", self.created),
+        ] {
+            let Some(range) = range else { continue };
+
+            eprintln!("eza scale: {name} time");
+            for ratio in DUMP_STOPS {
+                let value = range.at(ratio);
+                let description = match self.options.anchor {
+                    Some(_) => format!("{:.0}s from the anchor", -value / 1000.0),
+                    None => NaiveDateTime::from_timestamp_millis(value as i64)
+                        .map_or_else(String::new, |time| {
+                            time.format("%Y-%m-%d %H:%M:%S").to_string()
+                        }),
+                };
+                self.dump_stop(ratio, &description, theme.ui.date);
+            }
         }
+    }
 
-        style
+    /// Prints where one point on a scale is, the value there, and the
+    /// colour the value gets, with a swatch of it.
+    fn dump_stop(&self, ratio: f32, value: &str, style: Style) {
+        let style = scale_style(style, ratio, self.options.min_luminance);
+        let colour = match style.foreground {
+            Some(Colour::RGB(r, g, b)) => format!("#{r:02x}{g:02x}{b:02x}"),
+            Some(Colour::Fixed(n)) => format!("colour {n}"),
+            Some(other) => format!("{other:?}"),
+            None => {
+                eprintln!("eza scale: {:>4.0}%  {value}  no colour", ratio * 100.0);
+                return;
+            }
+        };
+        eprintln!(
+            "eza scale: {:>4.0}%  {value}  {colour}  {}",
+            ratio * 100.0,
+            style.paint("███")
+        );
     }
 
     pub fn apply_time_gradient(&self, style: Style, file: &File<'_>, time_type: TimeType) -> Style {
@@ -95,7 +201,7 @@ impl ColorScaleInformation {
         };
 
         if let Some(file_time) = time_type.get_corresponding_time(file) {
-            self.adjust_style(style, file_time.timestamp_millis() as f32, range)
+            self.adjust_style(style, self.options.age_value(file_time), range)
         } else {
             style
         }
@@ -114,19 +220,23 @@ fn update_information_recursively(
     for file in files {
         if information.options.age {
             Extremes::update(
-                file.created_time().map(|x| x.timestamp_millis() as f32),
+                file.created_time()
+                    .map(|x| information.options.age_value(x)),
                 &mut information.created,
             );
             Extremes::update(
-                file.modified_time().map(|x| x.timestamp_millis() as f32),
+                file.modified_time()
+                    .map(|x| information.options.age_value(x)),
                 &mut information.modified,
             );
             Extremes::update(
-                file.accessed_time().map(|x| x.timestamp_millis() as f32),
+                file.accessed_time()
+                    .map(|x| information.options.age_value(x)),
                 &mut information.accessed,
             );
             Extremes::update(
-                file.changed_time().map(|x| x.timestamp_millis() as f32),
+                file.changed_time()
+                    .map(|x| information.options.age_value(x)),
                 &mut information.changed,
             );
         }
@@ -141,8 +251,7 @@ fn update_information_recursively(
 
         // We don't want to recurse into . and .., but still want to list them, therefore bypass
         // the dot_filter.
-        if file.is_directory()
-            && r.is_some_and(|x| !x.is_too_deep(depth.0))
+        if r.is_some_and(|x| !x.is_too_deep(depth.0) && x.descends_into(file))
             && file.name != "."
             && file.name != ".."
         {
@@ -176,6 +285,11 @@ pub struct Extremes {
 }
 
 impl Extremes {
+    /// The value the given ratio of the way along this range.
+    fn at(self, ratio: f32) -> f64 {
+        f64::from(self.min) + f64::from(self.max - self.min) * f64::from(ratio)
+    }
+
     fn update(maybe_value: Option<f32>, maybe_range: &mut Option<Extremes>) {
         match (maybe_value, maybe_range) {
             (Some(value), Some(range)) => {
@@ -198,6 +312,16 @@ impl Extremes {
     }
 }
 
+/// Adjusts the brightness of a style’s foreground colour for where a value
+/// is on a scale, given as a ratio between 0 and 1.
+pub fn scale_style(mut style: Style, ratio: f32, min_luminance: isize) -> Style {
+    if let Some(fg) = style.foreground {
+        style.foreground = Some(adjust_luminance(fg, ratio, min_luminance as f32 / 100.0));
+    }
+
+    style
+}
+
 fn adjust_luminance(color: Colour, x: f32, min_l: f32) -> Colour {
     let color = Srgb::from_components(color.into_rgb()).into_linear();
 
@@ -205,9 +329,9 @@ fn adjust_luminance(color: Colour, x: f32, min_l: f32) -> Colour {
     lab.l = (min_l + (1.0 - min_l) * (-4.0 * (1.0 - x)).exp()).clamp(0.0, 1.0);
 
     let adjusted_rgb: Srgb<f32> = Srgb::from_color(lab);
-    Colour::RGB(
+    capabilities::downgrade(Colour::RGB(
         (adjusted_rgb.red * 255.0).round() as u8,
         (adjusted_rgb.green * 255.0).round() as u8,
         (adjusted_rgb.blue * 255.0).round() as u8,
-    )
+    ))
 }
@@ -0,0 +1,45 @@
+//! The parts drawn around a file’s name, and which order they come in.
+//!
+//! By default, a file’s icon comes before its name and its classify
+//! character after it, but the `decorations` configuration file can put
+//! them in a different order or leave some of them out. The order is
+//! carried in the file name options, so it gets read from wherever file
+//! names are drawn.
+
+/// One of the parts drawn for a file, besides the extra details after it
+/// such as mount information.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Decoration {
+    /// The icon, when icons are being shown.
+    Icon,
+
+    /// The name itself, along with its parent path and a symlink’s target
+    /// when they’re shown.
+    Name,
+
+    /// The classify character, when files are being classified.
+    Classify,
+
+    /// The hyperlink around the name, when names are made into links. This
+    /// always surrounds the name, so only whether it’s there matters, not
+    /// where it comes.
+    Hyperlink,
+}
+
+impl Decoration {
+    /// The decorations in the order they’re drawn in by default.
+    pub fn default_order() -> Vec<Self> {
+        vec![Self::Icon, Self::Name, Self::Classify, Self::Hyperlink]
+    }
+
+    /// The decoration with the given name in the configuration file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "icon" => Some(Self::Icon),
+            "name" => Some(Self::Name),
+            "classify" => Some(Self::Classify),
+            "hyperlink" => Some(Self::Hyperlink),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! The CSV and TSV views, for `--format`, which print one row per file for
+//! importing into spreadsheets and databases or picking apart with `awk`.
+//!
+//! The first row names the fields, and the files come after it in the same
+//! order as in the lines view, including the contents of every directory
+//! that `--recurse` descends into, each with its full path. Everything is
+//! written as plain text, without colours or any of the formatting meant for
+//! people to read: sizes are in bytes, and times are in UTC, in ISO 8601
+//! format. Anything a file doesn’t have is left empty.
+//!
+//! CSV fields are quoted as in RFC 4180 when they have a comma, a quote, or
+//! a line break in them. TSV fields can’t be quoted, so tabs, line breaks
+//! and backslashes in them are escaped with backslashes instead, the same
+//! way as in manifests.
+//!
+//! Unless `--no-metadata` is given, the header row comes after lines starting
+//! with `#` that record how the listing was made, as the JSON view does, with
+//! each line’s values quoted or escaped like the fields of a row:
+//!
+//! ```text
+//! # version: 0.18.3
+//! # time: 2024-01-01T12:00:00Z
+//! # roots: src,tests
+//! # filters: --only-dirs
+//! name,path,type,…
+//! ```
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+#[cfg(unix)]
+use uzers::{Groups, Users, UsersCache};
+
+use crate::diagnostics;
+use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
+use crate::fs::filter::FileFilter;
+use crate::fs::manifest::escape;
+use crate::fs::{Dir, File};
+use crate::output::json::{type_name, Metadata};
+use crate::output::relative_root;
+
+/// Which format to print, and which fields to print for each file.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Options {
+    pub format: Format,
+    pub fields: Vec<Field>,
+}
+
+/// What separates the fields in each row.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Format {
+    /// Comma-separated values, quoted where they need to be.
+    Csv,
+
+    /// Tab-separated values, escaped where they need to be.
+    Tsv,
+}
+
+/// Something about a file that can be printed as one of its fields.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Field {
+    Name,
+    Path,
+    Type,
+    Size,
+    Permissions,
+    Links,
+    Inode,
+    User,
+    Group,
+    Accessed,
+    Changed,
+    Created,
+    Modified,
+    Git,
+}
+
+/// Every field, in the order they’re printed in when `--fields` isn’t
+/// given.
+pub static ALL_FIELDS: &[Field] = &[
+    Field::Name,
+    Field::Path,
+    Field::Type,
+    Field::Size,
+    Field::Permissions,
+    Field::Links,
+    Field::Inode,
+    Field::User,
+    Field::Group,
+    Field::Accessed,
+    Field::Changed,
+    Field::Created,
+    Field::Modified,
+    Field::Git,
+];
+
+impl Field {
+    /// The field with the given name in `ALL_FIELDS`, if there is one.
+    pub fn named(name: &str) -> Option<Self> {
+        ALL_FIELDS.iter().copied().find(|f| f.name() == name)
+    }
+
+    /// The name this field is given by `--fields` and in the header row,
+    /// which is the same as the column it matches in the details view.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Path => "path",
+            Self::Type => "type",
+            Self::Size => "size",
+            Self::Permissions => "permissions",
+            Self::Links => "links",
+            Self::Inode => "inode",
+            Self::User => "user",
+            Self::Group => "group",
+            Self::Accessed => "accessed",
+            Self::Changed => "changed",
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Git => "git",
+        }
+    }
+}
+
+pub struct Render<'a> {
+    /// The files given as arguments that are listed as themselves.
+    pub files: Vec<File<'a>>,
+
+    /// The directories given as arguments that have their contents listed
+    /// instead of themselves.
+    pub dirs: Vec<Dir>,
+
+    pub opts: &'a Options,
+
+    /// How to sort and filter the files inside directories.
+    pub filter: &'a FileFilter,
+
+    /// Whether to recurse into directories.
+    pub recurse: Option<RecurseOptions>,
+
+    pub git: Option<&'a GitCache>,
+
+    /// Whether we are skipping Git-ignored files.
+    pub git_ignoring: bool,
+
+    pub deref_links: bool,
+
+    /// Whether directories’ sizes are the total size of everything in them.
+    pub total_size: bool,
+
+    /// The directory to show paths as if it were the root of the
+    /// filesystem, if there is one.
+    pub relative_root: Option<&'a Path>,
+
+    /// What to record about how the listing was made, if anything.
+    pub metadata: Option<Metadata<'a>>,
+
+    /// The users and groups that own files, looked up by ID.
+    #[cfg(unix)]
+    pub users: UsersCache,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files, self.git);
+
+        if let Some(metadata) = &self.metadata {
+            write_metadata(w, self.opts.format, metadata)?;
+        }
+
+        let header = self.opts.fields.iter().map(|field| field.name().to_owned());
+        write_row(w, self.opts.format, header)?;
+
+        for file in &self.files {
+            self.write_file(w, file)?;
+        }
+
+        for dir in &self.dirs {
+            self.write_contents(w, dir, 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the contents of a directory, followed by the contents of its
+    /// subdirectories if recursing.
+    fn write_contents<W: Write>(&self, w: &mut W, dir: &Dir, depth: usize) -> io::Result<()> {
+        let children = self.children(dir);
+
+        for child in &children {
+            self.write_file(w, child)?;
+        }
+
+        if let Some(recurse) = self.recurse {
+            if !recurse.is_too_deep(depth) {
+                for child in children
+                    .iter()
+                    .filter(|f| recurse.descends_into(f) && !f.is_all_all)
+                {
+                    match child.to_dir() {
+                        Ok(child_dir) => self.write_contents(w, &child_dir, depth + 1)?,
+                        Err(e) => {
+                            diagnostics::warning(format_args!("{}: {}", child.path.display(), e));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_file<W: Write>(&self, w: &mut W, file: &File<'_>) -> io::Result<()> {
+        let values = self
+            .opts
+            .fields
+            .iter()
+            .map(|field| self.value(*field, file));
+        write_row(w, self.opts.format, values)
+    }
+
+    /// One of a file’s fields, before it gets quoted.
+    fn value(&self, field: Field, file: &File<'_>) -> String {
+        match field {
+            Field::Name => file.name.clone(),
+            Field::Path => relative_root::display(self.relative_root, &file.path)
+                .to_string_lossy()
+                .into_owned(),
+            Field::Type => type_name(file.type_char()).into(),
+            Field::Size => match file.size() {
+                f::Size::Some(bytes) => bytes.to_string(),
+                f::Size::None | f::Size::Virtual | f::Size::DeviceIDs(_) => String::new(),
+            },
+            Field::Accessed => timestamp(file.accessed_time()),
+            Field::Changed => timestamp(file.changed_time()),
+            Field::Created => timestamp(file.created_time()),
+            Field::Modified => timestamp(file.modified_time()),
+            Field::Git => self.git_status(file),
+            Field::Permissions | Field::Links | Field::Inode | Field::User | Field::Group => {
+                self.ownership(field, file)
+            }
+        }
+    }
+
+    /// The permissions, link count, inode, owner or group of a file, with
+    /// owners named where their IDs belong to anyone.
+    #[cfg(unix)]
+    fn ownership(&self, field: Field, file: &File<'_>) -> String {
+        match field {
+            Field::Permissions => file
+                .permissions()
+                .map_or_else(String::new, |p| format!("{:04o}", p.bits())),
+            Field::Links => file.links().count.to_string(),
+            Field::Inode => file.inode().0.to_string(),
+            Field::User => file.user().map_or_else(String::new, |user| {
+                self.users
+                    .get_user_by_uid(user.0)
+                    .map_or_else(|| user.0.to_string(), |u| u.name().to_string_lossy().into())
+            }),
+            Field::Group => file.group().map_or_else(String::new, |group| {
+                self.users.get_group_by_gid(group.0).map_or_else(
+                    || group.0.to_string(),
+                    |g| g.name().to_string_lossy().into(),
+                )
+            }),
+            _ => unreachable!("{field:?} is not an ownership field"),
+        }
+    }
+
+    /// Files on Windows have none of the Unix ownership fields.
+    #[cfg(windows)]
+    fn ownership(&self, _field: Field, _file: &File<'_>) -> String {
+        String::new()
+    }
+
+    /// A file’s staged and unstaged Git status, as the two characters the
+    /// details view shows, or nothing if it isn’t in a Git repository.
+    fn git_status(&self, file: &File<'_>) -> String {
+        let Some(git) = self.git.filter(|g| g.has_anything_for(&file.path)) else {
+            return String::new();
+        };
+
+        let status = git.get(&file.path, file.is_directory());
+        format!(
+            "{}{}",
+            git_status_char(status.staged),
+            git_status_char(status.unstaged)
+        )
+    }
+
+    /// The files in a directory, filtered and sorted.
+    fn children<'dir>(&self, dir: &'dir Dir) -> Vec<File<'dir>> {
+        let mut children = Vec::new();
+
+        for file in dir.files(
+            self.filter.dot_filter,
+            self.git,
+            self.git_ignoring,
+            self.deref_links,
+            self.total_size,
+        ) {
+            match file {
+                Ok(file) => children.push(file),
+                Err((path, e)) => {
+                    diagnostics::warning(format_args!("[{}: {}]", path.display(), e));
+                }
+            }
+        }
+
+        self.filter.filter_child_files(&mut children, self.git);
+        self.filter.sort_files(&mut children, self.git);
+        children
+    }
+}
+
+/// A timestamp in UTC, in ISO 8601 format, or nothing if the file doesn’t
+/// have it.
+fn timestamp(time: Option<NaiveDateTime>) -> String {
+    time.map_or_else(String::new, |time| {
+        time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    })
+}
+
+/// The character the details view shows for a Git status.
+fn git_status_char(status: f::GitStatus) -> char {
+    match status {
+        f::GitStatus::NotModified => '-',
+        f::GitStatus::New => 'N',
+        f::GitStatus::Modified => 'M',
+        f::GitStatus::Deleted => 'D',
+        f::GitStatus::Renamed => 'R',
+        f::GitStatus::TypeChange => 'T',
+        f::GitStatus::Ignored => 'I',
+        f::GitStatus::Conflicted => 'U',
+    }
+}
+
+/// Writes one row of values, quoted or escaped for the format.
+fn write_row<W: Write>(
+    w: &mut W,
+    format: Format,
+    values: impl Iterator<Item = String>,
+) -> io::Result<()> {
+    let (separator, quote): (&str, fn(&str) -> String) = match format {
+        Format::Csv => (",", csv_field),
+        Format::Tsv => ("\t", tsv_field),
+    };
+
+    let row = values.map(|value| quote(&value)).collect::<Vec<_>>();
+    writeln!(w, "{}", row.join(separator))
+}
+
+/// Writes each piece of metadata on a line of its own, starting with `#`
+/// and its name, so it can’t be mistaken for the header row.
+fn write_metadata<W: Write>(w: &mut W, format: Format, metadata: &Metadata<'_>) -> io::Result<()> {
+    for (name, values) in metadata.entries() {
+        write!(w, "# {name}: ")?;
+        write_row(w, format, values.into_iter())?;
+    }
+
+    Ok(())
+}
+
+/// Quotes a CSV field if it has anything in it that would otherwise end it
+/// early, doubling any quotes inside.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Escapes a TSV field, which has no way of being quoted.
+fn tsv_field(value: &str) -> String {
+    escape(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn csv_plain() {
+        assert_eq!(csv_field("Cargo.toml"), "Cargo.toml");
+    }
+
+    #[test]
+    fn csv_quoted() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn tsv_escaped() {
+        assert_eq!(tsv_field("a\tb\\c\nd"), "a\\tb\\\\c\\nd");
+    }
+
+    #[test]
+    fn metadata_lines() {
+        let roots = [std::ffi::OsStr::new("src"), std::ffi::OsStr::new("a,b")];
+        let filters = [String::from("--only-dirs")];
+        let metadata = Metadata {
+            roots: &roots,
+            filters: &filters,
+        };
+
+        let mut csv = Vec::new();
+        write_metadata(&mut csv, Format::Csv, &metadata).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], concat!("# version: ", env!("CARGO_PKG_VERSION")));
+        assert!(lines[1].starts_with("# time: "));
+        assert_eq!(lines[2], "# roots: src,\"a,b\"");
+        assert_eq!(lines[3], "# filters: --only-dirs");
+    }
+
+    #[test]
+    fn fields_by_name() {
+        for field in ALL_FIELDS {
+            assert_eq!(Field::named(field.name()), Some(*field));
+        }
+        assert_eq!(Field::named("colour"), None);
+    }
+}
@@ -65,20 +65,26 @@ use std::vec::IntoIter as VecIntoIter;
 
 use ansiterm::Style;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use unicode_width::UnicodeWidthStr;
 
 use log::*;
 
+use crate::fs::compare::Comparison;
 use crate::fs::dir_action::RecurseOptions;
 use crate::fs::feature::git::GitCache;
 use crate::fs::feature::xattr::Attribute;
-use crate::fs::fields::SecurityContextType;
+use crate::fs::fields::{GitStatus, SecurityContextType, Size};
 use crate::fs::filter::FileFilter;
 use crate::fs::{Dir, File};
 use crate::output::cell::TextCell;
 use crate::output::color_scale::{ColorScaleInformation, ColorScaleOptions};
 use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Options as TableOptions, Row as TableRow, Table};
-use crate::output::tree::{TreeDepth, TreeParams, TreeTrunk};
+use crate::output::glyphs::Glyphs;
+use crate::output::highlight;
+use crate::output::layout_debug;
+use crate::output::stats;
+use crate::output::table::{Alignment, Options as TableOptions, Row as TableRow, Table};
+use crate::output::tree::{TreeDepth, TreeParams, TreeSummary, TreeTrunk};
 use crate::theme::Theme;
 
 /// With the **Details** view, the output gets formatted into columns, with
@@ -114,6 +120,10 @@ pub struct Options {
     /// Whether to show a directory's mounted filesystem details
     pub mounts: bool,
 
+    /// Whether to show how many files are beneath each directory in a
+    /// tree, and how big they are, after its name.
+    pub tree_summary: bool,
+
     pub color_scale: ColorScaleOptions,
 }
 
@@ -137,15 +147,50 @@ pub struct Render<'a> {
 
     pub git: Option<&'a GitCache>,
 
+    /// The comparison for the diff column, if there is one.
+    pub diff: Option<&'a Comparison>,
+
     pub git_repos: bool,
 }
 
+/// A state a file can be in that tints every cell of its row, rather than
+/// only its name, with a style from the theme.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum RowState {
+    /// An executable file.
+    Executable,
+
+    /// A file that Git ignores, when Git is being used.
+    Ignored,
+
+    /// A symlink whose target doesn’t exist.
+    BrokenLink,
+
+    /// A file modified recently enough to be highlighted.
+    Recent,
+
+    /// A file whose name matches the `--highlight` pattern.
+    Highlighted,
+}
+
+impl RowState {
+    /// Every state, in the order their overlays are applied, so the later
+    /// ones win where they clash.
+    const ALL: [Self; 5] = [
+        Self::Executable,
+        Self::Ignored,
+        Self::BrokenLink,
+        Self::Recent,
+        Self::Highlighted,
+    ];
+}
+
 #[rustfmt::skip]
 struct Egg<'a> {
     table_row: Option<TableRow>,
     xattrs:    &'a [Attribute],
-    errors:    Vec<(io::Error, Option<PathBuf>)>,
     dir:       Option<Dir>,
+    dir_error: Option<io::Error>,
     file:      &'a File<'a>,
 }
 
@@ -167,6 +212,9 @@ impl<'a> Render<'a> {
             self.git_ignoring,
             self.recurse,
         );
+        if let Some(info) = &color_scale_info {
+            info.dump(self.theme);
+        }
 
         if let Some(ref table) = self.opts.table {
             match (self.git, self.dir) {
@@ -183,7 +231,29 @@ impl<'a> Render<'a> {
                 (None, _) => { /* Keep Git how it is */ }
             }
 
-            let mut table = Table::new(table, self.git, self.theme, self.git_repos);
+            if table.columns.git && self.git.is_none() {
+                layout_debug::note(
+                    "dropped the git column, as nothing here is in a Git repository",
+                );
+            }
+
+            let mut table = Table::new(
+                table,
+                self.git,
+                self.diff,
+                self.theme,
+                self.git_repos,
+                self.file_style,
+            );
+
+            // Duplicates are looked for across the whole tree at once, but
+            // each directory listed by --recurse is a listing of its own.
+            table.find_dupes(
+                &self.files,
+                self.filter.dot_filter,
+                self.git_ignoring,
+                self.recurse.filter(|r| r.tree),
+            );
 
             if self.opts.header {
                 let header = table.header_row();
@@ -194,7 +264,7 @@ impl<'a> Render<'a> {
             // This is weird, but I can’t find a way around it:
             // https://internals.rust-lang.org/t/should-option-mut-t-implement-copy/3715/6
             let mut table = Some(table);
-            self.add_files_to_table(
+            let _ = self.add_files_to_table(
                 &mut table,
                 &mut rows,
                 &self.files,
@@ -202,11 +272,14 @@ impl<'a> Render<'a> {
                 color_scale_info,
             );
 
-            for row in self.iterate_with_table(table.unwrap(), rows) {
+            let table = table.unwrap();
+            table.note_layout();
+
+            for row in self.iterate_with_table(table, rows) {
                 writeln!(w, "{}", row.strings())?;
             }
         } else {
-            self.add_files_to_table(
+            let _ = self.add_files_to_table(
                 &mut None,
                 &mut rows,
                 &self.files,
@@ -235,8 +308,59 @@ impl<'a> Render<'a> {
         xattr_count > 1 || (xattr_count == 1 && !selinux_ctx_shown)
     }
 
+    /// The table row for a file, overlaid with the style for each state the
+    /// file is in that tints its whole row.
+    pub fn table_row(
+        &self,
+        table: &Table<'_>,
+        file: &File<'_>,
+        color_scale_info: Option<ColorScaleInformation>,
+    ) -> TableRow {
+        let mut row = table.row_for_file(file, self.show_xattr_hint(file), color_scale_info);
+        for state in RowState::ALL {
+            // Most overlays are empty, so skip working out the state.
+            let overlay = self.row_overlay(state);
+            if overlay != Style::default() && self.is_in_state(file, state) {
+                row.overlay(overlay);
+            }
+        }
+        row
+    }
+
+    /// Whether a file is in the given state.
+    fn is_in_state(&self, file: &File<'_>, state: RowState) -> bool {
+        match state {
+            RowState::Executable => file.is_executable_file(),
+            RowState::Ignored => self.git.is_some_and(|git| {
+                git.has_anything_for(&file.path)
+                    && git.get(&file.path, file.is_directory()).unstaged == GitStatus::Ignored
+            }),
+            RowState::BrokenLink => file.is_link() && file.link_target().is_broken(),
+            RowState::Recent => self.file_style.is_recent(file),
+            RowState::Highlighted => {
+                !highlight::matches(self.file_style.highlight.as_ref(), &file.name).is_empty()
+            }
+        }
+    }
+
+    /// The style that tints the row of a file in the given state.
+    fn row_overlay(&self, state: RowState) -> Style {
+        let ui = &self.theme.ui;
+        match state {
+            RowState::Executable => ui.executable_row_overlay,
+            RowState::Ignored => ui.ignored_row_overlay,
+            RowState::BrokenLink => ui.broken_row_overlay,
+            RowState::Recent => ui.recent_overlay,
+            RowState::Highlighted => ui.highlight_row_overlay,
+        }
+    }
+
     /// Adds files to the table, possibly recursively. This is easily
     /// parallelisable, and uses a pool of threads.
+    ///
+    /// Returns how many files were added beneath directories, and how big
+    /// they are, so each directory’s summary can be added to its row once
+    /// everything under it has been.
     fn add_files_to_table<'dir>(
         &self,
         table: &mut Option<Table<'a>>,
@@ -244,14 +368,16 @@ impl<'a> Render<'a> {
         src: &[File<'dir>],
         depth: TreeDepth,
         color_scale_info: Option<ColorScaleInformation>,
-    ) {
+    ) -> TreeSummary {
         use crate::fs::feature::xattr;
 
+        if let Some(table) = table.as_mut() {
+            table.rank_ages(src);
+        }
+
         let mut file_eggs: Vec<_> = src
             .par_iter()
             .map(|file| {
-                let mut errors = Vec::new();
-
                 // There are three “levels” of extended attribute support:
                 //
                 // 1. If we’re compiling without that feature, then
@@ -282,18 +408,19 @@ impl<'a> Render<'a> {
 
                 let table_row = table
                     .as_ref()
-                    .map(|t| t.row_for_file(file, self.show_xattr_hint(file), color_scale_info));
+                    .map(|t| self.table_row(t, file, color_scale_info));
 
                 let mut dir = None;
+                let mut dir_error = None;
                 if let Some(r) = self.recurse {
-                    if file.is_directory() && r.tree && !r.is_too_deep(depth.0) {
+                    if r.tree && !r.is_too_deep(depth.0) && r.descends_into(file) {
                         trace!("matching on to_dir");
                         match file.to_dir() {
                             Ok(d) => {
                                 dir = Some(d);
                             }
                             Err(e) => {
-                                errors.push((e, None));
+                                dir_error = Some(e);
                             }
                         }
                     }
@@ -302,38 +429,59 @@ impl<'a> Render<'a> {
                 Egg {
                     table_row,
                     xattrs,
-                    errors,
                     dir,
+                    dir_error,
                     file,
                 }
             })
             .collect();
 
         // this is safe because all entries have been initialized above
-        self.filter.sort_files(&mut file_eggs);
+        self.filter.sort_files(&mut file_eggs, self.git);
+
+        let mut summary = TreeSummary::default();
 
         for (tree_params, egg) in depth.iterate_over(file_eggs.into_iter()) {
             let mut files = Vec::new();
-            let mut errors = egg.errors;
+            let mut errors = Vec::new();
 
             if let (Some(ref mut t), Some(row)) = (table.as_mut(), egg.table_row.as_ref()) {
                 t.add_widths(row);
             }
 
-            let file_name = self
+            let mut file_name = self
                 .file_style
                 .for_file(egg.file, self.theme)
                 .with_link_paths()
                 .with_mount_details(self.opts.mounts)
+                .with_empty_dir(egg.dir.as_ref().is_some_and(Dir::is_empty))
+                .with_depth(depth.0)
                 .paint()
                 .promote();
 
+            // A directory that can’t be opened gets a note after its name,
+            // rather than an error line underneath it, so the tree carries
+            // on being drawn around it.
+            if let Some(error) = &egg.dir_error {
+                let note = format!(" [{}]", unreadable_note(error));
+                let width = note.width();
+                file_name.push(self.theme.ui.unreadable_dir.paint(note), width);
+            }
+
+            if egg.dir.is_none() && !egg.file.is_directory() {
+                summary.add_file(match egg.file.size() {
+                    Size::Some(bytes) => Some(bytes),
+                    _ => None,
+                });
+            }
+
             let row = Row {
                 tree: tree_params,
                 cells: egg.table_row,
                 name: file_name,
             };
 
+            let row_index = rows.len();
             rows.push(row);
 
             if let Some(ref dir) = egg.dir {
@@ -354,7 +502,8 @@ impl<'a> Render<'a> {
                     }
                 }
 
-                self.filter.filter_child_files(&mut files);
+                self.filter.filter_child_files(&mut files, self.git);
+                stats::record(&files);
 
                 if !files.is_empty() {
                     for xattr in egg.xattrs {
@@ -369,9 +518,19 @@ impl<'a> Render<'a> {
                         ));
                     }
 
-                    self.add_files_to_table(table, rows, &files, depth.deeper(), color_scale_info);
+                    let beneath = self.add_files_to_table(
+                        table,
+                        rows,
+                        &files,
+                        depth.deeper(),
+                        color_scale_info,
+                    );
+                    summary.add(beneath);
+                    self.add_summary(&mut rows[row_index], beneath);
                     continue;
                 }
+
+                self.add_summary(&mut rows[row_index], TreeSummary::default());
             }
 
             let count = egg.xattrs.len();
@@ -389,6 +548,18 @@ impl<'a> Render<'a> {
                 rows.push(r);
             }
         }
+
+        summary
+    }
+
+    /// Adds the summary of what’s beneath a directory after its name, if
+    /// summaries are being shown.
+    fn add_summary(&self, row: &mut Row, summary: TreeSummary) {
+        if self.opts.tree_summary {
+            let note = format!(" {}", summary.note());
+            let width = note.width();
+            row.name.push(self.theme.ui.tree_summary.paint(note), width);
+        }
     }
 
     pub fn render_header(&self, header: TableRow) -> Row {
@@ -436,20 +607,30 @@ impl<'a> Render<'a> {
     }
 
     pub fn iterate_with_table(&'a self, table: Table<'a>, rows: Vec<Row>) -> TableIter<'a> {
+        let name_width = match table.name_alignment() {
+            Alignment::Left if !table.name_first() => 0,
+            _ => rows.iter().map(Row::name_width).max().unwrap_or(0),
+        };
+
         TableIter {
             tree_trunk: TreeTrunk::default(),
-            total_width: table.widths().total(),
+            total_width: table.total_width(),
+            name_width,
             table,
             inner: rows.into_iter(),
-            tree_style: self.theme.ui.punctuation,
+            theme: self.theme,
+            glyphs: &self.file_style.glyphs,
+            depth_lines: self.file_style.depth_colours.lines(),
         }
     }
 
-    pub fn iterate(&'a self, rows: Vec<Row>) -> Iter {
+    pub fn iterate(&'a self, rows: Vec<Row>) -> Iter<'a> {
         Iter {
             tree_trunk: TreeTrunk::default(),
             inner: rows.into_iter(),
-            tree_style: self.theme.ui.punctuation,
+            theme: self.theme,
+            glyphs: &self.file_style.glyphs,
+            depth_lines: self.file_style.depth_colours.lines(),
         }
     }
 }
@@ -471,13 +652,46 @@ pub struct Row {
     pub tree: TreeParams,
 }
 
+impl Row {
+    /// The width of this row’s file name, along with the tree parts drawn
+    /// before it and the space after them.
+    fn name_width(&self) -> usize {
+        let tree_width = match self.tree.depth().0 {
+            0 => 0,
+            depth => depth * 4 + 1,
+        };
+
+        tree_width + *self.name.width
+    }
+}
+
+/// The style to paint the tree part in the given column with, which is the
+/// style for that column’s depth when lines are coloured by depth.
+fn tree_part_style(theme: &Theme, depth_lines: bool, column: usize) -> Style {
+    depth_lines
+        .then(|| theme.ui.depth_style(column + 1))
+        .flatten()
+        .unwrap_or(theme.ui.punctuation)
+}
+
+/// What to say about why a directory couldn’t be opened.
+fn unreadable_note(error: &io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::PermissionDenied => "permission denied".into(),
+        _ => error.to_string(),
+    }
+}
+
 #[rustfmt::skip]
 pub struct TableIter<'a> {
     inner: VecIntoIter<Row>,
     table: Table<'a>,
 
     total_width: usize,
-    tree_style:  Style,
+    name_width:  usize,
+    theme:       &'a Theme,
+    glyphs:      &'a Glyphs,
+    depth_lines: bool,
     tree_trunk:  TreeTrunk,
 }
 
@@ -486,45 +700,77 @@ impl<'a> Iterator for TableIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|row| {
-            let mut cell = if let Some(cells) = row.cells {
-                self.table.render(cells)
-            } else {
-                let mut cell = TextCell::default();
-                cell.add_spaces(self.total_width);
-                cell
-            };
+            let padding = self.name_width.saturating_sub(row.name_width());
+            let right_aligned = self.table.name_alignment() == Alignment::Right;
+
+            let mut name = TextCell::default();
+
+            // Right-aligned names are padded on the left, before any tree
+            // parts, so the tree stays attached to its names.
+            if right_aligned {
+                name.add_spaces(padding);
+            }
 
-            for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
+            for (column, tree_part) in self.tree_trunk.new_row(row.tree).iter().enumerate() {
+                let art = tree_part.ascii_art(self.glyphs);
+                let style = tree_part_style(self.theme, self.depth_lines, column);
+                name.push(style.paint(art.to_owned()), art.width() + 1);
             }
 
             // If any tree characters have been printed, then add an extra
             // space, which makes the output look much better.
             if !row.tree.is_at_root() {
-                cell.add_spaces(1);
+                name.add_spaces(1);
             }
 
-            cell.append(row.name);
+            name.append(row.name);
+
+            if self.table.name_first() {
+                // Names come first, padded out to line the columns up after
+                // them. Rows without cells, such as errors, have nothing to
+                // line up, so they don’t get padded.
+                if let Some(cells) = row.cells {
+                    if !right_aligned {
+                        name.add_spaces(padding);
+                    }
+                    name.append(self.table.render_after_name(cells));
+                }
+                return name;
+            }
+
+            let mut cell = if let Some(cells) = row.cells {
+                self.table.render(cells)
+            } else {
+                let mut cell = TextCell::default();
+                cell.add_spaces(self.total_width);
+                cell
+            };
+
+            cell.append(name);
             cell
         })
     }
 }
 
-pub struct Iter {
+pub struct Iter<'a> {
     tree_trunk: TreeTrunk,
-    tree_style: Style,
+    theme: &'a Theme,
+    glyphs: &'a Glyphs,
+    depth_lines: bool,
     inner: VecIntoIter<Row>,
 }
 
-impl Iterator for Iter {
+impl Iterator for Iter<'_> {
     type Item = TextCell;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|row| {
             let mut cell = TextCell::default();
 
-            for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
+            for (column, tree_part) in self.tree_trunk.new_row(row.tree).iter().enumerate() {
+                let art = tree_part.ascii_art(self.glyphs);
+                let style = tree_part_style(self.theme, self.depth_lines, column);
+                cell.push(style.paint(art.to_owned()), art.width() + 1);
             }
 
             // If any tree characters have been printed, then add an extra
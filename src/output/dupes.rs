@@ -0,0 +1,196 @@
+//! Spotting files with the same contents, for `--find-dupes`.
+//!
+//! Every regular file in a listing, including those further down a tree, is
+//! grouped by its size first, which rules most files out without having to
+//! read them. The files that share their size with another are then hashed
+//! the same way as manifests hash them, in parallel, on the pool of threads
+//! that the rows of the details view are made on, and only the files whose
+//! hashes match as well end up in the same group. With `--find-dupes=size`,
+//! the hashing is skipped, and files of the same size are taken to be the
+//! same. Empty files are never counted as duplicates of each other.
+//!
+//! Groups are numbered from 1, in the order of the path of the first file in
+//! each, so the numbers stay the same however the listing is sorted.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
+use crate::fs::manifest::hash_contents;
+use crate::fs::{DotFilter, File};
+use crate::output::tree::TreeDepth;
+
+/// How to decide whether two files are duplicates.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum DupeCheck {
+    /// Files of the same size are taken to be duplicates.
+    Size,
+
+    /// Files are only duplicates if their contents hash the same as well.
+    Contents,
+}
+
+/// Groups the given files, and the files in any directories being recursed
+/// into, by their contents, returning the group of each file that has at
+/// least one duplicate.
+pub fn find(
+    check: DupeCheck,
+    files: &[File<'_>],
+    dot_filter: DotFilter,
+    git: Option<&GitCache>,
+    git_ignoring: bool,
+    r: Option<RecurseOptions>,
+) -> HashMap<PathBuf, f::DupeGroup> {
+    let mut sizes = Vec::new();
+    gather_sizes(
+        &mut sizes,
+        files,
+        dot_filter,
+        git,
+        git_ignoring,
+        TreeDepth::root(),
+        r,
+    );
+
+    match check {
+        DupeCheck::Size => groups(sizes, |_| Some(0)),
+        DupeCheck::Contents => groups(sizes, |path| hash_contents(path).ok()),
+    }
+}
+
+/// Collects the path and size of every regular file, descending into
+/// directories the same way as the listing does.
+fn gather_sizes(
+    sizes: &mut Vec<(PathBuf, u64)>,
+    files: &[File<'_>],
+    dot_filter: DotFilter,
+    git: Option<&GitCache>,
+    git_ignoring: bool,
+    depth: TreeDepth,
+    r: Option<RecurseOptions>,
+) {
+    for file in files {
+        if file.is_file() && file.metadata.len() > 0 {
+            sizes.push((file.path.clone(), file.metadata.len()));
+        }
+
+        if r.is_some_and(|x| !x.is_too_deep(depth.0) && x.descends_into(file)) && !file.is_all_all {
+            if let Ok(dir) = file.to_dir() {
+                let files: Vec<File<'_>> = dir
+                    .files(dot_filter, git, git_ignoring, false, false)
+                    .flatten()
+                    .collect();
+
+                gather_sizes(
+                    sizes,
+                    &files,
+                    dot_filter,
+                    git,
+                    git_ignoring,
+                    depth.deeper(),
+                    r,
+                );
+            }
+        }
+    }
+}
+
+/// Numbers the groups of files that have the same size and the same hash,
+/// hashing only the files that share their size with another. Files that
+/// can’t be hashed aren’t put in any group.
+fn groups(
+    sizes: Vec<(PathBuf, u64)>,
+    hash: impl Fn(&Path) -> Option<u64> + Sync,
+) -> HashMap<PathBuf, f::DupeGroup> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for (path, size) in sizes {
+        by_size.entry(size).or_default().push(path);
+    }
+
+    let candidates: Vec<_> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (size, path)))
+        .collect();
+
+    let hashed: Vec<_> = candidates
+        .into_par_iter()
+        .filter_map(|(size, path)| Some(((size, hash(&path)?), path)))
+        .collect();
+
+    let mut by_contents: HashMap<(u64, u64), Vec<PathBuf>> = HashMap::new();
+    for (key, path) in hashed {
+        by_contents.entry(key).or_default().push(path);
+    }
+
+    let mut dupes: Vec<Vec<PathBuf>> = by_contents
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(|mut paths| {
+            paths.sort();
+            paths
+        })
+        .collect();
+    dupes.sort();
+
+    dupes
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, paths)| {
+            paths
+                .into_iter()
+                .map(move |path| (path, f::DupeGroup(index + 1)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sizes(files: &[(&str, u64)]) -> Vec<(PathBuf, u64)> {
+        files
+            .iter()
+            .map(|(path, size)| (PathBuf::from(path), *size))
+            .collect()
+    }
+
+    #[test]
+    fn same_size_and_hash() {
+        let found = groups(sizes(&[("b", 10), ("a", 10), ("c", 20)]), |_| Some(1));
+        assert_eq!(found.get(Path::new("a")), Some(&f::DupeGroup(1)));
+        assert_eq!(found.get(Path::new("b")), Some(&f::DupeGroup(1)));
+        assert_eq!(found.get(Path::new("c")), None);
+    }
+
+    #[test]
+    fn same_size_different_hash() {
+        let found = groups(sizes(&[("a", 10), ("b", 10)]), |path| {
+            Some(u64::from(path == Path::new("a")))
+        });
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn numbered_by_first_path() {
+        let found = groups(sizes(&[("z", 10), ("y", 20), ("x", 20), ("w", 10)]), |_| {
+            Some(1)
+        });
+        assert_eq!(found.get(Path::new("w")), Some(&f::DupeGroup(1)));
+        assert_eq!(found.get(Path::new("z")), Some(&f::DupeGroup(1)));
+        assert_eq!(found.get(Path::new("x")), Some(&f::DupeGroup(2)));
+        assert_eq!(found.get(Path::new("y")), Some(&f::DupeGroup(2)));
+    }
+
+    #[test]
+    fn unreadable() {
+        let found = groups(sizes(&[("a", 10), ("b", 10)]), |path| {
+            (path == Path::new("a")).then_some(1)
+        });
+        assert!(found.is_empty());
+    }
+}
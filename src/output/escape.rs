@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use super::file_name::QuoteStyle;
 use ansiterm::{ANSIString, Style};
 
@@ -37,3 +39,59 @@ pub fn escape(
         bits.push(quote_bit);
     }
 }
+
+/// Escapes the string as `escape` does, but paints the parts of it in the
+/// given byte ranges, which have to be in order, with the `highlight` style
+/// instead of the `good` one. Any quotes go around the whole string.
+pub fn escape_highlighted(
+    string: String,
+    ranges: &[Range<usize>],
+    bits: &mut Vec<ANSIString<'_>>,
+    good: Style,
+    highlight: Style,
+    bad: Style,
+    quote_style: QuoteStyle,
+) {
+    if ranges.is_empty() {
+        return escape(string, bits, good, bad, quote_style);
+    }
+
+    let bits_starting_length = bits.len();
+    let needs_quotes = string.contains(' ') || string.contains('\'');
+    let quote_bit = good.paint(if string.contains('\'') { "\"" } else { "\'" });
+
+    let mut start = 0;
+    for range in ranges {
+        if start < range.start {
+            escape(
+                string[start..range.start].to_string(),
+                bits,
+                good,
+                bad,
+                QuoteStyle::NoQuotes,
+            );
+        }
+        escape(
+            string[range.clone()].to_string(),
+            bits,
+            highlight,
+            bad,
+            QuoteStyle::NoQuotes,
+        );
+        start = range.end;
+    }
+    if start < string.len() {
+        escape(
+            string[start..].to_string(),
+            bits,
+            good,
+            bad,
+            QuoteStyle::NoQuotes,
+        );
+    }
+
+    if quote_style != QuoteStyle::NoQuotes && needs_quotes {
+        bits.insert(bits_starting_length, quote_bit.clone());
+        bits.push(quote_bit);
+    }
+}
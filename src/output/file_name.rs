@@ -1,17 +1,33 @@
 use std::fmt::Debug;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use ansiterm::{ANSIString, Style};
-use unicode_width::UnicodeWidthStr;
+use regex::Regex;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::fs::feature::special_dirs::SpecialDir;
+use crate::fs::feature::trash;
+#[cfg(target_os = "macos")]
+use crate::fs::feature::xattr::FinderColour;
 use crate::fs::{File, FileTarget};
+use crate::info::shortcuts::Shortcut;
+use crate::output::badges;
+use crate::output::capabilities::capabilities;
 use crate::output::cell::TextCellContents;
-use crate::output::escape;
-use crate::output::icons::{icon_for_file, iconify_style};
+use crate::output::decorations::Decoration;
+use crate::output::glyphs::Glyphs;
+use crate::output::highlight;
+use crate::output::icons::{icon_for_file, icon_for_user_dir, iconify_style, IconMappings};
+use crate::output::relative_root;
 use crate::output::render::FiletypeColours;
+use crate::output::usage_bar;
+use crate::output::{escape, escape_highlighted};
+use crate::theme::apply_overlay;
 
 /// Basically a file name factory.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Options {
     /// Whether to append file class characters to file names.
     pub classify: Classify,
@@ -22,18 +38,71 @@ pub struct Options {
     /// How to display file names with spaces (with or without quotes).
     pub quote_style: QuoteStyle,
 
-    /// Whether to make file names hyperlinks.
-    pub embed_hyperlinks: EmbedHyperlinks,
+    /// Whether to make file names, and other parts that name a file,
+    /// hyperlinks.
+    pub embed_hyperlinks: HyperlinkMode,
 
     /// Whether we are in a console or redirecting the output
     pub is_a_tty: bool,
+
+    /// How recently a file has to have been modified to be highlighted, if
+    /// recent files are being highlighted at all.
+    pub highlight_recent: Option<Duration>,
+
+    /// Whether to flag files whose names clash with another’s in the same
+    /// directory once normalisation and case are ignored.
+    pub name_clashes: bool,
+
+    /// Whether to mark files that something has been trashed from the same
+    /// path as.
+    pub show_trash: bool,
+
+    /// Whether to show the names of `.desktop` files and the targets of
+    /// `.lnk` files, which get read from their contents.
+    pub resolve_shortcuts: bool,
+
+    /// Whether to draw a dot for each of a file’s Finder tags, in the tag’s
+    /// colour, after its name.
+    pub finder_tags: bool,
+
+    /// Whether to draw how full the filesystem mounted on each mount point
+    /// is after its name.
+    pub mount_usage: bool,
+
+    /// Whether to decorate XDG user directories and directories that tools
+    /// fill, such as `node_modules`, with their own icons and colours.
+    pub special_dirs: bool,
+
+    /// Which parts of a tree to colour by how deep they are.
+    pub depth_colours: DepthColours,
+
+    /// Whether to shorten names that are too wide, and how.
+    pub truncate: Truncate,
+
+    /// The pattern to highlight the matching parts of names with, if there
+    /// is one.
+    pub highlight: Option<Regex>,
+
+    /// The directory to show paths as if it were the root of the
+    /// filesystem, if there is one.
+    pub relative_root: Option<PathBuf>,
+
+    /// The extended attribute to read the badges drawn after names from,
+    /// if they’re being drawn.
+    pub badges: Option<String>,
+
+    /// The punctuation to draw around names, and in the table and tree.
+    pub glyphs: Glyphs,
+
+    /// The parts to draw around names, in order.
+    pub decorations: Vec<Decoration>,
 }
 
 impl Options {
     /// Create a new `FileName` that prints the given file’s name, painting it
     /// with the remaining arguments.
     pub fn for_file<'a, 'dir, C>(
-        self,
+        &'a self,
         file: &'a File<'dir>,
         colours: &'a C,
     ) -> FileName<'a, 'dir, C> {
@@ -48,8 +117,16 @@ impl Options {
                 None
             },
             mount_style: MountStyle::JustDirectoryNames,
+            empty_dir: false,
+            depth: 0,
         }
     }
+
+    /// Whether the given file should be highlighted as recently modified.
+    pub fn is_recent(&self, file: &File<'_>) -> bool {
+        self.highlight_recent
+            .is_some_and(|window| file.modified_within(window))
+    }
 }
 
 /// When displaying a file name, there needs to be some way to handle broken
@@ -107,11 +184,171 @@ pub enum ShowIcons {
     Never,
 }
 
-/// Whether to embed hyperlinks.
+/// Which parts of the output to make hyperlinks to the files they name.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum HyperlinkMode {
+    /// Don’t make anything a hyperlink.
+    #[default]
+    Never,
+
+    /// Make file names hyperlinks, but only when output is going to a
+    /// terminal, not otherwise.
+    Auto,
+
+    /// Always make file names hyperlinks.
+    Always,
+
+    /// Make file names hyperlinks, along with symlink targets, the sources
+    /// of mounted filesystems, and the branches in the Git repository
+    /// column.
+    AllCells,
+}
+
+impl HyperlinkMode {
+    /// Whether file names should be hyperlinks.
+    pub fn links_names(self, is_a_tty: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Auto => is_a_tty,
+            Self::Always | Self::AllCells => true,
+        }
+    }
+
+    /// Whether the other parts of the output that name a file should be
+    /// hyperlinks too.
+    pub fn links_cells(self) -> bool {
+        self == Self::AllCells
+    }
+}
+
+/// The escape sequence that starts a hyperlink, which is followed by its
+/// destination, and the one that ends it.
+const HYPERLINK_START: &str = "\x1B]8;;";
+const HYPERLINK_END: &str = "\x1B\x5C";
+
+/// The zero-width strings to put before and after something to make it a
+/// hyperlink to the file at the given absolute path, or `None` if the path
+/// can’t be put in one.
+pub fn hyperlink_escapes(abs_path: &Path) -> Option<(ANSIString<'static>, ANSIString<'static>)> {
+    use percent_encoding::{utf8_percent_encode, CONTROLS};
+
+    let abs_path = abs_path.as_os_str().to_str()?;
+    let abs_path = utf8_percent_encode(abs_path, CONTROLS).to_string();
+
+    // On Windows, `std::fs::canonicalize` adds the Win32 File prefix, which we need to remove
+    #[cfg(target_os = "windows")]
+    let abs_path = abs_path.strip_prefix("\\\\?\\").unwrap_or(&abs_path);
+
+    Some((
+        ANSIString::from(format!("{HYPERLINK_START}file://{abs_path}{HYPERLINK_END}")),
+        ANSIString::from(format!("{HYPERLINK_START}{HYPERLINK_END}")),
+    ))
+}
+
+/// Which parts of a tree to colour by how deep in the tree they are, cycling
+/// through the depth palette, so the levels of a deep tree are easier to
+/// tell apart.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum DepthColours {
+    /// Colour everything as usual.
+    #[default]
+    Off,
+
+    /// Colour the lines of the tree.
+    Lines,
+
+    /// Tint the names of files.
+    Names,
+
+    /// Colour the lines and tint the names.
+    All,
+}
+
+impl DepthColours {
+    /// Whether the lines of the tree get coloured by depth.
+    pub fn lines(self) -> bool {
+        matches!(self, Self::Lines | Self::All)
+    }
+
+    /// Whether file names get tinted by depth.
+    pub fn names(self) -> bool {
+        matches!(self, Self::Names | Self::All)
+    }
+}
+
+/// Whether to shorten file names wider than a given number of columns, so
+/// that one long name doesn’t push everything else off the screen.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub enum EmbedHyperlinks {
+pub enum Truncate {
+    /// Display names in full, however wide they are.
     Off,
-    On,
+
+    /// Cut the end off names, replacing it with an ellipsis.
+    End(usize),
+
+    /// Cut the middle out of names, keeping the start and end, including
+    /// the extension if there’s room for it.
+    Middle(usize),
+}
+
+impl Truncate {
+    /// Shortens the given name to fit, if it doesn’t already. The ellipsis
+    /// counts towards the width. Names are only ever cut between
+    /// characters, and never in the middle of a wide one.
+    pub fn apply(self, name: &str) -> String {
+        let max_width = match self {
+            Self::Off => return name.to_string(),
+            Self::End(w) | Self::Middle(w) => w,
+        };
+
+        if name.width() <= max_width {
+            return name.to_string();
+        }
+
+        let available = max_width.saturating_sub(ELLIPSIS.width());
+
+        let tail = match self {
+            Self::Middle(_) => {
+                let ext_width = name
+                    .rfind('.')
+                    .filter(|&i| i > 0)
+                    .map_or(0, |i| name[i..].width());
+                let tail_width = if ext_width < available {
+                    ext_width.max(available / 2)
+                } else {
+                    available / 2
+                };
+                take_width(name.chars().rev(), tail_width)
+                    .chars()
+                    .rev()
+                    .collect()
+            }
+            _ => String::new(),
+        };
+
+        let head = take_width(name.chars(), available - tail.width());
+        format!("{head}{ELLIPSIS}{tail}")
+    }
+}
+
+/// What replaces the part of a name that got cut off.
+const ELLIPSIS: &str = "…";
+
+/// Takes characters from the iterator for as long as they fit in the given
+/// width, including any zero-width ones that follow the last of them.
+fn take_width(chars: impl Iterator<Item = char>, max_width: usize) -> String {
+    let mut taken = String::new();
+    let mut width = 0;
+
+    for c in chars {
+        width += c.width().unwrap_or(0);
+        if width > max_width {
+            break;
+        }
+        taken.push(c);
+    }
+
+    taken
 }
 
 /// Whether or not to wrap file names with spaces in quotes.
@@ -140,10 +377,17 @@ pub struct FileName<'a, 'dir, C> {
     /// How to handle displaying links.
     link_style: LinkStyle,
 
-    pub options: Options,
+    pub options: &'a Options,
 
     /// How to handle displaying a mounted filesystem.
     mount_style: MountStyle,
+
+    /// Whether this is a directory known to have nothing in it.
+    empty_dir: bool,
+
+    /// How deep in a tree this file is, with 0 for files that aren’t in
+    /// one or are at its root.
+    depth: usize,
 }
 
 impl<'a, 'dir, C> FileName<'a, 'dir, C> {
@@ -164,6 +408,19 @@ impl<'a, 'dir, C> FileName<'a, 'dir, C> {
         };
         self
     }
+
+    /// Sets the flag on this file name to paint it as an empty directory,
+    /// for views that have already read the directory’s contents.
+    pub fn with_empty_dir(mut self, is_empty: bool) -> Self {
+        self.empty_dir = is_empty;
+        self
+    }
+
+    /// Sets how deep in a tree this file is, for tinting its name by depth.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
 }
 
 impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
@@ -178,26 +435,115 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
 
         let spaces_count_opt = match self.options.show_icons {
             ShowIcons::Always(spaces_count) => Some(spaces_count),
-            ShowIcons::Automatic(spaces_count) if self.options.is_a_tty => Some(spaces_count),
+            ShowIcons::Automatic(spaces_count)
+                if self.options.is_a_tty && capabilities().unicode =>
+            {
+                Some(spaces_count)
+            }
             _ => None,
         };
 
-        let should_add_classify_char = match self.options.classify {
-            Classify::AddFileIndicators => true,
-            Classify::AutomaticAddFileIndicators if self.options.is_a_tty => true,
-            _ => false,
-        };
+        let should_add_classify_char = self.options.decorations.contains(&Decoration::Classify)
+            && match self.options.classify {
+                Classify::AddFileIndicators => true,
+                Classify::AutomaticAddFileIndicators if self.options.is_a_tty => true,
+                _ => false,
+            };
+
+        // A symlink whose target is shown gets the target classified
+        // instead, right after the target’s name.
+        let shows_target = self.link_style == LinkStyle::FullLinkPaths && self.target.is_some();
+
+        for decoration in &self.options.decorations {
+            match decoration {
+                Decoration::Icon => {
+                    if let Some(spaces_count) = spaces_count_opt {
+                        self.add_icon_bits(&mut bits, spaces_count);
+                    }
+                }
+
+                Decoration::Name => self.add_name_bits(&mut bits, should_add_classify_char),
+
+                Decoration::Classify => {
+                    if should_add_classify_char && !shows_target {
+                        if let Some(class) = self.classify_char(self.file) {
+                            bits.push(Style::default().paint(class));
+                        }
+                    }
+                }
+
+                // This goes around the name, so it’s added along with it.
+                Decoration::Hyperlink => {}
+            }
+        }
+
+        if let Some(badge) = badges::badge(self.options.badges.as_deref(), self.file) {
+            bits.push(Style::default().paint(" "));
+            bits.push(self.colours.badge().paint(format!("[{badge}]")));
+        }
+
+        #[cfg(target_os = "macos")]
+        if self.options.finder_tags {
+            self.add_finder_tag_bits(&mut bits);
+        }
+
+        if self.options.resolve_shortcuts {
+            self.add_shortcut_bits(&mut bits);
+        }
+
+        if self.mount_style == MountStyle::MountInfo {
+            if let Some(mount_details) = self.file.mount_point_info() {
+                // This is a filesystem mounted on the directory, output its details
+                bits.push(Style::default().paint(" ["));
+                let source = Path::new(&mount_details.source);
+                match hyperlink_escapes(source)
+                    .filter(|_| self.links_cells() && source.is_absolute())
+                {
+                    Some((start, end)) => {
+                        bits.push(start);
+                        bits.push(Style::default().paint(mount_details.source.clone()));
+                        bits.push(end);
+                    }
+                    None => bits.push(Style::default().paint(mount_details.source.clone())),
+                }
+                bits.push(Style::default().paint(" ("));
+                bits.push(Style::default().paint(mount_details.fstype.clone()));
+                bits.push(Style::default().paint(")]"));
+            }
+        }
+
+        if self.options.mount_usage {
+            if let Some(usage) = self.file.mount_usage() {
+                bits.extend(usage_bar::render(usage, self.colours));
+            }
+        }
+
+        bits.into()
+    }
 
-        if let Some(spaces_count) = spaces_count_opt {
-            let style = iconify_style(self.style());
-            let file_icon = icon_for_file(self.file).to_string();
-            bits.push(style.paint(file_icon));
-            bits.push(style.paint(" ".repeat(spaces_count as usize)));
+    /// Adds this file’s icon to the given bits vector, followed by the
+    /// given number of spaces.
+    fn add_icon_bits(&self, bits: &mut Vec<ANSIString<'_>>, spaces_count: u32) {
+        let style = iconify_style(self.style());
+        let icons = self.colours.icons();
+        let file_icon = match self.special_dir() {
+            Some(SpecialDir::User(dir)) => icons
+                .for_name(&self.file.name)
+                .unwrap_or_else(|| icon_for_user_dir(dir)),
+            _ => icon_for_file(self.file, icons),
         }
+        .to_string();
+        bits.push(style.paint(file_icon));
+        bits.push(style.paint(" ".repeat(spaces_count as usize)));
+    }
 
+    /// Adds this file’s name to the given bits vector, along with its parent
+    /// path and the target of a symlink, when they’re being shown. A shown
+    /// target gets classified here, as its character goes after it.
+    fn add_name_bits(&self, bits: &mut Vec<ANSIString<'_>>, should_add_classify_char: bool) {
         if self.file.parent_dir.is_none() {
             if let Some(parent) = self.file.path.parent() {
-                self.add_parent_bits(&mut bits, parent);
+                self.add_parent_bits(bits, parent);
             }
         }
 
@@ -217,11 +563,15 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
             match target {
                 FileTarget::Ok(target) => {
                     bits.push(Style::default().paint(" "));
-                    bits.push(self.colours.normal_arrow().paint("->"));
+                    bits.push(
+                        self.colours
+                            .normal_arrow()
+                            .paint(self.options.glyphs.link_arrow.clone()),
+                    );
                     bits.push(Style::default().paint(" "));
 
                     if let Some(parent) = target.path.parent() {
-                        self.add_parent_bits(&mut bits, parent);
+                        self.add_parent_bits(bits, parent);
                     }
 
                     if !target.name.is_empty() {
@@ -229,8 +579,26 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             classify: Classify::JustFilenames,
                             quote_style: QuoteStyle::QuoteSpaces,
                             show_icons: ShowIcons::Never,
-                            embed_hyperlinks: EmbedHyperlinks::Off,
+                            embed_hyperlinks: if self.links_cells() {
+                                HyperlinkMode::Always
+                            } else {
+                                HyperlinkMode::Never
+                            },
                             is_a_tty: self.options.is_a_tty,
+                            highlight_recent: None,
+                            name_clashes: false,
+                            show_trash: false,
+                            resolve_shortcuts: false,
+                            finder_tags: false,
+                            mount_usage: false,
+                            special_dirs: false,
+                            depth_colours: DepthColours::Off,
+                            truncate: Truncate::Off,
+                            highlight: self.options.highlight.clone(),
+                            relative_root: self.options.relative_root.clone(),
+                            badges: None,
+                            glyphs: self.options.glyphs.clone(),
+                            decorations: self.options.decorations.clone(),
                         };
 
                         let target_name = FileName {
@@ -238,8 +606,10 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             colours: self.colours,
                             target: None,
                             link_style: LinkStyle::FullLinkPaths,
-                            options: target_options,
+                            options: &target_options,
                             mount_style: MountStyle::JustDirectoryNames,
+                            empty_dir: false,
+                            depth: 0,
                         };
 
                         for bit in target_name.escaped_file_name() {
@@ -256,12 +626,18 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
 
                 FileTarget::Broken(broken_path) => {
                     bits.push(Style::default().paint(" "));
-                    bits.push(self.colours.broken_symlink().paint("->"));
+                    bits.push(
+                        self.colours
+                            .broken_symlink()
+                            .paint(self.options.glyphs.link_arrow.clone()),
+                    );
                     bits.push(Style::default().paint(" "));
 
                     escape(
-                        broken_path.display().to_string(),
-                        &mut bits,
+                        relative_root::display(self.options.relative_root.as_deref(), broken_path)
+                            .display()
+                            .to_string(),
+                        bits,
                         self.colours.broken_filename(),
                         self.colours.broken_control_char(),
                         self.options.quote_style,
@@ -272,29 +648,75 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                     // Do nothing — the error gets displayed on the next line
                 }
             }
-        } else if should_add_classify_char {
-            if let Some(class) = self.classify_char(self.file) {
-                bits.push(Style::default().paint(class));
-            }
         }
+    }
 
-        if self.mount_style == MountStyle::MountInfo {
-            if let Some(mount_details) = self.file.mount_point_info() {
-                // This is a filesystem mounted on the directory, output its details
-                bits.push(Style::default().paint(" ["));
-                bits.push(Style::default().paint(mount_details.source.clone()));
+    /// Adds a dot for each of this file’s Finder tags to the given bits
+    /// vector, painted in the tag’s colour, or hollow for a tag without one.
+    #[cfg(target_os = "macos")]
+    fn add_finder_tag_bits(&self, bits: &mut Vec<ANSIString<'_>>) {
+        let tags = self.file.finder_tags();
+        if tags.is_empty() {
+            return;
+        }
+
+        let (dot, hollow) = if capabilities().unicode {
+            ("●", "○")
+        } else {
+            ("*", "o")
+        };
+
+        bits.push(Style::default().paint(" "));
+        for colour in tags {
+            let glyph = if colour == FinderColour::None {
+                hollow
+            } else {
+                dot
+            };
+            bits.push(self.colours.finder_tag(colour).paint(glyph));
+        }
+    }
+
+    /// Adds what this file resolves to, if it’s a shortcut, to the given
+    /// bits vector: the name of a `.desktop` file in brackets, or an arrow
+    /// pointing to the target of a `.lnk` file, as with symlinks.
+    fn add_shortcut_bits(&self, bits: &mut Vec<ANSIString<'_>>) {
+        match self.file.resolve_shortcut() {
+            Some(Shortcut::Name(name)) => {
                 bits.push(Style::default().paint(" ("));
-                bits.push(Style::default().paint(mount_details.fstype.clone()));
-                bits.push(Style::default().paint(")]"));
+                escape(
+                    name,
+                    bits,
+                    Style::default(),
+                    self.colours.control_char(),
+                    QuoteStyle::NoQuotes,
+                );
+                bits.push(Style::default().paint(")"));
             }
+            Some(Shortcut::Target(target)) => {
+                bits.push(Style::default().paint(" "));
+                bits.push(
+                    self.colours
+                        .normal_arrow()
+                        .paint(self.options.glyphs.link_arrow.clone()),
+                );
+                bits.push(Style::default().paint(" "));
+                escape(
+                    target,
+                    bits,
+                    self.colours.symlink_path(),
+                    self.colours.control_char(),
+                    self.options.quote_style,
+                );
+            }
+            None => {}
         }
-
-        bits.into()
     }
 
     /// Adds the bits of the parent path to the given bits vector.
     /// The path gets its characters escaped based on the colours.
     fn add_parent_bits(&self, bits: &mut Vec<ANSIString<'_>>, parent: &Path) {
+        let parent = relative_root::display(self.options.relative_root.as_deref(), parent);
         let coconut = parent.components().count();
 
         if coconut == 1 && parent.has_root() {
@@ -362,57 +784,88 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
     /// So in that situation, those characters will be escaped and highlighted in
     /// a different colour.
     fn escaped_file_name<'unused>(&self) -> Vec<ANSIString<'unused>> {
-        use percent_encoding::{utf8_percent_encode, CONTROLS};
-
-        const HYPERLINK_START: &str = "\x1B]8;;";
-        const HYPERLINK_END: &str = "\x1B\x5C";
-
         let file_style = self.style();
         let mut bits = Vec::new();
 
-        let mut display_hyperlink = false;
-        if self.options.embed_hyperlinks == EmbedHyperlinks::On {
-            if let Some(abs_path) = self
-                .file
-                .absolute_path()
-                .and_then(|p| p.as_os_str().to_str())
+        let mut hyperlink_end = None;
+        if self.embeds_hyperlinks() {
+            if let Some((start, end)) = self.file.absolute_path().and_then(|p| hyperlink_escapes(p))
             {
-                let abs_path = utf8_percent_encode(abs_path, CONTROLS).to_string();
-
-                // On Windows, `std::fs::canonicalize` adds the Win32 File prefix, which we need to remove
-                #[cfg(target_os = "windows")]
-                let abs_path = abs_path.strip_prefix("\\\\?\\").unwrap_or(&abs_path);
-
-                bits.push(ANSIString::from(format!(
-                    "{HYPERLINK_START}file://{abs_path}{HYPERLINK_END}"
-                )));
-
-                display_hyperlink = true;
+                bits.push(start);
+                hyperlink_end = Some(end);
             }
         }
 
-        escape(
-            self.file.name.clone(),
+        let name = self.options.truncate.apply(&self.file.name);
+        let ranges = highlight::matches(self.options.highlight.as_ref(), &name);
+        escape_highlighted(
+            name,
+            &ranges,
             &mut bits,
             file_style,
+            apply_overlay(file_style, self.colours.highlight_overlay()),
             self.colours.control_char(),
             self.options.quote_style,
         );
 
-        if display_hyperlink {
-            bits.push(ANSIString::from(format!(
-                "{HYPERLINK_START}{HYPERLINK_END}"
-            )));
+        if let Some(end) = hyperlink_end {
+            bits.push(end);
         }
 
         bits
     }
 
+    /// Whether this file’s name gets made a hyperlink.
+    pub fn embeds_hyperlinks(&self) -> bool {
+        self.options
+            .embed_hyperlinks
+            .links_names(self.options.is_a_tty)
+            && capabilities().hyperlinks
+            && self.options.decorations.contains(&Decoration::Hyperlink)
+    }
+
+    /// Whether the other parts of this file’s name that name a file, such as
+    /// its symlink target, get made hyperlinks.
+    fn links_cells(&self) -> bool {
+        self.options.embed_hyperlinks.links_cells() && capabilities().hyperlinks
+    }
+
     /// Figures out which colour to paint the filename part of the output,
     /// depending on which “type” of file it appears to be — either from the
     /// class on the filesystem or from its name. (Or the broken link colour,
     /// if there’s nowhere else for that fact to be shown.)
     pub fn style(&self) -> Style {
+        let mut style = self.kind_style();
+
+        if let Some(owner) = self.colours.owner_style(self.file) {
+            style = apply_overlay(style, owner);
+        }
+
+        style = self.colours.git_age_style(self.file, style);
+
+        if self.options.depth_colours.names() {
+            if let Some(tint) = self.colours.depth_style(self.depth) {
+                style = apply_overlay(style, tint);
+            }
+        }
+
+        if self.options.is_recent(self.file) {
+            style = apply_overlay(style, self.colours.recent_overlay());
+        }
+
+        if self.options.name_clashes && self.file.has_name_clash() {
+            style = apply_overlay(style, self.colours.name_clash_overlay());
+        }
+
+        if self.options.show_trash && trash::is_trashed(&self.file.path) {
+            style = apply_overlay(style, self.colours.trashed_overlay());
+        }
+
+        style
+    }
+
+    /// The style for this file before any overlays are applied.
+    fn kind_style(&self) -> Style {
         if let LinkStyle::JustFilenames = self.link_style {
             if let Some(ref target) = self.target {
                 if target.is_broken() {
@@ -421,12 +874,31 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
             }
         }
 
+        if !self.file.is_mount_point() {
+            match self.special_dir() {
+                Some(SpecialDir::User(_)) => return self.colours.user_directory(),
+                Some(SpecialDir::WellKnown) => return self.colours.well_known_directory(),
+                None => {}
+            }
+        }
+
+        #[cfg(unix)]
+        if !self.file.is_mount_point() {
+            if let Some(style) = self.mode_style() {
+                return style;
+            }
+        }
+
         #[rustfmt::skip]
         return match self.file {
             f if f.is_mount_point()      => self.colours.mount_point(),
+            f if self.empty_dir && f.is_directory() => self.colours.empty_directory(),
             f if f.is_directory()        => self.colours.directory(),
             #[cfg(unix)]
             f if f.is_executable_file()  => self.colours.executable_file(),
+            #[cfg(unix)]
+            f if f.links().multiple && !self.colours.multi_link_file().is_plain()
+                                         => self.colours.multi_link_file(),
             f if f.is_link()             => self.colours.symlink(),
             #[cfg(unix)]
             f if f.is_pipe()             => self.colours.pipe(),
@@ -441,6 +913,44 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         };
     }
 
+    /// The style for this file’s mode bits or capabilities, if it has any
+    /// that have a style set, picked in the same order as GNU ls picks them.
+    #[cfg(unix)]
+    fn mode_style(&self) -> Option<Style> {
+        let permissions = self.file.permissions()?;
+        let set = |applies: bool, style: Style| (applies && !style.is_plain()).then_some(style);
+
+        if self.file.is_directory() {
+            set(
+                permissions.sticky && permissions.other_write,
+                self.colours.sticky_other_writable(),
+            )
+            .or_else(|| set(permissions.other_write, self.colours.other_writable()))
+            .or_else(|| set(permissions.sticky, self.colours.sticky_directory()))
+        } else if self.file.is_file() {
+            set(permissions.setuid, self.colours.setuid_file())
+                .or_else(|| set(permissions.setgid, self.colours.setgid_file()))
+                .or_else(|| {
+                    // Only look for capabilities when there’s a style to
+                    // paint them with, as it means reading xattrs.
+                    let style = self.colours.capability_file();
+                    set(!style.is_plain() && self.file.has_capabilities(), style)
+                })
+        } else {
+            None
+        }
+    }
+
+    /// Why this file is a special directory, if special directories are
+    /// being decorated and it is one.
+    fn special_dir(&self) -> Option<SpecialDir> {
+        if self.options.special_dirs {
+            self.file.special_dir()
+        } else {
+            None
+        }
+    }
+
     /// For grid's use, to cover the case of hyperlink escape sequences
     pub fn bare_utf8_width(&self) -> usize {
         UnicodeWidthStr::width(self.file.name.as_str())
@@ -448,7 +958,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
 }
 
 /// The set of colours that are needed to paint a file name.
-pub trait Colours: FiletypeColours {
+pub trait Colours: FiletypeColours + usage_bar::Colours {
     /// The style to paint the path of a symlink’s target, up to but not
     /// including the file’s name.
     fn symlink_path(&self) -> Style;
@@ -477,5 +987,115 @@ pub trait Colours: FiletypeColours {
     /// The style to paint a directory that has a filesystem mounted on it.
     fn mount_point(&self) -> Style;
 
+    /// The style to paint one of the user’s XDG user directories.
+    fn user_directory(&self) -> Style;
+
+    /// The style to paint a directory that tools fill, such as
+    /// `node_modules`.
+    fn well_known_directory(&self) -> Style;
+
+    /// The style to paint a directory that has nothing in it.
+    fn empty_directory(&self) -> Style;
+
+    /// The styles to paint files and directories with special mode bits,
+    /// files with more than one hard link, and files with capabilities, in
+    /// place of their usual styles. These are only used when they’re set.
+    fn setuid_file(&self) -> Style;
+    fn setgid_file(&self) -> Style;
+    fn sticky_other_writable(&self) -> Style;
+    fn other_writable(&self) -> Style;
+    fn sticky_directory(&self) -> Style;
+    fn multi_link_file(&self) -> Style;
+    fn capability_file(&self) -> Style;
+
+    /// The overlay style to paint a recently modified file with.
+    fn recent_overlay(&self) -> Style;
+
+    fn name_clash_overlay(&self) -> Style;
+
+    /// The style for the given depth in a tree, if there’s a palette of
+    /// them to cycle through.
+    fn depth_style(&self, depth: usize) -> Option<Style>;
+
+    /// The style for the given file’s owner, if names are coloured by
+    /// owner.
+    fn owner_style(&self, file: &File<'_>) -> Option<Style>;
+
+    /// The given style, brightened or darkened by how recently the given
+    /// file was committed, if names are coloured by commit age.
+    fn git_age_style(&self, file: &File<'_>, style: Style) -> Style;
+
+    /// The overlay style to paint the parts of a file name that match the
+    /// `--highlight` pattern with.
+    fn highlight_overlay(&self) -> Style;
+
+    fn trashed_overlay(&self) -> Style;
+
+    /// The style to paint the badge after a file’s name with.
+    fn badge(&self) -> Style;
+
+    /// The style to paint the dot for a Finder tag of the given colour
+    /// with.
+    #[cfg(target_os = "macos")]
+    fn finder_tag(&self, colour: FinderColour) -> Style;
+
+    /// The icons given by the user to show instead of the built-in ones.
+    fn icons(&self) -> &IconMappings;
+
     fn colour_file(&self, file: &File<'_>) -> Style;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn short_enough() {
+        assert_eq!(Truncate::End(10).apply("short.txt"), "short.txt");
+        assert_eq!(Truncate::Middle(9).apply("short.txt"), "short.txt");
+    }
+
+    #[test]
+    fn off() {
+        assert_eq!(
+            Truncate::Off.apply("a_very_long_name.txt"),
+            "a_very_long_name.txt"
+        );
+    }
+
+    #[test]
+    fn end() {
+        assert_eq!(
+            Truncate::End(10).apply("a_very_long_name.txt"),
+            "a_very_lo…"
+        );
+    }
+
+    #[test]
+    fn middle_keeps_extension() {
+        assert_eq!(
+            Truncate::Middle(10).apply("a_very_long_name.txt"),
+            "a_ver….txt"
+        );
+        assert_eq!(
+            Truncate::Middle(12).apply("report.final.markdown"),
+            "re….markdown"
+        );
+    }
+
+    #[test]
+    fn middle_without_extension() {
+        assert_eq!(Truncate::Middle(9).apply("abcdefghijklmnop"), "abcd…mnop");
+    }
+
+    #[test]
+    fn extension_too_long() {
+        assert_eq!(Truncate::Middle(7).apply("x.abcdefghijk"), "x.a…ijk");
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(Truncate::End(6).apply("日本語のファイル"), "日本…");
+        assert_eq!(Truncate::End(6).apply("日本語のファイル").width(), 5);
+    }
+}
@@ -0,0 +1,61 @@
+//! The punctuation that eza draws around file names: the arrow between a
+//! symlink and its target, the marker for files with extended attributes,
+//! and the lines of the tree view.
+//!
+//! These can be changed in the `glyphs` configuration file, to match a
+//! font, or to stick to ASCII on terminals that can’t draw box-drawing
+//! characters. They’re carried in the file name options, and handed to the
+//! table and tree from there.
+
+/// The set of punctuation glyphs to draw with.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Glyphs {
+    /// Between a symlink and its target, whether or not it’s broken.
+    pub link_arrow: String,
+
+    /// After the permissions of a file with extended attributes.
+    pub xattr: String,
+
+    /// The tree part for an entry that isn’t the last in its directory.
+    pub tree_edge: String,
+
+    /// The tree part below an entry that isn’t the last in its directory.
+    pub tree_line: String,
+
+    /// The tree part for the last entry in a directory.
+    pub tree_corner: String,
+
+    /// The tree part below the last entry in a directory.
+    pub tree_blank: String,
+}
+
+impl Glyphs {
+    /// The glyphs eza draws with by default, which use box-drawing
+    /// characters for the tree.
+    pub fn unicode() -> Self {
+        Self {
+            link_arrow: "->".into(),
+            xattr: "@".into(),
+            tree_edge: "├──".into(),
+            tree_line: "│  ".into(),
+            tree_corner: "└──".into(),
+            tree_blank: "   ".into(),
+        }
+    }
+
+    /// Glyphs that are all plain ASCII.
+    pub fn ascii() -> Self {
+        Self {
+            tree_edge: "|--".into(),
+            tree_line: "|  ".into(),
+            tree_corner: "`--".into(),
+            ..Self::unicode()
+        }
+    }
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self::unicode()
+    }
+}
@@ -2,10 +2,12 @@ use std::io::{self, Write};
 
 use term_grid as tg;
 
+use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
 use crate::fs::File;
+use crate::output::file_name::ShowIcons;
 use crate::output::file_name::{Classify, Options as FileStyle};
-use crate::output::file_name::{EmbedHyperlinks, ShowIcons};
+use crate::output::layout_debug;
 use crate::theme::Theme;
 
 use super::file_name::QuoteStyle;
@@ -13,6 +15,45 @@ use super::file_name::QuoteStyle;
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct Options {
     pub across: bool,
+    pub layout: Layout,
+}
+
+/// How the widths of the grid’s columns are worked out.
+///
+/// The exact layout gives each column the width of its widest file name,
+/// fitting as many columns in as it can, but it has to hold every painted
+/// file name in memory while it tries different numbers of columns. The
+/// fast layout gives every column the width of the widest name in the whole
+/// listing, which means it can paint and print each name as soon as it
+/// knows where to put it, at the cost of a wider, sparser grid.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum Layout {
+    /// Use the exact layout for listings of up to `EXACT_LAYOUT_LIMIT`
+    /// files, and the fast layout for any bigger than that.
+    #[default]
+    Auto,
+
+    /// Always use the exact layout.
+    Exact,
+
+    /// Always use the fast layout.
+    Fast,
+}
+
+/// The number of files above which the automatic layout switches from
+/// exact to fast.
+pub const EXACT_LAYOUT_LIMIT: usize = 10_000;
+
+impl Layout {
+    /// Whether a listing of the given number of files should be laid out
+    /// exactly.
+    pub fn is_exact(self, file_count: usize) -> bool {
+        match self {
+            Self::Auto => file_count <= EXACT_LAYOUT_LIMIT,
+            Self::Exact => true,
+            Self::Fast => false,
+        }
+    }
 }
 
 impl Options {
@@ -32,87 +73,214 @@ pub struct Render<'a> {
     pub opts: &'a Options,
     pub console_width: usize,
     pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
 }
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files, self.git);
+
+        let count = self.files.len();
+        let exact = self.opts.layout.is_exact(count);
+        match self.opts.layout {
+            Layout::Auto if exact => layout_debug::note(format_args!(
+                "laying out {count} files with the exact grid layout, as that’s no more than {EXACT_LAYOUT_LIMIT}"
+            )),
+            Layout::Auto => layout_debug::note(format_args!(
+                "laying out {count} files with the fast grid layout, as that’s more than {EXACT_LAYOUT_LIMIT}"
+            )),
+            Layout::Exact | Layout::Fast => layout_debug::note(format_args!(
+                "laying out {count} files with the {} grid layout, as --grid-layout asked for it",
+                if exact { "exact" } else { "fast" }
+            )),
+        }
+
+        if exact {
+            self.render_exact(w)
+        } else {
+            self.render_fast(w)
+        }
+    }
+
+    /// Lays the grid out with as many columns as will fit, each as wide as
+    /// the names in it, by holding every painted name until it fits.
+    fn render_exact<W: Write>(self, w: &mut W) -> io::Result<()> {
         let mut grid = tg::Grid::new(tg::GridOptions {
             direction: self.opts.direction(),
-            filling: tg::Filling::Spaces(2),
+            filling: tg::Filling::Spaces(COLUMN_SPACING),
         });
 
         grid.reserve(self.files.len());
 
-        self.filter.sort_files(&mut self.files);
+        let mut widest = 0;
         for file in &self.files {
-            let filename = self.file_style.for_file(file, self.theme);
-
-            // Calculate classification width
-            let classification_width =
-                if let Classify::AddFileIndicators = filename.options.classify {
-                    match filename.classify_char(file) {
-                        Some(s) => s.len(),
-                        None => 0,
-                    }
-                } else {
-                    0
-                };
-            let space_filename_offset = match self.file_style.quote_style {
-                QuoteStyle::QuoteSpaces if file.name.contains(' ') => 2,
-                QuoteStyle::NoQuotes => 0,
-                QuoteStyle::QuoteSpaces => 0, // Default case
-            };
-            let contents = filename.paint();
-            let width = match (
-                filename.options.embed_hyperlinks,
-                filename.options.show_icons,
-            ) {
-                (
-                    EmbedHyperlinks::On,
-                    ShowIcons::Always(spacing) | ShowIcons::Automatic(spacing),
-                ) => {
-                    filename.bare_utf8_width()
-                        + classification_width
-                        + 1
-                        + (spacing as usize)
-                        + space_filename_offset
-                }
-                (EmbedHyperlinks::On, ShowIcons::Never) => {
-                    filename.bare_utf8_width() + classification_width + space_filename_offset
-                }
-                (
-                    EmbedHyperlinks::Off,
-                    ShowIcons::Always(spacing) | ShowIcons::Automatic(spacing),
-                ) => {
-                    filename.bare_utf8_width()
-                        + classification_width
-                        + 1
-                        + (spacing as usize)
-                        + space_filename_offset
-                }
-                (EmbedHyperlinks::Off, _) => *contents.width(),
-            };
-
-            grid.add(tg::Cell {
-                contents: contents.strings().to_string(),
-                // with hyperlink escape sequences,
-                // the actual *contents.width() is larger than actually needed, so we take only the filename
-                width,
-            });
+            let cell = self.cell_for(file);
+            widest = widest.max(cell.width);
+            grid.add(cell);
         }
 
         if let Some(display) = grid.fit_into_width(self.console_width) {
+            if layout_debug::enabled() {
+                let rows = display.row_count();
+                let columns = (self.files.len() + rows.max(1) - 1) / rows.max(1);
+                layout_debug::note(format_args!(
+                    "grid has {columns} columns and {rows} rows, using {} of {} columns of width",
+                    display.width(),
+                    self.console_width
+                ));
+            }
             write!(w, "{display}")
         } else {
-            // File names too long for a grid - drop down to just listing them!
-            // This isn’t *quite* the same as the lines view, which also
-            // displays full link paths.
-            for file in &self.files {
-                let name_cell = self.file_style.for_file(file, self.theme).paint();
-                writeln!(w, "{}", name_cell.strings())?;
+            self.note_too_wide(widest);
+            self.render_lines(w)
+        }
+    }
+
+    /// Lays the grid out with columns that are all as wide as the widest
+    /// name, painting each name once to measure it and once more to print
+    /// it, so no more than one is held in memory at a time.
+    fn render_fast<W: Write>(self, w: &mut W) -> io::Result<()> {
+        let count = self.files.len();
+        let widest = self
+            .files
+            .iter()
+            .map(|f| self.cell_for(f).width)
+            .max()
+            .unwrap_or(0);
+
+        if count == 0 {
+            return Ok(());
+        }
+
+        if widest > self.console_width {
+            self.note_too_wide(widest);
+            return self.render_lines(w);
+        }
+
+        let columns = (self.console_width + COLUMN_SPACING) / (widest + COLUMN_SPACING);
+        let rows = (count + columns - 1) / columns;
+        layout_debug::note(format_args!(
+            "grid has {columns} columns and {rows} rows, each column {widest} wide to fit the widest name, in {} columns of width",
+            self.console_width
+        ));
+
+        for row in 0..rows {
+            let mut files = (0..columns)
+                .filter_map(|column| {
+                    if self.opts.across {
+                        self.files.get(row * columns + column)
+                    } else {
+                        self.files.get(column * rows + row)
+                    }
+                })
+                .peekable();
+
+            while let Some(file) = files.next() {
+                let cell = self.cell_for(file);
+                write!(w, "{}", cell.contents)?;
+
+                if files.peek().is_some() {
+                    write!(w, "{:1$}", "", widest - cell.width + COLUMN_SPACING)?;
+                }
+            }
+
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+
+    /// Explains why the grid was given up on in favour of listing one file
+    /// per line.
+    fn note_too_wide(&self, widest: usize) {
+        layout_debug::note(format_args!(
+            "no grid drawn, as the widest name is {widest} wide but there are only {} columns of width, so listing one file per line",
+            self.console_width
+        ));
+    }
+
+    /// File names too long for a grid - drop down to just listing them!
+    /// This isn’t *quite* the same as the lines view, which also displays
+    /// full link paths.
+    fn render_lines<W: Write>(self, w: &mut W) -> io::Result<()> {
+        for file in &self.files {
+            let name_cell = self.file_style.for_file(file, self.theme).paint();
+            writeln!(w, "{}", name_cell.strings())?;
+        }
+
+        Ok(())
+    }
+
+    /// Paints a file’s name into a grid cell, along with the width it takes
+    /// up on screen.
+    fn cell_for(&self, file: &File<'_>) -> tg::Cell {
+        let filename = self.file_style.for_file(file, self.theme);
+
+        // Calculate classification width
+        let classification_width = if let Classify::AddFileIndicators = filename.options.classify {
+            match filename.classify_char(file) {
+                Some(s) => s.len(),
+                None => 0,
+            }
+        } else {
+            0
+        };
+        let space_filename_offset = match self.file_style.quote_style {
+            QuoteStyle::QuoteSpaces if file.name.contains(' ') => 2,
+            QuoteStyle::NoQuotes => 0,
+            QuoteStyle::QuoteSpaces => 0, // Default case
+        };
+        let contents = filename.paint();
+        let width = match (filename.embeds_hyperlinks(), filename.options.show_icons) {
+            (true, ShowIcons::Always(spacing) | ShowIcons::Automatic(spacing)) => {
+                filename.bare_utf8_width()
+                    + classification_width
+                    + 1
+                    + (spacing as usize)
+                    + space_filename_offset
             }
+            (true, ShowIcons::Never) => {
+                filename.bare_utf8_width() + classification_width + space_filename_offset
+            }
+            (false, ShowIcons::Always(spacing) | ShowIcons::Automatic(spacing)) => {
+                filename.bare_utf8_width()
+                    + classification_width
+                    + 1
+                    + (spacing as usize)
+                    + space_filename_offset
+            }
+            (false, _) => *contents.width(),
+        };
 
-            Ok(())
+        tg::Cell {
+            contents: contents.strings().to_string(),
+            // with hyperlink escape sequences,
+            // the actual *contents.width() is larger than actually needed, so we take only the filename
+            width,
         }
     }
 }
+
+/// The number of spaces between columns.
+const COLUMN_SPACING: usize = 2;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn auto_small() {
+        assert!(Layout::Auto.is_exact(EXACT_LAYOUT_LIMIT));
+    }
+
+    #[test]
+    fn auto_big() {
+        assert!(!Layout::Auto.is_exact(EXACT_LAYOUT_LIMIT + 1));
+    }
+
+    #[test]
+    fn forced() {
+        assert!(Layout::Exact.is_exact(usize::MAX));
+        assert!(!Layout::Fast.is_exact(0));
+    }
+}
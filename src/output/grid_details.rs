@@ -5,6 +5,7 @@ use std::io::{self, Write};
 use ansiterm::ANSIStrings;
 use term_grid as grid;
 
+use crate::fs::compare::Comparison;
 use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
 use crate::fs::{Dir, File};
@@ -14,8 +15,9 @@ use crate::output::details::{
     Options as DetailsOptions, Render as DetailsRender, Row as DetailsRow,
 };
 use crate::output::file_name::Options as FileStyle;
-use crate::output::file_name::{EmbedHyperlinks, ShowIcons};
+use crate::output::file_name::ShowIcons;
 use crate::output::grid::Options as GridOptions;
+use crate::output::layout_debug;
 use crate::output::table::{Options as TableOptions, Row as TableRow, Table};
 use crate::output::tree::{TreeDepth, TreeParams};
 use crate::theme::Theme;
@@ -87,6 +89,9 @@ pub struct Render<'a> {
 
     pub git: Option<&'a GitCache>,
 
+    /// The comparison for the diff column, if there is one.
+    pub diff: Option<&'a Comparison>,
+
     pub console_width: usize,
 
     pub git_repos: bool,
@@ -111,6 +116,7 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            diff:          self.diff,
             git_repos:     self.git_repos,
         };
     }
@@ -131,6 +137,7 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            diff:          self.diff,
             git_repos:     self.git_repos,
         };
     }
@@ -163,15 +170,18 @@ impl<'a> Render<'a> {
             self.git_ignoring,
             None,
         );
+        if let Some(info) = &color_scale_info {
+            info.dump(self.theme);
+        }
 
-        let (first_table, _) = self.make_table(options, &drender);
+        let (mut first_table, _) = self.make_table(options, &drender);
+        first_table.rank_ages(&self.files);
+        first_table.find_dupes(&self.files, self.filter.dot_filter, self.git_ignoring, None);
 
         let rows = self
             .files
             .iter()
-            .map(|file| {
-                first_table.row_for_file(file, drender.show_xattr_hint(file), color_scale_info)
-            })
+            .map(|file| drender.table_row(&first_table, file, color_scale_info))
             .collect::<Vec<_>>();
 
         let file_names = self
@@ -185,20 +195,15 @@ impl<'a> Render<'a> {
                     QuoteStyle::NoQuotes => 0,
                     QuoteStyle::QuoteSpaces => 0, // Default case
                 };
-                let width = match (
-                    filename.options.embed_hyperlinks,
-                    filename.options.show_icons,
-                ) {
-                    (EmbedHyperlinks::On, ShowIcons::Automatic(spacing)) => {
+                let width = match (filename.embeds_hyperlinks(), filename.options.show_icons) {
+                    (true, ShowIcons::Automatic(spacing)) => {
                         filename.bare_utf8_width() + 1 + (spacing as usize) + space_filename_offset
                     }
-                    (EmbedHyperlinks::On, ShowIcons::Always(spacing)) => {
+                    (true, ShowIcons::Always(spacing)) => {
                         filename.bare_utf8_width() + 1 + (spacing as usize) + space_filename_offset
                     }
-                    (EmbedHyperlinks::On, ShowIcons::Never) => {
-                        filename.bare_utf8_width() + space_filename_offset
-                    }
-                    (EmbedHyperlinks::Off, _) => *contents.width(),
+                    (true, ShowIcons::Never) => filename.bare_utf8_width() + space_filename_offset,
+                    (false, _) => *contents.width(),
                 };
 
                 TextCell {
@@ -228,6 +233,12 @@ impl<'a> Render<'a> {
 
             if the_grid_fits {
                 last_working_grid = grid;
+            } else if layout_debug::enabled() {
+                let width = grid.fit_into_columns(column_count).width();
+                layout_debug::note(format_args!(
+                    "{column_count} columns of tables would be {width} wide, more than the {} columns of width available",
+                    self.console_width
+                ));
             }
 
             if !the_grid_fits || column_count == file_names.len() {
@@ -240,15 +251,21 @@ impl<'a> Render<'a> {
                 // and it turns out there aren’t enough rows to make it worthwhile
                 // (according to EZA_GRID_ROWS), then just resort to the lines view.
                 if let RowThreshold::MinimumRows(thresh) = self.row_threshold {
-                    if last_working_grid
+                    let rows = last_working_grid
                         .fit_into_columns(last_column_count)
-                        .row_count()
-                        < thresh
-                    {
+                        .row_count();
+                    if rows < thresh {
+                        layout_debug::note(format_args!(
+                            "{last_column_count} columns of tables would only have {rows} rows, fewer than the {thresh} set by EZA_GRID_ROWS, so using the details view"
+                        ));
                         return None;
                     }
                 }
 
+                layout_debug::note(format_args!(
+                    "grid has {last_column_count} columns of tables, in {} columns of width",
+                    self.console_width
+                ));
                 return Some((last_working_grid, last_column_count));
             }
         }
@@ -275,7 +292,14 @@ impl<'a> Render<'a> {
             (None, _) => { /* Keep Git how it is */ }
         }
 
-        let mut table = Table::new(options, self.git, self.theme, self.git_repos);
+        let mut table = Table::new(
+            options,
+            self.git,
+            self.diff,
+            self.theme,
+            self.git_repos,
+            self.file_style,
+        );
         let mut rows = Vec::new();
 
         if self.details.header {
@@ -0,0 +1,43 @@
+//! Highlighting the parts of file names that match a pattern, as `grep
+//! --color` does, while still listing every file.
+//!
+//! The pattern is carried in the file name options, so it gets read from
+//! wherever names get drawn.
+
+use std::ops::Range;
+
+use regex::Regex;
+
+/// The byte ranges of the given name that match the pattern, if there is
+/// one, in order, leaving out empty matches, which would have nothing to
+/// highlight.
+pub fn matches(pattern: Option<&Regex>, name: &str) -> Vec<Range<usize>> {
+    pattern
+        .map(|pattern| ranges(pattern, name))
+        .unwrap_or_default()
+}
+
+fn ranges(pattern: &Regex, name: &str) -> Vec<Range<usize>> {
+    pattern
+        .find_iter(name)
+        .map(|m| m.range())
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn several() {
+        let pattern = Regex::new("o+").unwrap();
+        assert_eq!(ranges(&pattern, "foo.book"), vec![1..3, 5..7]);
+    }
+
+    #[test]
+    fn empty_matches() {
+        let pattern = Regex::new("x*").unwrap();
+        assert_eq!(ranges(&pattern, "axb"), vec![1..2]);
+    }
+}
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use ansiterm::Style;
 use phf::{phf_map, Map};
 
+use crate::fs::feature::special_dirs::UserDir;
 use crate::fs::File;
 
 #[non_exhaustive]
@@ -773,12 +776,34 @@ pub fn iconify_style(style: Style) -> Style {
         .unwrap_or_default()
 }
 
+/// Lookup the icon for one of the user’s XDG user directories, which gets
+/// used whatever the directory is called.
+pub fn icon_for_user_dir(dir: UserDir) -> char {
+    #[rustfmt::skip]
+    return match dir {
+        UserDir::Desktop     => '\u{f108}',   // 
+        UserDir::Documents   => '\u{f0219}',  // 󰈙
+        UserDir::Download    => '\u{f024d}',  // 󰉍
+        UserDir::Music       => '\u{f1359}',  // 󱍙
+        UserDir::Pictures    => '\u{f024f}',  // 󰉏
+        UserDir::PublicShare => '\u{f1e0}',   // 
+        UserDir::Templates   => '\u{f0c5}',   // 
+        UserDir::Videos      => '\u{f03d}',   // 
+    };
+}
+
 /// Lookup the icon for a file based on the file's name, if the entry is a
-/// directory, or by the lowercase file extension.
-pub fn icon_for_file(file: &File<'_>) -> char {
-    if file.points_to_directory() {
+/// directory, or by the lowercase file extension. Icons the user has picked
+/// for globs come before all of these, and icons they’ve picked for kinds of
+/// file come before the generic folder and file icons.
+pub fn icon_for_file(file: &File<'_>, custom: &IconMappings) -> char {
+    if let Some(icon) = custom.for_name(&file.name) {
+        icon
+    } else if file.points_to_directory() {
         *DIRECTORY_ICONS.get(file.name.as_str()).unwrap_or_else(|| {
-            if file.is_empty_dir() {
+            if let Some(icon) = custom.kinds.get("di") {
+                icon
+            } else if file.is_empty_dir() {
                 &Icons::FOLDER_OPEN // 
             } else {
                 &Icons::FOLDER // 
@@ -786,9 +811,154 @@ pub fn icon_for_file(file: &File<'_>) -> char {
         })
     } else if let Some(icon) = FILENAME_ICONS.get(file.name.as_str()) {
         *icon
-    } else if let Some(ext) = file.ext.as_ref() {
-        *EXTENSION_ICONS.get(ext.as_str()).unwrap_or(&Icons::FILE) // 
+    } else if let Some(icon) = file
+        .ext
+        .as_ref()
+        .and_then(|ext| EXTENSION_ICONS.get(ext.as_str()))
+    {
+        *icon
+    } else if let Some(icon) = custom.for_kind(kind_code(file)) {
+        icon
+    } else if file.ext.is_some() {
+        Icons::FILE // 
     } else {
         Icons::FILE_OUTLINE // 
     }
 }
+
+/// The two-letter codes, as used in `LS_COLORS`, for the kinds of file that
+/// can be given their own icons.
+pub static ICON_KINDS: &[&str] = &["di", "fi", "ex", "ln", "pi", "so", "bd", "cd"];
+
+/// The code for the kind of file something that isn’t a directory is.
+fn kind_code(file: &File<'_>) -> &'static str {
+    if file.is_link() {
+        return "ln";
+    }
+
+    #[cfg(unix)]
+    {
+        if file.is_pipe() {
+            return "pi";
+        } else if file.is_socket() {
+            return "so";
+        } else if file.is_block_device() {
+            return "bd";
+        } else if file.is_char_device() {
+            return "cd";
+        } else if file.is_executable_file() {
+            return "ex";
+        }
+    }
+
+    "fi"
+}
+
+/// Icons picked by the user, in `EZA_ICONS` or the theme file, either for
+/// file names matching globs, or for kinds of file by their two-letter
+/// code.
+#[derive(Debug, Default)]
+pub struct IconMappings {
+    globs: Vec<(glob::Pattern, char)>,
+    kinds: HashMap<&'static str, char>,
+}
+
+impl IconMappings {
+    /// Adds an icon for a glob or a kind of file, replacing any icon given
+    /// for the same kind before. The icon is either a single character or a
+    /// `\u` escape of one, such as `\ue7a8` or `\u{f115}`.
+    pub fn add(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let Some(icon) = parse_icon(value) else {
+            return Err(format!("{value:?} is not a single character"));
+        };
+
+        if let Some(kind) = ICON_KINDS.iter().find(|k| **k == key) {
+            let _ = self.kinds.insert(kind, icon);
+            return Ok(());
+        }
+
+        let pattern = glob::Pattern::new(key)
+            .map_err(|e| format!("couldn’t parse glob pattern {key:?}: {e}"))?;
+        self.globs.push((pattern, icon));
+        Ok(())
+    }
+
+    /// The icon for a file name, going by the last glob that matches it.
+    pub fn for_name(&self, name: &str) -> Option<char> {
+        self.globs
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.matches(name))
+            .map(|(_, icon)| *icon)
+    }
+
+    /// The icon for a kind of file that isn’t a directory, falling back to
+    /// the icon for regular files.
+    fn for_kind(&self, code: &str) -> Option<char> {
+        self.kinds
+            .get(code)
+            .or_else(|| self.kinds.get("fi"))
+            .copied()
+    }
+}
+
+/// Reads an icon, which is either written as itself or as a `\u` escape of
+/// its code point in hex.
+fn parse_icon(value: &str) -> Option<char> {
+    if let Some(hex) = value.strip_prefix("\\u") {
+        let hex = hex
+            .strip_prefix('{')
+            .and_then(|h| h.strip_suffix('}'))
+            .unwrap_or(hex);
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(icon), None) => Some(icon),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn icon_itself() {
+        assert_eq!(parse_icon("λ"), Some('λ'));
+    }
+
+    #[test]
+    fn icon_escaped() {
+        assert_eq!(parse_icon("\\ue7a8"), Some('\u{e7a8}'));
+        assert_eq!(parse_icon("\\u{f0219}"), Some('\u{f0219}'));
+    }
+
+    #[test]
+    fn icon_not_one_character() {
+        assert_eq!(parse_icon("ab"), None);
+        assert_eq!(parse_icon(""), None);
+        assert_eq!(parse_icon("\\uzz"), None);
+    }
+
+    #[test]
+    fn last_glob_wins() {
+        let mut icons = IconMappings::default();
+        icons.add("*.rs", "a").unwrap();
+        icons.add("main.*", "b").unwrap();
+        assert_eq!(icons.for_name("main.rs"), Some('b'));
+        assert_eq!(icons.for_name("lib.rs"), Some('a'));
+        assert_eq!(icons.for_name("lib.c"), None);
+    }
+
+    #[test]
+    fn kinds_fall_back_to_files() {
+        let mut icons = IconMappings::default();
+        icons.add("fi", "f").unwrap();
+        icons.add("ex", "x").unwrap();
+        assert_eq!(icons.for_kind("ex"), Some('x'));
+        assert_eq!(icons.for_kind("ln"), Some('f'));
+        assert_eq!(icons.for_name("fi"), None);
+    }
+}
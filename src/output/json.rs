@@ -0,0 +1,434 @@
+//! The JSON view, for scripts that want eza’s listing without having to pick
+//! apart text that was laid out for people to read.
+//!
+//! Without `--tree`, the listing is one flat array of files: the same ones
+//! that would otherwise be printed, including the contents of every
+//! directory that `--recurse` descends into, each with its full path. With
+//! `--tree`, every directory instead gets a `children` array holding the
+//! files inside it, nested as deep as `--level` allows, so the hierarchy can
+//! be read straight out of the document.
+//!
+//! Each file has the same metadata the details view can show: its name,
+//! path, type and size; its accessed, changed, created and modified times;
+//! its permissions, link count, inode, owner and group on Unix; its Git
+//! status, if Git is being used; and the names of its extended attributes.
+//! Anything a file doesn’t have is `null`.
+//!
+//! Everything goes into a single document, so unlike the other views there
+//! are no headings or blank lines between directories.
+//!
+//! Unless `--no-metadata` is given, the listing is wrapped in an object
+//! that records how it was made, so it can be traced back later:
+//!
+//! ```text
+//! {"metadata":{"version":"…","time":"…","roots":["src"],"filters":["--only-dirs"]},"files":[…]}
+//! ```
+
+use std::ffi::OsStr;
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{NaiveDateTime, SecondsFormat, Utc};
+#[cfg(unix)]
+use uzers::{Groups, Users, UsersCache};
+
+use crate::diagnostics;
+use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
+use crate::fs::filter::FileFilter;
+use crate::fs::{Dir, File};
+use crate::json_string::json_string;
+use crate::output::relative_root;
+
+pub struct Render<'a> {
+    /// The files given as arguments that are listed as themselves. With
+    /// `--tree`, this includes directories, which become the roots of trees.
+    pub files: Vec<File<'a>>,
+
+    /// The directories given as arguments that have their contents listed
+    /// instead of themselves.
+    pub dirs: Vec<Dir>,
+
+    /// How to sort and filter the files inside directories.
+    pub filter: &'a FileFilter,
+
+    /// Whether to recurse into directories, and whether as a tree.
+    pub recurse: Option<RecurseOptions>,
+
+    pub git: Option<&'a GitCache>,
+
+    /// Whether we are skipping Git-ignored files.
+    pub git_ignoring: bool,
+
+    pub deref_links: bool,
+
+    /// Whether directories’ sizes are the total size of everything in them.
+    pub total_size: bool,
+
+    /// The directory to show paths as if it were the root of the
+    /// filesystem, if there is one.
+    pub relative_root: Option<&'a Path>,
+
+    /// What to record about how the listing was made, if anything.
+    pub metadata: Option<Metadata<'a>>,
+
+    /// The users and groups that own files, looked up by ID.
+    #[cfg(unix)]
+    pub users: UsersCache,
+}
+
+/// How a listing was made, written before the files themselves.
+pub struct Metadata<'a> {
+    /// The paths that were listed, as they were given.
+    pub roots: &'a [&'a OsStr],
+
+    /// The filtering options that were given.
+    pub filters: &'a [String],
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files, self.git);
+
+        if let Some(metadata) = &self.metadata {
+            write!(w, "{{\"metadata\":")?;
+            metadata.write(w)?;
+            write!(w, ",\"files\":")?;
+        }
+
+        let mut first = true;
+        write!(w, "[")?;
+
+        for file in &self.files {
+            self.write_entry(w, &mut first, file, 0)?;
+        }
+
+        for dir in &self.dirs {
+            self.write_contents(w, &mut first, dir, 1)?;
+        }
+
+        write!(w, "]")?;
+        if self.metadata.is_some() {
+            write!(w, "}}")?;
+        }
+        writeln!(w)
+    }
+
+    /// Writes the contents of a directory as entries in the flat listing,
+    /// followed by the contents of its subdirectories if recursing.
+    fn write_contents<W: Write>(
+        &self,
+        w: &mut W,
+        first: &mut bool,
+        dir: &Dir,
+        depth: usize,
+    ) -> io::Result<()> {
+        let children = self.children(dir);
+
+        for child in &children {
+            self.write_entry(w, first, child, depth)?;
+        }
+
+        if let Some(recurse) = self.recurse {
+            if !recurse.tree && !recurse.is_too_deep(depth) {
+                for child in children
+                    .iter()
+                    .filter(|f| recurse.descends_into(f) && !f.is_all_all)
+                {
+                    if let Some(child_dir) = Self::read_dir(child) {
+                        self.write_contents(w, first, &child_dir, depth + 1)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes one file as an object, with a `children` array of the files
+    /// inside it if it’s a directory being shown as a tree.
+    fn write_entry<W: Write>(
+        &self,
+        w: &mut W,
+        first: &mut bool,
+        file: &File<'_>,
+        depth: usize,
+    ) -> io::Result<()> {
+        if !*first {
+            write!(w, ",")?;
+        }
+        *first = false;
+
+        write!(
+            w,
+            "{{\"name\":{},\"path\":{},\"type\":\"{}\",\"size\":{}",
+            json_string(&file.name),
+            json_string(&relative_root::display(self.relative_root, &file.path).to_string_lossy()),
+            type_name(file.type_char()),
+            size(file.size()),
+        )?;
+
+        write!(
+            w,
+            ",\"accessed\":{},\"changed\":{},\"created\":{},\"modified\":{}",
+            timestamp(file.accessed_time()),
+            timestamp(file.changed_time()),
+            timestamp(file.created_time()),
+            timestamp(file.modified_time()),
+        )?;
+
+        self.write_ownership(w, file)?;
+
+        write!(
+            w,
+            ",\"git\":{},\"xattrs\":{}",
+            self.git_status(file),
+            xattrs(file),
+        )?;
+
+        let expand = self
+            .recurse
+            .is_some_and(|r| r.tree && !r.is_too_deep(depth) && r.descends_into(file));
+        if expand && !file.is_all_all {
+            if let Some(dir) = Self::read_dir(file) {
+                let mut first_child = true;
+                write!(w, ",\"children\":[")?;
+                for child in &self.children(&dir) {
+                    self.write_entry(w, &mut first_child, child, depth + 1)?;
+                }
+                write!(w, "]")?;
+            }
+        }
+
+        write!(w, "}}")
+    }
+
+    /// Writes the permissions, link count, inode, owner and group of a file.
+    #[cfg(unix)]
+    fn write_ownership<W: Write>(&self, w: &mut W, file: &File<'_>) -> io::Result<()> {
+        let permissions = file.permissions().map_or_else(
+            || "null".into(),
+            |p| json_string(&format!("{:04o}", p.bits())),
+        );
+
+        let user = file.user().map_or_else(
+            || "null".into(),
+            |user| {
+                let name = self.users.get_user_by_uid(user.0);
+                owner(user.0, name.as_ref().map(|u| u.name()))
+            },
+        );
+
+        let group = file.group().map_or_else(
+            || "null".into(),
+            |group| {
+                let name = self.users.get_group_by_gid(group.0);
+                owner(group.0, name.as_ref().map(|g| g.name()))
+            },
+        );
+
+        write!(
+            w,
+            ",\"permissions\":{},\"links\":{},\"inode\":{},\"user\":{},\"group\":{}",
+            permissions,
+            file.links().count,
+            file.inode().0,
+            user,
+            group,
+        )
+    }
+
+    /// Files on Windows have none of the Unix ownership fields.
+    #[cfg(windows)]
+    fn write_ownership<W: Write>(&self, w: &mut W, _file: &File<'_>) -> io::Result<()> {
+        write!(
+            w,
+            ",\"permissions\":null,\"links\":null,\"inode\":null,\"user\":null,\"group\":null"
+        )
+    }
+
+    /// A file’s staged and unstaged Git status, or `null` if it isn’t in a
+    /// Git repository.
+    fn git_status(&self, file: &File<'_>) -> String {
+        let Some(git) = self.git.filter(|g| g.has_anything_for(&file.path)) else {
+            return "null".into();
+        };
+
+        let status = git.get(&file.path, file.is_directory());
+        format!(
+            "{{\"staged\":\"{}\",\"unstaged\":\"{}\"}}",
+            git_status_name(status.staged),
+            git_status_name(status.unstaged),
+        )
+    }
+
+    /// The files in a directory, filtered and sorted.
+    fn children<'dir>(&self, dir: &'dir Dir) -> Vec<File<'dir>> {
+        let mut children = Vec::new();
+
+        for file in dir.files(
+            self.filter.dot_filter,
+            self.git,
+            self.git_ignoring,
+            self.deref_links,
+            self.total_size,
+        ) {
+            match file {
+                Ok(file) => children.push(file),
+                Err((path, e)) => {
+                    diagnostics::warning(format_args!("[{}: {}]", path.display(), e));
+                }
+            }
+        }
+
+        self.filter.filter_child_files(&mut children, self.git);
+        self.filter.sort_files(&mut children, self.git);
+        children
+    }
+
+    /// Reads a directory to recurse into, warning if it can’t be read.
+    fn read_dir(file: &File<'_>) -> Option<Dir> {
+        match file.to_dir() {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                diagnostics::warning(format_args!("{}: {}", file.path.display(), e));
+                None
+            }
+        }
+    }
+}
+
+impl Metadata<'_> {
+    fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let [(_, version), (_, time), (_, roots), (_, filters)] = self.entries();
+        let strings = |values: Vec<String>| {
+            values
+                .iter()
+                .map(|value| json_string(value))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        write!(
+            w,
+            "{{\"version\":{},\"time\":{},\"roots\":[{}],\"filters\":[{}]}}",
+            strings(version),
+            strings(time),
+            strings(roots),
+            strings(filters),
+        )
+    }
+
+    /// Each piece of metadata, by name, as plain strings: the version of eza
+    /// and the time, which have one value each, then the roots and filters.
+    pub fn entries(&self) -> [(&'static str, Vec<String>); 4] {
+        [
+            ("version", vec![env!("CARGO_PKG_VERSION").into()]),
+            (
+                "time",
+                vec![Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)],
+            ),
+            (
+                "roots",
+                self.roots
+                    .iter()
+                    .map(|root| root.to_string_lossy().into_owned())
+                    .collect(),
+            ),
+            ("filters", self.filters.to_vec()),
+        ]
+    }
+}
+
+/// The name of a type of file, as it appears in the `type` field.
+pub fn type_name(typ: f::Type) -> &'static str {
+    match typ {
+        f::Type::Directory => "directory",
+        f::Type::File => "file",
+        f::Type::Link => "link",
+        f::Type::Pipe => "pipe",
+        f::Type::Socket => "socket",
+        f::Type::CharDevice => "char_device",
+        f::Type::BlockDevice => "block_device",
+        f::Type::Special => "special",
+    }
+}
+
+/// A file’s size in bytes, or `null` if it doesn’t have one. Directories
+/// only have sizes when their total size is being calculated.
+fn size(size: f::Size) -> String {
+    match size {
+        f::Size::Some(bytes) => bytes.to_string(),
+        f::Size::None | f::Size::Virtual | f::Size::DeviceIDs(_) => "null".into(),
+    }
+}
+
+/// A timestamp as an ISO 8601 string in UTC, or `null` if the file doesn’t
+/// have it.
+fn timestamp(time: Option<NaiveDateTime>) -> String {
+    time.map_or_else(
+        || "null".into(),
+        |time| json_string(&time.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+    )
+}
+
+/// A user or group as an object with its numeric ID and its name, which is
+/// `null` if the ID doesn’t belong to anyone.
+#[cfg(unix)]
+fn owner(id: u32, name: Option<&OsStr>) -> String {
+    let name = name.map_or_else(|| "null".into(), |n| json_string(&n.to_string_lossy()));
+    format!("{{\"id\":{id},\"name\":{name}}}")
+}
+
+/// The name of a Git status, as it appears in the `git` field.
+fn git_status_name(status: f::GitStatus) -> &'static str {
+    match status {
+        f::GitStatus::NotModified => "not_modified",
+        f::GitStatus::New => "new",
+        f::GitStatus::Modified => "modified",
+        f::GitStatus::Deleted => "deleted",
+        f::GitStatus::Renamed => "renamed",
+        f::GitStatus::TypeChange => "type_change",
+        f::GitStatus::Ignored => "ignored",
+        f::GitStatus::Conflicted => "conflicted",
+    }
+}
+
+/// The names of a file’s extended attributes, as an array.
+fn xattrs(file: &File<'_>) -> String {
+    let names = file
+        .extended_attributes()
+        .iter()
+        .map(|attr| json_string(&attr.name));
+    format!("[{}]", names.collect::<Vec<_>>().join(","))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        let time = NaiveDateTime::from_timestamp_opt(1_700_000_000, 0);
+        assert_eq!(timestamp(time), "\"2023-11-14T22:13:20Z\"");
+        assert_eq!(timestamp(None), "null");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn owners() {
+        assert_eq!(
+            owner(0, Some(OsStr::new("root"))),
+            "{\"id\":0,\"name\":\"root\"}"
+        );
+        assert_eq!(owner(4242, None), "{\"id\":4242,\"name\":null}");
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(size(f::Size::Some(1024)), "1024");
+        assert_eq!(size(f::Size::None), "null");
+        assert_eq!(size(f::Size::Virtual), "null");
+    }
+}
@@ -0,0 +1,30 @@
+//! Explaining how a listing was laid out, for the `--debug-layout` option.
+//!
+//! When a grid wraps unexpectedly or a column goes missing, the only way to
+//! find out why is to know what widths eza was working with. The grid and
+//! table code reports each decision it makes here as it makes it, and if the
+//! user asked to see them, they get printed to standard error, out of the way
+//! of the listing itself.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether layout decisions made from now on should be printed.
+pub fn configure(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether layout decisions are being printed, for callers that would
+/// otherwise have to do extra work to describe them.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Reports a layout decision.
+pub fn note(message: impl fmt::Display) {
+    if enabled() {
+        eprintln!("eza layout: {message}");
+    }
+}
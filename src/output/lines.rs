@@ -1,27 +1,49 @@
 use std::io::{self, Write};
+use std::path::Path;
 
 use ansiterm::ANSIStrings;
 
+use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
 use crate::fs::File;
 use crate::output::cell::TextCellContents;
 use crate::output::file_name::Options as FileStyle;
 use crate::theme::Theme;
 
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// Whether each line should be written for fzf to read.
+    pub fzf: bool,
+}
+
 /// The lines view literally just displays each file, line-by-line.
+///
+/// For fzf, each “line” is instead the displayed name and the file’s raw
+/// path, separated by a tab and ended with a null byte, so fzf can show the
+/// coloured name while giving back the path the user picked, whatever
+/// characters are in it.
 pub struct Render<'a> {
     pub files: Vec<File<'a>>,
     pub theme: &'a Theme,
     pub file_style: &'a FileStyle,
+    pub opts: &'a Options,
     pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
 }
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
-        self.filter.sort_files(&mut self.files);
+        self.filter.sort_files(&mut self.files, self.git);
         for file in &self.files {
             let name_cell = self.render_file(file);
-            writeln!(w, "{}", ANSIStrings(&name_cell))?;
+
+            if self.opts.fzf {
+                write!(w, "{}\t", ANSIStrings(&name_cell))?;
+                write_path(w, &file.path)?;
+                w.write_all(b"\0")?;
+            } else {
+                writeln!(w, "{}", ANSIStrings(&name_cell))?;
+            }
         }
 
         Ok(())
@@ -35,3 +57,16 @@ impl<'a> Render<'a> {
             .paint()
     }
 }
+
+/// Writes a path exactly as it is, even if it isn’t valid UTF-8.
+#[cfg(unix)]
+fn write_path<W: Write>(w: &mut W, path: &Path) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    w.write_all(path.as_os_str().as_bytes())
+}
+
+/// Writes a path, replacing anything that isn’t valid Unicode.
+#[cfg(not(unix))]
+fn write_path<W: Write>(w: &mut W, path: &Path) -> io::Result<()> {
+    write!(w, "{}", path.to_string_lossy())
+}
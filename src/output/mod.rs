@@ -1,16 +1,32 @@
 pub use self::cell::{DisplayWidth, TextCell, TextCellContents};
-pub use self::escape::escape;
+pub use self::escape::{escape, escape_highlighted};
+pub use self::terminal::{Geometry, TerminalHeight, TerminalWidth};
 
+pub mod badges;
+pub mod blocks_map;
+pub mod capabilities;
+pub mod color_by;
 pub mod color_scale;
+pub mod decorations;
+pub mod delimited;
 pub mod details;
+pub mod dupes;
 pub mod file_name;
+pub mod glyphs;
 pub mod grid;
 pub mod grid_details;
+pub mod highlight;
 pub mod icons;
+pub mod json;
+pub mod layout_debug;
 pub mod lines;
+pub mod relative_root;
 pub mod render;
+pub mod stats;
 pub mod table;
+pub mod terminal;
 pub mod time;
+pub mod usage_bar;
 
 mod cell;
 mod escape;
@@ -21,11 +37,20 @@ mod tree;
 pub struct View {
     pub mode: Mode,
     pub width: TerminalWidth,
+    pub height: TerminalHeight,
     pub file_style: file_name::Options,
     pub deref_links: bool,
     pub total_size: bool,
 }
 
+impl View {
+    /// The size to lay the output out for, resolved from the sizes the user
+    /// asked for and the size of the terminal itself.
+    pub fn geometry(&self) -> Geometry {
+        Geometry::resolve(self.width, self.height)
+    }
+}
+
 /// The **mode** is the “type” of output.
 #[derive(PartialEq, Eq, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -33,45 +58,7 @@ pub enum Mode {
     Grid(grid::Options),
     Details(details::Options),
     GridDetails(grid_details::Options),
-    Lines,
-}
-
-/// The width of the terminal requested by the user.
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub enum TerminalWidth {
-    /// The user requested this specific number of columns.
-    Set(usize),
-
-    /// Look up the terminal size at runtime.
-    Automatic,
-}
-
-impl TerminalWidth {
-    pub fn actual_terminal_width(self) -> Option<usize> {
-        // All of stdin, stdout, and stderr could not be connected to a
-        // terminal, but we’re only interested in stdout because it’s
-        // where the output goes.
-
-        #[cfg(unix)]
-        let stdout_term_width = {
-            use std::os::fd::AsRawFd;
-            terminal_size::terminal_size_using_fd(std::io::stdout().as_raw_fd())
-                .map(|(w, _h)| w.0 as _)
-        };
-        #[cfg(windows)]
-        let stdout_term_width = {
-            use std::os::windows::io::RawHandle;
-            use windows_sys::Win32::System::Console::{GetStdHandle, STD_OUTPUT_HANDLE};
-            terminal_size::terminal_size_using_handle(unsafe {
-                GetStdHandle(STD_OUTPUT_HANDLE) as RawHandle
-            })
-            .map(|(w, _h)| w.0 as _)
-        };
-
-        #[rustfmt::skip]
-        return match self {
-            Self::Set(width)  => Some(width),
-            Self::Automatic   => stdout_term_width,
-        };
-    }
+    Lines(lines::Options),
+    Json,
+    Delimited(delimited::Options),
 }
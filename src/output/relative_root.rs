@@ -0,0 +1,68 @@
+//! Showing paths as they’d be seen from inside another root directory, for
+//! `--relative-root`, such as a container’s filesystem mounted on the host.
+//!
+//! Any path printed that’s inside the root gets the root taken off its
+//! start and is shown as an absolute path from there, so a file at
+//! `/mnt/rootfs/etc/hosts` is shown as `/etc/hosts`. Paths outside the root
+//! are shown as they are. Paths are compared component by component, without
+//! being resolved, so the root has to be given the same way as the paths
+//! being listed.
+//!
+//! The root is carried in the file name options, and handed to whatever else
+//! prints paths, such as directory headings and the JSON and CSV views.
+
+use std::borrow::Cow;
+use std::path::{Path, MAIN_SEPARATOR};
+
+/// The given path as it should be shown, relative to the root if there is
+/// one.
+pub fn display<'a>(root: Option<&Path>, path: &'a Path) -> Cow<'a, Path> {
+    match root {
+        Some(root) => relative_to(root, path),
+        None => Cow::Borrowed(path),
+    }
+}
+
+fn relative_to<'a>(root: &Path, path: &'a Path) -> Cow<'a, Path> {
+    match path.strip_prefix(root) {
+        Ok(rest) => Cow::Owned(Path::new(&MAIN_SEPARATOR.to_string()).join(rest)),
+        Err(_) => Cow::Borrowed(path),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn inside() {
+        assert_eq!(
+            relative_to(Path::new("/mnt/rootfs"), Path::new("/mnt/rootfs/etc/hosts")),
+            Path::new("/etc/hosts")
+        );
+    }
+
+    #[test]
+    fn the_root_itself() {
+        assert_eq!(
+            relative_to(Path::new("/mnt/rootfs/"), Path::new("/mnt/rootfs")),
+            Path::new("/")
+        );
+    }
+
+    #[test]
+    fn outside() {
+        assert_eq!(
+            relative_to(Path::new("/mnt/rootfs"), Path::new("/mnt/rootfs2/etc")),
+            Path::new("/mnt/rootfs2/etc")
+        );
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(
+            relative_to(Path::new("rootfs"), Path::new("rootfs/usr/lib")),
+            Path::new("/usr/lib")
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::AgeRank {
+    pub fn render(self, style: Style) -> TextCell {
+        TextCell::paint(style, self.0.to_string())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use ansiterm::Colour::*;
+
+    #[test]
+    fn third_newest() {
+        let rank = f::AgeRank(3);
+        let expected = TextCell::paint_str(Blue.normal(), "3");
+        assert_eq!(expected, rank.render(Blue.normal()));
+    }
+}
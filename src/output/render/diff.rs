@@ -0,0 +1,23 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::DiffStatus {
+    pub fn render(self, colours: &dyn Colours) -> TextCell {
+        #[rustfmt::skip]
+        return match self {
+            Self::Same       => TextCell::paint_str(colours.same(), "="),
+            Self::Different  => TextCell::paint_str(colours.different(), "~"),
+            Self::OnlyHere   => TextCell::paint_str(colours.only_here(), "+"),
+            Self::Unknown    => TextCell::paint_str(colours.unknown(), "?"),
+        };
+    }
+}
+
+pub trait Colours {
+    fn same(&self) -> Style;
+    fn different(&self) -> Style;
+    fn only_here(&self) -> Style;
+    fn unknown(&self) -> Style;
+}
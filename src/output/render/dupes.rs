@@ -0,0 +1,25 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::DupeGroup {
+    pub fn render(self, style: Style) -> TextCell {
+        TextCell::paint(style, self.0.to_string())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use ansiterm::Colour::*;
+
+    #[test]
+    fn second_group() {
+        let group = f::DupeGroup(2);
+        let expected = TextCell::paint_str(Yellow.normal(), "2");
+        assert_eq!(expected, group.render(Yellow.normal()));
+    }
+}
@@ -0,0 +1,72 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::TextEncoding {
+    pub fn render(self, colours: &dyn Colours) -> TextCell {
+        let (line_endings, bom) = match self {
+            Self::Unknown | Self::Binary => return TextCell::blank(colours.not_text()),
+            Self::Utf16 => return TextCell::paint_str(colours.unusual(), "UTF-16"),
+            Self::Text { line_endings, bom } => (line_endings, bom),
+        };
+
+        #[rustfmt::skip]
+        let mut cell = match line_endings {
+            f::LineEndings::None  => TextCell::blank(colours.not_text()),
+            f::LineEndings::Lf    => TextCell::paint_str(colours.lf(), "LF"),
+            f::LineEndings::Crlf  => TextCell::paint_str(colours.unusual(), "CRLF"),
+            f::LineEndings::Cr    => TextCell::paint_str(colours.unusual(), "CR"),
+        };
+
+        if bom {
+            cell.push(colours.unusual().paint("+BOM"), 4);
+        }
+
+        cell
+    }
+}
+
+pub trait Colours {
+    fn not_text(&self) -> Style;
+    fn lf(&self) -> Style;
+    fn unusual(&self) -> Style;
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::Colours;
+    use crate::fs::fields as f;
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    struct TestColours;
+
+    #[rustfmt::skip]
+    impl Colours for TestColours {
+        fn not_text(&self) -> Style { Black.normal() }
+        fn lf(&self)       -> Style { Blue.normal() }
+        fn unusual(&self)  -> Style { Yellow.normal() }
+    }
+
+    #[test]
+    fn crlf_with_bom() {
+        let encoding = f::TextEncoding::Text {
+            bom: true,
+            line_endings: f::LineEndings::Crlf,
+        };
+        let expected = TextCell {
+            width: DisplayWidth::from(8),
+            contents: vec![Yellow.paint("CRLF"), Yellow.paint("+BOM")].into(),
+        };
+        assert_eq!(expected, encoding.render(&TestColours));
+    }
+
+    #[test]
+    fn binary() {
+        let expected = TextCell::blank(Black.normal());
+        assert_eq!(expected, f::TextEncoding::Binary.render(&TestColours));
+    }
+}
@@ -1,8 +1,8 @@
-use ansiterm::Style;
+use ansiterm::{ANSIString, Style};
 use std::ffi::CStr;
 
 use crate::fs::fields as f;
-use crate::output::cell::TextCell;
+use crate::output::cell::{TextCell, TextCellContents};
 use crate::output::table::FlagsFormat;
 
 extern "C" {
@@ -33,8 +33,103 @@ fn flags_to_string(flags: f::flag_t) -> String {
     }
 }
 
+/// The single character a flag is shown as in the short format. Flags that
+/// can only be set by root are in upper case, and flags without a letter of
+/// their own are shown as a `+`.
+fn abbreviation(flag: f::FileFlag) -> &'static str {
+    match flag {
+        f::FileFlag::NoDump => "d",
+        f::FileFlag::UserImmutable => "i",
+        f::FileFlag::UserAppend => "a",
+        f::FileFlag::Opaque => "o",
+        f::FileFlag::Hidden => "h",
+        f::FileFlag::Archived => "R",
+        f::FileFlag::SystemImmutable => "I",
+        f::FileFlag::SystemAppend => "A",
+        f::FileFlag::Other(_) => "+",
+    }
+}
+
 impl f::Flags {
-    pub fn render(self, style: Style, _format: FlagsFormat) -> TextCell {
-        TextCell::paint(style, flags_to_string(self.0))
+    pub fn render<C: Colours>(self, colours: &C, format: FlagsFormat) -> TextCell {
+        let mut contents: Vec<ANSIString<'static>> = Vec::new();
+
+        for flag in self.iter() {
+            let style = colours.flag(flag);
+            if format == FlagsFormat::Short {
+                contents.push(style.paint(abbreviation(flag)));
+            } else {
+                if !contents.is_empty() {
+                    contents.push(colours.normal().paint(","));
+                }
+                contents.push(style.paint(flags_to_string(flag.bit())));
+            }
+        }
+
+        if contents.is_empty() {
+            return TextCell::paint_str(colours.normal(), "-");
+        }
+
+        TextCellContents::from(contents).promote()
+    }
+}
+
+pub trait Colours {
+    /// The style for flags without a style of their own, and the commas
+    /// between flags.
+    fn normal(&self) -> Style;
+
+    /// The style for the given flag.
+    fn flag(&self, flag: f::FileFlag) -> Style;
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::Colours;
+    use crate::fs::fields as f;
+    use crate::output::cell::{TextCell, TextCellContents};
+    use crate::output::table::FlagsFormat;
+
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    struct TestColours;
+
+    impl Colours for TestColours {
+        fn normal(&self) -> Style {
+            Fixed(244).normal()
+        }
+
+        fn flag(&self, flag: f::FileFlag) -> Style {
+            match flag {
+                f::FileFlag::SystemImmutable => Red.bold(),
+                f::FileFlag::Hidden => Purple.normal(),
+                _ => self.normal(),
+            }
+        }
+    }
+
+    #[test]
+    fn no_flags() {
+        let expected = TextCell::paint_str(Fixed(244).normal(), "-");
+        assert_eq!(
+            expected,
+            f::Flags(0).render(&TestColours, FlagsFormat::Short)
+        );
+    }
+
+    #[test]
+    fn short() {
+        let expected = TextCellContents::from(vec![
+            Purple.normal().paint("h"),
+            Red.bold().paint("I"),
+            Fixed(244).normal().paint("+"),
+        ])
+        .promote();
+        assert_eq!(
+            expected,
+            f::Flags(0x0000_8000 | 0x0002_0000 | 0x0010_0000)
+                .render(&TestColours, FlagsFormat::Short)
+        );
     }
 }
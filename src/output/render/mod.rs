@@ -3,9 +3,22 @@ mod blocks;
 #[cfg(unix)]
 pub use self::blocks::Colours as BlocksColours;
 
+mod age_rank;
+
+mod dupes;
+
+mod diff;
+pub use self::diff::Colours as DiffColours;
+
+mod encoding;
+pub use self::encoding::Colours as EncodingColours;
+
 mod filetype;
 pub use self::filetype::Colours as FiletypeColours;
 
+mod overlay;
+pub use self::overlay::Colours as OverlayColours;
+
 mod git;
 pub use self::git::Colours as GitColours;
 pub use self::git::RepoColours as GitRepoColours;
@@ -20,6 +33,9 @@ mod inode;
 // inode uses just one colour
 
 mod links;
+
+#[cfg(unix)]
+mod new_permissions;
 pub use self::links::Colours as LinksColours;
 
 mod permissions;
@@ -27,6 +43,12 @@ pub use self::permissions::{Colours as PermissionsColours, PermissionsPlusRender
 
 mod size;
 pub use self::size::Colours as SizeColours;
+pub use self::size::{DeviceWidths, DEFAULT_DEVICE_SEPARATOR};
+
+#[cfg(unix)]
+mod sockets;
+#[cfg(unix)]
+pub use self::sockets::{Colours as SocketColours, Render as SocketRender};
 
 mod times;
 pub use self::times::Render as TimeRender;
@@ -39,6 +61,8 @@ pub use self::users::Colours as UserColours;
 #[cfg(unix)]
 pub use self::users::Render as UserRender;
 
+mod version_info;
+
 mod octal;
 pub use self::octal::Render as OctalPermissionsRender;
 // octal uses just one colour
@@ -54,6 +78,14 @@ pub use self::securityctx::Colours as SecurityCtxColours;
     target_os = "dragonfly"
 ))]
 mod flags_bsd;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+pub use self::flags_bsd::Colours as FlagsColours;
 
 #[cfg(windows)]
 mod flags_windows;
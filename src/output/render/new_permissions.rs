@@ -0,0 +1,47 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::NewPermissions {
+    /// The permissions of new files and directories in octal, with a `+`
+    /// after them if they came from a default ACL, the same way as `ls`
+    /// marks files with ACLs.
+    pub fn render(self, style: Style) -> TextCell {
+        let acl = if self.from_acl { "+" } else { "" };
+        TextCell::paint(
+            style,
+            format!("{:04o} {:04o}{acl}", self.file, self.directory),
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use ansiterm::Colour::*;
+
+    #[test]
+    fn from_umask() {
+        let permissions = f::NewPermissions {
+            file: 0o644,
+            directory: 0o2755,
+            from_acl: false,
+        };
+        let expected = TextCell::paint_str(Purple.normal(), "0644 2755");
+        assert_eq!(expected, permissions.render(Purple.normal()));
+    }
+
+    #[test]
+    fn from_acl() {
+        let permissions = f::NewPermissions {
+            file: 0o660,
+            directory: 0o770,
+            from_acl: true,
+        };
+        let expected = TextCell::paint_str(Purple.normal(), "0660 0770+");
+        assert_eq!(expected, permissions.render(Purple.normal()));
+    }
+}
@@ -0,0 +1,64 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::OverlayLayer {
+    pub fn render(self, colours: &dyn Colours) -> TextCell {
+        #[rustfmt::skip]
+        return match self {
+            Self::NotOverlay  => TextCell::paint_str(colours.not_overlay(), "-"),
+            Self::Lower       => TextCell::paint_str(colours.lower(), "L"),
+            Self::Modified    => TextCell::paint_str(colours.modified(), "M"),
+            Self::Upper       => TextCell::paint_str(colours.upper(), "U"),
+            Self::Unknown     => TextCell::paint_str(colours.unknown(), "?"),
+        };
+    }
+}
+
+pub trait Colours {
+    fn not_overlay(&self) -> Style;
+    fn lower(&self) -> Style;
+    fn modified(&self) -> Style;
+    fn upper(&self) -> Style;
+    fn unknown(&self) -> Style;
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::Colours;
+    use crate::fs::fields as f;
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    struct TestColours;
+
+    #[rustfmt::skip]
+    impl Colours for TestColours {
+        fn not_overlay(&self) -> Style { Black.normal() }
+        fn lower(&self)       -> Style { Blue.normal() }
+        fn modified(&self)    -> Style { Yellow.normal() }
+        fn upper(&self)       -> Style { Green.normal() }
+        fn unknown(&self)     -> Style { Red.normal() }
+    }
+
+    #[test]
+    fn modified() {
+        let expected = TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![Yellow.paint("M")].into(),
+        };
+        assert_eq!(expected, f::OverlayLayer::Modified.render(&TestColours));
+    }
+
+    #[test]
+    fn not_overlay() {
+        let expected = TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![Black.paint("-")].into(),
+        };
+        assert_eq!(expected, f::OverlayLayer::NotOverlay.render(&TestColours));
+    }
+}
@@ -1,33 +1,41 @@
 use std::iter;
 
 use ansiterm::{ANSIString, Style};
+#[cfg(unix)]
+use unicode_width::UnicodeWidthStr;
 
 use crate::fs::fields as f;
 use crate::output::cell::{DisplayWidth, TextCell};
+use crate::output::glyphs::Glyphs;
 use crate::output::render::FiletypeColours;
 
 pub trait PermissionsPlusRender {
-    fn render<C: Colours + FiletypeColours>(&self, colours: &C) -> TextCell;
+    fn render<C: Colours + FiletypeColours>(&self, colours: &C, glyphs: &Glyphs) -> TextCell;
 }
 
 impl PermissionsPlusRender for Option<f::PermissionsPlus> {
     #[cfg(unix)]
-    fn render<C: Colours + FiletypeColours>(&self, colours: &C) -> TextCell {
+    fn render<C: Colours + FiletypeColours>(&self, colours: &C, glyphs: &Glyphs) -> TextCell {
         match self {
             Some(p) => {
                 let mut chars = vec![p.file_type.render(colours)];
                 let permissions = p.permissions;
                 chars.extend(Some(permissions).render(colours, p.file_type.is_regular_file()));
 
+                // As these are all ASCII characters, we can guarantee that they’re
+                // all going to be one character wide, and don’t need to compute the
+                // cell’s display width. The extended attribute marker is the only
+                // one that can be changed, so it gets measured separately.
+                let mut width = chars.len();
+
                 if p.xattrs {
-                    chars.push(colours.attribute().paint("@"));
+                    let marker = glyphs.xattr.clone();
+                    width += marker.width();
+                    chars.push(colours.attribute().paint(marker));
                 }
 
-                // As these are all ASCII characters, we can guarantee that they’re
-                // all going to be one character wide, and don’t need to compute the
-                // cell’s display width.
                 TextCell {
-                    width: DisplayWidth::from(chars.len()),
+                    width: DisplayWidth::from(width),
                     contents: chars.into(),
                 }
             }
@@ -42,7 +50,7 @@ impl PermissionsPlusRender for Option<f::PermissionsPlus> {
     }
 
     #[cfg(windows)]
-    fn render<C: Colours + FiletypeColours>(&self, colours: &C) -> TextCell {
+    fn render<C: Colours + FiletypeColours>(&self, colours: &C, _glyphs: &Glyphs) -> TextCell {
         match self {
             Some(p) => {
                 let mut chars = vec![p.attributes.render_type(colours)];
@@ -144,16 +152,16 @@ impl f::Attributes {
         };
 
         vec![
-            bit(self.archive, "a", colours.normal()),
-            bit(self.readonly, "r", colours.user_read()),
-            bit(self.hidden, "h", colours.special_user_file()),
-            bit(self.system, "s", colours.special_other()),
+            bit(self.archive, "a", colours.archive_attribute()),
+            bit(self.readonly, "r", colours.readonly_attribute()),
+            bit(self.hidden, "h", colours.hidden_attribute()),
+            bit(self.system, "s", colours.system_attribute()),
         ]
     }
 
     pub fn render_type<C: Colours + FiletypeColours>(self, colours: &C) -> ANSIString<'static> {
         if self.reparse_point {
-            return colours.pipe().paint("l");
+            return colours.reparse_point_attribute().paint("l");
         } else if self.directory {
             return colours.directory().paint("d");
         }
@@ -181,6 +189,14 @@ pub trait Colours {
     fn special_other(&self) -> Style;
 
     fn attribute(&self) -> Style;
+
+    /// The styles for Windows file attributes, which are shown in place of
+    /// the permissions there.
+    fn readonly_attribute(&self) -> Style;
+    fn hidden_attribute(&self) -> Style;
+    fn system_attribute(&self) -> Style;
+    fn archive_attribute(&self) -> Style;
+    fn reparse_point_attribute(&self) -> Style;
 }
 
 #[cfg(test)]
@@ -211,6 +227,11 @@ pub mod test {
         fn special_user_file(&self)   -> Style { Fixed(110).normal() }
         fn special_other(&self)       -> Style { Fixed(111).normal() }
         fn attribute(&self)           -> Style { Fixed(112).normal() }
+        fn readonly_attribute(&self)  -> Style { Fixed(114).normal() }
+        fn hidden_attribute(&self)    -> Style { Fixed(115).normal() }
+        fn system_attribute(&self)    -> Style { Fixed(116).normal() }
+        fn archive_attribute(&self)   -> Style { Fixed(117).normal() }
+        fn reparse_point_attribute(&self) -> Style { Fixed(118).normal() }
     }
 
     #[test]
@@ -2,34 +2,113 @@ use ansiterm::Style;
 
 use crate::fs::fields as f;
 use crate::output::cell::{DisplayWidth, TextCell};
+use crate::output::table::ContextFormat;
+
+/// The users and role that most files on an `SELinux` system have, which
+/// `ContextFormat::Smart` leaves out.
+const DEFAULT_USERS: &[&str] = &["system_u", "unconfined_u"];
+const DEFAULT_ROLE: &str = "object_r";
+
+/// The range that files have on systems that don’t use MLS.
+const DEFAULT_RANGE: &str = "s0";
+
+/// An `SELinux` context, split into its parts.
+struct SELinuxParts<'a> {
+    user: &'a str,
+    role: &'a str,
+    typ: &'a str,
+
+    /// The MLS range, which can itself contain colons, such as in
+    /// `s0:c0.c1023`.
+    range: Option<&'a str>,
+}
+
+impl<'a> SELinuxParts<'a> {
+    /// Splits a `user:role:type:range` context into its parts, or returns
+    /// `None` if it doesn’t have at least the first three.
+    fn parse(context: &'a str) -> Option<Self> {
+        let mut parts = context.splitn(4, ':');
+
+        Some(Self {
+            user: parts.next()?,
+            role: parts.next()?,
+            typ: parts.next()?,
+            range: parts.next(),
+        })
+    }
+
+    fn is_default(&self) -> bool {
+        DEFAULT_USERS.contains(&self.user) && self.role == DEFAULT_ROLE
+    }
+}
 
 impl f::SecurityContext<'_> {
-    pub fn render<C: Colours>(&self, colours: &C) -> TextCell {
+    pub fn render<C: Colours>(&self, colours: &C, format: ContextFormat) -> TextCell {
         match &self.context {
             f::SecurityContextType::None => TextCell::paint_str(colours.none(), "?"),
             f::SecurityContextType::SELinux(context) => {
+                let (ContextFormat::Smart, Some(parts)) = (format, SELinuxParts::parse(context))
+                else {
+                    return Self::render_full(colours, context);
+                };
+
+                let range = parts.range.filter(|r| *r != DEFAULT_RANGE);
                 let mut chars = Vec::with_capacity(7);
+                let mut width = 0;
 
-                for (i, part) in context.split(':').enumerate() {
-                    let partcolour = match i {
-                        0 => colours.selinux_user(),
-                        1 => colours.selinux_role(),
-                        2 => colours.selinux_type(),
-                        _ => colours.selinux_range(),
-                    };
-                    if i > 0 {
-                        chars.push(colours.selinux_colon().paint(":"));
+                if parts.is_default() {
+                    chars.push(colours.selinux_default().paint(parts.typ.to_string()));
+                    width += parts.typ.len();
+                } else {
+                    for (part, colour) in [
+                        (parts.user, colours.selinux_user()),
+                        (parts.role, colours.selinux_role()),
+                        (parts.typ, colours.selinux_type()),
+                    ] {
+                        if width > 0 {
+                            chars.push(colours.selinux_colon().paint(":"));
+                            width += 1;
+                        }
+                        chars.push(colour.paint(part.to_string()));
+                        width += part.len();
                     }
-                    chars.push(partcolour.paint(String::from(part)));
+                }
+
+                if let Some(range) = range {
+                    chars.push(colours.selinux_colon().paint(":"));
+                    chars.push(colours.selinux_range().paint(range.to_string()));
+                    width += 1 + range.len();
                 }
 
                 TextCell {
                     contents: chars.into(),
-                    width: DisplayWidth::from(context.len()),
+                    width: DisplayWidth::from(width),
                 }
             }
         }
     }
+
+    fn render_full<C: Colours>(colours: &C, context: &str) -> TextCell {
+        let mut chars = Vec::with_capacity(7);
+
+        for (i, part) in context.split(':').enumerate() {
+            let partcolour = match i {
+                0 => colours.selinux_user(),
+                1 => colours.selinux_role(),
+                2 => colours.selinux_type(),
+                _ => colours.selinux_range(),
+            };
+            if i > 0 {
+                chars.push(colours.selinux_colon().paint(":"));
+            }
+            chars.push(partcolour.paint(String::from(part)));
+        }
+
+        TextCell {
+            contents: chars.into(),
+            width: DisplayWidth::from(context.len()),
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -40,4 +119,114 @@ pub trait Colours {
     fn selinux_role(&self)  -> Style;
     fn selinux_type(&self)  -> Style;
     fn selinux_range(&self) -> Style;
+    fn selinux_default(&self) -> Style;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::output::cell::TextCellContents;
+    use ansiterm::Colour::*;
+
+    struct TestColours;
+
+    #[rustfmt::skip]
+    impl Colours for TestColours {
+        fn none(&self)            -> Style { Red.normal() }
+        fn selinux_colon(&self)   -> Style { White.normal() }
+        fn selinux_user(&self)    -> Style { Blue.normal() }
+        fn selinux_role(&self)    -> Style { Green.normal() }
+        fn selinux_type(&self)    -> Style { Yellow.normal() }
+        fn selinux_range(&self)   -> Style { Cyan.normal() }
+        fn selinux_default(&self) -> Style { Black.normal() }
+    }
+
+    fn render(context: &str, format: ContextFormat) -> TextCell {
+        f::SecurityContext {
+            context: f::SecurityContextType::SELinux(context),
+        }
+        .render(&TestColours, format)
+    }
+
+    #[test]
+    fn full() {
+        let expected = TextCell {
+            width: DisplayWidth::from(36),
+            contents: vec![
+                Blue.paint("unconfined_u"),
+                White.paint(":"),
+                Green.paint("object_r"),
+                White.paint(":"),
+                Yellow.paint("user_home_t"),
+                White.paint(":"),
+                Cyan.paint("s0"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(
+            expected,
+            render("unconfined_u:object_r:user_home_t:s0", ContextFormat::Full)
+        );
+    }
+
+    #[test]
+    fn smart_default() {
+        let expected = TextCell {
+            width: DisplayWidth::from(11),
+            contents: vec![Black.paint("user_home_t")].into(),
+        };
+
+        assert_eq!(
+            expected,
+            render("unconfined_u:object_r:user_home_t:s0", ContextFormat::Smart)
+        );
+    }
+
+    #[test]
+    fn smart_other_user() {
+        let expected = TextCell {
+            width: DisplayWidth::from(22),
+            contents: vec![
+                Blue.paint("staff_u"),
+                White.paint(":"),
+                Green.paint("object_r"),
+                White.paint(":"),
+                Yellow.paint("tmp_t"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(
+            expected,
+            render("staff_u:object_r:tmp_t:s0", ContextFormat::Smart)
+        );
+    }
+
+    #[test]
+    fn smart_mls_range() {
+        let expected = TextCell {
+            width: DisplayWidth::from(17),
+            contents: vec![
+                Black.paint("etc_t"),
+                White.paint(":"),
+                Cyan.paint("s0:c0.c1023"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(
+            expected,
+            render("system_u:object_r:etc_t:s0:c0.c1023", ContextFormat::Smart)
+        );
+    }
+
+    #[test]
+    fn smart_unparseable() {
+        let cell = render("kernel", ContextFormat::Smart);
+        assert_eq!(
+            cell.contents,
+            TextCellContents::from(vec![Blue.paint("kernel")])
+        );
+    }
 }
@@ -20,7 +20,10 @@ impl f::Size {
         let size = match self {
             Self::Some(s) => s,
             Self::None => return TextCell::blank(colours.no_size()),
-            Self::DeviceIDs(ref ids) => return ids.render(colours),
+            Self::Virtual => return TextCell::paint_str(colours.no_size(), "virt"),
+            Self::DeviceIDs(ref ids) => {
+                return ids.render(colours, DEFAULT_DEVICE_SEPARATOR, DeviceWidths::default())
+            }
         };
 
         let is_gradient_mode =
@@ -97,16 +100,48 @@ impl f::Size {
     }
 }
 
+/// The separator between major and minor device numbers, unless another
+/// one is picked with `EZA_DEVICE_SEPARATOR`.
+pub const DEFAULT_DEVICE_SEPARATOR: &str = ",";
+
+/// The widest major and minor device numbers in a column, so the two parts
+/// can each be right-aligned on their own.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct DeviceWidths {
+    pub major: usize,
+    pub minor: usize,
+}
+
+impl DeviceWidths {
+    /// Widens these widths to fit the given device numbers.
+    pub fn add(&mut self, ids: f::DeviceIDs) {
+        self.major = self.major.max(ids.major.to_string().len());
+        self.minor = self.minor.max(ids.minor.to_string().len());
+    }
+
+    /// The width of a whole cell rendered with these widths.
+    pub fn total(self, separator: &str) -> usize {
+        self.major + *DisplayWidth::from(separator) + self.minor
+    }
+}
+
 impl f::DeviceIDs {
-    fn render<C: Colours>(self, colours: &C) -> TextCell {
-        let major = self.major.to_string();
-        let minor = self.minor.to_string();
+    /// Renders the major and minor device numbers either side of the
+    /// separator, padding each on the left to the given widths.
+    pub fn render<C: Colours>(
+        self,
+        colours: &C,
+        separator: &str,
+        widths: DeviceWidths,
+    ) -> TextCell {
+        let major = format!("{:>1$}", self.major, widths.major);
+        let minor = format!("{:>1$}", self.minor, widths.minor);
 
         TextCell {
-            width: DisplayWidth::from(major.len() + 1 + minor.len()),
+            width: DisplayWidth::from(&*major) + *DisplayWidth::from(separator) + minor.len(),
             contents: vec![
                 colours.major().paint(major),
-                colours.comma().paint(","),
+                colours.comma().paint(separator.to_string()),
                 colours.minor().paint(minor),
             ]
             .into(),
@@ -247,4 +282,21 @@ pub mod test {
             )
         )
     }
+
+    #[test]
+    fn aligned_device_ids() {
+        let ids = f::DeviceIDs { major: 8, minor: 1 };
+        let widths = super::DeviceWidths { major: 3, minor: 2 };
+        let expected = TextCell {
+            width: DisplayWidth::from(8),
+            contents: vec![
+                Blue.on(Red).paint("  8"),
+                Green.italic().paint(" / "),
+                Cyan.on(Yellow).paint(" 1"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, ids.render(&TestColours, " / ", widths));
+    }
 }
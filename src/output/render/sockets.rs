@@ -0,0 +1,78 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::SocketState {
+    pub fn render<C: Colours>(self, colours: &C) -> TextCell {
+        match self {
+            Self::Listening(0) => TextCell::paint_str(colours.listening(), "listening"),
+            Self::Listening(n) => TextCell::paint(colours.listening(), format!("listening ({n})")),
+            Self::Connected(1) => TextCell::paint_str(colours.connected(), "connected"),
+            Self::Connected(n) => TextCell::paint(colours.connected(), format!("connected ({n})")),
+            Self::Unconnected => TextCell::paint_str(colours.unconnected(), "unconnected"),
+            Self::Stale => TextCell::paint_str(colours.stale(), "stale"),
+        }
+    }
+}
+
+pub trait Colours {
+    fn listening(&self) -> Style;
+    fn connected(&self) -> Style;
+    fn unconnected(&self) -> Style;
+    fn stale(&self) -> Style;
+    fn no_socket(&self) -> Style;
+}
+
+pub trait Render {
+    fn render<C: Colours>(self, colours: &C) -> TextCell;
+}
+
+impl Render for Option<f::SocketState> {
+    fn render<C: Colours>(self, colours: &C) -> TextCell {
+        match self {
+            Some(state) => state.render(colours),
+            None => TextCell::blank(colours.no_socket()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{Colours, Render};
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use ansiterm::Colour::*;
+    use ansiterm::Style;
+
+    struct TestColours;
+
+    #[rustfmt::skip]
+    impl Colours for TestColours {
+        fn listening(&self)   -> Style { Green.bold() }
+        fn connected(&self)   -> Style { Cyan.normal() }
+        fn unconnected(&self) -> Style { Fixed(100).normal() }
+        fn stale(&self)       -> Style { Red.normal() }
+        fn no_socket(&self)   -> Style { Black.italic() }
+    }
+
+    #[test]
+    fn listening() {
+        let state = f::SocketState::Listening(3);
+        let expected = TextCell::paint_str(Green.bold(), "listening (3)");
+        assert_eq!(expected, state.render(&TestColours));
+    }
+
+    #[test]
+    fn stale() {
+        let expected = TextCell::paint_str(Red.normal(), "stale");
+        assert_eq!(expected, Some(f::SocketState::Stale).render(&TestColours));
+    }
+
+    #[test]
+    fn not_a_socket() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(expected, None.render(&TestColours));
+    }
+}
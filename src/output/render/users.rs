@@ -23,7 +23,9 @@ impl Render for Option<f::User> {
             (UserFormat::Name, Some(user)) => user.name().to_string_lossy().into(),
         };
 
-        let style = if users.get_current_uid() == uid {
+        let style = if let Some(style) = colours.owner(uid) {
+            style
+        } else if users.get_current_uid() == uid {
             colours.you()
         } else if uid == 0 {
             colours.root()
@@ -39,6 +41,10 @@ pub trait Colours {
     fn other(&self) -> Style;
     fn root(&self) -> Style;
     fn no_user(&self) -> Style;
+
+    /// The style for everything owned by the given user, when the user
+    /// column is coloured by owner.
+    fn owner(&self, uid: u32) -> Option<Style>;
 }
 
 #[cfg(test)]
@@ -62,6 +68,7 @@ pub mod test {
         fn other(&self) -> Style { Blue.underline() }
         fn root(&self)         -> Style { Blue.underline() }
         fn no_user(&self)      -> Style { Black.italic() }
+        fn owner(&self, _uid: u32) -> Option<Style> { None }
     }
 
     #[test]
@@ -0,0 +1,26 @@
+use ansiterm::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::FileVersion {
+    pub fn render(self, style: Style) -> TextCell {
+        let [major, minor, build, revision] = self.0;
+        TextCell::paint(style, format!("{major}.{minor}.{build}.{revision}"))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use ansiterm::Colour::*;
+
+    #[test]
+    fn four_parts() {
+        let version = f::FileVersion([10, 0, 19041, 1]);
+        let expected = TextCell::paint_str(Green.normal(), "10.0.19041.1");
+        assert_eq!(expected, version.render(Green.normal()));
+    }
+}
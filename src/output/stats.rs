@@ -0,0 +1,229 @@
+//! The statistics printed after everything has been listed, for `--stats`.
+//!
+//! The files are totalled up as they’re listed, so the statistics only ever
+//! cover the files that made it past the filters, including any listed
+//! further down with `--recurse` or `--tree`.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+
+use crate::fs::fields as f;
+use crate::fs::File;
+use crate::output::table::SizeFormat;
+use crate::theme::Theme;
+
+/// How many extensions to list, largest first, before lumping the rest
+/// together.
+pub static TOP_EXTENSIONS: usize = 10;
+
+/// The statistics that can be printed.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Statistic {
+    /// The number and total size of the files with each extension.
+    Extensions,
+}
+
+/// Which statistics to print, and how.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Options {
+    pub statistic: Statistic,
+    pub size_format: SizeFormat,
+}
+
+/// The number of files with one extension, and their total size in bytes.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+struct Total {
+    files: u64,
+    bytes: u64,
+}
+
+/// The totals so far, keyed by extension, with `None` for files without
+/// one.
+#[derive(Debug)]
+struct Stats {
+    options: Options,
+    extensions: HashMap<Option<String>, Total>,
+}
+
+static STATS: OnceLock<Mutex<Stats>> = OnceLock::new();
+
+/// Starts totalling up files as they’re listed, if any statistics are going
+/// to be printed.
+pub fn configure(options: Option<Options>) {
+    if let Some(options) = options {
+        let _ = STATS.set(Mutex::new(Stats {
+            options,
+            extensions: HashMap::new(),
+        }));
+    }
+}
+
+/// Adds the given files, which are being listed, to the totals. Only
+/// regular files count, as directories have no extension to speak of.
+pub fn record(files: &[File<'_>]) {
+    let Some(stats) = STATS.get() else {
+        return;
+    };
+
+    let mut stats = stats.lock().unwrap();
+    for file in files.iter().filter(|file| file.is_file()) {
+        if let f::Size::Some(bytes) = file.size() {
+            let total = stats.extensions.entry(file.ext.clone()).or_default();
+            total.files += 1;
+            total.bytes += bytes;
+        }
+    }
+}
+
+/// Prints the statistics, if there are any, after a blank line.
+pub fn render<W: Write>(w: &mut W, theme: &Theme) -> io::Result<()> {
+    let Some(stats) = STATS.get() else {
+        return Ok(());
+    };
+
+    let stats = stats.lock().unwrap();
+    let Statistic::Extensions = stats.options.statistic;
+
+    let rows = top_extensions(&stats.extensions, TOP_EXTENSIONS);
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let numeric =
+        locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+    let cells = rows
+        .iter()
+        .map(|(extension, total)| {
+            let size =
+                f::Size::Some(total.bytes).render(theme, stats.options.size_format, &numeric, None);
+            (size, numeric.format_int(total.files), extension.clone())
+        })
+        .collect::<Vec<_>>();
+
+    let header = theme.ui.header;
+    let size_width = cells
+        .iter()
+        .map(|(size, ..)| *size.width)
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let files_width = cells
+        .iter()
+        .map(|(_, files, _)| files.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+
+    writeln!(w)?;
+    writeln!(
+        w,
+        "{}{} {}{} {}",
+        " ".repeat(size_width - 4),
+        header.paint("Size"),
+        " ".repeat(files_width - 5),
+        header.paint("Files"),
+        header.paint("Extension"),
+    )?;
+
+    for (size, files, extension) in cells {
+        writeln!(
+            w,
+            "{}{} {files:>files_width$} {extension}",
+            " ".repeat(size_width - *size.width),
+            size.strings(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The extensions with the largest total sizes, largest first, with any
+/// past the first `count` added together into a final `other` row. Files
+/// without an extension are listed as `(none)`.
+fn top_extensions(
+    extensions: &HashMap<Option<String>, Total>,
+    count: usize,
+) -> Vec<(String, Total)> {
+    let mut sorted = extensions.iter().collect::<Vec<_>>();
+    sorted.sort_by(|(a_ext, a), (b_ext, b)| b.bytes.cmp(&a.bytes).then(a_ext.cmp(b_ext)));
+
+    let mut rows = sorted
+        .iter()
+        .take(count)
+        .map(|(extension, total)| {
+            let name = extension
+                .as_ref()
+                .map_or_else(|| String::from("(none)"), |ext| format!(".{ext}"));
+            (name, **total)
+        })
+        .collect::<Vec<_>>();
+
+    if sorted.len() > count {
+        let rest = sorted[count..]
+            .iter()
+            .fold(Total::default(), |sum, (_, total)| Total {
+                files: sum.files + total.files,
+                bytes: sum.bytes + total.bytes,
+            });
+        rows.push((String::from("other"), rest));
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn total(files: u64, bytes: u64) -> Total {
+        Total { files, bytes }
+    }
+
+    #[test]
+    fn largest_first() {
+        let mut extensions = HashMap::new();
+        extensions.insert(Some("rs".into()), total(3, 300));
+        extensions.insert(Some("md".into()), total(1, 900));
+        extensions.insert(None, total(2, 50));
+
+        assert_eq!(
+            top_extensions(&extensions, 10),
+            vec![
+                (".md".into(), total(1, 900)),
+                (".rs".into(), total(3, 300)),
+                ("(none)".into(), total(2, 50)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rest_lumped_together() {
+        let mut extensions = HashMap::new();
+        extensions.insert(Some("a".into()), total(1, 40));
+        extensions.insert(Some("b".into()), total(2, 30));
+        extensions.insert(Some("c".into()), total(3, 20));
+        extensions.insert(Some("d".into()), total(4, 10));
+
+        assert_eq!(
+            top_extensions(&extensions, 2),
+            vec![
+                (".a".into(), total(1, 40)),
+                (".b".into(), total(2, 30)),
+                ("other".into(), total(7, 30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_by_name() {
+        let mut extensions = HashMap::new();
+        extensions.insert(Some("z".into()), total(1, 10));
+        extensions.insert(Some("a".into()), total(1, 10));
+
+        assert_eq!(
+            top_extensions(&extensions, 10),
+            vec![(".a".into(), total(1, 10)), (".z".into(), total(1, 10))]
+        );
+    }
+}
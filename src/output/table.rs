@@ -1,5 +1,7 @@
 use std::cmp::max;
+use std::collections::HashMap;
 use std::ops::Deref;
+use std::path::PathBuf;
 #[cfg(unix)]
 use std::sync::{Mutex, MutexGuard};
 
@@ -10,15 +12,25 @@ use once_cell::sync::Lazy;
 #[cfg(unix)]
 use uzers::UsersCache;
 
+use crate::fs::compare::{Comparison, DiffAgainst};
+use crate::fs::dir_action::RecurseOptions;
 use crate::fs::feature::git::GitCache;
-use crate::fs::{fields as f, File};
-use crate::options::vars::EZA_WINDOWS_ATTRIBUTES;
+use crate::fs::feature::version_info::VersionKind;
+use crate::fs::{fields as f, DotFilter, File};
+use crate::options::vars::{EZA_FILE_FLAGS, EZA_WINDOWS_ATTRIBUTES};
 use crate::options::Vars;
+use ansiterm::Style;
+
+use crate::output::capabilities::capabilities;
 use crate::output::cell::TextCell;
 use crate::output::color_scale::ColorScaleInformation;
+use crate::output::dupes::{self, DupeCheck};
+use crate::output::file_name::{hyperlink_escapes, Options as FileStyle};
+use crate::output::layout_debug;
+use crate::output::relative_root;
+use crate::output::render::{DeviceWidths, PermissionsPlusRender, TimeRender};
 #[cfg(unix)]
-use crate::output::render::{GroupRender, OctalPermissionsRender, UserRender};
-use crate::output::render::{PermissionsPlusRender, TimeRender};
+use crate::output::render::{GroupRender, OctalPermissionsRender, SocketRender, UserRender};
 use crate::output::time::TimeFormat;
 use crate::theme::Theme;
 
@@ -32,7 +44,26 @@ pub struct Options {
     pub user_format: UserFormat,
     pub group_format: GroupFormat,
     pub flags_format: FlagsFormat,
+    pub links_format: LinksFormat,
+    pub context_format: ContextFormat,
+    pub virtual_files: VirtualFiles,
+    pub device_separator: String,
+    pub alignments: Alignments,
     pub columns: Columns,
+
+    /// The columns picked with `--fields`, in the order to display them,
+    /// if they were picked that way.
+    pub fields: Option<Vec<Column>>,
+
+    /// Whether file names come before the other columns, rather than after.
+    pub name_first: bool,
+
+    /// The directory to compare each file with, if any.
+    pub diff_against: Option<DiffAgainst>,
+
+    /// Whether to make the cells that name a file, such as the branches in
+    /// the Git repository column, hyperlinks to it.
+    pub hyperlink_cells: bool,
 }
 
 /// Extra columns to display in the table.
@@ -42,6 +73,10 @@ pub struct Columns {
     /// At least one of these timestamps will be shown.
     pub time_types: TimeTypes,
 
+    /// Whether to head the timestamp columns with a single letter each, to
+    /// keep them narrow when all four are shown at once.
+    pub compact_time_headers: bool,
+
     // The rest are just on/off
     pub inode: bool,
     pub links: bool,
@@ -53,6 +88,30 @@ pub struct Columns {
     pub octal: bool,
     pub security_context: bool,
     pub file_flags: bool,
+    pub socket_info: bool,
+    pub overlay_layer: bool,
+    pub age_rank: bool,
+    pub text_size: bool,
+    pub encoding: bool,
+
+    /// How many characters of the first line of each text file to show,
+    /// if any.
+    pub preview: Option<usize>,
+
+    /// Whether to show which of the directories given as arguments each
+    /// file was listed from, for `--merge`.
+    pub root: bool,
+
+    /// How to decide which files are duplicates of each other, if they’re
+    /// being looked for.
+    pub dupes: Option<DupeCheck>,
+
+    /// Which version numbers of executables and DLLs to show, if any.
+    pub file_version: Option<VersionKind>,
+
+    /// The umask to work out the permissions of new files in each
+    /// directory with, if they’re being shown.
+    pub simulate_umask: Option<u32>,
 
     // Defaults to true:
     pub permissions: bool,
@@ -61,9 +120,13 @@ pub struct Columns {
 }
 
 impl Columns {
-    pub fn collect(&self, actually_enable_git: bool, git_repos: bool) -> Vec<Column> {
+    pub fn collect(&self, actually_enable_git: bool, git_repos: bool, diff: bool) -> Vec<Column> {
         let mut columns = Vec::with_capacity(4);
 
+        if self.root {
+            columns.push(Column::Root);
+        }
+
         if self.inode {
             #[cfg(unix)]
             columns.push(Column::Inode);
@@ -78,6 +141,11 @@ impl Columns {
             columns.push(Column::Permissions);
         }
 
+        #[cfg(unix)]
+        if let Some(umask) = self.simulate_umask {
+            columns.push(Column::NewPermissions(umask));
+        }
+
         if self.links {
             #[cfg(unix)]
             columns.push(Column::HardLinks);
@@ -87,6 +155,10 @@ impl Columns {
             columns.push(Column::FileSize);
         }
 
+        if self.text_size {
+            columns.push(Column::TextSize);
+        }
+
         if self.blocksize {
             #[cfg(unix)]
             columns.push(Column::Blocksize);
@@ -111,6 +183,15 @@ impl Columns {
             columns.push(Column::SecurityContext);
         }
 
+        if self.socket_info {
+            #[cfg(unix)]
+            columns.push(Column::SocketInfo);
+        }
+
+        if self.overlay_layer {
+            columns.push(Column::OverlayLayer);
+        }
+
         if self.time_types.modified {
             columns.push(Column::Timestamp(TimeType::Modified));
         }
@@ -127,10 +208,34 @@ impl Columns {
             columns.push(Column::Timestamp(TimeType::Accessed));
         }
 
+        if self.age_rank {
+            columns.push(Column::AgeRank);
+        }
+
+        if let Some(check) = self.dupes {
+            columns.push(Column::Dupes(check));
+        }
+
+        if let Some(kind) = self.file_version {
+            columns.push(Column::FileVersion(kind));
+        }
+
+        if self.encoding {
+            columns.push(Column::Encoding);
+        }
+
+        if let Some(length) = self.preview {
+            columns.push(Column::Preview(length));
+        }
+
         if self.git && actually_enable_git {
             columns.push(Column::GitStatus);
         }
 
+        if diff {
+            columns.push(Column::DiffStatus);
+        }
+
         if self.subdir_git_repos && git_repos {
             columns.push(Column::SubdirGitRepo(true));
         }
@@ -144,10 +249,11 @@ impl Columns {
 }
 
 /// A table contains these.
-#[derive(Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Column {
     Permissions,
     FileSize,
+    TextSize,
     Timestamp(TimeType),
     #[cfg(unix)]
     Blocksize,
@@ -160,31 +266,147 @@ pub enum Column {
     #[cfg(unix)]
     Inode,
     GitStatus,
+    DiffStatus,
     SubdirGitRepo(bool),
     #[cfg(unix)]
     Octal,
     #[cfg(unix)]
+    NewPermissions(u32),
+    #[cfg(unix)]
     SecurityContext,
     FileFlags,
+    #[cfg(unix)]
+    SocketInfo,
+    OverlayLayer,
+    AgeRank,
+    Dupes(DupeCheck),
+    FileVersion(VersionKind),
+    Encoding,
+    Preview(usize),
+    Root,
 }
 
+/// How many characters of each text file’s first line the preview column
+/// shows, unless `--preview` says otherwise.
+pub const DEFAULT_PREVIEW_LENGTH: usize = 40;
+
+/// The umask that the new permissions column uses when it’s picked with
+/// `--fields` rather than `--simulate-umask`.
+pub const DEFAULT_UMASK: u32 = 0o022;
+
+/// Every column that can be picked by name with `--fields`, in the order
+/// they’re displayed in when they’re picked with the other flags instead.
+pub static FIELD_COLUMNS: &[Column] = &[
+    Column::Root,
+    #[cfg(unix)]
+    Column::Inode,
+    #[cfg(unix)]
+    Column::Octal,
+    Column::Permissions,
+    #[cfg(unix)]
+    Column::NewPermissions(DEFAULT_UMASK),
+    #[cfg(unix)]
+    Column::HardLinks,
+    Column::FileSize,
+    Column::TextSize,
+    #[cfg(unix)]
+    Column::Blocksize,
+    #[cfg(unix)]
+    Column::User,
+    #[cfg(unix)]
+    Column::Group,
+    Column::FileFlags,
+    #[cfg(unix)]
+    Column::SecurityContext,
+    #[cfg(unix)]
+    Column::SocketInfo,
+    Column::OverlayLayer,
+    Column::Timestamp(TimeType::Modified),
+    Column::Timestamp(TimeType::Changed),
+    Column::Timestamp(TimeType::Created),
+    Column::Timestamp(TimeType::Accessed),
+    Column::AgeRank,
+    Column::Dupes(DupeCheck::Contents),
+    Column::FileVersion(VersionKind::File),
+    Column::Encoding,
+    Column::Preview(DEFAULT_PREVIEW_LENGTH),
+    Column::GitStatus,
+    Column::DiffStatus,
+    Column::SubdirGitRepo(true),
+];
+
 /// Each column can pick its own **Alignment**. Usually, numbers are
 /// right-aligned, and text is left-aligned.
-#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Alignment {
     Left,
     Right,
 }
 
+/// The names that columns are given by `--align`, with `name` standing for
+/// the file name, which isn’t one of the table’s columns.
+pub static ALIGNABLE_COLUMNS: &[&str] = &[
+    "root",
+    "inode",
+    "octal",
+    "permissions",
+    "new",
+    "links",
+    "size",
+    "text",
+    "blocksize",
+    "user",
+    "group",
+    "flags",
+    "context",
+    "socket",
+    "layer",
+    "modified",
+    "changed",
+    "accessed",
+    "created",
+    "rank",
+    "dupe",
+    "version",
+    "encoding",
+    "preview",
+    "git",
+    "diff",
+    "repo",
+    "name",
+];
+
+/// The columns whose alignment has been picked by the user, overriding the
+/// alignment each column would have by default.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Alignments(pub Vec<(&'static str, Alignment)>);
+
+impl Alignments {
+    /// The alignment picked for the column with the given name, if any. The
+    /// last one given wins.
+    pub fn get(&self, name: &str) -> Option<Alignment> {
+        self.0
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, alignment)| *alignment)
+    }
+}
+
 impl Column {
     /// Get the alignment this column should use.
     #[cfg(unix)]
     pub fn alignment(self) -> Alignment {
         #[allow(clippy::wildcard_in_or_patterns)]
         match self {
-            Self::FileSize | Self::HardLinks | Self::Inode | Self::Blocksize | Self::GitStatus => {
-                Alignment::Right
-            }
+            Self::FileSize
+            | Self::TextSize
+            | Self::HardLinks
+            | Self::Inode
+            | Self::Blocksize
+            | Self::GitStatus
+            | Self::AgeRank
+            | Self::Dupes(_) => Alignment::Right,
             Self::Timestamp(_) | _ => Alignment::Left,
         }
     }
@@ -192,11 +414,73 @@ impl Column {
     #[cfg(windows)]
     pub fn alignment(self) -> Alignment {
         match self {
-            Self::FileSize | Self::GitStatus => Alignment::Right,
+            Self::FileSize | Self::TextSize | Self::GitStatus | Self::AgeRank | Self::Dupes(_) => {
+                Alignment::Right
+            }
             _ => Alignment::Left,
         }
     }
 
+    /// The column with the given name in `FIELD_COLUMNS`, if there is one.
+    pub fn named(name: &str) -> Option<Self> {
+        FIELD_COLUMNS.iter().copied().find(|c| c.name() == name)
+    }
+
+    /// The name this column is given by `--align` and `--fields`, as listed
+    /// in `ALIGNABLE_COLUMNS`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Permissions => "permissions",
+            Self::FileSize => "size",
+            Self::TextSize => "text",
+            Self::Timestamp(TimeType::Modified) => "modified",
+            Self::Timestamp(TimeType::Changed) => "changed",
+            Self::Timestamp(TimeType::Accessed) => "accessed",
+            Self::Timestamp(TimeType::Created) => "created",
+            #[cfg(unix)]
+            Self::Blocksize => "blocksize",
+            #[cfg(unix)]
+            Self::User => "user",
+            #[cfg(unix)]
+            Self::Group => "group",
+            #[cfg(unix)]
+            Self::HardLinks => "links",
+            #[cfg(unix)]
+            Self::Inode => "inode",
+            Self::GitStatus => "git",
+            Self::DiffStatus => "diff",
+            Self::SubdirGitRepo(_) => "repo",
+            #[cfg(unix)]
+            Self::Octal => "octal",
+            #[cfg(unix)]
+            Self::NewPermissions(_) => "new",
+            #[cfg(unix)]
+            Self::SecurityContext => "context",
+            #[cfg(unix)]
+            Self::SocketInfo => "socket",
+            Self::OverlayLayer => "layer",
+            Self::AgeRank => "rank",
+            Self::Dupes(_) => "dupe",
+            Self::FileVersion(_) => "version",
+            Self::Encoding => "encoding",
+            Self::Preview(_) => "preview",
+            Self::Root => "root",
+            Self::FileFlags => "flags",
+        }
+    }
+
+    /// Whether this column should only be displayed if at least one file
+    /// has something to show in it.
+    fn is_deferred(self, options: &Options) -> bool {
+        match self {
+            #[cfg(unix)]
+            Self::HardLinks => options.links_format == LinksFormat::Auto,
+            #[cfg(unix)]
+            Self::Group => options.group_format == GroupFormat::Smart,
+            _ => false,
+        }
+    }
+
     /// Get the text that should be printed at the top, when the user elects
     /// to have a header row printed.
     pub fn header(self) -> &'static str {
@@ -206,6 +490,7 @@ impl Column {
             #[cfg(windows)]
             Self::Permissions => "Mode",
             Self::FileSize => "Size",
+            Self::TextSize => "Text",
             Self::Timestamp(t) => t.header(),
             #[cfg(unix)]
             Self::Blocksize => "Blocksize",
@@ -218,11 +503,24 @@ impl Column {
             #[cfg(unix)]
             Self::Inode => "inode",
             Self::GitStatus => "Git",
+            Self::DiffStatus => "Diff",
             Self::SubdirGitRepo(_) => "Repo",
             #[cfg(unix)]
             Self::Octal => "Octal",
             #[cfg(unix)]
+            Self::NewPermissions(_) => "New Files",
+            #[cfg(unix)]
             Self::SecurityContext => "Security Context",
+            #[cfg(unix)]
+            Self::SocketInfo => "Socket",
+            Self::OverlayLayer => "Layer",
+            Self::AgeRank => "Age",
+            Self::Dupes(_) => "Dupe",
+            Self::FileVersion(VersionKind::File) => "Version",
+            Self::FileVersion(VersionKind::Product) => "Product",
+            Self::Encoding => "Encoding",
+            Self::Preview(_) => "Preview",
+            Self::Root => "Root",
             Self::FileFlags => "Flags",
         }
     }
@@ -291,6 +589,18 @@ impl TimeType {
         }
     }
 
+    /// Returns a single letter to head the column with, when there are
+    /// several timestamp columns that need to fit side by side. Creation
+    /// times use `B`, for “birth”, as `C` is taken by changed times.
+    pub fn short_header(self) -> &'static str {
+        match self {
+            Self::Modified => "M",
+            Self::Changed => "C",
+            Self::Accessed => "A",
+            Self::Created => "B",
+        }
+    }
+
     /// Returns the corresponding time from [File]
     pub fn get_corresponding_time(self, file: &File<'_>) -> Option<NaiveDateTime> {
         match self {
@@ -308,22 +618,67 @@ pub enum FlagsFormat {
     /// Display flags as comma seperated descriptions
     #[default]
     Long,
-    /// Display flags as single character abbreviations (Windows only)
+    /// Display flags as single character abbreviations
     Short,
 }
 
 impl FlagsFormat {
     pub(crate) fn deduce<V: Vars>(vars: &V) -> FlagsFormat {
-        vars.get(EZA_WINDOWS_ATTRIBUTES)
-            .and_then(|v| match v.to_ascii_lowercase().to_str() {
-                Some("short") => Some(FlagsFormat::Short),
-                Some("long") => Some(FlagsFormat::Long),
-                _ => None,
-            })
+        let format = |name| {
+            vars.get(name)
+                .and_then(|v| match v.to_ascii_lowercase().to_str() {
+                    Some("short") => Some(FlagsFormat::Short),
+                    Some("long") => Some(FlagsFormat::Long),
+                    _ => None,
+                })
+        };
+
+        format(EZA_FILE_FLAGS)
+            .or_else(|| format(EZA_WINDOWS_ATTRIBUTES))
             .unwrap_or_default()
     }
 }
 
+/// How to show the sizes of files on virtual filesystems, such as procfs and
+/// sysfs, which all claim to be empty.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum VirtualFiles {
+    /// Show the size they report, like any other file.
+    #[default]
+    Show,
+
+    /// Mark them as virtual instead of showing a size.
+    Mark,
+
+    /// Read them to find out how long their contents actually are.
+    Read,
+}
+
+/// When to display a file’s number of hard links.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum LinksFormat {
+    /// Display the count for every file.
+    #[default]
+    Always,
+
+    /// Only display the count for files with more than one link, and leave
+    /// out the column entirely if there are no such files.
+    Auto,
+}
+
+/// How much of a file’s security context to display.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum ContextFormat {
+    /// Display every part of the context.
+    #[default]
+    Full,
+
+    /// Leave out the parts that are the same for most files: the `s0`
+    /// range, and the user and role of files with default ones, which get
+    /// shortened to just their type and dimmed.
+    Smart,
+}
+
 /// Fields for which of a file’s time fields should be displayed in the
 /// columns output.
 ///
@@ -396,6 +751,9 @@ static ENVIRONMENT: Lazy<Environment> = Lazy::new(Environment::load_all);
 
 pub struct Table<'a> {
     columns: Vec<Column>,
+    alignments: Vec<Alignment>,
+    name_alignment: Alignment,
+    deferred: Vec<Deferred>,
     theme: &'a Theme,
     env: &'a Environment,
     widths: TableWidths,
@@ -406,23 +764,102 @@ pub struct Table<'a> {
     #[cfg(unix)]
     group_format: GroupFormat,
     flags_format: FlagsFormat,
+    links_format: LinksFormat,
+    context_format: ContextFormat,
+    virtual_files: VirtualFiles,
+    device_separator: String,
+    device_widths: DeviceWidths,
+    size_column: Option<usize>,
     git: Option<&'a GitCache>,
+    diff: Option<&'a Comparison>,
+    age_ranks: HashMap<PathBuf, f::AgeRank>,
+    dupe_groups: HashMap<PathBuf, f::DupeGroup>,
+    compact_time_headers: bool,
+    name_first: bool,
+    hyperlink_cells: bool,
+    file_style: &'a FileStyle,
+}
+
+/// Whether a column is only displayed if at least one of its cells has
+/// something in it, which can’t be known until every row has been added.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum Deferred {
+    /// The column is always displayed.
+    No,
+
+    /// The column is waiting for a notable cell.
+    Hidden,
+
+    /// The column has had a notable cell, so it will be displayed.
+    Shown,
 }
 
 #[derive(Clone)]
 pub struct Row {
     cells: Vec<TextCell>,
+
+    /// Whether this is the header row, which doesn’t count towards showing
+    /// deferred columns.
+    header: bool,
+
+    /// Whether each cell has something in it worth showing a deferred
+    /// column for, which is usually whether it’s empty.
+    notable: Vec<bool>,
+
+    /// The device numbers in the size column, which get padded to line up
+    /// with every other row’s once the whole table is known.
+    device: Option<f::DeviceIDs>,
+}
+
+impl Row {
+    /// Applies an overlay style on top of every cell in this row.
+    pub fn overlay(&mut self, overlay: Style) {
+        for cell in &mut self.cells {
+            cell.overlay(overlay);
+        }
+    }
 }
 
 impl<'a> Table<'a> {
     pub fn new(
         options: &'a Options,
         git: Option<&'a GitCache>,
+        diff: Option<&'a Comparison>,
         theme: &'a Theme,
         git_repos: bool,
+        file_style: &'a FileStyle,
     ) -> Table<'a> {
-        let columns = options.columns.collect(git.is_some(), git_repos);
+        let mut columns = options
+            .columns
+            .collect(git.is_some(), git_repos, diff.is_some());
+
+        // Picked fields are only shown if they’d have been shown otherwise,
+        // as the Git columns need a repository to show anything.
+        if let Some(fields) = &options.fields {
+            columns = fields
+                .iter()
+                .copied()
+                .filter(|c| columns.contains(c))
+                .collect();
+        }
+
         let widths = TableWidths::zero(columns.len());
+        let deferred = columns
+            .iter()
+            .map(|c| {
+                if c.is_deferred(options) {
+                    Deferred::Hidden
+                } else {
+                    Deferred::No
+                }
+            })
+            .collect();
+        let alignments = columns
+            .iter()
+            .map(|c| options.alignments.get(c.name()).unwrap_or(c.alignment()))
+            .collect();
+        let name_alignment = options.alignments.get("name").unwrap_or(Alignment::Left);
+        let size_column = columns.iter().position(|c| matches!(c, Column::FileSize));
         let env = &*ENVIRONMENT;
 
         debug!("Creating table with columns: {:?}", columns);
@@ -431,7 +868,17 @@ impl<'a> Table<'a> {
             theme,
             widths,
             columns,
+            alignments,
+            name_alignment,
+            deferred,
             git,
+            diff,
+            age_ranks: HashMap::new(),
+            dupe_groups: HashMap::new(),
+            compact_time_headers: options.columns.compact_time_headers,
+            name_first: options.name_first,
+            hyperlink_cells: options.hyperlink_cells,
+            file_style,
             env,
             time_format: options.time_format.clone(),
             size_format: options.size_format,
@@ -440,21 +887,129 @@ impl<'a> Table<'a> {
             #[cfg(unix)]
             group_format: options.group_format,
             flags_format: options.flags_format,
+            links_format: options.links_format,
+            context_format: options.context_format,
+            virtual_files: options.virtual_files,
+            device_separator: options.device_separator.clone(),
+            device_widths: DeviceWidths::default(),
+            size_column,
+        }
+    }
+
+    /// The total width of the columns that will actually be displayed,
+    /// including the spaces between them.
+    pub fn total_width(&self) -> usize {
+        self.widths
+            .iter()
+            .enumerate()
+            .filter(|(n, _)| self.is_displayed(*n))
+            .map(|(_, width)| width + 1)
+            .sum()
+    }
+
+    /// How file names should be aligned, which happens after the table’s
+    /// own columns have been rendered.
+    pub fn name_alignment(&self) -> Alignment {
+        self.name_alignment
+    }
+
+    /// Whether file names are rendered before the table’s columns, instead
+    /// of after them.
+    pub fn name_first(&self) -> bool {
+        self.name_first
+    }
+
+    /// Explains the width of each column, and why any were left out, once
+    /// every row has been added.
+    pub fn note_layout(&self) {
+        if !layout_debug::enabled() {
+            return;
+        }
+
+        for (n, (column, width)) in self.columns.iter().zip(self.widths.iter()).enumerate() {
+            if self.is_displayed(n) {
+                let side = match self.alignments.get(n) {
+                    Some(Alignment::Right) => "right",
+                    _ => "left",
+                };
+                layout_debug::note(format_args!(
+                    "column {} is {width} wide, aligned to the {side}",
+                    column.name()
+                ));
+            } else {
+                layout_debug::note(format_args!(
+                    "dropped the {} column, as no file had anything to show in it",
+                    column.name()
+                ));
+            }
         }
+
+        layout_debug::note(format_args!(
+            "columns before the name take up {} characters; names are never truncated, so rows may be wider than the terminal",
+            self.total_width()
+        ));
     }
 
-    pub fn widths(&self) -> &TableWidths {
-        &self.widths
+    fn is_displayed(&self, n: usize) -> bool {
+        self.deferred[n] != Deferred::Hidden
     }
 
     pub fn header_row(&self) -> Row {
         let cells = self
             .columns
             .iter()
-            .map(|c| TextCell::paint_str(self.theme.ui.header, c.header()))
+            .map(|c| {
+                let header = match c {
+                    Column::Timestamp(t) if self.compact_time_headers => t.short_header(),
+                    c => c.header(),
+                };
+                TextCell::paint_str(self.theme.ui.header, header)
+            })
+            .collect();
+
+        Row {
+            cells,
+            header: true,
+            notable: Vec::new(),
+            device: None,
+        }
+    }
+
+    /// Ranks the files in a listing by how recently they were modified, for
+    /// the age rank column, if it’s being shown. This has to be done for each
+    /// listing before any of its rows are made.
+    pub fn rank_ages(&mut self, files: &[File<'_>]) {
+        if !self.columns.iter().any(|c| matches!(c, Column::AgeRank)) {
+            return;
+        }
+
+        let times: Vec<_> = files.iter().map(File::modified_time).collect();
+        self.age_ranks = files
+            .iter()
+            .zip(age_ranks(&times))
+            .filter_map(|(file, rank)| Some((file.path.clone(), rank?)))
             .collect();
+    }
 
-        Row { cells }
+    /// Finds the files in a listing that have the same contents, including
+    /// those under any directories being recursed into, for the duplicates
+    /// column, if it’s being shown. This has to be done for each listing
+    /// before any of its rows are made.
+    pub fn find_dupes(
+        &mut self,
+        files: &[File<'_>],
+        dot_filter: DotFilter,
+        git_ignoring: bool,
+        r: Option<RecurseOptions>,
+    ) {
+        let Some(check) = self.columns.iter().find_map(|c| match c {
+            Column::Dupes(check) => Some(*check),
+            _ => None,
+        }) else {
+            return;
+        };
+
+        self.dupe_groups = dupes::find(check, files, dot_filter, self.git, git_ignoring, r);
     }
 
     pub fn row_for_file(
@@ -467,13 +1022,98 @@ impl<'a> Table<'a> {
             .columns
             .iter()
             .map(|c| self.display(file, *c, xattrs, color_scale_info))
+            .collect::<Vec<_>>();
+
+        let notable = self
+            .columns
+            .iter()
+            .zip(&cells)
+            .map(|(column, cell)| self.is_notable(file, *column, cell))
             .collect();
 
-        Row { cells }
+        Row {
+            cells,
+            header: false,
+            notable,
+            device: self.size_column.and_then(|_| Self::device_ids(file)),
+        }
     }
 
     pub fn add_widths(&mut self, row: &Row) {
         self.widths.add_widths(row);
+
+        if let (Some(n), Some(ids)) = (self.size_column, row.device) {
+            self.device_widths.add(ids);
+            let width = self.device_widths.total(&self.device_separator);
+            self.widths.0[n] = max(self.widths.0[n], width);
+        }
+
+        if !row.header {
+            for (deferred, notable) in self.deferred.iter_mut().zip(row.notable.iter()) {
+                if *deferred == Deferred::Hidden && *notable {
+                    *deferred = Deferred::Shown;
+                }
+            }
+        }
+    }
+
+    /// Whether a file’s cell is worth showing a deferred column for. With
+    /// `--smart-group`, a group that’s just the owner’s primary group isn’t,
+    /// so the column is left out when every file’s group is.
+    fn is_notable(&self, file: &File<'_>, column: Column, cell: &TextCell) -> bool {
+        match column {
+            #[cfg(unix)]
+            Column::Group if self.group_format == GroupFormat::Smart => {
+                !self.is_owners_primary_group(file)
+            }
+            _ => *cell.width > 0,
+        }
+    }
+
+    #[cfg(unix)]
+    fn is_owners_primary_group(&self, file: &File<'_>) -> bool {
+        use uzers::Users;
+
+        let (Some(user), Some(group)) = (file.user(), file.group()) else {
+            return false;
+        };
+
+        self.env
+            .lock_users()
+            .get_user_by_uid(user.0)
+            .is_some_and(|user| user.primary_group_id() == group.0)
+    }
+
+    /// The size to display for a file, which depends on how files on
+    /// virtual filesystems should be shown. They all claim to be empty, so
+    /// empty files are only checked for being on one when `--virtual-files`
+    /// asks for them to be shown differently.
+    fn file_size(&self, file: &File<'_>) -> f::Size {
+        let size = file.size();
+        if self.virtual_files == VirtualFiles::Show
+            || !matches!(size, f::Size::Some(0))
+            || !file.is_on_virtual_filesystem()
+        {
+            return size;
+        }
+
+        match self.virtual_files {
+            #[cfg(unix)]
+            VirtualFiles::Read => file
+                .virtual_length()
+                .map_or(f::Size::Virtual, f::Size::Some),
+            _ => f::Size::Virtual,
+        }
+    }
+
+    #[cfg(unix)]
+    fn device_ids(file: &File<'_>) -> Option<f::DeviceIDs> {
+        file.device_ids()
+    }
+
+    #[cfg(windows)]
+    fn device_ids(_file: &File<'_>) -> Option<f::DeviceIDs> {
+        None
     }
 
     #[cfg(unix)]
@@ -510,15 +1150,28 @@ impl<'a> Table<'a> {
         color_scale_info: Option<ColorScaleInformation>,
     ) -> TextCell {
         match column {
-            Column::Permissions => self.permissions_plus(file, xattrs).render(self.theme),
-            Column::FileSize => file.size().render(
+            Column::Permissions => self
+                .permissions_plus(file, xattrs)
+                .render(self.theme, &self.file_style.glyphs),
+            Column::FileSize => self.file_size(file).render(
                 self.theme,
                 self.size_format,
                 &self.env.numeric,
                 color_scale_info,
             ),
+            Column::TextSize => {
+                file.text_size()
+                    .render(self.theme, self.size_format, &self.env.numeric, None)
+            }
             #[cfg(unix)]
-            Column::HardLinks => file.links().render(self.theme, &self.env.numeric),
+            Column::HardLinks => {
+                let links = file.links();
+                if self.links_format == LinksFormat::Auto && links.count <= 1 {
+                    TextCell::default()
+                } else {
+                    links.render(self.theme, &self.env.numeric)
+                }
+            }
             #[cfg(unix)]
             Column::Inode => file.inode().render(self.theme.ui.inode),
             #[cfg(unix)]
@@ -540,12 +1193,64 @@ impl<'a> Table<'a> {
                 file.user(),
             ),
             #[cfg(unix)]
-            Column::SecurityContext => file.security_context().render(self.theme),
+            Column::SecurityContext => file
+                .security_context()
+                .render(self.theme, self.context_format),
+            #[cfg(unix)]
+            Column::SocketInfo => file.socket_state().render(self.theme),
+            Column::OverlayLayer => file.overlay_layer().render(self.theme),
+            Column::AgeRank => match self.age_ranks.get(&file.path) {
+                Some(rank) => rank.render(self.theme.ui.date),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
+            Column::Dupes(_) => match self.dupe_groups.get(&file.path) {
+                Some(group) => group.render(self.theme.ui.dupe_group),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
+            Column::FileVersion(kind) => match file.version_info() {
+                Some(info) => info.get(kind).render(self.theme.ui.file_version),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
+            Column::Encoding => file.text_encoding().render(self.theme),
+            Column::Preview(length) => match file.preview(length) {
+                Some(line) => TextCell::paint(self.theme.ui.preview, line),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
+            Column::Root => match file.parent_dir {
+                Some(dir) => TextCell::paint(
+                    self.theme.ui.filekinds.directory,
+                    relative_root::display(self.file_style.relative_root.as_deref(), &dir.path)
+                        .display()
+                        .to_string(),
+                ),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
+            #[cfg(any(
+                target_os = "macos",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "dragonfly"
+            ))]
+            Column::FileFlags => file.flags().render(self.theme, self.flags_format),
+            #[cfg(not(any(
+                target_os = "macos",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "dragonfly"
+            )))]
             Column::FileFlags => file.flags().render(self.theme.ui.flags, self.flags_format),
             Column::GitStatus => self.git_status(file).render(self.theme),
-            Column::SubdirGitRepo(status) => self.subdir_git_repo(file, status).render(self.theme),
+            Column::DiffStatus => self.diff_status(file).render(self.theme),
+            Column::SubdirGitRepo(status) => self.subdir_git_repo_cell(file, status),
             #[cfg(unix)]
             Column::Octal => self.octal_permissions(file).render(self.theme.ui.octal),
+            #[cfg(unix)]
+            Column::NewPermissions(umask) => match file.new_permissions(umask) {
+                Some(permissions) => permissions.render(self.theme.ui.octal),
+                None => TextCell::blank(self.theme.ui.punctuation),
+            },
 
             Column::Timestamp(time_type) => time_type.get_corresponding_time(file).render(
                 if color_scale_info.is_some_and(|csi| csi.options.mode == ColorScaleMode::Gradient)
@@ -572,6 +1277,11 @@ impl<'a> Table<'a> {
             .unwrap_or_default()
     }
 
+    fn diff_status(&self, file: &File<'_>) -> f::DiffStatus {
+        self.diff
+            .map_or(f::DiffStatus::Unknown, |d| d.status(&file.path))
+    }
+
     fn subdir_git_repo(&self, file: &File<'_>, status: bool) -> f::SubdirGitRepo {
         debug!("Getting subdir repo status for path {:?}", file.path);
 
@@ -581,18 +1291,72 @@ impl<'a> Table<'a> {
         f::SubdirGitRepo::default()
     }
 
+    /// Renders a directory’s Git repository cell, which is a hyperlink to
+    /// the repository if it is one and cells are being made hyperlinks.
+    fn subdir_git_repo_cell(&self, file: &File<'_>, status: bool) -> TextCell {
+        let repo = self.subdir_git_repo(file, status);
+        let is_repo = repo.branch.is_some();
+        let mut cell = repo.render(self.theme);
+
+        if is_repo && self.hyperlink_cells && capabilities().hyperlinks {
+            if let Some((start, end)) = file.absolute_path().and_then(|p| hyperlink_escapes(p)) {
+                cell.wrap(start, end);
+            }
+        }
+
+        cell
+    }
+
+    /// Renders a row’s cells, each followed by a space to separate it from
+    /// the file name that comes after them.
     pub fn render(&self, row: Row) -> TextCell {
+        self.render_with_spaces(row, false)
+    }
+
+    /// Renders a row’s cells, each preceded by a space to separate it from
+    /// the file name that comes before them, so lines don’t end in spaces.
+    pub fn render_after_name(&self, row: Row) -> TextCell {
+        self.render_with_spaces(row, true)
+    }
+
+    fn render_with_spaces(&self, row: Row, space_before: bool) -> TextCell {
         let mut cell = TextCell::default();
 
-        let iter = row.cells.into_iter().zip(self.widths.iter()).enumerate();
+        // When the name comes first, there’s nothing after the last column,
+        // so it doesn’t need padding out if it’s left-aligned.
+        let last = (0..self.columns.len())
+            .rev()
+            .find(|&n| self.is_displayed(n));
+
+        let device = row.device;
+        let iter = row
+            .cells
+            .into_iter()
+            .zip(self.widths.iter())
+            .zip(self.alignments.iter())
+            .enumerate();
+
+        for (n, ((mut this_cell, width), alignment)) in iter {
+            if !self.is_displayed(n) {
+                continue;
+            }
+
+            if let (true, Some(ids)) = (self.size_column == Some(n), device) {
+                this_cell = ids.render(self.theme, &self.device_separator, self.device_widths);
+            }
 
-        for (n, (this_cell, width)) in iter {
             let padding = width - *this_cell.width;
 
-            match self.columns[n].alignment() {
+            if space_before {
+                cell.add_spaces(1);
+            }
+
+            match alignment {
                 Alignment::Left => {
                     cell.append(this_cell);
-                    cell.add_spaces(padding);
+                    if !(space_before && Some(n) == last) {
+                        cell.add_spaces(padding);
+                    }
                 }
                 Alignment::Right => {
                     cell.add_spaces(padding);
@@ -600,7 +1364,9 @@ impl<'a> Table<'a> {
                 }
             }
 
-            cell.add_spaces(1);
+            if !space_before {
+                cell.add_spaces(1);
+            }
         }
 
         cell
@@ -627,8 +1393,58 @@ impl TableWidths {
             *old_width = max(*old_width, *cell.width);
         }
     }
+}
+
+/// Ranks each of the given modification times, newest first, with equal
+/// times sharing the best rank between them, so the ranks go 1, 2, 2, 4.
+/// Files without a modification time aren’t ranked.
+fn age_ranks(times: &[Option<NaiveDateTime>]) -> Vec<Option<f::AgeRank>> {
+    let mut order: Vec<_> = (0..times.len()).filter(|i| times[*i].is_some()).collect();
+    order.sort_by(|a, b| times[*b].cmp(&times[*a]));
+
+    let mut ranks = vec![None; times.len()];
+    for (position, &index) in order.iter().enumerate() {
+        let rank = match position.checked_sub(1).map(|p| order[p]) {
+            Some(previous) if times[previous] == times[index] => ranks[previous],
+            _ => Some(f::AgeRank(position + 1)),
+        };
+        ranks[index] = rank;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn time(secs: i64) -> Option<NaiveDateTime> {
+        DateTime::from_timestamp(secs, 0).map(|t| t.naive_utc())
+    }
+
+    #[test]
+    fn newest_first() {
+        let ranks = age_ranks(&[time(10), time(30), time(20)]);
+        assert_eq!(
+            ranks,
+            vec![
+                Some(f::AgeRank(3)),
+                Some(f::AgeRank(1)),
+                Some(f::AgeRank(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_share_a_rank() {
+        let ranks = age_ranks(&[time(30), time(20), time(20), time(10)]);
+        let ranks: Vec<_> = ranks.into_iter().map(|r| r.unwrap().0).collect();
+        assert_eq!(ranks, vec![1, 2, 2, 4]);
+    }
 
-    pub fn total(&self) -> usize {
-        self.0.len() + self.0.iter().sum::<usize>()
+    #[test]
+    fn unknown_times_unranked() {
+        let ranks = age_ranks(&[None, time(5)]);
+        assert_eq!(ranks, vec![None, Some(f::AgeRank(1))]);
     }
 }
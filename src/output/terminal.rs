@@ -0,0 +1,225 @@
+//! Querying the terminal that standard output is connected to.
+//!
+//! Everything that needs to know how big the terminal is should get it from
+//! here, rather than asking the operating system itself, so the sizes the
+//! user gave with `--width` and `--height` (or `COLUMNS` and `LINES`) are
+//! honoured everywhere, even when output isn’t going to a terminal at all.
+
+/// The width of the terminal requested by the user.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TerminalWidth {
+    /// The user requested this specific number of columns.
+    Set(usize),
+
+    /// Look up the terminal size at runtime.
+    Automatic,
+}
+
+impl TerminalWidth {
+    pub fn actual_terminal_width(self) -> Option<usize> {
+        Geometry::resolve(self, TerminalHeight::Automatic).width
+    }
+}
+
+/// The height of the terminal requested by the user.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TerminalHeight {
+    /// The user requested this specific number of lines.
+    Set(usize),
+
+    /// Look up the terminal size at runtime.
+    Automatic,
+}
+
+/// The size of the area that output is laid out for. Either side is `None`
+/// if the user didn’t give one and standard output isn’t a terminal.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct Geometry {
+    /// The number of columns, which grids are fitted into.
+    pub width: Option<usize>,
+
+    /// The number of lines, which decides whether to use a pager.
+    pub height: Option<usize>,
+}
+
+impl Geometry {
+    /// Works out the geometry from the sizes the user asked for, asking the
+    /// terminal for any they left automatic.
+    pub fn resolve(width: TerminalWidth, height: TerminalHeight) -> Self {
+        // All of stdin, stdout, and stderr could not be connected to a
+        // terminal, but we’re only interested in stdout because it’s
+        // where the output goes.
+        let info = match (width, height) {
+            (TerminalWidth::Set(_), TerminalHeight::Set(_)) => TerminalInfo::default(),
+            _ => TerminalInfo::query(),
+        };
+
+        Self::resolve_with(width, height, info)
+    }
+
+    fn resolve_with(width: TerminalWidth, height: TerminalHeight, info: TerminalInfo) -> Self {
+        #[rustfmt::skip]
+        return Self {
+            width: match width {
+                TerminalWidth::Set(width)    => Some(width),
+                TerminalWidth::Automatic     => info.columns,
+            },
+            height: match height {
+                TerminalHeight::Set(height)  => Some(height),
+                TerminalHeight::Automatic    => info.rows,
+            },
+        };
+    }
+}
+
+/// What the terminal told us about its size, if it’s a terminal at all.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct TerminalInfo {
+    /// The width of the terminal, in character cells.
+    pub columns: Option<usize>,
+
+    /// The height of the terminal, in character cells.
+    pub rows: Option<usize>,
+
+    /// The size of a single character cell, in pixels. Many terminals don’t
+    /// report their pixel size, in which case this is `None`.
+    pub cell: Option<CellSize>,
+}
+
+/// The size of one character cell, in pixels.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct CellSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl TerminalInfo {
+    /// Asks the operating system about the terminal standard output is
+    /// connected to, returning an empty `TerminalInfo` if it isn’t one.
+    #[cfg(unix)]
+    pub fn query() -> Self {
+        use std::os::fd::AsRawFd;
+
+        let fd = std::io::stdout().as_raw_fd();
+        // SAFETY: `winsize` is plain old data, and `TIOCGWINSZ` only ever
+        // writes one of them to the pointer it’s given.
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let result = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, std::ptr::addr_of_mut!(size)) };
+
+        if result == -1 {
+            Self::default()
+        } else {
+            Self::from_window_size(size.ws_col, size.ws_row, size.ws_xpixel, size.ws_ypixel)
+        }
+    }
+
+    /// Asks the operating system about the terminal standard output is
+    /// connected to, returning an empty `TerminalInfo` if it isn’t one.
+    /// Windows consoles don’t report their pixel size.
+    #[cfg(windows)]
+    pub fn query() -> Self {
+        use std::os::windows::io::RawHandle;
+        use windows_sys::Win32::System::Console::{GetStdHandle, STD_OUTPUT_HANDLE};
+
+        let size = terminal_size::terminal_size_using_handle(unsafe {
+            GetStdHandle(STD_OUTPUT_HANDLE) as RawHandle
+        });
+
+        match size {
+            Some((w, h)) => Self::from_window_size(w.0, h.0, 0, 0),
+            None => Self::default(),
+        }
+    }
+
+    /// Builds the information from the fields of a window size, where a
+    /// zero means the terminal didn’t say.
+    fn from_window_size(columns: u16, rows: u16, x_pixels: u16, y_pixels: u16) -> Self {
+        let cell = if columns == 0 || rows == 0 || x_pixels == 0 || y_pixels == 0 {
+            None
+        } else {
+            Some(CellSize {
+                width: x_pixels / columns,
+                height: y_pixels / rows,
+            })
+        };
+
+        Self {
+            columns: Some(usize::from(columns)).filter(|&c| c > 0),
+            rows: Some(usize::from(rows)).filter(|&r| r > 0),
+            cell,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn with_pixels() {
+        let info = TerminalInfo::from_window_size(80, 24, 800, 480);
+        assert_eq!(info.columns, Some(80));
+        assert_eq!(info.rows, Some(24));
+        assert_eq!(
+            info.cell,
+            Some(CellSize {
+                width: 10,
+                height: 20
+            })
+        );
+    }
+
+    #[test]
+    fn without_pixels() {
+        let info = TerminalInfo::from_window_size(80, 24, 0, 0);
+        assert_eq!(info.columns, Some(80));
+        assert_eq!(info.cell, None);
+    }
+
+    #[test]
+    fn set_sizes_win() {
+        let info = TerminalInfo::from_window_size(80, 24, 0, 0);
+        assert_eq!(
+            Geometry::resolve_with(TerminalWidth::Set(120), TerminalHeight::Set(40), info),
+            Geometry {
+                width: Some(120),
+                height: Some(40)
+            }
+        );
+    }
+
+    #[test]
+    fn automatic_sizes() {
+        let info = TerminalInfo::from_window_size(80, 24, 0, 0);
+        assert_eq!(
+            Geometry::resolve_with(TerminalWidth::Automatic, TerminalHeight::Automatic, info),
+            Geometry {
+                width: Some(80),
+                height: Some(24)
+            }
+        );
+    }
+
+    #[test]
+    fn piped_with_height() {
+        assert_eq!(
+            Geometry::resolve_with(
+                TerminalWidth::Automatic,
+                TerminalHeight::Set(50),
+                TerminalInfo::default()
+            ),
+            Geometry {
+                width: None,
+                height: Some(50)
+            }
+        );
+    }
+
+    #[test]
+    fn not_a_terminal() {
+        assert_eq!(
+            TerminalInfo::from_window_size(0, 0, 0, 0),
+            TerminalInfo::default()
+        );
+    }
+}
@@ -43,8 +43,9 @@ pub enum TimeFormat {
     /// only numbers so doesn’t require any special consideration.
     FullISO,
 
-    /// Use a relative but fixed width representation.
-    Relative,
+    /// Use a relative but fixed width representation, measured from the
+    /// given moment, or from now if there isn’t one.
+    Relative(Option<TimeAnchor>),
 
     /// Use custom formats, optionally a different custom format can be
     /// specified for recent times, otherwise the same custom format will be
@@ -55,6 +56,11 @@ pub enum TimeFormat {
     },
 }
 
+/// A moment other than now to measure relative times and the age colour
+/// scale from, in UTC.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct TimeAnchor(pub NaiveDateTime);
+
 impl TimeFormat {
     pub fn format(self, time: &DateTime<FixedOffset>) -> String {
         #[rustfmt::skip]
@@ -63,7 +69,7 @@ impl TimeFormat {
             Self::ISOFormat                     => iso(time),
             Self::LongISO                       => long(time),
             Self::FullISO                       => full(time),
-            Self::Relative                      => relative(time),
+            Self::Relative(anchor)              => relative(time, anchor),
             Self::Custom { non_recent, recent } => custom(
                 time, non_recent.as_str(), recent.as_deref()
             ),
@@ -106,7 +112,21 @@ fn long(time: &DateTime<FixedOffset>) -> String {
 }
 
 // #[allow(trivial_numeric_casts)]
-fn relative(time: &DateTime<FixedOffset>) -> String {
+fn relative(time: &DateTime<FixedOffset>, anchor: Option<TimeAnchor>) -> String {
+    if let Some(TimeAnchor(anchor)) = anchor {
+        // Times after the anchor are as likely as times before it, so
+        // they have to be told apart.
+        let difference = anchor.and_utc().timestamp() - time.timestamp();
+        let (ago, seconds) = if difference < 0 {
+            (" after", difference.unsigned_abs())
+        } else {
+            (" before", difference.unsigned_abs())
+        };
+        return timeago::Formatter::new()
+            .ago(ago)
+            .convert(Duration::from_secs(seconds));
+    }
+
     timeago::Formatter::new()
         .ago("")
         .convert(Duration::from_secs(
@@ -38,6 +38,9 @@
 //! successfully `stat`ted, we don’t know how many files are going to exist in
 //! each directory)
 
+use crate::output::blocks_map::human_size;
+use crate::output::glyphs::Glyphs;
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum TreePart {
     /// Rightmost column, *not* the last in the directory.
@@ -55,14 +58,14 @@ pub enum TreePart {
 
 impl TreePart {
     /// Turn this tree part into ASCII-licious box drawing characters!
-    /// (Warning: not actually ASCII)
-    pub fn ascii_art(self) -> &'static str {
+    /// (Warning: not actually ASCII, unless the ASCII glyphs are in use)
+    pub fn ascii_art(self, glyphs: &Glyphs) -> &str {
         #[rustfmt::skip]
         return match self {
-            Self::Edge    => "├──",
-            Self::Line    => "│  ",
-            Self::Corner  => "└──",
-            Self::Blank   => "   ",
+            Self::Edge    => &glyphs.tree_edge,
+            Self::Line    => &glyphs.tree_line,
+            Self::Corner  => &glyphs.tree_corner,
+            Self::Blank   => &glyphs.tree_blank,
         };
     }
 }
@@ -147,6 +150,10 @@ impl TreeParams {
     pub fn is_at_root(&self) -> bool {
         self.depth.0 == 0
     }
+
+    pub fn depth(&self) -> TreeDepth {
+        self.depth
+    }
 }
 
 impl TreeDepth {
@@ -191,6 +198,40 @@ where
     }
 }
 
+/// The number of files beneath a directory in a tree, and their total size
+/// in bytes, for `--tree-summary`.
+///
+/// A directory’s summary can only be worked out once everything beneath it
+/// has been listed, so each level of the tree adds up its own files and
+/// passes the total back up to the level above, which adds it to its own.
+/// Directories themselves aren’t counted, only what’s in them.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct TreeSummary {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+impl TreeSummary {
+    /// Counts one file, of the given size if it has one.
+    pub fn add_file(&mut self, bytes: Option<u64>) {
+        self.files += 1;
+        self.bytes += bytes.unwrap_or(0);
+    }
+
+    /// Adds in the summary of a directory further down.
+    pub fn add(&mut self, other: Self) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+    }
+
+    /// The note to show after a directory’s name, such as
+    /// `[3 files, 1.5 KiB]`.
+    pub fn note(self) -> String {
+        let plural = if self.files == 1 { "" } else { "s" };
+        format!("[{} file{plural}, {}]", self.files, human_size(self.bytes))
+    }
+}
+
 #[cfg(test)]
 mod trunk_test {
     use super::*;
@@ -283,3 +324,40 @@ mod iter_test {
         assert!(iter.next().is_none());
     }
 }
+
+#[cfg(test)]
+mod summary_test {
+    use super::*;
+
+    #[test]
+    fn adds_up() {
+        let mut inner = TreeSummary::default();
+        inner.add_file(Some(1024));
+        inner.add_file(None);
+
+        let mut outer = TreeSummary::default();
+        outer.add_file(Some(512));
+        outer.add(inner);
+
+        assert_eq!(
+            outer,
+            TreeSummary {
+                files: 3,
+                bytes: 1536
+            }
+        );
+    }
+
+    #[test]
+    fn notes() {
+        assert_eq!(TreeSummary::default().note(), "[0 files, 0 B]");
+        assert_eq!(
+            TreeSummary {
+                files: 1,
+                bytes: 1536
+            }
+            .note(),
+            "[1 file, 1.5 KiB]"
+        );
+    }
+}
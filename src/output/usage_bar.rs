@@ -0,0 +1,85 @@
+//! The bar that `--mount-usage` draws after a mount point’s name, showing
+//! how full the filesystem mounted there is.
+
+use ansiterm::{ANSIString, Style};
+
+use crate::fs::mounts::Usage;
+use crate::output::capabilities::capabilities;
+use crate::output::color_scale;
+
+/// How many characters wide the bar is, not counting its brackets.
+const WIDTH: usize = 10;
+
+pub trait Colours {
+    /// The style to paint the part of the bar that’s in use, which gets
+    /// brighter the fuller the filesystem is.
+    fn used(&self) -> Style;
+
+    /// The style to paint the rest of the bar, and the brackets.
+    fn free(&self) -> Style;
+}
+
+/// How many characters of the bar are filled for the given fraction, with
+/// any use at all filling at least one, and only a full filesystem filling
+/// every one.
+fn filled(fraction: f32, width: usize) -> usize {
+    if fraction <= 0.0 {
+        0
+    } else if fraction >= 1.0 {
+        width
+    } else {
+        ((fraction * width as f32).round() as usize).clamp(1, width - 1)
+    }
+}
+
+/// Draws the bar for a filesystem, followed by how much of it is in use.
+pub fn render(usage: Usage, colours: &dyn Colours) -> Vec<ANSIString<'static>> {
+    let fraction = usage.fraction();
+    let filled = filled(fraction, WIDTH);
+    let used =
+        color_scale::scale_style(colours.used(), fraction, color_scale::DEFAULT_MIN_LUMINANCE);
+
+    let (full, empty) = if capabilities().unicode {
+        ("█", "░")
+    } else {
+        ("#", "-")
+    };
+
+    vec![
+        colours.free().paint(" ["),
+        used.paint(full.repeat(filled)),
+        colours.free().paint(empty.repeat(WIDTH - filled)),
+        colours.free().paint("] "),
+        used.paint(format!("{:.0}%", fraction * 100.0)),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(filled(0.0, 10), 0);
+    }
+
+    #[test]
+    fn full() {
+        assert_eq!(filled(1.0, 10), 10);
+    }
+
+    #[test]
+    fn nearly_empty() {
+        assert_eq!(filled(0.01, 10), 1);
+    }
+
+    #[test]
+    fn nearly_full() {
+        assert_eq!(filled(0.99, 10), 9);
+    }
+
+    #[test]
+    fn half() {
+        assert_eq!(filled(0.5, 10), 5);
+    }
+}
@@ -0,0 +1,163 @@
+//! Sending listings that are too long to fit on screen through a pager.
+//!
+//! With `--paginate`, eza writes the whole listing into a buffer first, as
+//! there’s no way to know how long it is until it’s finished. If it turns
+//! out to have more lines than the terminal is tall, it’s handed to a pager
+//! such as `less`; otherwise it’s printed as usual.
+//!
+//! Without a pager, how often the listing gets passed on to standard output
+//! is up to the `--flush` policy: after every line, so something reading a
+//! slow listing as it goes sees each file promptly; in large blocks, which is
+//! faster when the output is going to a file; or all at once at the end.
+
+use std::io::{self, BufWriter, Write};
+use std::process::{Command, Stdio};
+
+use log::*;
+
+/// The pager to send long listings to.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Pager {
+    /// The program to run, followed by its arguments.
+    pub command: Vec<String>,
+}
+
+/// When to pass what’s been written on to standard output.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Flush {
+    /// After each line.
+    Line,
+
+    /// Whenever a block of output has built up.
+    Block,
+
+    /// Once the listing is complete.
+    End,
+}
+
+/// How much output builds up before it’s written, when flushing in blocks.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Where the listing gets written to.
+pub enum Output {
+    /// Straight to standard output, which passes it on after each line.
+    Stdout(io::Stdout),
+
+    /// To standard output, in blocks.
+    Block(BufWriter<io::Stdout>),
+
+    /// Into a buffer, to be paged or printed once the listing is complete.
+    Buffer(Vec<u8>),
+}
+
+impl Output {
+    /// Creates the writer for a listing, which gets buffered whole if it
+    /// might need paging.
+    pub fn new(flush: Flush, paging: bool) -> Self {
+        match flush {
+            _ if paging => Self::Buffer(Vec::new()),
+            Flush::Line => Self::Stdout(io::stdout()),
+            Flush::Block => Self::Block(BufWriter::with_capacity(BLOCK_SIZE, io::stdout())),
+            Flush::End => Self::Buffer(Vec::new()),
+        }
+    }
+
+    /// Passes anything that hasn’t been written yet on to standard output,
+    /// once the listing is complete and isn’t being paged.
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Block(writer) => writer.flush(),
+            Self::Buffer(buffer) => {
+                let mut stdout = io::stdout();
+                stdout.write_all(buffer)?;
+                buffer.clear();
+                stdout.flush()
+            }
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::Block(writer) => writer.write(buf),
+            Self::Buffer(buffer) => buffer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Block(writer) => writer.flush(),
+            Self::Buffer(buffer) => buffer.flush(),
+        }
+    }
+}
+
+impl Pager {
+    /// Prints a complete listing, through the pager if it has more lines
+    /// than fit in the given height. If the pager can’t be started, the
+    /// listing is printed directly instead.
+    pub fn print(&self, listing: &[u8], height: Option<usize>) -> io::Result<()> {
+        if !Self::is_needed(listing, height) {
+            return io::stdout().write_all(listing);
+        }
+
+        match self.spawn(listing) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            Err(e) => {
+                warn!("Failed to run pager {:?}: {e}", self.command);
+                io::stdout().write_all(listing)
+            }
+        }
+    }
+
+    /// Whether a listing is too tall to fit in the given height. Without a
+    /// height, there’s no way to tell, so it never is.
+    fn is_needed(listing: &[u8], height: Option<usize>) -> bool {
+        height.is_some_and(|h| listing.split(|&b| b == b'\n').skip(1).count() > h)
+    }
+
+    /// Runs the pager with the listing as its input, and waits for the user
+    /// to quit it.
+    fn spawn(&self, listing: &[u8]) -> io::Result<()> {
+        let Some((program, args)) = self.command.split_first() else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "empty command"));
+        };
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        let written = child
+            .stdin
+            .take()
+            .map_or(Ok(()), |mut stdin| stdin.write_all(listing));
+        child.wait()?;
+        written
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn short_listing() {
+        assert!(!Pager::is_needed(b"a\nb\nc\n", Some(3)));
+    }
+
+    #[test]
+    fn tall_listing() {
+        assert!(Pager::is_needed(b"a\nb\nc\nd\n", Some(3)));
+    }
+
+    #[test]
+    fn no_height() {
+        assert!(!Pager::is_needed(b"a\nb\nc\nd\n", None));
+    }
+}
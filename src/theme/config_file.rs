@@ -0,0 +1,363 @@
+//! Reading colours from a `theme.yml` file in the configuration directory,
+//! instead of having to fit all of them into the `EZA_COLORS` variable:
+//!
+//! ```yaml
+//! ui:
+//!   di: "1;34"
+//!   hd: { foreground: "#ff8800", underline: true }
+//! files:
+//!   "*.rs": "38;5;208"
+//!   Makefile: { foreground: bright-yellow, bold: true }
+//! icons:
+//!   "*.rs": "\ue7a8"
+//!   di: "\uf115"
+//! ```
+//!
+//! The keys under `ui` are the two-letter codes that `LS_COLORS` and
+//! `EZA_COLORS` understand, and the keys under `files` are globs. A style is
+//! either a string of the same codes those variables use, or a mapping of a
+//! foreground colour, a background colour, and attributes to turn on. Like
+//! in `EZA_COLORS`, a `reset: true` line turns off the default file type
+//! colours. The keys under `icons` are either globs or the two-letter codes
+//! for kinds of file, and each value is the icon to show for them instead of
+//! the built-in one.
+//!
+//! Everything in the file is turned into codes here, so it can go through
+//! the same path as the environment variables, which get applied after it.
+
+use serde::Deserialize;
+
+use crate::output::icons::IconMappings;
+use crate::theme::lsc::Pair;
+use crate::theme::ui_styles::UiStyles;
+
+/// The name of the file, in the configuration directory, that the theme is
+/// read from.
+pub static THEME_FILE: &str = "theme.yml";
+
+/// The colours read from a theme file, in the order they were written.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct ThemeFile {
+    /// Whether the default file type colours should be turned off.
+    pub reset: bool,
+
+    /// Styles for parts of the interface, keyed by their two-letter code.
+    pub ui: Vec<(String, String)>,
+
+    /// Styles for file names, keyed by glob.
+    pub files: Vec<(String, String)>,
+
+    /// Icons for file names, keyed by glob or by kind of file.
+    pub icons: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawThemeFile {
+    #[serde(default)]
+    reset: bool,
+
+    #[serde(default)]
+    ui: serde_yaml::Mapping,
+
+    #[serde(default)]
+    files: serde_yaml::Mapping,
+
+    #[serde(default)]
+    icons: serde_yaml::Mapping,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawStyle {
+    Codes(String),
+    Code(u8),
+    Attributes(Attributes),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawColour {
+    Name(String),
+    Number(u8),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+struct Attributes {
+    foreground: Option<RawColour>,
+    background: Option<RawColour>,
+
+    #[serde(default)]
+    bold: bool,
+    #[serde(default)]
+    dimmed: bool,
+    #[serde(default)]
+    italic: bool,
+    #[serde(default)]
+    underline: bool,
+    #[serde(default)]
+    blink: bool,
+    #[serde(default)]
+    reverse: bool,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    strikethrough: bool,
+}
+
+impl ThemeFile {
+    /// Parses the contents of a theme file, returning the line number (or 0
+    /// if it isn’t known) and a description of the first problem found.
+    pub fn parse(contents: &str) -> Result<Self, (usize, String)> {
+        let raw: Option<RawThemeFile> = serde_yaml::from_str(contents).map_err(|e| {
+            let line = e.location().map_or(0, |l| l.line());
+            (line, e.to_string())
+        })?;
+
+        // An empty file, or one that’s all comments, is an empty theme.
+        let Some(raw) = raw else {
+            return Ok(Self::default());
+        };
+
+        let ui = styles(raw.ui, |key| {
+            let pair = Pair { key, value: "" };
+            let mut scratch = UiStyles::plain();
+            if scratch.set_ls(&pair) || scratch.set_exa(&pair) {
+                Ok(())
+            } else {
+                Err(format!("unknown colour code {key:?}"))
+            }
+        })?;
+
+        let files = styles(raw.files, |key| {
+            glob::Pattern::new(key)
+                .map(|_| ())
+                .map_err(|e| format!("couldn’t parse glob pattern {key:?}: {e}"))
+        })?;
+
+        let icons = icons(raw.icons)?;
+
+        Ok(Self {
+            reset: raw.reset,
+            ui,
+            files,
+            icons,
+        })
+    }
+}
+
+/// Turns a mapping of keys to icons into pairs, checking that each one
+/// could be used.
+fn icons(mapping: serde_yaml::Mapping) -> Result<Vec<(String, String)>, (usize, String)> {
+    let mut pairs = Vec::new();
+
+    for (key, value) in mapping {
+        let (Some(key), Some(value)) = (key.as_str(), value.as_str()) else {
+            return Err((0, format!("the icon for {key:?} is not a string")));
+        };
+
+        IconMappings::default()
+            .add(key, value)
+            .map_err(|msg| (0, format!("{msg} in the icon for {key:?}")))?;
+        pairs.push((key.to_string(), value.to_string()));
+    }
+
+    Ok(pairs)
+}
+
+/// Turns a mapping of keys to styles into pairs of keys and codes, checking
+/// each key with the given function.
+fn styles<F>(
+    mapping: serde_yaml::Mapping,
+    check_key: F,
+) -> Result<Vec<(String, String)>, (usize, String)>
+where
+    F: Fn(&str) -> Result<(), String>,
+{
+    let mut pairs = Vec::new();
+
+    for (key, value) in mapping {
+        let Some(key) = key.as_str() else {
+            return Err((0, format!("key {key:?} is not a string")));
+        };
+
+        check_key(key).map_err(|msg| (0, msg))?;
+
+        let style: RawStyle = serde_yaml::from_value(value)
+            .map_err(|_| (0, format!("the style for {key:?} is not valid")))?;
+
+        let codes = style
+            .to_codes()
+            .map_err(|msg| (0, format!("{msg} in the style for {key:?}")))?;
+        pairs.push((key.to_string(), codes));
+    }
+
+    Ok(pairs)
+}
+
+impl RawStyle {
+    /// The semicolon-separated codes for this style, as they would be
+    /// written in `LS_COLORS`.
+    fn to_codes(&self) -> Result<String, String> {
+        let attrs = match self {
+            Self::Codes(codes) => return Ok(codes.clone()),
+            Self::Code(code) => return Ok(code.to_string()),
+            Self::Attributes(attrs) => attrs,
+        };
+
+        let mut codes = Vec::new();
+
+        #[rustfmt::skip]
+        let flags = [
+            (attrs.bold,          "1"),
+            (attrs.dimmed,        "2"),
+            (attrs.italic,        "3"),
+            (attrs.underline,     "4"),
+            (attrs.blink,         "5"),
+            (attrs.reverse,       "7"),
+            (attrs.hidden,        "8"),
+            (attrs.strikethrough, "9"),
+        ];
+
+        for (on, code) in flags {
+            if on {
+                codes.push(code.to_string());
+            }
+        }
+
+        if let Some(colour) = &attrs.foreground {
+            codes.push(colour_codes(colour, 30)?);
+        }
+
+        if let Some(colour) = &attrs.background {
+            codes.push(colour_codes(colour, 40)?);
+        }
+
+        Ok(codes.join(";"))
+    }
+}
+
+/// The codes for a colour given by name, number in the 256-colour palette,
+/// or `#rrggbb` hex triple, where `base` is 30 for the foreground or 40 for
+/// the background.
+fn colour_codes(colour: &RawColour, base: u8) -> Result<String, String> {
+    static NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+    ];
+
+    let named = |name: &str| {
+        let name = if name == "magenta" { "purple" } else { name };
+        NAMES.iter().position(|n| *n == name).map(|i| i as u8)
+    };
+
+    let colour = match colour {
+        RawColour::Number(number) => return Ok(format!("{};5;{number}", base + 8)),
+        RawColour::Name(name) => name.as_str(),
+    };
+
+    if let Some(index) = named(colour) {
+        return Ok((base + index).to_string());
+    }
+
+    if let Some(index) = colour.strip_prefix("bright-").and_then(named) {
+        return Ok((base + 60 + index).to_string());
+    }
+
+    if let Some(hex) = colour
+        .strip_prefix('#')
+        .filter(|h| h.len() == 6 && h.is_ascii())
+    {
+        let channel = |i| u8::from_str_radix(&hex[i..i + 2], 16);
+        if let (Ok(r), Ok(g), Ok(b)) = (channel(0), channel(2), channel(4)) {
+            return Ok(format!("{};2;{r};{g};{b}", base + 8));
+        }
+    }
+
+    Err(format!("unknown colour {colour:?}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(ThemeFile::parse(""), Ok(ThemeFile::default()));
+        assert_eq!(ThemeFile::parse("# nothing\n"), Ok(ThemeFile::default()));
+    }
+
+    #[test]
+    fn codes() {
+        let file = ThemeFile::parse("ui:\n  di: \"1;34\"\n  hd: 4\nfiles:\n  \"*.rs\": 38;5;208\n");
+        assert_eq!(
+            file,
+            Ok(ThemeFile {
+                reset: false,
+                ui: pairs(&[("di", "1;34"), ("hd", "4")]),
+                files: pairs(&[("*.rs", "38;5;208")]),
+                icons: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        let file = ThemeFile::parse(
+            "reset: true\nui:\n  hd: { foreground: \"#ff8800\", background: bright-blue, bold: true }\n  xx: { foreground: 244 }\n",
+        );
+        assert_eq!(
+            file,
+            Ok(ThemeFile {
+                reset: true,
+                ui: pairs(&[("hd", "1;38;2;255;136;0;104"), ("xx", "38;5;244")]),
+                files: Vec::new(),
+                icons: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_order() {
+        let file = ThemeFile::parse("files:\n  \"*.b\": 31\n  \"*.a\": 32\n").unwrap();
+        assert_eq!(file.files, pairs(&[("*.b", "31"), ("*.a", "32")]));
+    }
+
+    #[test]
+    fn icon_overrides() {
+        let file = ThemeFile::parse("icons:\n  \"*.rs\": \"\\ue7a8\"\n  di: D\n").unwrap();
+        assert_eq!(file.icons, pairs(&[("*.rs", "\u{e7a8}"), ("di", "D")]));
+    }
+
+    #[test]
+    fn icon_too_long() {
+        assert!(ThemeFile::parse("icons:\n  di: dir\n").is_err());
+    }
+
+    #[test]
+    fn unknown_code() {
+        assert!(ThemeFile::parse("ui:\n  zz: 31\n").is_err());
+    }
+
+    #[test]
+    fn unknown_colour() {
+        assert!(ThemeFile::parse("ui:\n  di: { foreground: mauve }\n").is_err());
+    }
+
+    #[test]
+    fn unknown_section() {
+        assert!(ThemeFile::parse("colours:\n  di: 31\n").is_err());
+    }
+
+    #[test]
+    fn bad_glob() {
+        assert!(ThemeFile::parse("files:\n  \"[\": 31\n").is_err());
+    }
+}
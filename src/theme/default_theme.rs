@@ -22,6 +22,15 @@ impl UiStyles {
                 special:      Yellow.normal(),
                 executable:   Green.bold(),
                 mount_point:  Blue.bold().underline(),
+                user_dir:     Cyan.bold(),
+                well_known_dir: Blue.bold().dimmed(),
+                setuid:                Style::default(),
+                setgid:                Style::default(),
+                sticky_other_writable: Style::default(),
+                other_writable:        Style::default(),
+                sticky:                Style::default(),
+                multi_link_file:       Style::default(),
+                capability:            Style::default(),
             },
 
             #[rustfmt::skip]
@@ -90,9 +99,49 @@ impl UiStyles {
                     role:  Green.normal(),
                     typ:   Yellow.normal(),
                     range: Cyan.normal(),
+                    default: Style::default().dimmed(),
                 },
             },
 
+            #[rustfmt::skip]
+            windows_attributes: WindowsAttributes {
+                readonly:      Yellow.bold(),
+                hidden:        Purple.normal(),
+                system:        Purple.normal(),
+                archive:       Style::default(),
+                reparse_point: Yellow.normal(),
+            },
+
+            #[rustfmt::skip]
+            file_flags: FileFlags {
+                user_immutable:   Yellow.normal(),
+                user_append:      Cyan.normal(),
+                system_immutable: Red.bold(),
+                system_append:    Red.normal(),
+                hidden:           Purple.normal(),
+                nodump:           Style::default().dimmed(),
+                archived:         Style::default(),
+                opaque:           Blue.normal(),
+            },
+
+            #[rustfmt::skip]
+            finder_tags: FinderTags {
+                gray:   Fixed(245).normal(),
+                green:  Green.normal(),
+                purple: Purple.normal(),
+                blue:   Blue.normal(),
+                yellow: Yellow.normal(),
+                red:    Red.normal(),
+                orange: Fixed(208).normal(),
+            },
+
+            #[rustfmt::skip]
+            sockets: Sockets {
+                listening: Green.bold(),
+                connected: Cyan.normal(),
+                stale:     Red.normal(),
+            },
+
             #[rustfmt::skip]
             file_type: FileType {
                 image:      Purple.normal(),
@@ -114,12 +163,38 @@ impl UiStyles {
             blocks: Cyan.normal(),
             octal: Purple.normal(),
             flags: Style::default(),
+            preview: Style::default().dimmed(),
             header: Style::default().underline(),
 
             symlink_path: Cyan.normal(),
             control_char: Red.normal(),
             broken_symlink: Red.normal(),
             broken_path_overlay: Style::default().underline(),
+            empty_dir_overlay: Style::default().dimmed(),
+            recent_overlay: Style::default().bold(),
+            name_clash_overlay: Red.reverse(),
+            trashed_overlay: Style::default().strikethrough(),
+            badge: Yellow.bold(),
+            highlight_overlay: Style::default().reverse(),
+            unreadable_dir: Red.normal(),
+            tree_summary: Style::default().dimmed(),
+            dupe_group: Yellow.normal(),
+            file_version: Green.normal(),
+
+            // Rows are only tinted when a theme asks for it.
+            broken_row_overlay: Style::default(),
+            ignored_row_overlay: Style::default(),
+            executable_row_overlay: Style::default(),
+            highlight_row_overlay: Style::default(),
+
+            tree_depths: vec![
+                Blue.normal(),
+                Cyan.normal(),
+                Green.normal(),
+                Yellow.normal(),
+                Purple.normal(),
+                Red.normal(),
+            ],
         }
     }
 }
@@ -4,6 +4,8 @@ use std::ops::FnMut;
 use ansiterm::Colour::*;
 use ansiterm::{Colour, Style};
 
+use crate::output::capabilities;
+
 // Parsing the LS_COLORS environment variable into a map of names to Style values.
 //
 // This is sitting around undocumented at the moment because it’s a feature
@@ -50,7 +52,7 @@ where
             let _ = iter.next();
             if let Some(byte) = iter.next() {
                 if let Ok(num) = byte.parse() {
-                    return Some(Fixed(num));
+                    return Some(capabilities::downgrade(Fixed(num)));
                 }
             }
         }
@@ -75,7 +77,7 @@ where
                     iter.next().and_then(|s| s.parse().ok()),
                     iter.next().and_then(|s| s.parse().ok()),
                 ) {
-                    return Some(RGB(r, g, b));
+                    return Some(capabilities::downgrade(RGB(r, g, b)));
                 }
             }
         }
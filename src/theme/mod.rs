@@ -1,25 +1,47 @@
 use ansiterm::Style;
 
+use crate::diagnostics;
+use crate::fs::feature::git;
+#[cfg(target_os = "macos")]
+use crate::fs::feature::xattr::FinderColour;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+use crate::fs::fields::FileFlag;
 use crate::fs::File;
 use crate::info::filetype::FileType;
+use crate::output::blocks_map;
+use crate::output::color_by::{self, ColorBy};
 use crate::output::color_scale::ColorScaleOptions;
 use crate::output::file_name::Colours as FileNameColours;
+use crate::output::icons::IconMappings;
 use crate::output::render;
+use crate::output::usage_bar;
 
 mod ui_styles;
 pub use self::ui_styles::UiStyles;
 
 mod lsc;
 pub use self::lsc::LSColors;
+use self::lsc::Pair;
 
 mod default_theme;
 
+pub mod config_file;
+use self::config_file::ThemeFile;
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
     pub use_colours: UseColours,
 
     pub colour_scale: ColorScaleOptions,
 
+    pub color_by: ColorBy,
+
     pub definitions: Definitions,
 }
 
@@ -44,13 +66,35 @@ pub enum UseColours {
 
 #[derive(PartialEq, Eq, Debug, Default)]
 pub struct Definitions {
+    /// Colours from the theme file, which either environment variable
+    /// takes priority over.
+    pub theme_file: Option<ThemeFile>,
+
     pub ls: Option<String>,
     pub exa: Option<String>,
+
+    /// Icon overrides from the `EZA_ICONS` variable, which take priority
+    /// over those from the theme file.
+    pub icons: Option<String>,
+
+    /// Glob colour rules given on the command line, which take priority
+    /// over those from either environment variable.
+    pub globs: Vec<String>,
 }
 
 pub struct Theme {
     pub ui: UiStyles,
     pub exts: Box<dyn FileStyle>,
+
+    /// What to colour by the owner or commit age of each file, which is
+    /// nothing when there are no colours.
+    pub color_by: ColorBy,
+
+    /// How dark the least recently committed files get, as a percentage.
+    pub min_luminance: isize,
+
+    /// The icons given by the user instead of the built-in ones.
+    pub icons: IconMappings,
 }
 
 impl Options {
@@ -60,7 +104,13 @@ impl Options {
         {
             let ui = UiStyles::plain();
             let exts = Box::new(NoFileStyle);
-            return Theme { ui, exts };
+            return Theme {
+                ui,
+                exts,
+                color_by: ColorBy::Off,
+                min_luminance: self.colour_scale.min_luminance,
+                icons: self.definitions.parse_icon_vars(),
+            };
         }
 
         // Parse the environment variables into colours and extension mappings
@@ -75,7 +125,13 @@ impl Options {
             (true, true) => Box::new((exts, FileTypes)),
         };
 
-        Theme { ui, exts }
+        Theme {
+            ui,
+            exts,
+            color_by: self.color_by,
+            min_luminance: self.colour_scale.min_luminance,
+            icons: self.definitions.parse_icon_vars(),
+        }
     }
 }
 
@@ -86,10 +142,31 @@ impl Definitions {
     ///
     /// Also returns if the `EZA_COLORS` variable should reset the existing file
     /// type mappings or not. The `reset` code needs to be the first one.
+    ///
+    /// The theme file, if there is one, goes first, so anything set in the
+    /// environment overrides it.
     fn parse_color_vars(&self, colours: &mut UiStyles) -> (ExtensionMappings, bool) {
-        use log::*;
-
         let mut exts = ExtensionMappings::default();
+        let mut use_default_filetypes = true;
+
+        if let Some(file) = &self.theme_file {
+            if file.reset {
+                use_default_filetypes = false;
+            }
+
+            for (key, value) in &file.ui {
+                let pair = Pair { key, value };
+                if !colours.set_exa(&pair) {
+                    colours.set_ls(&pair);
+                }
+            }
+
+            for (key, value) in &file.files {
+                if let Ok(pat) = glob::Pattern::new(key) {
+                    exts.add(pat, Pair { key, value }.to_style());
+                }
+            }
+        }
 
         if let Some(lsc) = &self.ls {
             LSColors(lsc).each_pair(|pair| {
@@ -99,15 +176,16 @@ impl Definitions {
                             exts.add(pat, pair.to_style());
                         }
                         Err(e) => {
-                            warn!("Couldn't parse glob pattern {:?}: {}", pair.key, e);
+                            diagnostics::warning(format_args!(
+                                "eza: Couldn't parse glob pattern {:?}: {}",
+                                pair.key, e
+                            ));
                         }
                     }
                 }
             });
         }
 
-        let mut use_default_filetypes = true;
-
         if let Some(exa) = &self.exa {
             // Is this hacky? Yes.
             if exa == "reset" || exa.starts_with("reset:") {
@@ -115,21 +193,62 @@ impl Definitions {
             }
 
             LSColors(exa).each_pair(|pair| {
-                if !colours.set_ls(&pair) && !colours.set_exa(&pair) {
+                if !colours.set_exa(&pair) && !colours.set_ls(&pair) {
                     match glob::Pattern::new(pair.key) {
                         Ok(pat) => {
                             exts.add(pat, pair.to_style());
                         }
                         Err(e) => {
-                            warn!("Couldn't parse glob pattern {:?}: {}", pair.key, e);
+                            diagnostics::warning(format_args!(
+                                "eza: Couldn't parse glob pattern {:?}: {}",
+                                pair.key, e
+                            ));
                         }
                     }
                 };
             });
         }
 
+        // Rules from the command line go last, so they take priority over
+        // everything set in the environment.
+        for glob in &self.globs {
+            LSColors(glob).each_pair(|pair| match glob::Pattern::new(pair.key) {
+                Ok(pat) => exts.add(pat, pair.to_style()),
+                Err(e) => {
+                    diagnostics::warning(format_args!(
+                        "eza: Couldn't parse glob pattern {:?}: {}",
+                        pair.key, e
+                    ));
+                }
+            });
+        }
+
         (exts, use_default_filetypes)
     }
+
+    /// Parse the icons from the theme file and then the `EZA_ICONS`
+    /// variable, so the variable overrides the file. Icons that can’t be
+    /// used are warned about and skipped.
+    fn parse_icon_vars(&self) -> IconMappings {
+        let mut icons = IconMappings::default();
+
+        if let Some(file) = &self.theme_file {
+            for (key, value) in &file.icons {
+                // These were checked when the file was read.
+                let _ = icons.add(key, value);
+            }
+        }
+
+        if let Some(vars) = &self.icons {
+            LSColors(vars).each_pair(|pair| {
+                if let Err(e) = icons.add(pair.key, pair.value) {
+                    diagnostics::warning(format_args!("eza: EZA_ICONS: {e}"));
+                }
+            });
+        }
+
+        icons
+    }
 }
 
 /// Determine the style to paint the text for the filename part of the output.
@@ -273,6 +392,42 @@ impl render::GitColours for Theme {
     fn conflicted(&self)    -> Style { self.ui.git.conflicted }
 }
 
+#[rustfmt::skip]
+impl render::DiffColours for Theme {
+    fn same(&self)       -> Style { self.ui.punctuation }
+    fn different(&self)  -> Style { self.ui.git.modified }
+    fn only_here(&self)  -> Style { self.ui.git.new }
+    fn unknown(&self)    -> Style { self.ui.punctuation }
+}
+
+#[rustfmt::skip]
+impl blocks_map::Colours for Theme {
+    fn data(&self)  -> Style { self.ui.blocks }
+    fn hole(&self)  -> Style { self.ui.punctuation }
+}
+
+#[rustfmt::skip]
+impl usage_bar::Colours for Theme {
+    fn used(&self)  -> Style { self.ui.filekinds.mount_point }
+    fn free(&self)  -> Style { self.ui.punctuation }
+}
+
+#[rustfmt::skip]
+impl render::OverlayColours for Theme {
+    fn not_overlay(&self)  -> Style { self.ui.punctuation }
+    fn lower(&self)        -> Style { self.ui.punctuation }
+    fn modified(&self)     -> Style { self.ui.git.modified }
+    fn upper(&self)        -> Style { self.ui.git.new }
+    fn unknown(&self)      -> Style { self.ui.punctuation }
+}
+
+#[rustfmt::skip]
+impl render::EncodingColours for Theme {
+    fn not_text(&self)  -> Style { self.ui.punctuation }
+    fn lf(&self)        -> Style { self.ui.punctuation }
+    fn unusual(&self)   -> Style { self.ui.git.modified }
+}
+
 #[rustfmt::skip]
 impl render::GitRepoColours for Theme {
     fn branch_main(&self)  -> Style { self.ui.git_repo.branch_main }
@@ -313,6 +468,11 @@ impl render::PermissionsColours for Theme {
     fn special_user_file(&self)  -> Style { self.ui.perms.special_user_file }
     fn special_other(&self)      -> Style { self.ui.perms.special_other }
     fn attribute(&self)          -> Style { self.ui.perms.attribute }
+    fn readonly_attribute(&self) -> Style { self.ui.windows_attributes.readonly }
+    fn hidden_attribute(&self)   -> Style { self.ui.windows_attributes.hidden }
+    fn system_attribute(&self)   -> Style { self.ui.windows_attributes.system }
+    fn archive_attribute(&self)  -> Style { self.ui.windows_attributes.archive }
+    fn reparse_point_attribute(&self) -> Style { self.ui.windows_attributes.reparse_point }
 }
 
 impl render::SizeColours for Theme {
@@ -359,6 +519,7 @@ impl render::UserColours for Theme {
     fn other(&self)         -> Style { self.ui.users.user_other }
     fn root(&self)          -> Style { self.ui.users.user_root }
     fn no_user(&self)       -> Style { self.ui.punctuation }
+    fn owner(&self, uid: u32) -> Option<Style> { self.color_by.users().then(|| color_by::style(uid)) }
 }
 
 #[rustfmt::skip]
@@ -371,6 +532,65 @@ impl FileNameColours for Theme {
     fn broken_control_char(&self) -> Style { apply_overlay(self.ui.control_char,   self.ui.broken_path_overlay) }
     fn executable_file(&self)     -> Style { self.ui.filekinds.executable }
     fn mount_point(&self)         -> Style { self.ui.filekinds.mount_point }
+    fn user_directory(&self)      -> Style { self.ui.filekinds.user_dir }
+    fn well_known_directory(&self) -> Style { self.ui.filekinds.well_known_dir }
+    fn setuid_file(&self)         -> Style { self.ui.filekinds.setuid }
+    fn setgid_file(&self)         -> Style { self.ui.filekinds.setgid }
+    fn sticky_other_writable(&self) -> Style { self.ui.filekinds.sticky_other_writable }
+    fn other_writable(&self)      -> Style { self.ui.filekinds.other_writable }
+    fn sticky_directory(&self)    -> Style { self.ui.filekinds.sticky }
+    fn multi_link_file(&self)     -> Style { self.ui.filekinds.multi_link_file }
+    fn capability_file(&self)     -> Style { self.ui.filekinds.capability }
+    fn empty_directory(&self)     -> Style { apply_overlay(self.ui.filekinds.directory, self.ui.empty_dir_overlay) }
+    fn recent_overlay(&self)      -> Style { self.ui.recent_overlay }
+    fn name_clash_overlay(&self)  -> Style { self.ui.name_clash_overlay }
+    fn trashed_overlay(&self)     -> Style { self.ui.trashed_overlay }
+    fn badge(&self)               -> Style { self.ui.badge }
+
+    #[cfg(target_os = "macos")]
+    fn finder_tag(&self, colour: FinderColour) -> Style {
+        match colour {
+            FinderColour::None   => Style::default(),
+            FinderColour::Gray   => self.ui.finder_tags.gray,
+            FinderColour::Green  => self.ui.finder_tags.green,
+            FinderColour::Purple => self.ui.finder_tags.purple,
+            FinderColour::Blue   => self.ui.finder_tags.blue,
+            FinderColour::Yellow => self.ui.finder_tags.yellow,
+            FinderColour::Red    => self.ui.finder_tags.red,
+            FinderColour::Orange => self.ui.finder_tags.orange,
+        }
+    }
+    fn highlight_overlay(&self)   -> Style { self.ui.highlight_overlay }
+    fn depth_style(&self, depth: usize) -> Option<Style> { self.ui.depth_style(depth) }
+
+    #[cfg(unix)]
+    fn owner_style(&self, file: &File<'_>) -> Option<Style> {
+        if !self.color_by.names() {
+            return None;
+        }
+
+        file.user().map(|user| color_by::style(user.0))
+    }
+
+    #[cfg(windows)]
+    fn owner_style(&self, _file: &File<'_>) -> Option<Style> {
+        None
+    }
+
+    fn git_age_style(&self, file: &File<'_>, style: Style) -> Style {
+        if !self.color_by.git_age() {
+            return style;
+        }
+
+        match git::commit_age(&file.path) {
+            Some(ratio) => color_by::git_age_style(style, ratio, self.min_luminance),
+            None => style,
+        }
+    }
+
+    fn icons(&self) -> &IconMappings {
+        &self.icons
+    }
 
     fn colour_file(&self, file: &File<'_>) -> Style {
         self.exts
@@ -379,6 +599,44 @@ impl FileNameColours for Theme {
     }
 }
 
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+impl render::FlagsColours for Theme {
+    fn normal(&self) -> Style {
+        self.ui.flags
+    }
+
+    #[rustfmt::skip]
+    fn flag(&self, flag: FileFlag) -> Style {
+        match flag {
+            FileFlag::NoDump          => self.ui.file_flags.nodump,
+            FileFlag::UserImmutable   => self.ui.file_flags.user_immutable,
+            FileFlag::UserAppend      => self.ui.file_flags.user_append,
+            FileFlag::Opaque          => self.ui.file_flags.opaque,
+            FileFlag::Hidden          => self.ui.file_flags.hidden,
+            FileFlag::Archived        => self.ui.file_flags.archived,
+            FileFlag::SystemImmutable => self.ui.file_flags.system_immutable,
+            FileFlag::SystemAppend    => self.ui.file_flags.system_append,
+            FileFlag::Other(_)        => self.ui.flags,
+        }
+    }
+}
+
+#[rustfmt::skip]
+#[cfg(unix)]
+impl render::SocketColours for Theme {
+    fn listening(&self)   -> Style { self.ui.sockets.listening }
+    fn connected(&self)   -> Style { self.ui.sockets.connected }
+    fn unconnected(&self) -> Style { self.ui.punctuation }
+    fn stale(&self)       -> Style { self.ui.sockets.stale }
+    fn no_socket(&self)   -> Style { self.ui.punctuation }
+}
+
 #[rustfmt::skip]
 impl render::SecurityCtxColours for Theme {
     fn none(&self)          -> Style { self.ui.security_context.none }
@@ -387,6 +645,7 @@ impl render::SecurityCtxColours for Theme {
     fn selinux_role(&self)  -> Style { self.ui.security_context.selinux.role }
     fn selinux_type(&self)  -> Style { self.ui.security_context.selinux.typ }
     fn selinux_range(&self) -> Style { self.ui.security_context.selinux.range }
+    fn selinux_default(&self) -> Style { self.ui.security_context.selinux.default }
 }
 
 /// Some of the styles are **overlays**: although they have the same attribute
@@ -402,7 +661,7 @@ impl render::SecurityCtxColours for Theme {
 /// “broken link overlay”, the latter of which is just set to override the
 /// underline attribute on the other two.
 #[rustfmt::skip]
-fn apply_overlay(mut base: Style, overlay: Style) -> Style {
+pub fn apply_overlay(mut base: Style, overlay: Style) -> Style {
     if let Some(fg) = overlay.foreground { base.foreground = Some(fg); }
     if let Some(bg) = overlay.background { base.background = Some(bg); }
 
@@ -435,8 +694,11 @@ mod customs_test {
                 $process_expected();
 
                 let definitions = Definitions {
+                    theme_file: None,
                     ls: Some($ls.into()),
                     exa: Some($exa.into()),
+                    icons: None,
+                    globs: Vec::new(),
                 };
 
                 let mut result = UiStyles::default();
@@ -453,8 +715,11 @@ mod customs_test {
                     .collect();
 
                 let definitions = Definitions {
+                    theme_file: None,
                     ls: Some($ls.into()),
                     exa: Some($exa.into()),
+                    icons: None,
+                    globs: Vec::new(),
                 };
 
                 let (result, _) = definitions.parse_color_vars(&mut UiStyles::default());
@@ -473,8 +738,11 @@ mod customs_test {
                     .collect();
 
                 let definitions = Definitions {
+                    theme_file: None,
                     ls: Some($ls.into()),
                     exa: Some($exa.into()),
+                    icons: None,
+                    globs: Vec::new(),
                 };
 
                 let mut result = UiStyles::default();
@@ -495,6 +763,13 @@ mod customs_test {
     test!(ls_cd:   ls "cd=35", exa ""  =>  colours c -> { c.filekinds.char_device  = Purple.normal(); });
     test!(ls_ln:   ls "ln=34", exa ""  =>  colours c -> { c.filekinds.symlink      = Blue.normal();   });
     test!(ls_or:   ls "or=33", exa ""  =>  colours c -> { c.broken_symlink         = Yellow.normal(); });
+    test!(ls_su:   ls "su=37;41", exa ""  =>  colours c -> { c.filekinds.setuid                = White.on(Red);    });
+    test!(ls_sg:   ls "sg=30;43", exa ""  =>  colours c -> { c.filekinds.setgid                = Black.on(Yellow); });
+    test!(ls_tw:   ls "tw=30;42", exa ""  =>  colours c -> { c.filekinds.sticky_other_writable = Black.on(Green);  });
+    test!(ls_ow:   ls "ow=34;42", exa ""  =>  colours c -> { c.filekinds.other_writable        = Blue.on(Green);   });
+    test!(ls_st:   ls "st=37;44", exa ""  =>  colours c -> { c.filekinds.sticky                = White.on(Blue);   });
+    test!(ls_mh:   ls "mh=1",     exa ""  =>  colours c -> { c.filekinds.multi_link_file       = Style::default().bold(); });
+    test!(ls_ca:   ls "ca=30;41", exa ""  =>  colours c -> { c.filekinds.capability            = Black.on(Red);    });
 
     // EZA_COLORS can affect all those colours too:
     test!(exa_di:  ls "", exa "di=32"  =>  colours c -> { c.filekinds.directory    = Green.normal();  });
@@ -512,6 +787,10 @@ mod customs_test {
     test!(ls_exa_ex: ls "ex=32", exa "ex=33"  =>  colours c -> { c.filekinds.executable = Yellow.normal(); });
     test!(ls_exa_fi: ls "fi=33", exa "fi=34"  =>  colours c -> { c.filekinds.normal     = Blue.normal();   });
 
+    // In EZA_COLORS, these are permission bits rather than files
+    test!(ls_exa_su: ls "su=31", exa "su=32"  =>  colours c -> { c.filekinds.setuid = Red.normal(); c.perms.special_user_file = Green.normal(); });
+    test!(ls_exa_tw: ls "tw=31", exa "tw=32"  =>  colours c -> { c.filekinds.sticky_other_writable = Red.normal(); c.perms.other_write = Green.normal(); });
+
     // But more importantly, EZA_COLORS has its own, special list of colours:
     test!(exa_ur:  ls "", exa "ur=38;5;100"  =>  colours c -> { c.perms.user_read           = Fixed(100).normal(); });
     test!(exa_uw:  ls "", exa "uw=38;5;101"  =>  colours c -> { c.perms.user_write          = Fixed(101).normal(); });
@@ -583,8 +862,34 @@ mod customs_test {
     test!(exa_oc:  ls "", exa "oc=38;5;135"  =>  colours c -> { c.octal                                 = Fixed(135).normal(); });
     test!(exa_ff:  ls "", exa "ff=38;5;136"  =>  colours c -> { c.flags                                 = Fixed(136).normal(); });
     test!(exa_bo:  ls "", exa "bO=4"         =>  colours c -> { c.broken_path_overlay                   = Style::default().underline(); });
+    test!(exa_kl:  ls "", exa "Kl=32"        =>  colours c -> { c.sockets.listening                     = Green.normal(); });
+    test!(exa_w_r: ls "", exa "Wr=33"        =>  colours c -> { c.windows_attributes.readonly           = Yellow.normal(); });
+    test!(exa_w_l: ls "", exa "Wl=36"        =>  colours c -> { c.windows_attributes.reparse_point      = Cyan.normal(); });
+    test!(exa_f_r: ls "", exa "Fr=31"        =>  colours c -> { c.finder_tags.red                       = Red.normal(); });
+    test!(exa_f_o: ls "", exa "Fo=38;5;208"  =>  colours c -> { c.finder_tags.orange                    = Fixed(208).normal(); });
+    test!(exa_o_i: ls "", exa "Oi=31"        =>  colours c -> { c.file_flags.user_immutable             = Red.normal(); });
+    test!(exa_o_s: ls "", exa "OI=1;31"      =>  colours c -> { c.file_flags.system_immutable           = Red.bold(); });
+    test!(exa_eo:  ls "", exa "eO=2"         =>  colours c -> { c.empty_dir_overlay                     = Style::default().dimmed(); });
+    test!(exa_ro:  ls "", exa "rO=4"         =>  colours c -> { c.recent_overlay                        = Style::default().underline(); });
+    test!(exa_d_o: ls "", exa "dO=4"         =>  colours c -> { c.name_clash_overlay                    = Style::default().underline(); });
+    test!(exa_t_o: ls "", exa "tO=4"         =>  colours c -> { c.trashed_overlay                       = Style::default().underline(); });
+    test!(exa_pv:  ls "", exa "pv=4"         =>  colours c -> { c.preview                               = Style::default().underline(); });
+    test!(exa_bg:  ls "", exa "Bg=4"         =>  colours c -> { c.badge                                 = Style::default().underline(); });
+    test!(exa_m_o: ls "", exa "mO=7"         =>  colours c -> { c.highlight_overlay                     = Style::default().reverse(); });
+    test!(exa_l1:  ls "", exa "L1=31"        =>  colours c -> { c.tree_depths                           = vec![Red.normal()]; });
+    test!(exa_l3:  ls "", exa "L3=32"        =>  colours c -> { c.tree_depths                           = vec![Style::default(), Style::default(), Green.normal()]; });
+    test!(exa_ud:  ls "", exa "ud=31"        =>  colours c -> { c.unreadable_dir                        = Red.normal(); });
+    test!(exa_ts:  ls "", exa "Ts=4"         =>  colours c -> { c.tree_summary                          = Style::default().underline(); });
+    test!(exa_dg:  ls "", exa "dg=4"         =>  colours c -> { c.dupe_group                            = Style::default().underline(); });
+    test!(exa_fv:  ls "", exa "fv=4"         =>  colours c -> { c.file_version                          = Style::default().underline(); });
+    test!(exa_b_r: ls "", exa "bR=2;31"      =>  colours c -> { c.broken_row_overlay                    = Red.dimmed(); });
+    test!(exa_i_r: ls "", exa "iR=2"         =>  colours c -> { c.ignored_row_overlay                   = Style::default().dimmed(); });
+    test!(exa_x_r: ls "", exa "xR=1"         =>  colours c -> { c.executable_row_overlay                = Style::default().bold(); });
+    test!(exa_m_r: ls "", exa "mR=4"         =>  colours c -> { c.highlight_row_overlay                 = Style::default().underline(); });
 
     test!(exa_mp:  ls "", exa "mp=1;34;4"    =>  colours c -> { c.filekinds.mount_point                 = Blue.bold().underline(); });
+    test!(exa_du:  ls "", exa "Du=1;36"      =>  colours c -> { c.filekinds.user_dir                    = Cyan.bold(); });
+    test!(exa_dw:  ls "", exa "Dw=2;34"      =>  colours c -> { c.filekinds.well_known_dir              = Blue.dimmed(); });
     test!(exa_sp:  ls "", exa "sp=1;35;4"    =>  colours c -> { c.filekinds.special                     = Purple.bold().underline(); });
 
     test!(exa_im:  ls "", exa "im=38;5;128"  =>  colours c -> { c.file_type.image                       = Fixed(128).normal(); });
@@ -605,6 +910,7 @@ mod customs_test {
     test!(exa_Sr:  ls "", exa "Sr=38;5;130"  =>  colours c -> { c.security_context.selinux.role         = Fixed(130).normal(); });
     test!(exa_St:  ls "", exa "St=38;5;131"  =>  colours c -> { c.security_context.selinux.typ          = Fixed(131).normal(); });
     test!(exa_Sl:  ls "", exa "Sl=38;5;132"  =>  colours c -> { c.security_context.selinux.range        = Fixed(132).normal(); });
+    test!(exa_Sd:  ls "", exa "Sd=38;5;133"  =>  colours c -> { c.security_context.selinux.default      = Fixed(133).normal(); });
 
     // All the while, LS_COLORS treats them as filenames:
     test!(ls_uu:   ls "uu=38;5;117", exa ""  =>  exts [ ("uu", Fixed(117).normal()) ]);
@@ -623,6 +929,124 @@ mod customs_test {
     // Testing whether a glob from EZA_COLORS overrides a glob from LS_COLORS
     // can’t be tested here, because they’ll both be added to the same vec
 
+    // Globs from the command line go after those from both variables, so
+    // they take priority:
+    #[test]
+    fn command_line_globs_last() {
+        let definitions = Definitions {
+            theme_file: None,
+            ls: Some("*.log=31".into()),
+            exa: Some("*.log=32".into()),
+            icons: None,
+            globs: vec!["*.log=33:*.tmp=34".into()],
+        };
+
+        let (result, _) = definitions.parse_color_vars(&mut UiStyles::default());
+        let mappings = [
+            ("*.log", Red.normal()),
+            ("*.log", Green.normal()),
+            ("*.log", Yellow.normal()),
+            ("*.tmp", Blue.normal()),
+        ]
+        .iter()
+        .map(|t| (glob::Pattern::new(t.0).unwrap(), t.1))
+        .collect();
+        assert_eq!(ExtensionMappings { mappings }, result);
+    }
+
+    // The theme file goes before both variables, so they override it:
+    fn theme_file(contents: &str) -> ThemeFile {
+        ThemeFile::parse(contents).unwrap()
+    }
+
+    #[test]
+    fn theme_file_alone() {
+        let definitions = Definitions {
+            theme_file: Some(theme_file(
+                "ui:\n  di: { foreground: red, bold: true }\n  hd: 4\n",
+            )),
+            ..Definitions::default()
+        };
+
+        let mut expected = UiStyles::default();
+        expected.filekinds.directory = Red.bold();
+        expected.header = Style::default().underline();
+
+        let mut result = UiStyles::default();
+        let (_, use_default_filetypes) = definitions.parse_color_vars(&mut result);
+        assert_eq!(expected, result);
+        assert!(use_default_filetypes);
+    }
+
+    #[test]
+    fn theme_file_under_variables() {
+        let definitions = Definitions {
+            theme_file: Some(theme_file("ui:\n  di: 31\n  ex: 31\n  da: 31\n")),
+            ls: Some("di=32".into()),
+            exa: Some("ex=33".into()),
+            icons: None,
+            globs: Vec::new(),
+        };
+
+        let mut expected = UiStyles::default();
+        expected.filekinds.directory = Green.normal();
+        expected.filekinds.executable = Yellow.normal();
+        expected.date = Red.normal();
+
+        let mut result = UiStyles::default();
+        definitions.parse_color_vars(&mut result);
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn theme_file_globs_first() {
+        let definitions = Definitions {
+            theme_file: Some(theme_file("files:\n  \"*.log\": 30\n")),
+            ls: Some("*.log=31".into()),
+            exa: Some("*.log=32".into()),
+            icons: None,
+            globs: vec!["*.log=33".into()],
+        };
+
+        let (result, _) = definitions.parse_color_vars(&mut UiStyles::default());
+        let mappings = [
+            ("*.log", Black.normal()),
+            ("*.log", Red.normal()),
+            ("*.log", Green.normal()),
+            ("*.log", Yellow.normal()),
+        ]
+        .iter()
+        .map(|t| (glob::Pattern::new(t.0).unwrap(), t.1))
+        .collect();
+        assert_eq!(ExtensionMappings { mappings }, result);
+    }
+
+    #[test]
+    fn theme_file_reset() {
+        let definitions = Definitions {
+            theme_file: Some(theme_file("reset: true\n")),
+            ..Definitions::default()
+        };
+
+        let (_, use_default_filetypes) = definitions.parse_color_vars(&mut UiStyles::default());
+        assert!(!use_default_filetypes);
+    }
+
+    // Icons from the variable override those from the theme file:
+    #[test]
+    fn icons_variable_last() {
+        let definitions = Definitions {
+            theme_file: Some(theme_file("icons:\n  \"*.rs\": a\n  \"*.md\": b\n")),
+            icons: Some("*.rs=c".into()),
+            ..Definitions::default()
+        };
+
+        let icons = definitions.parse_icon_vars();
+        assert_eq!(icons.for_name("main.rs"), Some('c'));
+        assert_eq!(icons.for_name("README.md"), Some('b'));
+        assert_eq!(icons.for_name("Cargo.toml"), None);
+    }
+
     // Values get separated by colons:
     test!(ls_multi:     ls "*.txt=31:*.rtf=32", exa ""  => exts [ ("*.txt", Red.normal()),   ("*.rtf", Green.normal()) ]);
     test!(exa_multi:    ls "", exa "*.tmp=37:*.log=37"  => exts [ ("*.tmp", White.normal()), ("*.log", White.normal()) ]);
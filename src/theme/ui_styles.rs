@@ -15,7 +15,11 @@ pub struct UiStyles {
     pub git:              Git,
     pub git_repo:         GitRepo,
     pub security_context: SecurityContext,
+    pub sockets:          Sockets,
     pub file_type:        FileType,
+    pub windows_attributes: WindowsAttributes,
+    pub finder_tags:      FinderTags,
+    pub file_flags:       FileFlags,
 
     pub punctuation:  Style,          // xx
     pub date:         Style,          // da
@@ -24,11 +28,29 @@ pub struct UiStyles {
     pub header:       Style,          // hd
     pub octal:        Style,          // oc
     pub flags:        Style,          // ff
+    pub preview:      Style,          // pv
 
     pub symlink_path:         Style,  // lp
     pub control_char:         Style,  // cc
     pub broken_symlink:       Style,  // or
     pub broken_path_overlay:  Style,  // bO
+    pub empty_dir_overlay:    Style,  // eO
+    pub recent_overlay:       Style,  // rO
+    pub name_clash_overlay:   Style,  // dO
+    pub trashed_overlay:      Style,  // tO
+    pub badge:                Style,  // Bg
+    pub highlight_overlay:    Style,  // mO
+    pub unreadable_dir:       Style,  // ud
+    pub tree_summary:         Style,  // Ts
+    pub dupe_group:           Style,  // dg
+    pub file_version:         Style,  // fv
+
+    pub broken_row_overlay:      Style,  // bR
+    pub ignored_row_overlay:     Style,  // iR
+    pub executable_row_overlay:  Style,  // xR
+    pub highlight_row_overlay:   Style,  // mR
+
+    pub tree_depths:  Vec<Style>,     // L1 to L9
 }
 
 #[rustfmt::skip]
@@ -44,6 +66,17 @@ pub struct FileKinds {
     pub special: Style,       // sp
     pub executable: Style,    // ex
     pub mount_point: Style,   // mp
+    pub user_dir: Style,      // Du
+    pub well_known_dir: Style,  // Dw
+
+    // These are only used when they’re set, as with GNU ls
+    pub setuid: Style,                 // su
+    pub setgid: Style,                 // sg
+    pub sticky_other_writable: Style,  // tw
+    pub other_writable: Style,         // ow
+    pub sticky: Style,                 // st
+    pub multi_link_file: Style,        // mh
+    pub capability: Style,             // ca
 }
 
 #[rustfmt::skip]
@@ -68,6 +101,47 @@ pub struct Permissions {
     pub attribute: Style,           // xa
 }
 
+/// The styles for the letters of Windows file attributes, which are shown in
+/// place of the permissions.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WindowsAttributes {
+    pub readonly:      Style,  // Wr
+    pub hidden:        Style,  // Wh
+    pub system:        Style,  // Ws
+    pub archive:       Style,  // Wa
+    pub reparse_point: Style,  // Wl
+}
+
+/// The styles for BSD file flags in the flags column. Flags without a style
+/// of their own use the `ff` style.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FileFlags {
+    pub user_immutable:   Style,  // Oi
+    pub user_append:      Style,  // Oa
+    pub system_immutable: Style,  // OI
+    pub system_append:    Style,  // OA
+    pub hidden:           Style,  // Oh
+    pub nodump:           Style,  // Od
+    pub archived:         Style,  // OR
+    pub opaque:           Style,  // Oo
+}
+
+/// The styles for the dots drawn after file names for each colour of Finder
+/// tag, on macOS.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FinderTags {
+    pub gray:   Style,  // Fe
+    pub green:  Style,  // Fg
+    pub purple: Style,  // Fp
+    pub blue:   Style,  // Fb
+    pub yellow: Style,  // Fy
+    pub red:    Style,  // Fr
+    pub orange: Style,  // Fo
+}
+
 #[rustfmt::skip]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Size {
@@ -129,10 +203,11 @@ pub struct GitRepo {
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct SELinuxContext {
     pub colon: Style,
-    pub user: Style,  // Su
-    pub role: Style,  // Sr
-    pub typ: Style,   // St
-    pub range: Style, // Sl
+    pub user: Style,    // Su
+    pub role: Style,    // Sr
+    pub typ: Style,     // St
+    pub range: Style,   // Sl
+    pub default: Style, // Sd
 }
 
 #[rustfmt::skip]
@@ -142,6 +217,14 @@ pub struct SecurityContext {
     pub selinux: SELinuxContext,
 }
 
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sockets {
+    pub listening: Style,  // Kl
+    pub connected: Style,  // Kc
+    pub stale:     Style,  // Ks
+}
+
 /// Drawing styles based on the type of file (video, image, compressed, etc)
 #[rustfmt::skip]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -181,10 +264,16 @@ impl UiStyles {
             "cd" => self.filekinds.char_device  = pair.to_style(),  // CHR
             "ln" => self.filekinds.symlink      = pair.to_style(),  // LINK
             "or" => self.broken_symlink         = pair.to_style(),  // ORPHAN
+            "su" => self.filekinds.setuid       = pair.to_style(),  // SETUID
+            "sg" => self.filekinds.setgid       = pair.to_style(),  // SETGID
+            "tw" => self.filekinds.sticky_other_writable = pair.to_style(),  // STICKY_OTHER_WRITABLE
+            "ow" => self.filekinds.other_writable = pair.to_style(),  // OTHER_WRITABLE
+            "st" => self.filekinds.sticky       = pair.to_style(),  // STICKY
+            "mh" => self.filekinds.multi_link_file = pair.to_style(),  // MULTIHARDLINK
+            "ca" => self.filekinds.capability   = pair.to_style(),  // CAPABILITY
              _   => return false,
              // Codes we don’t do anything with:
-             // MULTIHARDLINK, DOOR, SETUID, SETGID, CAPABILITY,
-             // STICKY_OTHER_WRITABLE, OTHER_WRITABLE, STICKY, MISSING
+             // DOOR, MISSING
         };
         true
     }
@@ -192,7 +281,9 @@ impl UiStyles {
     /// Sets a value on this set of colours using one of the keys understood
     /// by the `EZA_COLORS` environment variable. Invalid keys set nothing,
     /// but return false. This doesn’t take the `LS_COLORS` keys into account,
-    /// so `set_ls` should have been run first.
+    /// so `set_ls` should be run for the keys this doesn’t know. It should be
+    /// run after this, as `su` and `tw` mean permission bits here, rather
+    /// than the files `LS_COLORS` uses them for.
     pub fn set_exa(&mut self, pair: &Pair<'_>) -> bool {
         #[rustfmt::skip]
         match pair.key {
@@ -210,6 +301,29 @@ impl UiStyles {
             "sf" => self.perms.special_other            = pair.to_style(),
             "xa" => self.perms.attribute                = pair.to_style(),
 
+            "Wr" => self.windows_attributes.readonly    = pair.to_style(),
+            "Wh" => self.windows_attributes.hidden      = pair.to_style(),
+            "Ws" => self.windows_attributes.system      = pair.to_style(),
+            "Wa" => self.windows_attributes.archive     = pair.to_style(),
+            "Wl" => self.windows_attributes.reparse_point = pair.to_style(),
+
+            "Oi" => self.file_flags.user_immutable      = pair.to_style(),
+            "Oa" => self.file_flags.user_append         = pair.to_style(),
+            "OI" => self.file_flags.system_immutable    = pair.to_style(),
+            "OA" => self.file_flags.system_append       = pair.to_style(),
+            "Oh" => self.file_flags.hidden              = pair.to_style(),
+            "Od" => self.file_flags.nodump              = pair.to_style(),
+            "OR" => self.file_flags.archived            = pair.to_style(),
+            "Oo" => self.file_flags.opaque              = pair.to_style(),
+
+            "Fe" => self.finder_tags.gray               = pair.to_style(),
+            "Fg" => self.finder_tags.green              = pair.to_style(),
+            "Fp" => self.finder_tags.purple             = pair.to_style(),
+            "Fb" => self.finder_tags.blue               = pair.to_style(),
+            "Fy" => self.finder_tags.yellow             = pair.to_style(),
+            "Fr" => self.finder_tags.red                = pair.to_style(),
+            "Fo" => self.finder_tags.orange             = pair.to_style(),
+
             "sn" => self.set_number_style(pair.to_style()),
             "sb" => self.set_unit_style(pair.to_style()),
             "nb" => self.size.number_byte               = pair.to_style(),
@@ -248,6 +362,10 @@ impl UiStyles {
             "Gc" => self.git_repo.git_clean             = pair.to_style(),
             "Gd" => self.git_repo.git_dirty             = pair.to_style(),
 
+            "Kl" => self.sockets.listening              = pair.to_style(),
+            "Kc" => self.sockets.connected              = pair.to_style(),
+            "Ks" => self.sockets.stale                  = pair.to_style(),
+
             "xx" => self.punctuation                    = pair.to_style(),
             "da" => self.date                           = pair.to_style(),
             "in" => self.inode                          = pair.to_style(),
@@ -255,11 +373,31 @@ impl UiStyles {
             "hd" => self.header                         = pair.to_style(),
             "oc" => self.octal                          = pair.to_style(),
             "ff" => self.flags                          = pair.to_style(),
+            "pv" => self.preview                        = pair.to_style(),
             "lp" => self.symlink_path                   = pair.to_style(),
             "cc" => self.control_char                   = pair.to_style(),
             "bO" => self.broken_path_overlay            = pair.to_style(),
+            "eO" => self.empty_dir_overlay              = pair.to_style(),
+            "rO" => self.recent_overlay                 = pair.to_style(),
+            "dO" => self.name_clash_overlay             = pair.to_style(),
+            "tO" => self.trashed_overlay                = pair.to_style(),
+            "Bg" => self.badge                          = pair.to_style(),
+            "mO" => self.highlight_overlay              = pair.to_style(),
+            "ud" => self.unreadable_dir                 = pair.to_style(),
+            "Ts" => self.tree_summary                   = pair.to_style(),
+            "dg" => self.dupe_group                     = pair.to_style(),
+            "fv" => self.file_version                   = pair.to_style(),
+            "bR" => self.broken_row_overlay             = pair.to_style(),
+            "iR" => self.ignored_row_overlay            = pair.to_style(),
+            "xR" => self.executable_row_overlay         = pair.to_style(),
+            "mR" => self.highlight_row_overlay          = pair.to_style(),
+
+            "L1" | "L2" | "L3" | "L4" | "L5" | "L6" | "L7" | "L8" | "L9"
+                 => self.set_depth_style(pair.key, pair.to_style()),
 
             "mp" => self.filekinds.mount_point          = pair.to_style(),
+            "Du" => self.filekinds.user_dir             = pair.to_style(),
+            "Dw" => self.filekinds.well_known_dir       = pair.to_style(),
             "sp" => self.filekinds.special              = pair.to_style(),  // Catch-all for unrecognized file kind
 
             "im" => self.file_type.image                = pair.to_style(),
@@ -279,6 +417,7 @@ impl UiStyles {
             "Sr" => self.security_context.selinux.role  = pair.to_style(),
             "St" => self.security_context.selinux.typ   = pair.to_style(),
             "Sl" => self.security_context.selinux.range = pair.to_style(),
+            "Sd" => self.security_context.selinux.default = pair.to_style(),
 
              _   => return false,
         };
@@ -286,6 +425,26 @@ impl UiStyles {
         true
     }
 
+    /// Sets one of the styles in the palette that tree depths cycle through,
+    /// from its `L1` to `L9` key, making the palette longer if it has to.
+    fn set_depth_style(&mut self, key: &str, style: Style) {
+        let index = usize::from(key.as_bytes()[1] - b'1');
+        if self.tree_depths.len() <= index {
+            self.tree_depths.resize(index + 1, Style::default());
+        }
+        self.tree_depths[index] = style;
+    }
+
+    /// The style for the given depth in a tree, cycling through the palette,
+    /// or nothing at the root or if the palette is empty.
+    pub fn depth_style(&self, depth: usize) -> Option<Style> {
+        if depth == 0 || self.tree_depths.is_empty() {
+            return None;
+        }
+
+        Some(self.tree_depths[(depth - 1) % self.tree_depths.len()])
+    }
+
     pub fn set_number_style(&mut self, style: Style) {
         self.size.number_byte = style;
         self.size.number_kilo = style;